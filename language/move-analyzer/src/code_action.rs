@@ -0,0 +1,507 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module is responsible for computing quick fixes for the diagnostics reported by the
+//! compiler. Diagnostics are recognized by their code (the diagnostic's category message, see
+//! `diagnostics::lsp_diagnostics`) and fixes are computed as textual edits to the source file the
+//! diagnostic was reported for. Candidates for auto-imports are found among the modules
+//! symbolicated across all packages in the resolution graph.
+
+use crate::{
+    context::Context,
+    diagnostics::DIAGNOSTIC_SOURCE,
+    symbols::{addr_to_ide_string, Symbols},
+};
+use lsp_server::Request;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Diagnostic, NumberOrString,
+    Position, Range, TextEdit, WorkspaceEdit,
+};
+use move_compiler::shared::Identifier;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+use url::Url;
+
+#[cfg(test)]
+use crate::symbols::Symbolicator;
+#[cfg(test)]
+use lsp_types::{DiagnosticRelatedInformation, Location};
+
+/// Diagnostic codes (see `move_compiler::diagnostics::codes`) for which quick fixes are provided
+const UNBOUND_MODULE: &str = "unbound module";
+const UNBOUND_UNSCOPED_NAME: &str = "unbound unscoped name";
+const MISSING_ACQUIRES: &str = "missing acquires annotation";
+const ABILITY_CONSTRAINT: &str = "ability constraint not satisfied";
+const UNUSED_VARIABLE: &str = "unused variable";
+const UNUSED_ALIAS: &str = "unused alias";
+
+/// Sends the given connection a response to a code action request, containing quick fixes for
+/// the diagnostics in the request's context.
+pub fn on_code_action_request(context: &Context, request: &Request, symbols: &Symbols) {
+    eprintln!("handling code action request");
+    let parameters = serde_json::from_value::<CodeActionParams>(request.params.clone())
+        .expect("could not deserialize code action request");
+
+    let uri = parameters.text_document.uri;
+    let path = uri.to_file_path().unwrap();
+    let mut actions = vec![];
+    if let Some(text) = file_text(context, &path) {
+        for diag in &parameters.context.diagnostics {
+            actions.extend(
+                quick_fixes(&uri, &text, diag, symbols)
+                    .into_iter()
+                    .map(CodeActionOrCommand::CodeAction),
+            );
+        }
+    } else {
+        eprintln!(
+            "Could not read '{:?}' when handling code action request",
+            path
+        );
+    }
+
+    let result = serde_json::to_value(actions).expect("could not serialize code action response");
+    let response = lsp_server::Response::new_ok(request.id.clone(), result);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send code action response: {:?}", err);
+    }
+}
+
+/// Computes quick fixes for a single diagnostic reported for the file with the given text.
+pub fn quick_fixes(uri: &Url, text: &str, diag: &Diagnostic, symbols: &Symbols) -> Vec<CodeAction> {
+    if diag.source.as_deref() != Some(DIAGNOSTIC_SOURCE) {
+        return vec![];
+    }
+    let code = match &diag.code {
+        Some(NumberOrString::String(code)) => code.as_str(),
+        _ => return vec![],
+    };
+    match code {
+        UNBOUND_MODULE | UNBOUND_UNSCOPED_NAME => auto_imports(uri, text, diag, symbols),
+        MISSING_ACQUIRES => add_acquires(uri, text, diag).into_iter().collect(),
+        ABILITY_CONSTRAINT => add_abilities(uri, text, diag).into_iter().collect(),
+        UNUSED_VARIABLE => vec![quick_fix(
+            "Prefix unused variable with '_'",
+            diag,
+            uri,
+            vec![TextEdit::new(
+                Range::new(diag.range.start, diag.range.start),
+                "_".to_string(),
+            )],
+        )],
+        UNUSED_ALIAS => remove_use(uri, text, diag).into_iter().collect(),
+        _ => vec![],
+    }
+}
+
+/// Imports an unbound module (or an unbound function) from any module with a matching name
+/// (containing a matching public function).
+fn auto_imports(uri: &Url, text: &str, diag: &Diagnostic, symbols: &Symbols) -> Vec<CodeAction> {
+    let name = match quoted(&diag.message) {
+        Some(name) => name,
+        None => return vec![],
+    };
+    let is_module = diag.code == Some(NumberOrString::String(UNBOUND_MODULE.to_string()));
+    let candidates = symbols
+        .file_mods()
+        .values()
+        .flatten()
+        .filter_map(|mod_defs| {
+            let mod_ident = mod_defs.name();
+            let mod_path = format!(
+                "{}::{}",
+                addr_to_ide_string(&mod_ident.address),
+                mod_ident.module.value()
+            );
+            if is_module {
+                (mod_ident.module.value().as_str() == name).then_some(mod_path)
+            } else {
+                mod_defs
+                    .functions()
+                    .iter()
+                    .any(|(fname, fdef)| fname.as_str() == name && fdef.is_public())
+                    .then(|| format!("{}::{}", mod_path, name))
+            }
+        })
+        .collect::<BTreeSet<_>>();
+    let (insert_pos, indent) = match use_insertion_point(text, diag.range.start) {
+        Some(v) => v,
+        None => return vec![],
+    };
+    candidates
+        .into_iter()
+        .map(|path| {
+            quick_fix(
+                &format!("Import '{}'", path),
+                diag,
+                uri,
+                vec![TextEdit::new(
+                    Range::new(insert_pos, insert_pos),
+                    format!("{}use {};\n", indent, path),
+                )],
+            )
+        })
+        .collect()
+}
+
+/// Adds the resource acquired by the call to the `acquires` list of the enclosing function.
+fn add_acquires(uri: &Url, text: &str, diag: &Diagnostic) -> Option<CodeAction> {
+    // the resource is only mentioned in the related information, as a fully qualified name
+    let acquired = diag
+        .related_information
+        .iter()
+        .flatten()
+        .find_map(|info| quoted(&info.message))?;
+    let resource = acquired.rsplit("::").next()?;
+    let diag_offset = offset(text, diag.range.start)?;
+    let fun_offset = text[..diag_offset].rfind("fun ")?;
+    let body_offset = fun_offset + text[fun_offset..].find('{')?;
+    let signature = text[fun_offset..body_offset].trim_end();
+    let insert_offset = fun_offset + signature.len();
+    let new_text = if signature.contains(" acquires ") {
+        format!(", {}", resource)
+    } else {
+        format!(" acquires {}", resource)
+    };
+    let insert_pos = position(text, insert_offset);
+    Some(quick_fix(
+        &format!("Add '{}' to the acquires list", resource),
+        diag,
+        uri,
+        vec![TextEdit::new(Range::new(insert_pos, insert_pos), new_text)],
+    ))
+}
+
+/// Adds the missing ability to the struct declaration the compiler points to. The struct may be
+/// declared in a different file than the one the diagnostic was reported for.
+fn add_abilities(uri: &Url, text: &str, diag: &Diagnostic) -> Option<CodeAction> {
+    let info = diag
+        .related_information
+        .iter()
+        .flatten()
+        .find(|info| info.message.starts_with("To satisfy the constraint"))?;
+    let ability = quoted(&info.message)?;
+    let decl_uri = &info.location.uri;
+    let decl_text = if decl_uri == uri {
+        text.to_string()
+    } else {
+        std::fs::read_to_string(decl_uri.to_file_path().ok()?).ok()?
+    };
+    let name_offset = offset(&decl_text, info.location.range.start)?;
+    let decl_end = name_offset + decl_text[name_offset..].find(['{', ';'])?;
+    let decl = decl_text[name_offset..decl_end].trim_end();
+    let insert_offset = name_offset + decl.len();
+    let new_text = if decl.contains(" has ") {
+        format!(", {}", ability)
+    } else {
+        format!(" has {}", ability)
+    };
+    let insert_pos = position(&decl_text, insert_offset);
+    Some(quick_fix(
+        &format!("Add '{}' ability to the struct", ability),
+        diag,
+        decl_uri,
+        vec![TextEdit::new(Range::new(insert_pos, insert_pos), new_text)],
+    ))
+}
+
+/// Removes an unused alias, either the whole `use` declaration or only the unused member if
+/// other members are imported by the same declaration.
+fn remove_use(uri: &Url, text: &str, diag: &Diagnostic) -> Option<CodeAction> {
+    let alias_start = offset(text, diag.range.start)?;
+    let alias_end = offset(text, diag.range.end)?;
+    let use_start = text[..alias_start].rfind("use ")?;
+    let use_end = use_start + text[use_start..].find(';')? + 1;
+    let members_start = text[use_start..use_end]
+        .find('{')
+        .map(|i| use_start + i + 1);
+    let members_end = text[use_start..use_end].rfind('}').map(|i| use_start + i);
+    let range = match (members_start, members_end) {
+        (Some(members_start), Some(members_end))
+            if text[members_start..members_end].contains(',') =>
+        {
+            // remove the member along with a separating comma
+            let before = &text[members_start..alias_start];
+            let after = &text[alias_end..members_end];
+            if let Some(comma) = after.find(',') {
+                let next = alias_end + comma + 1;
+                let next = next + (text[next..].len() - text[next..].trim_start().len());
+                (alias_start, next)
+            } else {
+                let comma = before.rfind(',')?;
+                (members_start + comma, alias_end)
+            }
+        }
+        _ => {
+            // remove the whole declaration, including its line if there is nothing else on it
+            let line_start = text[..use_start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = text[use_end..]
+                .find('\n')
+                .map_or(text.len(), |i| use_end + i + 1);
+            if text[line_start..use_start].trim().is_empty()
+                && text[use_end..line_end].trim().is_empty()
+            {
+                (line_start, line_end)
+            } else {
+                (use_start, use_end)
+            }
+        }
+    };
+    Some(quick_fix(
+        "Remove unused 'use'",
+        diag,
+        uri,
+        vec![TextEdit::new(
+            Range::new(position(text, range.0), position(text, range.1)),
+            String::new(),
+        )],
+    ))
+}
+
+/// Finds where a new `use` declaration should be inserted for a diagnostic at the given position
+/// (after the last `use` in the enclosing module, or at the beginning of the module) along with
+/// the indentation of the new declaration.
+fn use_insertion_point(text: &str, pos: Position) -> Option<(Position, String)> {
+    let diag_offset = offset(text, pos)?;
+    let mod_offset = text[..diag_offset]
+        .rfind("module ")
+        .or_else(|| text[..diag_offset].rfind("script"))?;
+    let body_offset = mod_offset + text[mod_offset..].find('{')? + 1;
+    let mut insert_line = position(text, body_offset).line + 1;
+    let mut indent = None;
+    for (idx, line) in text.lines().enumerate().skip(insert_line as usize) {
+        if idx >= pos.line as usize {
+            break;
+        }
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("use ") {
+            insert_line = idx as u32 + 1;
+            indent = Some(line[..line.len() - trimmed.len()].to_string());
+        }
+    }
+    let indent = indent.unwrap_or_else(|| {
+        let mod_line = text[..mod_offset].rfind('\n').map_or(0, |i| i + 1);
+        format!("{}    ", &text[mod_line..mod_offset])
+    });
+    Some((Position::new(insert_line, 0), indent))
+}
+
+fn quick_fix(title: &str, diag: &Diagnostic, uri: &Url, edits: Vec<TextEdit>) -> CodeAction {
+    CodeAction {
+        title: title.to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diag.clone()]),
+        edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+        ..Default::default()
+    }
+}
+
+/// Returns the contents of the file, preferably from the IDE's buffer.
+fn file_text(context: &Context, path: &Path) -> Option<String> {
+    match context.files.get(&path.to_path_buf()) {
+        Some(text) => Some(text.to_string()),
+        None => std::fs::read_to_string(path).ok(),
+    }
+}
+
+/// Returns the first single-quoted fragment of the message.
+fn quoted(msg: &str) -> Option<&str> {
+    let start = msg.find('\'')? + 1;
+    let len = msg[start..].find('\'')?;
+    Some(&msg[start..start + len])
+}
+
+/// Converts a (0-based line/character) position to a byte offset in the text.
+fn offset(text: &str, pos: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..pos.line {
+        line_start += text[line_start..].find('\n')? + 1;
+    }
+    let line = &text[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    match line.char_indices().nth(pos.character as usize) {
+        Some((idx, _)) => Some(line_start + idx),
+        None => Some(line_start + line.len()),
+    }
+}
+
+/// Converts a byte offset in the text to a (0-based line/character) position.
+fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].chars().count() as u32,
+    )
+}
+
+#[cfg(test)]
+fn diagnostic(code: &str, range: Range, message: &str) -> Diagnostic {
+    Diagnostic::new(
+        range,
+        None,
+        Some(NumberOrString::String(code.to_string())),
+        Some(DIAGNOSTIC_SOURCE.to_string()),
+        message.to_string(),
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+fn apply(text: &str, action: &CodeAction) -> String {
+    let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+    let mut edits = changes.values().flatten().collect::<Vec<_>>();
+    edits.sort_by_key(|e| std::cmp::Reverse(offset(text, e.range.start)));
+    let mut text = text.to_string();
+    for edit in edits {
+        let start = offset(&text, edit.range.start).unwrap();
+        let end = offset(&text, edit.range.end).unwrap();
+        text.replace_range(start..end, &edit.new_text);
+    }
+    text
+}
+
+#[cfg(test)]
+fn test_uri() -> Url {
+    Url::from_file_path("/tmp/M.move").unwrap()
+}
+
+#[test]
+fn unused_variable_test() {
+    let text = "module 0x1::M {\n    fun f(x: u64) {}\n}\n";
+    let diag = diagnostic(
+        UNUSED_VARIABLE,
+        Range::new(Position::new(1, 10), Position::new(1, 11)),
+        "Unused local variable 'x'",
+    );
+    let actions = quick_fixes(&test_uri(), text, &diag, &Symbolicator::empty_symbols());
+    assert_eq!(
+        apply(text, &actions[0]),
+        "module 0x1::M {\n    fun f(_x: u64) {}\n}\n"
+    );
+}
+
+#[test]
+fn unused_use_test() {
+    let text = "module 0x1::M {\n    use 0x1::A;\n    use 0x1::B::{f, g};\n}\n";
+    let symbols = Symbolicator::empty_symbols();
+    let diag = diagnostic(
+        UNUSED_ALIAS,
+        Range::new(Position::new(1, 13), Position::new(1, 14)),
+        "Unused 'use' of alias 'A'",
+    );
+    let actions = quick_fixes(&test_uri(), text, &diag, &symbols);
+    assert_eq!(
+        apply(text, &actions[0]),
+        "module 0x1::M {\n    use 0x1::B::{f, g};\n}\n"
+    );
+    let diag = diagnostic(
+        UNUSED_ALIAS,
+        Range::new(Position::new(2, 17), Position::new(2, 18)),
+        "Unused 'use' of alias 'f'",
+    );
+    let actions = quick_fixes(&test_uri(), text, &diag, &symbols);
+    assert_eq!(
+        apply(text, &actions[0]),
+        "module 0x1::M {\n    use 0x1::A;\n    use 0x1::B::{g};\n}\n"
+    );
+    let diag = diagnostic(
+        UNUSED_ALIAS,
+        Range::new(Position::new(2, 20), Position::new(2, 21)),
+        "Unused 'use' of alias 'g'",
+    );
+    let actions = quick_fixes(&test_uri(), text, &diag, &symbols);
+    assert_eq!(
+        apply(text, &actions[0]),
+        "module 0x1::M {\n    use 0x1::A;\n    use 0x1::B::{f};\n}\n"
+    );
+}
+
+#[test]
+fn missing_acquires_test() {
+    let text = "module 0x1::M {\n    struct R has key {}\n    struct S has key {}\n    \
+                fun f(): bool acquires R {\n        borrow_global<S>(@0x1); true\n    }\n}\n";
+    let mut diag = diagnostic(
+        MISSING_ACQUIRES,
+        Range::new(Position::new(4, 8), Position::new(4, 30)),
+        "Invalid operation: 'borrow_global' ",
+    );
+    diag.related_information = Some(vec![DiagnosticRelatedInformation {
+        location: Location::new(test_uri(), diag.range),
+        message: "The call acquires '0x1::M::S', but the 'acquires' list ...".to_string(),
+    }]);
+    let actions = quick_fixes(&test_uri(), text, &diag, &Symbolicator::empty_symbols());
+    assert!(apply(text, &actions[0]).contains("fun f(): bool acquires R, S {"));
+}
+
+#[test]
+fn missing_ability_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("S.move");
+    let decl_text = "module 0x1::S {\n    struct S<T> has drop { f: T }\n    struct N {}\n}\n";
+    std::fs::write(&path, decl_text).unwrap();
+    let decl_uri = Url::from_file_path(&path).unwrap();
+    for (line, ability, expected) in [
+        (1, "copy", "struct S<T> has drop, copy { f: T }"),
+        (2, "key", "struct N has key {}"),
+    ] {
+        let mut diag = diagnostic(
+            ABILITY_CONSTRAINT,
+            Range::new(Position::new(0, 0), Position::new(0, 1)),
+            "Invalid call",
+        );
+        diag.related_information = Some(vec![DiagnosticRelatedInformation {
+            location: Location::new(
+                decl_uri.clone(),
+                Range::new(Position::new(line, 11), Position::new(line, 12)),
+            ),
+            message: format!(
+                "To satisfy the constraint, the '{}' ability would need to be added here",
+                ability
+            ),
+        }]);
+        let actions = quick_fixes(&test_uri(), "", &diag, &Symbolicator::empty_symbols());
+        assert!(apply(decl_text, &actions[0]).contains(expected));
+    }
+}
+
+#[test]
+fn auto_import_test() {
+    let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/symbols");
+    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let text = "module Symbols::M {\n    use std::option;\n\n    fun f() {\n        \
+                vector::empty<u64>();\n    }\n}\n";
+    let diag = diagnostic(
+        UNBOUND_MODULE,
+        Range::new(Position::new(4, 8), Position::new(4, 14)),
+        "Unbound module alias 'vector'",
+    );
+    let actions = quick_fixes(&test_uri(), text, &diag, &symbols);
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        apply(text, &actions[0]),
+        "module Symbols::M {\n    use std::option;\n    use std::vector;\n\n    fun f() {\n        \
+         vector::empty<u64>();\n    }\n}\n"
+    );
+
+    let diag = diagnostic(
+        UNBOUND_UNSCOPED_NAME,
+        Range::new(Position::new(4, 8), Position::new(4, 14)),
+        "Unbound function 'destroy_empty' in current scope",
+    );
+    let titles = quick_fixes(&test_uri(), text, &diag, &symbols)
+        .into_iter()
+        .map(|action| action.title)
+        .collect::<Vec<_>>();
+    assert!(titles.contains(&"Import 'std::vector::destroy_empty'".to_string()));
+}
@@ -3,14 +3,21 @@
 
 use crate::utils::get_loc;
 use codespan_reporting::{diagnostic::Severity, files::SimpleFiles};
-use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Range};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Range,
+};
 use move_command_line_common::files::FileHash;
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::collections::{BTreeMap, HashMap};
 use url::Url;
 
+/// Source of diagnostics reported by the language server.
+pub const DIAGNOSTIC_SOURCE: &str = "move";
+
 /// Converts diagnostics from the codespan format to the format understood by the language server.
+/// The diagnostic's category message (e.g., "unused variable") is used as its code so that
+/// diagnostics can be recognized when computing code actions.
 pub fn lsp_diagnostics(
     diagnostics: &Vec<(
         codespan_reporting::diagnostic::Severity,
@@ -24,7 +31,7 @@ pub fn lsp_diagnostics(
     file_name_mapping: &BTreeMap<FileHash, Symbol>,
 ) -> BTreeMap<Symbol, Vec<Diagnostic>> {
     let mut lsp_diagnostics = BTreeMap::new();
-    for (s, _, (loc, msg), labels, _) in diagnostics {
+    for (s, code, (loc, msg), labels, _) in diagnostics {
         let fpath = file_name_mapping.get(&loc.file_hash()).unwrap();
         if let Some(start) = get_loc(&loc.file_hash(), loc.start(), files, file_id_mapping) {
             if let Some(end) = get_loc(&loc.file_hash(), loc.end(), files, file_id_mapping) {
@@ -63,8 +70,8 @@ pub fn lsp_diagnostics(
                     .push(Diagnostic::new(
                         range,
                         Some(severity(*s)),
-                        None,
-                        None,
+                        Some(NumberOrString::String(code.to_string())),
+                        Some(DIAGNOSTIC_SOURCE.to_string()),
                         msg.to_string(),
                         related_info_opt,
                         None,
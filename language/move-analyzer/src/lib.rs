@@ -5,6 +5,7 @@
 #[macro_use(sp)]
 extern crate move_ir_types;
 
+pub mod code_action;
 pub mod completion;
 pub mod context;
 pub mod diagnostics;
@@ -45,6 +45,10 @@
 //! processes function bodies and struct definitions to match uses to definitions. For local
 //! definitions, the symbolicator builds a scope stack, entering encountered definitions and
 //! matching uses to a definition in the innermost scope.
+//!
+//! Specifications are not part of the typed AST and are instead symbolicated from the expansion
+//! AST. As they are not type checked, spec-only items (schemas, spec functions and variables,
+//! builtins, and pragmas) are described by their source code declarations.
 
 use crate::{
     context::Context,
@@ -68,23 +72,34 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
     path::{Path, PathBuf},
-    sync::{Arc, Condvar, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
     thread,
+    time::Duration,
 };
 use tempfile::tempdir;
 use url::Url;
 
 use move_command_line_common::files::FileHash;
 use move_compiler::{
-    expansion::ast::{Address, Fields, ModuleIdent, ModuleIdent_},
+    compiled_unit::AnnotatedCompiledUnit,
+    construct_pre_compiled_lib,
+    diagnostics::{Diagnostics, FilesSourceText},
+    expansion::ast::{
+        self as E, Address, Fields, ModuleIdent, ModuleIdent_, Program as ExpansionProgram,
+        Visibility,
+    },
     naming::ast::{StructDefinition, StructFields, TParam, Type, TypeName_, Type_},
     parser::ast::StructName,
-    shared::Identifier,
+    shared::{Identifier, Name, PackagePaths},
     typing::ast::{
         BuiltinFunction_, Exp, ExpListItem, Function, FunctionBody_, LValue, LValueList, LValue_,
-        ModuleCall, ModuleDefinition, SequenceItem, SequenceItem_, UnannotatedExp_,
+        ModuleCall, ModuleDefinition, Program as TypedProgram, SequenceItem, SequenceItem_,
+        UnannotatedExp_,
     },
-    PASS_TYPING,
+    Compiler, Flags, FullyCompiledProgram, PASS_EXPANSION, PASS_TYPING,
 };
 use move_ir_types::location::*;
 use move_package::compilation::build_plan::BuildPlan;
@@ -96,6 +111,86 @@ pub const DEFS_AND_REFS_SUPPORT: bool = true;
 // Building Move code requires a larger stack size on Windows (16M has been chosen somewhat
 // arbitrarily)
 pub const STACK_SIZE_BYTES: usize = 16 * 1024 * 1024;
+/// Time without further edits after which symbols and diagnostics are recomputed for edited files
+pub const DEBOUNCE_PERIOD: Duration = Duration::from_millis(250);
+/// Name of the variable holding the function's return value in its specification
+const RESULT_VAR: &str = "result";
+/// Functions that are only available in specifications along with their hover information
+const SPEC_BUILTINS: &[(&str, &str)] = &[
+    (
+        "old",
+        "fun old<T>(e: T): T\n\nValue of the expression in the pre-state of the function",
+    ),
+    (
+        "global",
+        "fun global<T: key>(addr: address): T\n\nResource of type T stored at the address",
+    ),
+    (
+        "exists",
+        "fun exists<T: key>(addr: address): bool\n\nWhether resource of type T is stored at the address",
+    ),
+    (
+        "len",
+        "fun len<T>(v: vector<T>): num\n\nLength of the vector",
+    ),
+    (
+        "update_field",
+        "fun update_field<S, T>(s: S, f: Field, v: T): S\n\nCopy of the struct with the field updated",
+    ),
+];
+/// Pragmas recognized by the prover along with their descriptions
+const PRAGMAS: &[(&str, &str)] = &[
+    (
+        "verify",
+        "Whether the function (or all functions in the module) should be verified",
+    ),
+    (
+        "intrinsic",
+        "The type or function has a built-in implementation in the prover",
+    ),
+    (
+        "opaque",
+        "Callers use the function's specification rather than its implementation",
+    ),
+    ("timeout", "Verification timeout (in seconds)"),
+    (
+        "verify_duration_estimate",
+        "Estimated verification time (in seconds)",
+    ),
+    ("seed", "Random seed used by the SMT solver"),
+    (
+        "aborts_if_is_strict",
+        "Aborts not covered by any `aborts_if` condition are verification errors",
+    ),
+    (
+        "aborts_if_is_partial",
+        "The function may abort in cases not covered by any `aborts_if` condition",
+    ),
+    (
+        "emits_is_strict",
+        "Events not covered by any `emits` condition are verification errors",
+    ),
+    (
+        "emits_is_partial",
+        "The function may emit events not covered by any `emits` condition",
+    ),
+    (
+        "disable_invariants_in_body",
+        "Global invariants are assumed to hold only before and after the function is called",
+    ),
+    (
+        "delegate_invariants_to_caller",
+        "Global invariants are verified by the callers of the function",
+    ),
+    (
+        "addition_overflow_unchecked",
+        "Overflow of additions is not checked",
+    ),
+    (
+        "friend",
+        "The function can only be called from the specified function",
+    ),
+];
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Copy)]
 /// Location of a definition's identifier
@@ -132,6 +227,9 @@ pub enum IdentType {
         Type,         /* ret */
         Vec<Type>,    /* acquires */
     ),
+    /// Specification-only items (schemas, spec functions, spec variables, builtins, and pragmas)
+    /// that are not type checked by the compiler and are described by their rendered declaration
+    SpecType(String),
 }
 
 /// Information about both the use identifier (source file is specified wherever an instance of this
@@ -173,11 +271,21 @@ pub struct FunctionDef {
     name: Symbol,
     start: Position,
     attrs: Vec<String>,
+    /// Whether the function can be called from other modules
+    public: bool,
     #[derivative(PartialOrd = "ignore")]
     #[derivative(Ord = "ignore")]
     ident_type: IdentType,
 }
 
+/// Definition of a specification-only item (schema, spec function, or global spec variable)
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+struct SpecDef {
+    start: Position,
+    /// Declaration of the item as displayed on hover
+    info: String,
+}
+
 /// Module-level definitions
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
 pub struct ModuleDefs {
@@ -193,6 +301,8 @@ pub struct ModuleDefs {
     constants: BTreeMap<Symbol, Position>,
     /// Function definitions
     functions: BTreeMap<Symbol, FunctionDef>,
+    /// Schema, spec function, and global spec variable definitions
+    spec_defs: BTreeMap<Symbol, SpecDef>,
 }
 
 /// Data used during symbolication
@@ -216,6 +326,10 @@ pub struct Symbolicator {
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct UseDefMap(BTreeMap<u32, BTreeSet<UseDef>>);
 
+/// Local variables visible in a specification (e.g., function parameters, schema variables, or
+/// quantified variables) mapped to their definition location (if any) and displayed information
+type SpecScope = BTreeMap<Symbol, (Option<DefLoc>, String)>;
+
 /// Maps a function name to its usage definition
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FunctionIdentTypeMap(BTreeMap<String, IdentType>);
@@ -232,9 +346,21 @@ pub struct Symbols {
     file_mods: BTreeMap<PathBuf, BTreeSet<ModuleDefs>>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+/// A request to (re)compute symbolication information and diagnostics
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RunRequest {
+    /// Location (file or directory) for which the enclosing package should be symbolicated
+    starting_path: PathBuf,
+    /// Contents of the buffers opened in the IDE (keyed by canonicalized file paths)
+    buffers: BTreeMap<PathBuf, String>,
+    /// Whether the request was triggered by an edit, in which case it's debounced and dependency
+    /// compilation results are reused if possible
+    on_change: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 enum RunnerState {
-    Run(PathBuf),
+    Run(RunRequest),
     Wait,
     Quit,
 }
@@ -242,14 +368,39 @@ enum RunnerState {
 /// Data used during symbolication running and symbolication info updating
 pub struct SymbolicatorRunner {
     mtx_cvar: Arc<(Mutex<RunnerState>, Condvar)>,
+    /// Incremented whenever a new run is scheduled so that stale runs can be abandoned
+    generation: Arc<AtomicU64>,
+}
+
+/// Error returned when symbolication is abandoned as a newer run has been scheduled
+#[derive(Debug)]
+pub struct SymbolicationCancelled;
+
+/// Compilation results of package dependencies that are reused across symbolication runs as long
+/// as none of the dependencies change
+#[derive(Default)]
+pub struct DependencyCache {
+    /// A mapping from package root to the pre-compiled dependencies of this package along with
+    /// the digest of dependencies' sources and named addresses they were compiled from
+    pre_compiled_deps: BTreeMap<PathBuf, (FileHash, Arc<FullyCompiledProgram>)>,
 }
 
 impl ModuleDefs {
+    pub fn name(&self) -> &ModuleIdent_ {
+        &self.name
+    }
+
     pub fn functions(&self) -> &BTreeMap<Symbol, FunctionDef> {
         &self.functions
     }
 }
 
+impl FunctionDef {
+    pub fn is_public(&self) -> bool {
+        self.public
+    }
+}
+
 impl fmt::Display for IdentType {
     fn fmt(&self, f: &mut fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -296,10 +447,22 @@ impl fmt::Display for IdentType {
                     acquires_str
                 )
             }
+            Self::SpecType(info) => write!(f, "{}", info),
         }
     }
 }
 
+fn type_params_to_ide_string(type_params: &[(Name, E::AbilitySet)]) -> String {
+    if type_params.is_empty() {
+        return "".to_string();
+    }
+    let names = type_params
+        .iter()
+        .map(|(n, _)| n.value.as_str())
+        .collect::<Vec<_>>();
+    format!("<{}>", names.join(", "))
+}
+
 fn arg_list_to_ide_string(names: &[Symbol], types: &[Type]) -> String {
     names
         .iter()
@@ -348,7 +511,7 @@ fn type_to_ide_string(sp!(_, t): &Type) -> String {
     }
 }
 
-fn addr_to_ide_string(addr: &Address) -> String {
+pub fn addr_to_ide_string(addr: &Address) -> String {
     match addr {
         Address::Numerical(None, sp!(_, bytes)) => format!("{}", bytes),
         Address::Numerical(Some(name), _) => format!("{}", name),
@@ -368,7 +531,11 @@ impl SymbolicatorRunner {
     /// Create a new idle runner (one that does not actually symbolicate)
     pub fn idle() -> Self {
         let mtx_cvar = Arc::new((Mutex::new(RunnerState::Wait), Condvar::new()));
-        SymbolicatorRunner { mtx_cvar }
+        let generation = Arc::new(AtomicU64::new(0));
+        SymbolicatorRunner {
+            mtx_cvar,
+            generation,
+        }
     }
 
     /// Create a new runner
@@ -377,8 +544,13 @@ impl SymbolicatorRunner {
         sender: Sender<Result<BTreeMap<Symbol, Vec<Diagnostic>>>>,
     ) -> Self {
         let mtx_cvar = Arc::new((Mutex::new(RunnerState::Wait), Condvar::new()));
+        let generation = Arc::new(AtomicU64::new(0));
         let thread_mtx_cvar = mtx_cvar.clone();
-        let runner = SymbolicatorRunner { mtx_cvar };
+        let thread_generation = generation.clone();
+        let runner = SymbolicatorRunner {
+            mtx_cvar,
+            generation,
+        };
 
         thread::Builder::new()
             .stack_size(STACK_SIZE_BYTES)
@@ -386,54 +558,87 @@ impl SymbolicatorRunner {
                 let (mtx, cvar) = &*thread_mtx_cvar;
                 // Locations opened in the IDE (files or directories) for which manifest file is missing
                 let mut missing_manifests = BTreeSet::new();
+                // Dependencies compiled during previous runs
+                let mut deps_cache = DependencyCache::default();
                 // infinite loop to wait for symbolication requests
                 eprintln!("starting symbolicator runner loop");
                 loop {
-                    let starting_path_opt = {
+                    let request_opt = {
                         // hold the lock only as long as it takes to get the data, rather than through
                         // the whole symbolication process (hence a separate scope here)
                         let mut symbolicate = mtx.lock().unwrap();
+                        if *symbolicate == RunnerState::Wait {
+                            // wait for next request
+                            symbolicate = cvar.wait(symbolicate).unwrap();
+                        }
+                        // debounce requests triggered by edits - keep waiting for as long as new
+                        // edits keep coming in, always keeping the latest request
+                        while matches!(&*symbolicate, RunnerState::Run(r) if r.on_change) {
+                            let current = symbolicate.clone();
+                            let (guard, timeout) = cvar
+                                .wait_timeout_while(symbolicate, DEBOUNCE_PERIOD, |state| {
+                                    *state == current
+                                })
+                                .unwrap();
+                            symbolicate = guard;
+                            if timeout.timed_out() {
+                                break;
+                            }
+                        }
                         match symbolicate.clone() {
                             RunnerState::Quit => break,
-                            RunnerState::Run(root_dir) => {
+                            RunnerState::Run(request) => {
                                 *symbolicate = RunnerState::Wait;
-                                Some(root_dir)
-                            }
-                            RunnerState::Wait => {
-                                // wait for next request
-                                symbolicate = cvar.wait(symbolicate).unwrap();
-                                match symbolicate.clone() {
-                                    RunnerState::Quit => break,
-                                    RunnerState::Run(root_dir) => {
-                                        *symbolicate = RunnerState::Wait;
-                                        Some(root_dir)
-                                    }
-                                    RunnerState::Wait => None,
-                                }
+                                Some((request, thread_generation.load(Ordering::SeqCst)))
                             }
+                            RunnerState::Wait => None,
                         }
                     };
-                    if let Some(starting_path) = starting_path_opt {
+                    if let Some((request, generation)) = request_opt {
+                        let RunRequest {
+                            starting_path,
+                            buffers,
+                            on_change,
+                        } = request;
                         let root_dir = Self::root_dir(&starting_path);
-                        if root_dir.is_none() && !missing_manifests.contains(&starting_path) {
-                            eprintln!("reporting missing manifest");
-
-                            // report missing manifest file only once to avoid cluttering IDE's UI in
-                            // cases when developer indeed intended to open a standalone file that was
-                            // not meant to compile
-                            missing_manifests.insert(starting_path);
-                            if let Err(err) = sender.send(Err(anyhow!(
-                                "Unable to find package manifest. Make sure that
+                        if root_dir.is_none() {
+                            if !missing_manifests.contains(&starting_path) {
+                                eprintln!("reporting missing manifest");
+
+                                // report missing manifest file only once to avoid cluttering IDE's UI in
+                                // cases when developer indeed intended to open a standalone file that was
+                                // not meant to compile
+                                missing_manifests.insert(starting_path);
+                                if let Err(err) = sender.send(Err(anyhow!(
+                                    "Unable to find package manifest. Make sure that
                             the source files are located in a sub-directory of a package containing
                             a Move.toml file. "
-                            ))) {
-                                eprintln!("could not pass missing manifest error: {:?}", err);
+                                ))) {
+                                    eprintln!("could not pass missing manifest error: {:?}", err);
+                                }
                             }
                             continue;
                         }
+                        // a run is stale if another one has been scheduled after it
+                        let is_cancelled =
+                            || thread_generation.load(Ordering::SeqCst) != generation;
                         eprintln!("symbolication started");
-                        match Symbolicator::get_symbols(root_dir.unwrap().as_path()) {
+                        match Symbolicator::get_symbols_with_buffers(
+                            root_dir.unwrap().as_path(),
+                            &buffers,
+                            // a full recompilation (e.g., on save) also reports dependency warnings
+                            if on_change {
+                                Some(&mut deps_cache)
+                            } else {
+                                None
+                            },
+                            &is_cancelled,
+                        ) {
                             Ok((symbols_opt, lsp_diagnostics)) => {
+                                if is_cancelled() {
+                                    eprintln!("symbolication results are stale");
+                                    continue;
+                                }
                                 eprintln!("symbolication finished");
                                 if let Some(new_symbols) = symbols_opt {
                                     // merge the new symbols with the old ones to support a
@@ -452,6 +657,9 @@ impl SymbolicatorRunner {
                                     eprintln!("could not pass diagnostics: {:?}", err);
                                 }
                             }
+                            Err(err) if err.is::<SymbolicationCancelled>() => {
+                                eprintln!("symbolication cancelled");
+                            }
                             Err(err) => {
                                 eprintln!("symbolication failed: {:?}", err);
                                 if let Err(err) = sender.send(Err(err)) {
@@ -467,11 +675,32 @@ impl SymbolicatorRunner {
         runner
     }
 
-    pub fn run(&self, starting_path: PathBuf) {
-        eprintln!("scheduling run for {:?}", starting_path);
+    /// Schedules a run computing symbols and diagnostics right away (e.g., when a file is opened
+    /// or saved), superseding any previously scheduled runs
+    pub fn run(&self, starting_path: PathBuf, buffers: BTreeMap<PathBuf, String>) {
+        self.schedule(RunRequest {
+            starting_path,
+            buffers,
+            on_change: false,
+        });
+    }
+
+    /// Schedules a run computing symbols and diagnostics after a file has been edited - the run
+    /// starts only after no further edits arrive for `DEBOUNCE_PERIOD`
+    pub fn run_on_change(&self, starting_path: PathBuf, buffers: BTreeMap<PathBuf, String>) {
+        self.schedule(RunRequest {
+            starting_path,
+            buffers,
+            on_change: true,
+        });
+    }
+
+    fn schedule(&self, request: RunRequest) {
+        eprintln!("scheduling run for {:?}", request.starting_path);
         let (mtx, cvar) = &*self.mtx_cvar;
         let mut symbolicate = mtx.lock().unwrap();
-        *symbolicate = RunnerState::Run(starting_path);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        *symbolicate = RunnerState::Run(request);
         cvar.notify_one();
         eprintln!("scheduled run");
     }
@@ -479,6 +708,7 @@ impl SymbolicatorRunner {
     pub fn quit(&self) {
         let (mtx, cvar) = &*self.mtx_cvar;
         let mut symbolicate = mtx.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
         *symbolicate = RunnerState::Quit;
         cvar.notify_one();
     }
@@ -498,6 +728,71 @@ impl SymbolicatorRunner {
     }
 }
 
+impl fmt::Display for SymbolicationCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "symbolication cancelled")
+    }
+}
+
+impl std::error::Error for SymbolicationCancelled {}
+
+impl DependencyCache {
+    /// Returns the root package sources and pre-compiled dependencies of the package being built,
+    /// compiling the dependencies only if they changed since they were last compiled. Returns
+    /// `None` if dependencies cannot be pre-compiled, either because they have been edited in the
+    /// IDE or because they fail to compile, in which case the whole package should be compiled.
+    fn get_or_compile(
+        &mut self,
+        pkg_path: &Path,
+        build_plan: &BuildPlan,
+        source_overrides: &BTreeMap<Symbol, String>,
+    ) -> Result<Option<(PackagePaths, Arc<FullyCompiledProgram>)>> {
+        let (sources, deps) = build_plan.source_and_dep_package_paths()?;
+        if deps
+            .iter()
+            .any(|dep| dep.paths.iter().any(|p| source_overrides.contains_key(p)))
+        {
+            return Ok(None);
+        }
+        let digest = Self::deps_digest(&deps)?;
+        if let Some((cached_digest, pre_compiled_deps)) = self.pre_compiled_deps.get(pkg_path) {
+            if *cached_digest == digest {
+                eprintln!("reusing pre-compiled dependencies");
+                return Ok(Some((sources, pre_compiled_deps.clone())));
+            }
+        }
+        eprintln!("pre-compiling dependencies");
+        match construct_pre_compiled_lib(deps, None, Flags::testing())? {
+            Ok(pre_compiled_deps) => {
+                let pre_compiled_deps = Arc::new(pre_compiled_deps);
+                self.pre_compiled_deps
+                    .insert(pkg_path.to_path_buf(), (digest, pre_compiled_deps.clone()));
+                Ok(Some((sources, pre_compiled_deps)))
+            }
+            Err(_) => {
+                self.pre_compiled_deps.remove(pkg_path);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Computes a digest of dependencies' source files and named address assignments
+    fn deps_digest(deps: &[PackagePaths]) -> Result<FileHash> {
+        let mut contents = String::new();
+        for dep in deps {
+            contents.push_str(&format!("{:?}\n", dep.name));
+            for (name, addr) in &dep.named_address_map {
+                contents.push_str(&format!("{}={}\n", name, addr));
+            }
+            for path in &dep.paths {
+                let source = std::fs::read_to_string(path.as_str())?;
+                contents.push_str(&format!("{}:{}\n", path, FileHash::new(&source)));
+            }
+        }
+        Ok(FileHash::new(&contents))
+    }
+}
+
 impl UseDef {
     fn new(
         references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
@@ -615,6 +910,20 @@ impl Symbolicator {
     /// be retained even if it's getting out-of-date.
     pub fn get_symbols(
         pkg_path: &Path,
+    ) -> Result<(Option<Symbols>, BTreeMap<Symbol, Vec<Diagnostic>>)> {
+        Self::get_symbols_with_buffers(pkg_path, &BTreeMap::new(), None, &|| false)
+    }
+
+    /// Same as `get_symbols` but the contents of the source files are taken from the (possibly
+    /// unsaved) buffers, if available, rather than from disk. If a dependency cache is passed,
+    /// compilation results for dependencies are reused if they have not changed since the last
+    /// run. Symbolication is abandoned with the `SymbolicationCancelled` error if `is_cancelled`
+    /// starts returning `true`.
+    pub fn get_symbols_with_buffers(
+        pkg_path: &Path,
+        buffers: &BTreeMap<PathBuf, String>,
+        deps_cache: Option<&mut DependencyCache>,
+        is_cancelled: &dyn Fn() -> bool,
     ) -> Result<(Option<Symbols>, BTreeMap<Symbol, Vec<Diagnostic>>)> {
         let build_config = move_package::BuildConfig {
             test_mode: true,
@@ -630,8 +939,25 @@ impl Symbolicator {
             build_config.resolution_graph_for_package(pkg_path, &mut Vec::new())?;
 
         // get source files to be able to correlate positions (in terms of byte offsets) with actual
-        // file locations (in terms of line/column numbers)
-        let source_files = &resolution_graph.file_sources();
+        // file locations (in terms of line/column numbers), using buffer contents for files that
+        // are opened in the IDE
+        let mut source_overrides = BTreeMap::new();
+        let source_files = &resolution_graph
+            .file_sources()
+            .into_values()
+            .map(|(fname, contents)| {
+                let fpath = dunce::canonicalize(fname.as_str())
+                    .unwrap_or_else(|_| PathBuf::from(fname.as_str()));
+                let contents = match buffers.get(&fpath) {
+                    Some(buffer) => {
+                        source_overrides.insert(fname, buffer.clone());
+                        buffer.clone()
+                    }
+                    None => contents,
+                };
+                (FileHash::new(&contents), (fname, contents))
+            })
+            .collect::<BTreeMap<_, _>>();
         let mut files = SimpleFiles::new();
         let mut file_id_mapping = HashMap::new();
         let mut file_id_to_lines = HashMap::new();
@@ -644,43 +970,56 @@ impl Symbolicator {
             file_id_to_lines.insert(id, lines);
         }
 
+        if is_cancelled() {
+            return Err(SymbolicationCancelled.into());
+        }
+
         let build_plan = BuildPlan::create(resolution_graph)?;
+        let mut expansion_ast = None;
         let mut typed_ast = None;
         let mut diagnostics = None;
-        build_plan.compile_with_driver(&mut std::io::sink(), |compiler| {
-            let (files, compilation_result) = compiler.run::<PASS_TYPING>()?;
-            let (_, compiler) = match compilation_result {
-                Ok(v) => v,
-                Err(diags) => {
-                    let failure = true;
-                    diagnostics = Some((diags, failure));
-                    eprintln!("typed AST compilation failed");
-                    return Ok((files, vec![]));
+        let pre_compiled = match deps_cache {
+            Some(cache) => cache.get_or_compile(pkg_path, &build_plan, &source_overrides)?,
+            None => None,
+        };
+        match pre_compiled {
+            Some((sources, pre_compiled_deps)) => {
+                let compiler = Compiler::from_package_paths(vec![sources], vec![])
+                    .set_flags(Flags::testing())
+                    .set_pre_compiled_lib(&pre_compiled_deps)
+                    .set_source_overrides(source_overrides);
+                Self::compile(
+                    compiler,
+                    &mut expansion_ast,
+                    &mut typed_ast,
+                    &mut diagnostics,
+                    is_cancelled,
+                )?;
+                // ASTs only contain modules of the package being compiled, so add the ones from
+                // dependencies to have them symbolicated as well
+                if let Some(typed_program) = typed_ast.as_mut() {
+                    for (mident, mdef) in pre_compiled_deps.typing.modules.key_cloned_iter() {
+                        let _ = typed_program.modules.add(mident, mdef.clone());
+                    }
                 }
-            };
-            eprintln!("compiled to typed AST");
-            let (compiler, typed_program) = compiler.into_ast();
-            typed_ast = Some(typed_program.clone());
-            eprintln!("compiling to bytecode");
-            let compilation_result = compiler.at_typing(typed_program).build();
-            let (units, diags) = match compilation_result {
-                Ok(v) => v,
-                Err(diags) => {
-                    let failure = false;
-                    diagnostics = Some((diags, failure));
-                    eprintln!("bytecode compilation failed");
-                    return Ok((files, vec![]));
+                if let Some(expansion_program) = expansion_ast.as_mut() {
+                    for (mident, mdef) in pre_compiled_deps.expansion.modules.key_cloned_iter() {
+                        let _ = expansion_program.modules.add(mident, mdef.clone());
+                    }
                 }
-            };
-            // warning diagnostics (if any) since compilation succeeded
-            if !diags.is_empty() {
-                // assign only if non-empty, otherwise return None to reset previous diagnostics
-                let failure = false;
-                diagnostics = Some((diags, failure));
             }
-            eprintln!("compiled to bytecode");
-            Ok((files, units))
-        })?;
+            None => {
+                build_plan.compile_with_driver(&mut std::io::sink(), |compiler| {
+                    Self::compile(
+                        compiler.set_source_overrides(source_overrides.clone()),
+                        &mut expansion_ast,
+                        &mut typed_ast,
+                        &mut diagnostics,
+                        is_cancelled,
+                    )
+                })?;
+            }
+        }
 
         let mut ide_diagnostics = lsp_empty_diagnostics(&file_name_mapping);
         if let Some((compiler_diagnostics, failure)) = diagnostics {
@@ -703,19 +1042,24 @@ impl Symbolicator {
         }
 
         let modules = &typed_ast.unwrap().modules;
+        // specifications are dropped after expansion so they are symbolicated from expansion AST
+        let spec_modules = &expansion_ast.unwrap().modules;
 
         let mut mod_outer_defs = BTreeMap::new();
         let mut mod_use_defs = BTreeMap::new();
         let mut file_mods = BTreeMap::new();
 
         for (pos, module_ident, module_def) in modules {
-            let (defs, symbols) = Self::get_mod_outer_defs(
+            let (mut defs, symbols) = Self::get_mod_outer_defs(
                 &pos,
                 &sp(pos, *module_ident),
                 module_def,
                 &files,
                 &file_id_mapping,
             );
+            if let Some(spec_module_def) = spec_modules.get_(module_ident) {
+                defs.spec_defs = Self::get_mod_spec_defs(spec_module_def, &files, &file_id_mapping);
+            }
 
             let cloned_defs = defs.clone();
             let path = file_name_mapping.get(&cloned_defs.fhash.clone()).unwrap();
@@ -755,6 +1099,9 @@ impl Symbolicator {
                 &mut use_defs,
                 &mut function_ident_type,
             );
+            if let Some(spec_module_def) = spec_modules.get_(module_ident) {
+                symbolicator.spec_symbols(spec_module_def, &mut references, &mut use_defs);
+            }
 
             let fpath = match source_files.get(&pos.file_hash()) {
                 Some((p, _)) => p,
@@ -782,6 +1129,59 @@ impl Symbolicator {
         Ok((Some(symbols), ide_diagnostics))
     }
 
+    /// Compiles the package to bytecode, recording its expansion and typed ASTs (if type checking
+    /// succeeded) and compiler diagnostics (if any) along the way
+    fn compile(
+        compiler: Compiler,
+        expansion_ast: &mut Option<ExpansionProgram>,
+        typed_ast: &mut Option<TypedProgram>,
+        diagnostics: &mut Option<(Diagnostics, bool)>,
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> Result<(FilesSourceText, Vec<AnnotatedCompiledUnit>)> {
+        let (files, compilation_result) = compiler.run::<PASS_EXPANSION>()?;
+        let compiler = match compilation_result.and_then(|(_, compiler)| {
+            let (compiler, expansion_program) = compiler.into_ast();
+            *expansion_ast = Some(expansion_program.clone());
+            compiler
+                .at_expansion(expansion_program)
+                .run::<PASS_TYPING>()
+        }) {
+            Ok(v) => v,
+            Err(diags) => {
+                let failure = true;
+                *diagnostics = Some((diags, failure));
+                *expansion_ast = None;
+                eprintln!("typed AST compilation failed");
+                return Ok((files, vec![]));
+            }
+        };
+        eprintln!("compiled to typed AST");
+        if is_cancelled() {
+            return Err(SymbolicationCancelled.into());
+        }
+        let (compiler, typed_program) = compiler.into_ast();
+        *typed_ast = Some(typed_program.clone());
+        eprintln!("compiling to bytecode");
+        let compilation_result = compiler.at_typing(typed_program).build();
+        let (units, diags) = match compilation_result {
+            Ok(v) => v,
+            Err(diags) => {
+                let failure = false;
+                *diagnostics = Some((diags, failure));
+                eprintln!("bytecode compilation failed");
+                return Ok((files, vec![]));
+            }
+        };
+        // warning diagnostics (if any) since compilation succeeded
+        if !diags.is_empty() {
+            // assign only if non-empty, otherwise return None to reset previous diagnostics
+            let failure = false;
+            *diagnostics = Some((diags, failure));
+        }
+        eprintln!("compiled to bytecode");
+        Ok((files, units))
+    }
+
     /// Get empty symbols
     pub fn empty_symbols() -> Symbols {
         Symbols {
@@ -897,6 +1297,7 @@ impl Symbolicator {
                         .iter()
                         .map(|(_loc, name, _attr)| name.to_string())
                         .collect(),
+                    public: matches!(fun.visibility, Visibility::Public(_)),
                     ident_type,
                 },
             );
@@ -921,6 +1322,7 @@ impl Symbolicator {
                         structs,
                         constants,
                         functions,
+                        spec_defs: BTreeMap::new(),
                     },
                     use_def_map,
                 );
@@ -934,11 +1336,87 @@ impl Symbolicator {
             structs,
             constants,
             functions,
+            spec_defs: BTreeMap::new(),
         };
 
         (module_defs, use_def_map)
     }
 
+    /// Get definitions of specification-only items in the module (schemas, spec functions, and
+    /// global spec variables)
+    fn get_mod_spec_defs(
+        mod_def: &E::ModuleDefinition,
+        files: &SimpleFiles<Symbol, String>,
+        file_id_mapping: &HashMap<FileHash, usize>,
+    ) -> BTreeMap<Symbol, SpecDef> {
+        let mut spec_defs = BTreeMap::new();
+        let mut add_def = |name: &Name, info: String| {
+            match Self::get_start_loc(&name.loc, files, file_id_mapping) {
+                Some(start) => {
+                    spec_defs.insert(name.value, SpecDef { start, info });
+                }
+                None => debug_assert!(false),
+            };
+        };
+
+        for sp!(_, spec) in &mod_def.specs {
+            if let E::SpecBlockTarget_::Schema(name, type_params) = &spec.target.value {
+                let members = spec
+                    .members
+                    .iter()
+                    .map(|m| {
+                        let text = Self::source_text(&m.loc, files, file_id_mapping);
+                        format!("    {};\n", text.trim_end_matches(';'))
+                    })
+                    .collect::<String>();
+                add_def(
+                    name,
+                    format!(
+                        "schema {}{} {{\n{}}}",
+                        name,
+                        type_params_to_ide_string(type_params),
+                        members
+                    ),
+                );
+            }
+            for sp!(loc, member) in &spec.members {
+                let text = Self::source_text(loc, files, file_id_mapping);
+                match member {
+                    E::SpecBlockMember_::Function { name, .. } => {
+                        // only the signature is displayed so drop the body (if any)
+                        let decl = text.split('{').next().unwrap_or_default();
+                        add_def(
+                            &name.0,
+                            format!("spec {}", decl.trim().trim_end_matches(';')),
+                        );
+                    }
+                    E::SpecBlockMember_::Variable {
+                        is_global: true,
+                        name,
+                        ..
+                    } => add_def(name, text.trim_end_matches(';').to_string()),
+                    _ => (),
+                }
+            }
+        }
+        spec_defs
+    }
+
+    /// Returns source code at a given location with all whitespace sequences (including line
+    /// breaks) collapsed into single spaces
+    fn source_text(
+        loc: &Loc,
+        files: &SimpleFiles<Symbol, String>,
+        file_id_mapping: &HashMap<FileHash, usize>,
+    ) -> String {
+        file_id_mapping
+            .get(&loc.file_hash())
+            .and_then(|file_id| files.get(*file_id).ok())
+            .and_then(|file| file.source().get(loc.start() as usize..loc.end() as usize))
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default()
+    }
+
     /// Get symbols for the whole module
     fn mod_symbols(
         &mut self,
@@ -1649,6 +2127,592 @@ impl Symbolicator {
         };
     }
 
+    /// Get symbols for specification blocks in the module, both the module-level ones and the
+    /// ones inlined in function bodies
+    fn spec_symbols(
+        &self,
+        mod_def: &E::ModuleDefinition,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        for spec in &mod_def.specs {
+            self.spec_block_symbols(mod_def, spec, SpecScope::new(), references, use_defs);
+        }
+        for (_, _, fun) in &mod_def.functions {
+            let mut scope = SpecScope::new();
+            self.add_spec_params(&fun.signature, &mut scope);
+            for spec in fun.specs.values() {
+                self.spec_block_symbols(mod_def, spec, scope.clone(), references, use_defs);
+            }
+        }
+    }
+
+    /// Get symbols for a specification block
+    fn spec_block_symbols(
+        &self,
+        mod_def: &E::ModuleDefinition,
+        sp!(_, spec): &E::SpecBlock,
+        mut scope: SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        let mod_ident = self.current_mod.unwrap();
+        let mod_defs = self.mod_outer_defs.get(&mod_ident.value).unwrap();
+        match &spec.target.value {
+            E::SpecBlockTarget_::Member(name, signature) => {
+                if let Some(fun_def) = mod_defs.functions.get(&name.value) {
+                    self.add_fun_use_def(
+                        &mod_ident,
+                        &name.value,
+                        &name.loc,
+                        references,
+                        use_defs,
+                        fun_def.ident_type.clone(),
+                    );
+                    let signature = match signature {
+                        Some(s) => Some(s.as_ref()),
+                        None => mod_def.functions.get_(&name.value).map(|f| &f.signature),
+                    };
+                    if let Some(signature) = signature {
+                        self.add_spec_params(signature, &mut scope);
+                        if signature.return_type.value != E::Type_::Unit {
+                            let ret_type = self.spec_source_text(&signature.return_type.loc);
+                            scope
+                                .insert(RESULT_VAR.into(), (None, format!("result: {}", ret_type)));
+                        }
+                    }
+                } else if mod_defs.structs.contains_key(&name.value) {
+                    self.add_struct_use_def(
+                        &mod_ident,
+                        &name.value,
+                        &name.loc,
+                        references,
+                        use_defs,
+                        &Self::create_struct_type(mod_ident, StructName(*name), name.loc, vec![]),
+                    );
+                    // struct invariants refer to the struct's fields directly
+                    if let Some(E::StructFields::Defined(fields)) =
+                        mod_def.structs.get_(&name.value).map(|s| &s.fields)
+                    {
+                        for (floc, fname, (_, ftype)) in fields {
+                            let info = format!("{}: {}", fname, self.spec_source_text(&ftype.loc));
+                            self.add_spec_local(&sp(floc, *fname), info, &mut scope);
+                        }
+                    }
+                }
+            }
+            E::SpecBlockTarget_::Schema(name, _) => {
+                self.add_spec_member_use_def(&mod_ident, name, references, use_defs);
+            }
+            E::SpecBlockTarget_::Code | E::SpecBlockTarget_::Module => (),
+        }
+
+        for member in &spec.members {
+            self.spec_member_symbols(member, &mut scope, references, use_defs);
+        }
+    }
+
+    /// Get symbols for a member of a specification block
+    fn spec_member_symbols(
+        &self,
+        sp!(loc, member): &E::SpecBlockMember,
+        scope: &mut SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        let mod_ident = self.current_mod.unwrap();
+        match member {
+            E::SpecBlockMember_::Condition {
+                exp,
+                additional_exps,
+                ..
+            } => {
+                self.spec_exp_symbols(exp, scope, references, use_defs);
+                for e in additional_exps {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+            }
+            E::SpecBlockMember_::Function {
+                name,
+                signature,
+                body,
+                ..
+            } => {
+                self.add_spec_member_use_def(&mod_ident, &name.0, references, use_defs);
+                for (_, t) in &signature.parameters {
+                    self.spec_type_symbols(t, references, use_defs);
+                }
+                self.spec_type_symbols(&signature.return_type, references, use_defs);
+                let mut fun_scope = SpecScope::new();
+                self.add_spec_params(signature, &mut fun_scope);
+                if let E::FunctionBody_::Defined(seq) = &body.value {
+                    self.spec_seq_symbols(seq, &mut fun_scope, references, use_defs);
+                }
+            }
+            E::SpecBlockMember_::Variable {
+                is_global,
+                name,
+                type_,
+                init,
+                ..
+            } => {
+                self.spec_type_symbols(type_, references, use_defs);
+                if let Some(e) = init {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+                if *is_global {
+                    self.add_spec_member_use_def(&mod_ident, name, references, use_defs);
+                } else {
+                    // schema variable
+                    let info = self.spec_source_text(loc);
+                    let info = info.trim_end_matches(';');
+                    self.add_spec_local(name, info.to_string(), scope);
+                    self.add_spec_local_use_def(name, scope, references, use_defs);
+                }
+            }
+            E::SpecBlockMember_::Update { lhs, rhs } => {
+                self.spec_exp_symbols(lhs, scope, references, use_defs);
+                self.spec_exp_symbols(rhs, scope, references, use_defs);
+            }
+            E::SpecBlockMember_::Let { name, def, .. } => {
+                self.spec_exp_symbols(def, scope, references, use_defs);
+                let info = self.spec_source_text(loc);
+                let info = info.trim_end_matches(';');
+                self.add_spec_local(name, info.to_string(), scope);
+                self.add_spec_local_use_def(name, scope, references, use_defs);
+            }
+            E::SpecBlockMember_::Include { exp, .. } | E::SpecBlockMember_::Apply { exp, .. } => {
+                self.spec_exp_symbols(exp, scope, references, use_defs);
+            }
+            E::SpecBlockMember_::Pragma { properties } => {
+                for sp!(_, prop) in properties {
+                    let info = match PRAGMAS.iter().find(|(n, _)| *n == prop.name.value.as_str()) {
+                        Some((_, description)) => {
+                            format!("pragma {}\n\n{}", prop.name, description)
+                        }
+                        None => format!("pragma {}", prop.name),
+                    };
+                    let doc_string = String::new();
+                    self.add_spec_use_def(&prop.name, None, info, doc_string, references, use_defs);
+                }
+            }
+        }
+    }
+
+    /// Get symbols for a sequence in a specification (e.g., in spec function body)
+    fn spec_seq_symbols(
+        &self,
+        seq: &E::Sequence,
+        scope: &mut SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        for sp!(_, item) in seq {
+            match item {
+                E::SequenceItem_::Seq(e) => self.spec_exp_symbols(e, scope, references, use_defs),
+                E::SequenceItem_::Declare(lvalues, t) => {
+                    if let Some(t) = t {
+                        self.spec_type_symbols(t, references, use_defs);
+                    }
+                    self.spec_lvalues_symbols(&lvalues.value, scope, references, use_defs);
+                }
+                E::SequenceItem_::Bind(lvalues, e) => {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                    self.spec_lvalues_symbols(&lvalues.value, scope, references, use_defs);
+                }
+            }
+        }
+    }
+
+    /// Get symbols for local variables bound in a specification
+    fn spec_lvalues_symbols(
+        &self,
+        lvalues: &[E::LValue],
+        scope: &mut SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        for sp!(_, lvalue) in lvalues {
+            match lvalue {
+                E::LValue_::Var(sp!(_, E::ModuleAccess_::Name(name)), _) => {
+                    self.add_spec_local(name, name.value.to_string(), scope);
+                    self.add_spec_local_use_def(name, scope, references, use_defs);
+                }
+                E::LValue_::Var(..) => (),
+                E::LValue_::Unpack(sp!(_, access), _, fields) => {
+                    self.add_spec_access_use_def(access, None, references, use_defs);
+                    let field_lvalues = fields
+                        .iter()
+                        .map(|(_, _, (_, lv))| lv.clone())
+                        .collect::<Vec<_>>();
+                    self.spec_lvalues_symbols(&field_lvalues, scope, references, use_defs);
+                }
+            }
+        }
+    }
+
+    /// Get symbols for an expression in a specification
+    fn spec_exp_symbols(
+        &self,
+        sp!(_, exp): &E::Exp,
+        scope: &SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        let mut spec_types_symbols = |tys: &Option<Vec<E::Type>>| {
+            for t in tys.iter().flatten() {
+                self.spec_type_symbols(t, references, use_defs);
+            }
+        };
+        match exp {
+            E::Exp_::Name(sp!(_, access), tys) => {
+                spec_types_symbols(tys);
+                self.add_spec_access_use_def(access, Some(scope), references, use_defs);
+            }
+            E::Exp_::Call(sp!(_, access), _, tys, sp!(_, args)) => {
+                spec_types_symbols(tys);
+                match access {
+                    E::ModuleAccess_::Name(name) => {
+                        match SPEC_BUILTINS
+                            .iter()
+                            .find(|(n, _)| *n == name.value.as_str())
+                        {
+                            Some((_, info)) => self.add_spec_use_def(
+                                name,
+                                None,
+                                info.to_string(),
+                                String::new(),
+                                references,
+                                use_defs,
+                            ),
+                            None => {
+                                self.add_spec_access_use_def(access, None, references, use_defs)
+                            }
+                        }
+                    }
+                    E::ModuleAccess_::ModuleAccess(..) => {
+                        self.add_spec_access_use_def(access, None, references, use_defs)
+                    }
+                }
+                for e in args {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+            }
+            E::Exp_::Pack(sp!(_, access), tys, fields) => {
+                spec_types_symbols(tys);
+                self.add_spec_access_use_def(access, None, references, use_defs);
+                for (_, _, (_, e)) in fields {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+            }
+            E::Exp_::Vector(_, tys, sp!(_, args)) => {
+                spec_types_symbols(tys);
+                for e in args {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+            }
+            E::Exp_::IfElse(cond, t, f) => {
+                self.spec_exp_symbols(cond, scope, references, use_defs);
+                self.spec_exp_symbols(t, scope, references, use_defs);
+                self.spec_exp_symbols(f, scope, references, use_defs);
+            }
+            E::Exp_::While(e1, e2)
+            | E::Exp_::Mutate(e1, e2)
+            | E::Exp_::BinopExp(e1, _, e2)
+            | E::Exp_::Index(e1, e2) => {
+                self.spec_exp_symbols(e1, scope, references, use_defs);
+                self.spec_exp_symbols(e2, scope, references, use_defs);
+            }
+            E::Exp_::Loop(e)
+            | E::Exp_::Return(e)
+            | E::Exp_::Abort(e)
+            | E::Exp_::Dereference(e)
+            | E::Exp_::UnaryExp(_, e)
+            | E::Exp_::Borrow(_, e) => self.spec_exp_symbols(e, scope, references, use_defs),
+            E::Exp_::Cast(e, t) | E::Exp_::Annotate(e, t) => {
+                self.spec_exp_symbols(e, scope, references, use_defs);
+                self.spec_type_symbols(t, references, use_defs);
+            }
+            E::Exp_::Block(seq) => {
+                let mut block_scope = scope.clone();
+                self.spec_seq_symbols(seq, &mut block_scope, references, use_defs);
+            }
+            E::Exp_::Lambda(sp!(_, lvalues), e) => {
+                let mut lambda_scope = scope.clone();
+                self.spec_lvalues_symbols(lvalues, &mut lambda_scope, references, use_defs);
+                self.spec_exp_symbols(e, &lambda_scope, references, use_defs);
+            }
+            E::Exp_::Quant(_, sp!(_, ranges), triggers, cond, body) => {
+                let mut quant_scope = scope.clone();
+                for sp!(range_loc, (sp!(_, lvalue), range)) in ranges {
+                    self.spec_exp_symbols(range, &quant_scope, references, use_defs);
+                    if let E::LValue_::Var(sp!(_, E::ModuleAccess_::Name(name)), _) = lvalue {
+                        // show the range along with the variable (e.g., `a: address`)
+                        let info = self.spec_source_text(range_loc);
+                        self.add_spec_local(name, info, &mut quant_scope);
+                        self.add_spec_local_use_def(name, &quant_scope, references, use_defs);
+                    }
+                }
+                for e in triggers.iter().flatten() {
+                    self.spec_exp_symbols(e, &quant_scope, references, use_defs);
+                }
+                if let Some(e) = cond {
+                    self.spec_exp_symbols(e, &quant_scope, references, use_defs);
+                }
+                self.spec_exp_symbols(body, &quant_scope, references, use_defs);
+            }
+            E::Exp_::Assign(sp!(_, lvalues), e) => {
+                self.spec_exp_symbols(e, scope, references, use_defs);
+                for sp!(_, lvalue) in lvalues {
+                    if let E::LValue_::Var(sp!(_, access), _) = lvalue {
+                        self.add_spec_access_use_def(access, Some(scope), references, use_defs);
+                    }
+                }
+            }
+            E::Exp_::FieldMutate(dotted, e) => {
+                self.spec_dotted_symbols(dotted, scope, references, use_defs);
+                self.spec_exp_symbols(e, scope, references, use_defs);
+            }
+            E::Exp_::ExpDotted(dotted) => {
+                self.spec_dotted_symbols(dotted, scope, references, use_defs)
+            }
+            E::Exp_::ExpList(exps) => {
+                for e in exps {
+                    self.spec_exp_symbols(e, scope, references, use_defs);
+                }
+            }
+            E::Exp_::Value(_)
+            | E::Exp_::Move(_)
+            | E::Exp_::Copy(_)
+            | E::Exp_::Break
+            | E::Exp_::Continue
+            | E::Exp_::Unit { .. }
+            | E::Exp_::Spec(_, _)
+            | E::Exp_::UnresolvedError => (),
+        }
+    }
+
+    /// Get symbols for a dotted expression in a specification
+    fn spec_dotted_symbols(
+        &self,
+        sp!(_, dotted): &E::ExpDotted,
+        scope: &SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        match dotted {
+            E::ExpDotted_::Exp(e) => self.spec_exp_symbols(e, scope, references, use_defs),
+            E::ExpDotted_::Dot(d, _) => self.spec_dotted_symbols(d, scope, references, use_defs),
+        }
+    }
+
+    /// Get symbols for a type in a specification
+    fn spec_type_symbols(
+        &self,
+        sp!(_, t): &E::Type,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        match t {
+            E::Type_::Apply(sp!(_, access), tys) => {
+                let (mod_ident, name) = match access {
+                    E::ModuleAccess_::Name(name) => (self.current_mod.unwrap(), name),
+                    E::ModuleAccess_::ModuleAccess(mod_ident, name) => (*mod_ident, name),
+                };
+                if self
+                    .mod_outer_defs
+                    .get(&mod_ident.value)
+                    .and_then(|mod_defs| mod_defs.structs.get(&name.value))
+                    .is_some()
+                {
+                    self.add_struct_use_def(
+                        &mod_ident,
+                        &name.value,
+                        &name.loc,
+                        references,
+                        use_defs,
+                        &Self::create_struct_type(mod_ident, StructName(*name), name.loc, vec![]),
+                    );
+                }
+                for t in tys {
+                    self.spec_type_symbols(t, references, use_defs);
+                }
+            }
+            E::Type_::Multiple(tys) => {
+                for t in tys {
+                    self.spec_type_symbols(t, references, use_defs);
+                }
+            }
+            E::Type_::Ref(_, t) => self.spec_type_symbols(t, references, use_defs),
+            E::Type_::Fun(tys, ret) => {
+                for t in tys {
+                    self.spec_type_symbols(t, references, use_defs);
+                }
+                self.spec_type_symbols(ret, references, use_defs);
+            }
+            E::Type_::Unit | E::Type_::UnresolvedError => (),
+        }
+    }
+
+    /// Add function parameters to the specification scope
+    fn add_spec_params(&self, signature: &E::FunctionSignature, scope: &mut SpecScope) {
+        for (v, t) in &signature.parameters {
+            let info = format!("{}: {}", v.0.value, self.spec_source_text(&t.loc));
+            self.add_spec_local(&v.0, info, scope);
+        }
+    }
+
+    /// Add a local variable to the specification scope
+    fn add_spec_local(&self, name: &Name, info: String, scope: &mut SpecScope) {
+        match Self::get_start_loc(&name.loc, &self.files, &self.file_id_mapping) {
+            Some(start) => {
+                let def_loc = DefLoc {
+                    fhash: name.loc.file_hash(),
+                    start,
+                };
+                scope.insert(name.value, (Some(def_loc), info));
+            }
+            None => debug_assert!(false),
+        }
+    }
+
+    /// Add use of a local variable in the specification scope (if it's there)
+    fn add_spec_local_use_def(
+        &self,
+        name: &Name,
+        scope: &SpecScope,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        if let Some((def_loc, info)) = scope.get(&name.value) {
+            let doc_string = String::new();
+            self.add_spec_use_def(
+                name,
+                *def_loc,
+                info.clone(),
+                doc_string,
+                references,
+                use_defs,
+            );
+        }
+    }
+
+    /// Add use of a name appearing in a specification - if local scope is given, the name may
+    /// refer to a local variable, otherwise it refers to a module member
+    fn add_spec_access_use_def(
+        &self,
+        access: &E::ModuleAccess_,
+        scope: Option<&SpecScope>,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        match access {
+            E::ModuleAccess_::Name(name) => match scope {
+                Some(scope) if scope.contains_key(&name.value) => {
+                    self.add_spec_local_use_def(name, scope, references, use_defs)
+                }
+                _ => self.add_spec_member_use_def(
+                    &self.current_mod.unwrap(),
+                    name,
+                    references,
+                    use_defs,
+                ),
+            },
+            E::ModuleAccess_::ModuleAccess(mod_ident, name) => {
+                self.add_spec_member_use_def(mod_ident, name, references, use_defs)
+            }
+        }
+    }
+
+    /// Add use of a module member (spec-only item, function, or struct) referenced in a
+    /// specification
+    fn add_spec_member_use_def(
+        &self,
+        mod_ident: &ModuleIdent,
+        name: &Name,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        let mod_defs = match self.mod_outer_defs.get(&mod_ident.value) {
+            Some(v) => v,
+            None => return,
+        };
+        if let Some(spec_def) = mod_defs.spec_defs.get(&name.value) {
+            let def_loc = DefLoc {
+                fhash: mod_defs.fhash,
+                start: spec_def.start,
+            };
+            self.add_spec_use_def(
+                name,
+                Some(def_loc),
+                spec_def.info.clone(),
+                self.extract_doc_string(&def_loc.start, &def_loc.fhash),
+                references,
+                use_defs,
+            );
+        } else if let Some(fun_def) = mod_defs.functions.get(&name.value) {
+            self.add_fun_use_def(
+                mod_ident,
+                &name.value,
+                &name.loc,
+                references,
+                use_defs,
+                fun_def.ident_type.clone(),
+            );
+        } else if mod_defs.structs.contains_key(&name.value) {
+            self.add_struct_use_def(
+                mod_ident,
+                &name.value,
+                &name.loc,
+                references,
+                use_defs,
+                &Self::create_struct_type(*mod_ident, StructName(*name), name.loc, vec![]),
+            );
+        }
+    }
+
+    /// Add use of a specification-only identifier - if definition location is not given (e.g.,
+    /// for builtins and pragmas), the identifier is its own definition
+    fn add_spec_use_def(
+        &self,
+        name: &Name,
+        def_loc: Option<DefLoc>,
+        info: String,
+        doc_string: String,
+        references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
+        use_defs: &mut UseDefMap,
+    ) {
+        let use_start = match Self::get_start_loc(&name.loc, &self.files, &self.file_id_mapping) {
+            Some(v) => v,
+            None => {
+                debug_assert!(false);
+                return;
+            }
+        };
+        let def_loc = def_loc.unwrap_or(DefLoc {
+            fhash: name.loc.file_hash(),
+            start: use_start,
+        });
+        use_defs.insert(
+            use_start.line,
+            UseDef::new(
+                references,
+                name.loc.file_hash(),
+                use_start,
+                def_loc.fhash,
+                def_loc.start,
+                &name.value,
+                IdentType::SpecType(info),
+                None,
+                doc_string,
+            ),
+        );
+    }
+
+    fn spec_source_text(&self, loc: &Loc) -> String {
+        Self::source_text(loc, &self.files, &self.file_id_mapping)
+    }
+
     /// Add use of one of identifiers defined at the module level
     fn add_outer_use_def(
         &self,
@@ -2010,6 +3074,7 @@ impl Symbolicator {
         match ident_type {
             IdentType::RegularType(t) => self.type_def_loc(t),
             IdentType::FunctionType(_, _, _, _, _, ret, _) => self.type_def_loc(ret),
+            IdentType::SpecType(_) => None,
         }
     }
 
@@ -3677,3 +4742,234 @@ fn symbols_test() {
         None,
     );
 }
+
+#[test]
+/// Tests if symbolication uses the contents of (unsaved) buffers and reuses dependencies compiled
+/// during the previous run.
+fn buffers_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+    let source = std::fs::read_to_string(&cpath).unwrap();
+    let (mod_end, _) = source.rmatch_indices('}').next().unwrap();
+    let mut broken_source = source.clone();
+    broken_source.insert_str(mod_end, "    fun broken(): u64 { false }\n");
+
+    // type error in the buffer
+    let mut deps_cache = DependencyCache::default();
+    let buffers = BTreeMap::from([(cpath.clone(), broken_source)]);
+    let (symbols_opt, diagnostics) = Symbolicator::get_symbols_with_buffers(
+        path.as_path(),
+        &buffers,
+        Some(&mut deps_cache),
+        &|| false,
+    )
+    .unwrap();
+    assert!(symbols_opt.is_none());
+    assert!(diagnostics
+        .iter()
+        .any(|(fname, diags)| fname.as_str().ends_with("M1.move") && !diags.is_empty()));
+    assert!(deps_cache.pre_compiled_deps.contains_key(path.as_path()));
+
+    // type error fixed in the buffer, dependencies are reused but still symbolicated
+    let buffers = BTreeMap::from([(cpath.clone(), source)]);
+    let (symbols_opt, _) = Symbolicator::get_symbols_with_buffers(
+        path.as_path(),
+        &buffers,
+        Some(&mut deps_cache),
+        &|| false,
+    )
+    .unwrap();
+    let symbols = symbols_opt.unwrap();
+    assert!(symbols.file_use_defs.contains_key(&cpath));
+    assert!(symbols
+        .file_use_defs
+        .keys()
+        .any(|p| p.ends_with("vector.move")));
+
+    // cancelled run
+    let err = Symbolicator::get_symbols_with_buffers(path.as_path(), &buffers, None, &|| true)
+        .err()
+        .unwrap();
+    assert!(err.is::<SymbolicationCancelled>());
+}
+
+#[test]
+/// Tests if symbolication information for specification constructs is constructed correctly.
+fn spec_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M8.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let mod_symbols = symbols.file_use_defs.get(&cpath).unwrap();
+
+    // struct as spec target
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        6,
+        9,
+        2,
+        11,
+        "M8.move",
+        "Symbols::M8::Counter",
+        Some((2, 11, "M8.move")),
+    );
+    // struct field in struct invariant
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        7,
+        18,
+        3,
+        8,
+        "M8.move",
+        "value: u64",
+        None,
+    );
+    // function as spec target
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        15,
+        9,
+        10,
+        15,
+        "M8.move",
+        "fun Symbols::M8::increment(addr: address) acquires Symbols::M8::Counter",
+        None,
+    );
+    // pragma name
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        16,
+        15,
+        16,
+        15,
+        "M8.move",
+        "pragma opaque\n\nCallers use the function's specification rather than its implementation",
+        None,
+    );
+    // included schema
+    assert_use_def_with_doc_string(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        17,
+        16,
+        22,
+        16,
+        "M8.move",
+        "schema IncrementAbortsIf {\n    addr: address;\n    aborts_if !exists<Counter>(addr);\n    aborts_if global<Counter>(addr).value + 1 >= 1000;\n}",
+        None,
+        "Abort conditions of counter increment\n",
+    );
+    // spec function call
+    assert_use_def_with_doc_string(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        18,
+        16,
+        30,
+        12,
+        "M8.move",
+        "spec fun counter_value(addr: address): u64",
+        None,
+        "Value of the counter at the address\n",
+    );
+    // function parameter in function spec
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        1,
+        18,
+        30,
+        10,
+        25,
+        "M8.move",
+        "addr: address",
+        None,
+    );
+    // old builtin
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        2,
+        18,
+        39,
+        18,
+        39,
+        "M8.move",
+        "fun old<T>(e: T): T\n\nValue of the expression in the pre-state of the function",
+        None,
+    );
+    // schema variable def
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        23,
+        8,
+        23,
+        8,
+        "M8.move",
+        "addr: address",
+        None,
+    );
+    // struct type in global builtin call
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        1,
+        24,
+        26,
+        2,
+        11,
+        "M8.move",
+        "Symbols::M8::Counter",
+        Some((2, 11, "M8.move")),
+    );
+    // schema variable use
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        2,
+        24,
+        35,
+        23,
+        8,
+        "M8.move",
+        "addr: address",
+        None,
+    );
+    // spec function parameter use
+    assert_use_def(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        2,
+        31,
+        28,
+        30,
+        26,
+        "M8.move",
+        "addr: address",
+        None,
+    );
+}
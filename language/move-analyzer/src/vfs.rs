@@ -16,7 +16,7 @@ use lsp_types::{
     notification::Notification as _, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
     DidOpenTextDocumentParams, DidSaveTextDocumentParams,
 };
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 /// A mapping from identifiers (file names, potentially, but not necessarily) to their contents.
 #[derive(Debug, Default)]
@@ -45,6 +45,20 @@ impl VirtualFileSystem {
     pub fn remove(&mut self, identifier: &PathBuf) {
         self.files.remove(identifier);
     }
+
+    /// Returns a copy of all buffers in the system, keyed by canonicalized identifiers so that they
+    /// can be matched against the source files discovered during package resolution.
+    pub fn snapshot(&self) -> BTreeMap<PathBuf, String> {
+        self.files
+            .iter()
+            .map(|(identifier, content)| {
+                (
+                    dunce::canonicalize(identifier).unwrap_or_else(|_| identifier.clone()),
+                    content.clone(),
+                )
+            })
+            .collect()
+    }
 }
 
 /// Updates the given virtual file system based on the text document sync notification that was sent.
@@ -63,7 +77,10 @@ pub fn on_text_document_sync_notification(
                 parameters.text_document.uri.to_file_path().unwrap(),
                 &parameters.text_document.text,
             );
-            symbolicator_runner.run(parameters.text_document.uri.to_file_path().unwrap());
+            symbolicator_runner.run(
+                parameters.text_document.uri.to_file_path().unwrap(),
+                files.snapshot(),
+            );
         }
         lsp_types::notification::DidChangeTextDocument::METHOD => {
             let parameters =
@@ -73,6 +90,10 @@ pub fn on_text_document_sync_notification(
                 parameters.text_document.uri.to_file_path().unwrap(),
                 &parameters.content_changes.last().unwrap().text,
             );
+            symbolicator_runner.run_on_change(
+                parameters.text_document.uri.to_file_path().unwrap(),
+                files.snapshot(),
+            );
         }
         lsp_types::notification::DidSaveTextDocument::METHOD => {
             let parameters =
@@ -82,13 +103,22 @@ pub fn on_text_document_sync_notification(
                 parameters.text_document.uri.to_file_path().unwrap(),
                 &parameters.text.unwrap(),
             );
-            symbolicator_runner.run(parameters.text_document.uri.to_file_path().unwrap());
+            symbolicator_runner.run(
+                parameters.text_document.uri.to_file_path().unwrap(),
+                files.snapshot(),
+            );
         }
         lsp_types::notification::DidCloseTextDocument::METHOD => {
             let parameters =
                 serde_json::from_value::<DidCloseTextDocumentParams>(notification.params.clone())
                     .expect("could not deserialize notification");
             files.remove(&parameters.text_document.uri.to_file_path().unwrap());
+            // the closed buffer may have had unsaved changes, so recompute diagnostics from the
+            // contents of the file on disk
+            symbolicator_runner.run_on_change(
+                parameters.text_document.uri.to_file_path().unwrap(),
+                files.snapshot(),
+            );
         }
         _ => eprintln!("invalid notification '{}'", notification.method),
     }
@@ -0,0 +1,35 @@
+module Symbols::M8 {
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    spec Counter {
+        invariant value < 1000;
+    }
+
+    public fun increment(addr: address) acquires Counter {
+        let counter = borrow_global_mut<Counter>(addr);
+        counter.value = counter.value + 1;
+    }
+
+    spec increment {
+        pragma opaque;
+        include IncrementAbortsIf;
+        ensures counter_value(addr) == old(counter_value(addr)) + 1;
+    }
+
+    /// Abort conditions of counter increment
+    spec schema IncrementAbortsIf {
+        addr: address;
+        aborts_if !exists<Counter>(addr);
+        aborts_if global<Counter>(addr).value + 1 >= 1000;
+    }
+
+    spec module {
+        /// Value of the counter at the address
+        fun counter_value(addr: address): u64 {
+            global<Counter>(addr).value
+        }
+    }
+}
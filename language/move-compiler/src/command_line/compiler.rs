@@ -42,6 +42,7 @@ pub struct Compiler<'a> {
     pre_compiled_lib: Option<&'a FullyCompiledProgram>,
     compiled_module_named_address_mapping: BTreeMap<CompiledModuleId, String>,
     flags: Flags,
+    source_overrides: BTreeMap<Symbol, String>,
 }
 
 pub struct SteppedCompiler<'a, const P: Pass> {
@@ -130,6 +131,7 @@ impl<'a> Compiler<'a> {
             pre_compiled_lib: None,
             compiled_module_named_address_mapping: BTreeMap::new(),
             flags: Flags::empty(),
+            source_overrides: BTreeMap::new(),
         }
     }
 
@@ -193,6 +195,15 @@ impl<'a> Compiler<'a> {
         self
     }
 
+    /// Use the given contents instead of reading the corresponding (source) files from disk, e.g.
+    /// for files edited in an IDE but not yet saved. Keys must match the file paths as they are
+    /// discovered from the target and dependency paths.
+    pub fn set_source_overrides(mut self, source_overrides: BTreeMap<Symbol, String>) -> Self {
+        assert!(self.source_overrides.is_empty());
+        self.source_overrides = source_overrides;
+        self
+    }
+
     pub fn run<const TARGET: Pass>(
         self,
     ) -> anyhow::Result<(
@@ -207,6 +218,7 @@ impl<'a> Compiler<'a> {
             pre_compiled_lib,
             compiled_module_named_address_mapping,
             flags,
+            source_overrides,
         } = self;
         generate_interface_files_for_deps(
             &mut deps,
@@ -215,7 +227,7 @@ impl<'a> Compiler<'a> {
         )?;
         let mut compilation_env = CompilationEnv::new(flags);
         let (source_text, pprog_and_comments_res) =
-            parse_program(&mut compilation_env, maps, targets, deps)?;
+            parse_program(&mut compilation_env, maps, targets, deps, &source_overrides)?;
         let res: Result<_, Diagnostics> = pprog_and_comments_res.and_then(|(pprog, comments)| {
             SteppedCompiler::new_at_parser(compilation_env, pre_compiled_lib, pprog)
                 .run::<TARGET>()
@@ -752,6 +764,7 @@ fn run(
     match cur {
         PassResult::Parser(prog) => {
             let prog = parser::merge_spec_modules::program(compilation_env, prog);
+            let prog = parser::feature_filter::program(compilation_env, prog);
             let prog = unit_test::filter_test_members::program(compilation_env, prog);
             let prog = verification::ast_filter::program(compilation_env, prog);
             let eprog = expansion::translate::program(compilation_env, pre_compiled_lib, prog);
@@ -34,3 +34,6 @@ pub use command_line::{
 };
 pub use parser::comments::{CommentMap, FileCommentMap, MatchedFileCommentMap};
 pub use shared::Flags;
+
+/// The version of the compiler, recorded in the build metadata of packages.
+pub const COMPILER_VERSION: &str = env!("CARGO_PKG_VERSION");
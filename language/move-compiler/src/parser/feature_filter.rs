@@ -0,0 +1,160 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_symbol_pool::Symbol;
+
+use crate::{
+    diag,
+    parser::{
+        ast as P,
+        filter::{filter_program, FilterContext},
+    },
+    shared::{known_attributes::ConditionalAttribute, CompilationEnv},
+};
+
+struct Context<'env> {
+    env: &'env mut CompilationEnv,
+    package: Option<Symbol>,
+}
+
+impl<'env> Context<'env> {
+    fn new(compilation_env: &'env mut CompilationEnv) -> Self {
+        Self {
+            env: compilation_env,
+            package: None,
+        }
+    }
+}
+
+impl FilterContext for Context<'_> {
+    fn set_package(&mut self, package: Option<Symbol>) {
+        self.package = package;
+    }
+
+    fn should_remove_by_attributes(
+        &mut self,
+        attrs: &[P::Attributes],
+        _is_source_def: bool,
+    ) -> bool {
+        let mut should_remove = false;
+        for attr in attrs.iter().flat_map(|attrs| &attrs.value) {
+            if attr.value.attribute_name().value.as_str() != ConditionalAttribute::CFG {
+                continue;
+            }
+            // Elements with malformed predicates are kept, to not report errors about their uses
+            // on top of the error about the predicate.
+            should_remove |= self.cfg_predicate(attr) == Some(false);
+        }
+        should_remove
+    }
+}
+
+impl Context<'_> {
+    /// Evaluate the predicate of a `#[cfg(...)]` attribute, or report it and return `None` if it
+    /// is malformed.
+    fn cfg_predicate(&mut self, attr: &P::Attribute) -> Option<bool> {
+        match &attr.value {
+            P::Attribute_::Parameterized(_, sp!(_, predicates)) if predicates.len() == 1 => {
+                self.predicate(&predicates[0])
+            }
+            _ => {
+                let msg = format!(
+                    "Expected a single predicate for '{}', e.g. '#[{}({} = <name>)]'",
+                    ConditionalAttribute::CFG,
+                    ConditionalAttribute::CFG,
+                    ConditionalAttribute::FEATURE_NAME,
+                );
+                self.env
+                    .add_diag(diag!(Attributes::InvalidValue, (attr.loc, msg)));
+                None
+            }
+        }
+    }
+
+    fn predicate(&mut self, predicate: &P::Attribute) -> Option<bool> {
+        use P::Attribute_ as A;
+        match &predicate.value {
+            A::Assigned(sp!(_, name), value)
+                if name.as_str() == ConditionalAttribute::FEATURE_NAME =>
+            {
+                let feature = self.feature_name(value)?;
+                Some(self.env.flags().is_feature_enabled(self.package, feature))
+            }
+            A::Parameterized(sp!(_, name), sp!(loc, predicates))
+                if name.as_str() == ConditionalAttribute::NOT_NAME =>
+            {
+                if predicates.len() != 1 {
+                    let msg = format!(
+                        "Expected a single predicate for '{}'",
+                        ConditionalAttribute::NOT_NAME
+                    );
+                    self.env
+                        .add_diag(diag!(Attributes::InvalidValue, (*loc, msg)));
+                    return None;
+                }
+                self.predicate(&predicates[0]).map(|holds| !holds)
+            }
+            A::Parameterized(sp!(_, name), sp!(_, predicates))
+                if name.as_str() == ConditionalAttribute::ALL_NAME =>
+            {
+                let holds = self.predicates(predicates)?;
+                Some(holds.into_iter().all(|holds| holds))
+            }
+            A::Parameterized(sp!(_, name), sp!(_, predicates))
+                if name.as_str() == ConditionalAttribute::ANY_NAME =>
+            {
+                let holds = self.predicates(predicates)?;
+                Some(holds.into_iter().any(|holds| holds))
+            }
+            _ => {
+                let msg = format!(
+                    "Invalid predicate. Expected '{} = <name>', '{}(...)', '{}(...)' or '{}(...)'",
+                    ConditionalAttribute::FEATURE_NAME,
+                    ConditionalAttribute::NOT_NAME,
+                    ConditionalAttribute::ALL_NAME,
+                    ConditionalAttribute::ANY_NAME,
+                );
+                self.env
+                    .add_diag(diag!(Attributes::InvalidValue, (predicate.loc, msg)));
+                None
+            }
+        }
+    }
+
+    fn predicates(&mut self, predicates: &[P::Attribute]) -> Option<Vec<bool>> {
+        // Evaluate all predicates, to report every malformed one.
+        let holds: Vec<_> = predicates
+            .iter()
+            .map(|predicate| self.predicate(predicate))
+            .collect();
+        holds.into_iter().collect()
+    }
+
+    /// The name of a feature, given either as a name or as a byte string.
+    fn feature_name(&mut self, value: &P::AttributeValue) -> Option<Symbol> {
+        match &value.value {
+            P::AttributeValue_::ModuleAccess(sp!(_, P::NameAccessChain_::One(sp!(_, name)))) => {
+                Some(*name)
+            }
+            P::AttributeValue_::Value(sp!(_, P::Value_::ByteString(name))) => Some(*name),
+            _ => {
+                let msg = "Invalid feature. Expected a name, e.g. 'evm', or a byte string, \
+                           e.g. 'b\"evm\"'";
+                self.env
+                    .add_diag(diag!(Attributes::InvalidValue, (value.loc, msg)));
+                None
+            }
+        }
+    }
+}
+
+//***************************************************************************
+// Filtering of feature-gated elements
+//***************************************************************************
+
+// This filters out all AST elements annotated with a `#[cfg(...)]` attribute whose predicate does
+// not hold for the features enabled in their package.
+pub fn program(compilation_env: &mut CompilationEnv, prog: P::Program) -> P::Program {
+    let mut context = Context::new(compilation_env);
+    filter_program(&mut context, prog)
+}
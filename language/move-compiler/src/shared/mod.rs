@@ -12,7 +12,7 @@ use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
@@ -316,6 +316,11 @@ pub struct Flags {
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
     keep_testing_functions: bool,
+
+    /// The features enabled in each package, which its `#[cfg(feature = ...)]` attributes are
+    /// evaluated against.
+    #[clap(skip)]
+    features: BTreeMap<Symbol, BTreeSet<Symbol>>,
 }
 
 impl Flags {
@@ -327,6 +332,7 @@ impl Flags {
             flavor: "".to_string(),
             bytecode_version: None,
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -338,6 +344,7 @@ impl Flags {
             flavor: "".to_string(),
             bytecode_version: None,
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -349,6 +356,7 @@ impl Flags {
             flavor: "".to_string(),
             bytecode_version: None,
             keep_testing_functions: false,
+            features: BTreeMap::new(),
         }
     }
 
@@ -373,6 +381,10 @@ impl Flags {
         }
     }
 
+    pub fn set_features(self, features: BTreeMap<Symbol, BTreeSet<Symbol>>) -> Self {
+        Self { features, ..self }
+    }
+
     pub fn is_empty(&self) -> bool {
         self == &Self::empty()
     }
@@ -397,6 +409,14 @@ impl Flags {
         self.flavor == flavor
     }
 
+    /// Whether `feature` is enabled in `package`. No features are enabled in sources that are not
+    /// part of a package.
+    pub fn is_feature_enabled(&self, package: Option<Symbol>, feature: Symbol) -> bool {
+        package
+            .and_then(|package| self.features.get(&package))
+            .map_or(false, |features| features.contains(&feature))
+    }
+
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
@@ -428,6 +448,7 @@ pub mod known_attributes {
         Testing(TestingAttribute),
         Verification(VerificationAttribute),
         Native(NativeAttribute),
+        Conditional(ConditionalAttribute),
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -436,8 +457,14 @@ pub mod known_attributes {
         TestOnly,
         // Is a test that will be run
         Test,
+        // Is a test that will be run repeatedly with generated arguments
+        RandomTest,
         // This test is expected to fail
         ExpectedFailure,
+        // Sets up global storage for the tests that declare it in a `before_each` attribute
+        Fixture,
+        // The fixtures to apply before running the test(s)
+        BeforeEach,
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -452,6 +479,13 @@ pub mod known_attributes {
         BytecodeInstruction,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum ConditionalAttribute {
+        // The associated AST node will be included in the compilation only if its predicate over
+        // the enabled features holds
+        Cfg,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -472,16 +506,20 @@ pub mod known_attributes {
         pub fn resolve(attribute_str: impl AsRef<str>) -> Option<Self> {
             Some(match attribute_str.as_ref() {
                 TestingAttribute::TEST => Self::Testing(TestingAttribute::Test),
+                TestingAttribute::RANDOM_TEST => Self::Testing(TestingAttribute::RandomTest),
                 TestingAttribute::TEST_ONLY => Self::Testing(TestingAttribute::TestOnly),
                 TestingAttribute::EXPECTED_FAILURE => {
                     Self::Testing(TestingAttribute::ExpectedFailure)
                 }
+                TestingAttribute::FIXTURE => Self::Testing(TestingAttribute::Fixture),
+                TestingAttribute::BEFORE_EACH => Self::Testing(TestingAttribute::BeforeEach),
                 VerificationAttribute::VERIFY_ONLY => {
                     Self::Verification(VerificationAttribute::VerifyOnly)
                 }
                 NativeAttribute::BYTECODE_INSTRUCTION => {
                     Self::Native(NativeAttribute::BytecodeInstruction)
                 }
+                ConditionalAttribute::CFG => Self::Conditional(ConditionalAttribute::Cfg),
                 _ => return None,
             })
         }
@@ -491,6 +529,7 @@ pub mod known_attributes {
                 Self::Testing(a) => a.name(),
                 Self::Verification(a) => a.name(),
                 Self::Native(a) => a.name(),
+                Self::Conditional(a) => a.name(),
             }
         }
 
@@ -499,14 +538,18 @@ pub mod known_attributes {
                 Self::Testing(a) => a.expected_positions(),
                 Self::Verification(a) => a.expected_positions(),
                 Self::Native(a) => a.expected_positions(),
+                Self::Conditional(a) => a.expected_positions(),
             }
         }
     }
 
     impl TestingAttribute {
         pub const TEST: &'static str = "test";
+        pub const RANDOM_TEST: &'static str = "random_test";
         pub const EXPECTED_FAILURE: &'static str = "expected_failure";
         pub const TEST_ONLY: &'static str = "test_only";
+        pub const FIXTURE: &'static str = "fixture";
+        pub const BEFORE_EACH: &'static str = "before_each";
         pub const ABORT_CODE_NAME: &'static str = "abort_code";
         pub const ARITHMETIC_ERROR_NAME: &'static str = "arithmetic_error";
         pub const VECTOR_ERROR_NAME: &'static str = "vector_error";
@@ -518,8 +561,11 @@ pub mod known_attributes {
         pub const fn name(&self) -> &str {
             match self {
                 Self::Test => Self::TEST,
+                Self::RandomTest => Self::RANDOM_TEST,
                 Self::TestOnly => Self::TEST_ONLY,
                 Self::ExpectedFailure => Self::EXPECTED_FAILURE,
+                Self::Fixture => Self::FIXTURE,
+                Self::BeforeEach => Self::BEFORE_EACH,
             }
         }
 
@@ -540,10 +586,17 @@ pub mod known_attributes {
                 Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
             static EXPECTED_FAILURE_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
                 Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
+            static BEFORE_EACH_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([AttributePosition::Module, AttributePosition::Function])
+                    .collect()
+            });
             match self {
                 TestingAttribute::TestOnly => &TEST_ONLY_POSITIONS,
-                TestingAttribute::Test => &TEST_POSITIONS,
+                TestingAttribute::Test
+                | TestingAttribute::RandomTest
+                | TestingAttribute::Fixture => &TEST_POSITIONS,
                 TestingAttribute::ExpectedFailure => &EXPECTED_FAILURE_POSITIONS,
+                TestingAttribute::BeforeEach => &BEFORE_EACH_POSITIONS,
             }
         }
 
@@ -603,4 +656,38 @@ pub mod known_attributes {
             }
         }
     }
+
+    impl ConditionalAttribute {
+        pub const CFG: &'static str = "cfg";
+        pub const FEATURE_NAME: &'static str = "feature";
+        pub const NOT_NAME: &'static str = "not";
+        pub const ALL_NAME: &'static str = "all";
+        pub const ANY_NAME: &'static str = "any";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                ConditionalAttribute::Cfg => Self::CFG,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static CFG_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::AddressBlock,
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Use,
+                    AttributePosition::Friend,
+                    AttributePosition::Constant,
+                    AttributePosition::Struct,
+                    AttributePosition::Function,
+                    AttributePosition::Spec,
+                ])
+                .collect()
+            });
+            match self {
+                ConditionalAttribute::Cfg => &CFG_POSITIONS,
+            }
+        }
+    }
 }
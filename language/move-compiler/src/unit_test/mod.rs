@@ -8,8 +8,11 @@ use crate::{
     shared::NumericalAddress,
 };
 use move_core_types::{
-    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
-    value::MoveValue, vm_status::StatusCode,
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
 };
 use std::{collections::BTreeMap, fmt};
 
@@ -34,8 +37,28 @@ pub struct ModuleTestPlan {
 #[derive(Debug, Clone)]
 pub struct TestCase {
     pub test_name: TestName,
-    pub arguments: Vec<MoveValue>,
+    pub arguments: Vec<TestArgument>,
     pub expected_failure: Option<ExpectedFailure>,
+    // fixtures to run, in order, before the test body
+    pub fixtures: Vec<Fixture>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    // name of the #[fixture] function, declared in the same module as the test
+    pub name: String,
+    pub arguments: Vec<MoveValue>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TestArgument {
+    // value assigned in the test attribute, e.g. `#[test(a = @0x1)]`
+    Value(MoveValue),
+    // value generated for each run of a #[random_test]
+    Generate {
+        name: String,
+        layout: MoveTypeLayout,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +96,15 @@ impl ModuleTestPlan {
     }
 }
 
+impl TestCase {
+    /// Whether the arguments of this test are generated, i.e. it is a `#[random_test]`.
+    pub fn is_random_test(&self) -> bool {
+        self.arguments
+            .iter()
+            .any(|arg| matches!(arg, TestArgument::Generate { .. }))
+    }
+}
+
 impl TestPlan {
     pub fn new(
         tests: Vec<ModuleTestPlan>,
@@ -8,17 +8,24 @@ use crate::{
     expansion::ast::{
         self as E, Address, Attribute, AttributeValue, ModuleAccess_, ModuleIdent, ModuleIdent_,
     },
-    parser::ast::ConstantName,
+    hlir::ast as H,
+    naming::ast::{BuiltinTypeName_, TParamID},
+    parser::ast::{ConstantName, StructName},
     shared::{
         known_attributes::{KnownAttribute, TestingAttribute},
         unique_map::UniqueMap,
         CompilationEnv, Identifier, NumericalAddress,
     },
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
+    unit_test::{
+        ExpectedFailure, ExpectedMoveError, Fixture, ModuleTestPlan, TestArgument, TestCase,
+    },
 };
 use move_core_types::{
-    account_address::AccountAddress as MoveAddress, language_storage::ModuleId, u256::U256,
-    value::MoveValue, vm_status::StatusCode,
+    account_address::AccountAddress as MoveAddress,
+    language_storage::ModuleId,
+    u256::U256,
+    value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
 };
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
@@ -27,6 +34,7 @@ use std::collections::BTreeMap;
 struct Context<'env> {
     env: &'env mut CompilationEnv,
     constants: UniqueMap<ModuleIdent, UniqueMap<ConstantName, (Loc, Option<u64>)>>,
+    structs: UniqueMap<ModuleIdent, UniqueMap<StructName, H::StructDefinition>>,
 }
 
 impl<'env> Context<'env> {
@@ -40,9 +48,13 @@ impl<'env> Context<'env> {
                 (constant.loc, v_opt)
             })
         });
+        let structs = prog
+            .modules
+            .ref_map(|_mident, module| module.structs.clone());
         Self {
             env: compilation_env,
             constants,
+            structs,
         }
     }
 
@@ -90,11 +102,25 @@ fn construct_module_test_plan(
     if package_filter.is_some() && module.package_name != package_filter {
         return None;
     }
+    let fixtures: BTreeMap<_, _> = module
+        .functions
+        .iter()
+        .filter_map(|(loc, fn_name, func)| {
+            build_fixture(context, loc, fn_name, func).map(|fixture| (*fn_name, fixture))
+        })
+        .collect();
+    let module_fixtures = module
+        .attributes
+        .get_(&E::AttributeName_::Known(KnownAttribute::Testing(
+            TestingAttribute::BeforeEach,
+        )))
+        .map(|attr| parse_before_each_attribute(context, attr, &fixtures))
+        .unwrap_or_default();
     let tests: BTreeMap<_, _> = module
         .functions
         .iter()
         .filter_map(|(loc, fn_name, func)| {
-            build_test_info(context, loc, fn_name, func)
+            build_test_info(context, loc, fn_name, func, &fixtures, &module_fixtures)
                 .map(|test_case| (fn_name.to_string(), test_case))
         })
         .collect();
@@ -113,6 +139,8 @@ fn build_test_info<'func>(
     fn_loc: Loc,
     fn_name: &str,
     function: &'func G::Function,
+    fixture_defs: &BTreeMap<Symbol, Fixture>,
+    module_fixtures: &[Fixture],
 ) -> Option<TestCase> {
     let get_attrs = |attr: TestingAttribute| -> Option<&'func E::Attribute> {
         function
@@ -124,15 +152,34 @@ fn build_test_info<'func>(
     const IN_THIS_TEST_MSG: &str = "Error found in this test";
 
     let test_attribute_opt = get_attrs(TestingAttribute::Test);
+    let random_test_attribute_opt = get_attrs(TestingAttribute::RandomTest);
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
+    let before_each_attribute_opt = get_attrs(TestingAttribute::BeforeEach);
+
+    // A #[test] function cannot also be annotated #[random_test]. As its parameters would be
+    // checked against the wrong attribute, no further errors are reported for it.
+    if let (Some(test_attribute), Some(random_test_attribute)) =
+        (test_attribute_opt, random_test_attribute_opt)
+    {
+        let msg = "Function annotated as both #[test(...)] and #[random_test(...)]. You need to \
+                   declare it as either one or the other";
+        context.env.add_diag(diag!(
+            Attributes::InvalidUsage,
+            (random_test_attribute.loc, msg),
+            (test_attribute.loc, PREVIOUSLY_ANNOTATED_MSG),
+            (fn_loc, IN_THIS_TEST_MSG),
+        ));
+        return None;
+    }
 
-    let test_attribute = match test_attribute_opt {
+    let is_random_test = test_attribute_opt.is_none() && random_test_attribute_opt.is_some();
+    let test_attribute = match test_attribute_opt.or(random_test_attribute_opt) {
         None => {
             // expected failures cannot be annotated on non-#[test] functions
             if let Some(abort_attribute) = abort_attribute_opt {
-                let fn_msg = "Only functions defined as a test with #[test] can also have an \
-                              #[expected_failure] attribute";
+                let fn_msg = "Only functions defined as a test with #[test] or #[random_test] can \
+                              also have an #[expected_failure] attribute";
                 let abort_msg = "Attributed as #[expected_failure] here";
                 context.env.add_diag(diag!(
                     Attributes::InvalidUsage,
@@ -140,6 +187,17 @@ fn build_test_info<'func>(
                     (abort_attribute.loc, abort_msg),
                 ))
             }
+            // nor can fixtures
+            if let Some(before_each_attribute) = before_each_attribute_opt {
+                let fn_msg = "Only functions defined as a test with #[test] or #[random_test] can \
+                              also have a #[before_each] attribute";
+                let before_each_msg = "Attributed as #[before_each] here";
+                context.env.add_diag(diag!(
+                    Attributes::InvalidUsage,
+                    (fn_loc, fn_msg),
+                    (before_each_attribute.loc, before_each_msg),
+                ))
+            }
             return None;
         }
         Some(test_attribute) => test_attribute,
@@ -159,9 +217,27 @@ fn build_test_info<'func>(
 
     let test_annotation_params = parse_test_attribute(context, test_attribute, 0);
     let mut arguments = Vec::new();
-    for (var, _) in &function.signature.parameters {
+    for (var, ty) in &function.signature.parameters {
         match test_annotation_params.get(&var.value()) {
-            Some(value) => arguments.push(value.clone()),
+            Some(value) => arguments.push(TestArgument::Value(value.clone())),
+            None if is_random_test => match generated_argument_layout(context, ty) {
+                Some(layout) => arguments.push(TestArgument::Generate {
+                    name: var.value().to_string(),
+                    layout,
+                }),
+                None => {
+                    let unsupported_msg = "Unsupported type for a generated test parameter. \
+                                           Only integers, bools, addresses, and vectors and \
+                                           structs of those can be generated. Other parameters \
+                                           must be assigned in the attribute";
+                    context.env.add_diag(diag!(
+                        Attributes::InvalidTest,
+                        (ty.loc, unsupported_msg),
+                        (var.loc(), "Corresponding to this parameter"),
+                        (fn_loc, IN_THIS_TEST_MSG),
+                    ))
+                }
+            },
             None => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
                                          parameter to be assigned in this attribute";
@@ -180,10 +256,78 @@ fn build_test_info<'func>(
         Some(abort_attribute) => parse_failure_attribute(context, abort_attribute),
     };
 
+    // The fixtures of the module are applied first, followed by those of the test itself
+    let mut fixtures = module_fixtures.to_vec();
+    if let Some(before_each_attribute) = before_each_attribute_opt {
+        for fixture in parse_before_each_attribute(context, before_each_attribute, fixture_defs) {
+            if !fixtures.contains(&fixture) {
+                fixtures.push(fixture)
+            }
+        }
+    }
+
     Some(TestCase {
         test_name: fn_name.to_string(),
         arguments,
         expected_failure,
+        fixtures,
+    })
+}
+
+fn build_fixture(
+    context: &mut Context,
+    fn_loc: Loc,
+    fn_name: &Symbol,
+    function: &G::Function,
+) -> Option<Fixture> {
+    let get_attrs = |attr: TestingAttribute| -> Option<&E::Attribute> {
+        function
+            .attributes
+            .get_(&E::AttributeName_::Known(KnownAttribute::Testing(attr)))
+    };
+
+    let fixture_attribute = get_attrs(TestingAttribute::Fixture)?;
+
+    // A #[fixture] function cannot also be a test
+    for attr in [TestingAttribute::Test, TestingAttribute::RandomTest] {
+        if let Some(test_attribute) = get_attrs(attr) {
+            let msg = format!(
+                "Function annotated as both #[{}(...)] and #[fixture(...)]. You need to declare \
+                 it as either one or the other",
+                attr.name()
+            );
+            context.env.add_diag(diag!(
+                Attributes::InvalidUsage,
+                (fixture_attribute.loc, msg),
+                (test_attribute.loc, "Previously annotated here"),
+                (fn_loc, "Error found in this fixture"),
+            ));
+            return None;
+        }
+    }
+
+    let fixture_annotation_params = parse_test_attribute(context, fixture_attribute, 0);
+    let mut arguments = Vec::new();
+    for (var, _) in &function.signature.parameters {
+        match fixture_annotation_params.get(&var.value()) {
+            Some(value) => arguments.push(value.clone()),
+            None => {
+                let missing_param_msg = "Missing fixture parameter assignment. Expected a \
+                                         parameter to be assigned in this attribute";
+                context.env.add_diag(diag!(
+                    Attributes::InvalidTest,
+                    (fixture_attribute.loc, missing_param_msg),
+                    (var.loc(), "Corresponding to this parameter"),
+                    (fn_loc, "Error found in this fixture"),
+                ));
+                return None;
+            }
+        }
+    }
+
+    Some(Fixture {
+        name: fn_name.to_string(),
+        arguments,
     })
 }
 
@@ -208,7 +352,7 @@ fn parse_test_attribute(
         }
         EA::Name(nm) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                is_test_attribute_name(nm.value.as_str()) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             BTreeMap::new()
@@ -240,7 +384,7 @@ fn parse_test_attribute(
         }
         EA::Parameterized(nm, attributes) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                is_test_attribute_name(nm.value.as_str()) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             attributes
@@ -251,6 +395,64 @@ fn parse_test_attribute(
     }
 }
 
+fn is_test_attribute_name(name: &str) -> bool {
+    name == TestingAttribute::Test.name()
+        || name == TestingAttribute::RandomTest.name()
+        || name == TestingAttribute::Fixture.name()
+}
+
+fn parse_before_each_attribute(
+    context: &mut Context,
+    sp!(aloc, before_each_attr): &E::Attribute,
+    fixtures: &BTreeMap<Symbol, Fixture>,
+) -> Vec<Fixture> {
+    use E::Attribute_ as EA;
+    match before_each_attr {
+        EA::Name(_) | EA::Assigned(_, _) => {
+            context.env.add_diag(diag!(
+                Attributes::InvalidValue,
+                (
+                    *aloc,
+                    "Expected a list of fixtures, e.g. #[before_each(setup)]"
+                ),
+            ));
+            vec![]
+        }
+        EA::Parameterized(_, attrs) => {
+            // The attributes are keyed by name, so the order in which the fixtures are listed is
+            // recovered from their locations
+            let mut attrs = attrs.iter().collect::<Vec<_>>();
+            attrs.sort_by_key(|(loc, _, _)| loc.start());
+            attrs
+                .into_iter()
+                .filter_map(|(_, _, sp!(loc, attr))| match attr {
+                    EA::Name(nm) => match fixtures.get(&nm.value) {
+                        Some(fixture) => Some(fixture.clone()),
+                        None => {
+                            let msg = format!(
+                                "Unbound fixture '{}'. Expected a function annotated with \
+                                 #[fixture] in this module",
+                                nm
+                            );
+                            context
+                                .env
+                                .add_diag(diag!(Attributes::InvalidValue, (*loc, msg)));
+                            None
+                        }
+                    },
+                    EA::Assigned(_, _) | EA::Parameterized(_, _) => {
+                        let msg = "Expected the name of a function annotated with #[fixture]";
+                        context
+                            .env
+                            .add_diag(diag!(Attributes::InvalidValue, (*loc, msg)));
+                        None
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
 const BAD_ABORT_VALUE_WARNING: &str = "WARNING: passes for an abort from any module.";
 const INVALID_VALUE: &str = "Invalid value in attribute assignment";
 
@@ -678,6 +880,65 @@ fn convert_attribute_value_to_move_value(
     }
 }
 
+//***************************************************************************
+// Generated arguments
+//***************************************************************************
+
+// Computes the layout of values generated for a parameter of a #[random_test], if values of its
+// type can be generated.
+fn generated_argument_layout(context: &Context, ty: &H::SingleType) -> Option<MoveTypeLayout> {
+    match &ty.value {
+        H::SingleType_::Base(bt) => base_type_layout(context, bt, &BTreeMap::new()),
+        H::SingleType_::Ref(_, _) => None,
+    }
+}
+
+fn base_type_layout(
+    context: &Context,
+    sp!(_, bt_): &H::BaseType,
+    subst: &BTreeMap<TParamID, MoveTypeLayout>,
+) -> Option<MoveTypeLayout> {
+    use BuiltinTypeName_ as B;
+    use H::{BaseType_ as HB, TypeName_ as HT};
+    match bt_ {
+        HB::Param(tp) => subst.get(&tp.id).cloned(),
+        HB::Apply(_, sp!(_, HT::Builtin(sp!(_, b))), ty_args) => Some(match b {
+            B::Address => MoveTypeLayout::Address,
+            B::U8 => MoveTypeLayout::U8,
+            B::U16 => MoveTypeLayout::U16,
+            B::U32 => MoveTypeLayout::U32,
+            B::U64 => MoveTypeLayout::U64,
+            B::U128 => MoveTypeLayout::U128,
+            B::U256 => MoveTypeLayout::U256,
+            B::Bool => MoveTypeLayout::Bool,
+            B::Vector => {
+                let elem = base_type_layout(context, ty_args.first()?, subst)?;
+                MoveTypeLayout::Vector(Box::new(elem))
+            }
+            B::Signer => return None,
+        }),
+        HB::Apply(_, sp!(_, HT::ModuleType(m, s)), ty_args) => {
+            let sdef = context.structs.get(m)?.get(s)?;
+            let fields = match &sdef.fields {
+                H::StructFields::Defined(fields) => fields,
+                H::StructFields::Native(_) => return None,
+            };
+            let struct_subst = sdef
+                .type_parameters
+                .iter()
+                .zip(ty_args)
+                .map(|(tp, ty_arg)| Some((tp.param.id, base_type_layout(context, ty_arg, subst)?)))
+                .collect::<Option<BTreeMap<_, _>>>()?;
+            let field_layouts = fields
+                .iter()
+                .map(|(_, fty)| base_type_layout(context, fty, &struct_subst))
+                .collect::<Option<Vec<_>>>()?;
+            Some(MoveTypeLayout::Struct(MoveStructLayout::new(field_layouts)))
+        }
+        HB::Unreachable | HB::UnresolvedError => None,
+    }
+}
+
 fn check_location<T>(
     context: &mut Context,
     loc: Loc,
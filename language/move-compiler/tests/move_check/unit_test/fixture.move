@@ -0,0 +1,20 @@
+// fixtures can be declared on modules and tests, and take signer arguments
+#[before_each(publish)]
+module 0x1::M {
+    struct R has key { x: u64 }
+
+    #[fixture(s = @0x1)]
+    fun publish(s: signer) {
+        move_to(&s, R { x: 0 })
+    }
+
+    #[fixture]
+    fun other() { }
+
+    #[test]
+    fun uses_module_fixture() { }
+
+    #[test]
+    #[before_each(other, publish)]
+    fun uses_fixtures() { }
+}
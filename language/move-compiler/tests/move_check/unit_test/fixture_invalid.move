@@ -0,0 +1,26 @@
+// fixtures must be declared with #[fixture] in the same module, and tests must list them by name
+#[before_each]
+module 0x1::M {
+    #[fixture]
+    fun missing_param(_s: signer) { }
+
+    #[test]
+    #[fixture]
+    fun both() { }
+
+    #[fixture]
+    fun setup() { }
+
+    #[test]
+    #[before_each(unknown, setup = 1)]
+    fun bad_fixtures() { }
+
+    #[test]
+    #[before_each(no_fixture)]
+    fun not_a_fixture() { }
+
+    fun no_fixture() { }
+
+    #[before_each(setup)]
+    fun not_a_test() { }
+}
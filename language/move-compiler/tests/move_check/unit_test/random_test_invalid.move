@@ -0,0 +1,25 @@
+// #[random_test] parameters must be assigned or of a type values can be generated for
+address 0x1 {
+module M {
+    struct R has key { x: u64 }
+    struct S<T> has drop { t: T }
+
+    #[random_test]
+    fun signer_not_assigned(_s: signer) { }
+
+    #[random_test]
+    fun reference_param(_x: &u64) { }
+
+    #[random_test]
+    fun nested_signer(_x: S<signer>) { }
+
+    #[random_test(_s = @0x1)]
+    fun valid(_s: signer, _x: u64, _v: vector<S<bool>>, _a: address, _r: R) {
+        let R { x: _ } = _r;
+    }
+
+    #[test]
+    #[random_test]
+    fun both(_x: u64) { }
+}
+}
@@ -13,7 +13,7 @@ use std::{
 
 use anyhow::anyhow;
 use codespan::{ByteIndex, ColumnIndex, LineIndex, Location, Span};
-use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::diagnostic::{Diagnostic, Label, Severity};
 use itertools::Itertools;
 use log::{debug, info, warn};
 use num::BigInt;
@@ -25,17 +25,23 @@ use move_binary_format::file_format::FunctionDefinitionIndex;
 use move_model::{
     ast::TempIndex,
     code_writer::CodeWriter,
-    model::{FunId, GlobalEnv, Loc, ModuleId, NodeId, QualifiedId, StructEnv},
+    model::{
+        FunId, GlobalEnv, Loc, ModuleId, NodeId, QualifiedId, QualifiedInstId, StructEnv, StructId,
+    },
     pragmas::INTRINSIC_TYPE_MAP,
     ty::{PrimitiveType, Type},
 };
-use move_stackless_bytecode::function_target_pipeline::{FunctionTargetsHolder, FunctionVariant};
+use move_stackless_bytecode::{
+    function_target_pipeline::{FunctionTargetsHolder, FunctionVariant},
+    usage_analysis::UsageState,
+};
 
 // DEBUG
 // use backtrace::Backtrace;
 use crate::{
-    boogie_helpers::{boogie_inst_suffix, boogie_struct_name},
+    boogie_helpers::{boogie_inst_suffix, boogie_resource_memory_name, boogie_struct_name},
     options::{BoogieOptions, VectorTheory},
+    proof_cache::{self, ProofCache},
     prover_task_runner::{ProverTaskRunner, RunBoogieWithSeeds},
 };
 
@@ -221,6 +227,70 @@ impl<'env> BoogieWrapper<'env> {
 
     /// Calls boogie and analyzes output.
     pub fn call_boogie_and_verify_output(&self, boogie_file: &str) -> anyhow::Result<()> {
+        if let Some(cache_dir) = &self.options.proof_cache_dir {
+            // Concurrent instances would all write the same XML log, from which results
+            // are recorded.
+            if self.options.num_instances == 1 {
+                return self.call_boogie_with_cache(boogie_file, cache_dir);
+            }
+            self.env.diag(
+                Severity::Warning,
+                &self.env.unknown_loc(),
+                &format!(
+                    "proof cache `{}` is not used with multiple boogie instances \
+                     (`num_instances = {}`)",
+                    cache_dir, self.options.num_instances
+                ),
+            );
+        }
+        self.call_boogie_and_report(boogie_file)
+    }
+
+    /// Calls boogie for the verification conditions which are not known to be verified
+    /// from the proof cache, and records those which verify in this run.
+    fn call_boogie_with_cache(&self, boogie_file: &str, cache_dir: &str) -> anyhow::Result<()> {
+        let mut cache = ProofCache::load(cache_dir);
+        let vcs =
+            proof_cache::verification_conditions(&fs::read_to_string(boogie_file)?, self.options);
+        let (stats, pending) = cache.pending(vcs);
+        info!("{}", stats);
+        // Make the statistics available to the caller, which reports them with the result
+        // of the prover run.
+        self.env.set_extension(stats);
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(cache_dir)?;
+        let xml_file = cache.xml_log_file();
+        let mut options = self.options.clone();
+        options
+            .boogie_flags
+            .push(format!("-xml:{}", xml_file.display()));
+        if stats.cached > 0 {
+            options
+                .boogie_flags
+                .extend(pending.iter().map(|vc| format!("-proc:{}", vc.procedure)));
+        }
+        BoogieWrapper {
+            options: &options,
+            ..*self
+        }
+        .call_boogie_and_report(boogie_file)?;
+
+        let verified =
+            proof_cache::verified_procedures(&fs::read_to_string(&xml_file).unwrap_or_default());
+        fs::remove_file(&xml_file).unwrap_or_default();
+        for vc in &pending {
+            if verified.contains(&vc.procedure) {
+                cache.record_verified(vc);
+            }
+        }
+        cache.save()
+    }
+
+    /// Calls boogie on all verification conditions and reports errors found.
+    fn call_boogie_and_report(&self, boogie_file: &str) -> anyhow::Result<()> {
         let BoogieOutput { errors, all_output } = self.call_boogie(boogie_file)?;
         let boogie_log_file = self.options.get_boogie_log_file(boogie_file);
         let log_file_existed = std::path::Path::new(&boogie_log_file).exists();
@@ -410,6 +480,13 @@ impl<'env> BoogieWrapper<'env> {
                 .with_message(&format!("abort happened here{}", code))]);
             }
 
+            // Render the counterexample as a unit test before global memory is consumed below
+            let replay_test = if self.options.counterexample_tests && error.model.is_some() {
+                Some(self.counterexample_test(error, &global_mem_map))
+            } else {
+                None
+            };
+
             // Inject information about sub-expressions of this failure
             if !subexp_map.is_empty() {
                 let mut trace_display = std::mem::take(&mut display);
@@ -447,11 +524,373 @@ impl<'env> BoogieWrapper<'env> {
                 display.append(&mut trace_display)
             }
 
+            match replay_test {
+                Some(Ok((module_name, test))) => {
+                    display.push(format!(
+                        "Unit test replaying the counterexample (to be added to module `{}`):",
+                        module_name
+                    ));
+                    display.extend(test.lines().map(|s| "    ".to_string() + s));
+                }
+                Some(Err(reason)) => display.push(format!(
+                    "Counterexample cannot be replayed as a unit test: {}",
+                    reason
+                )),
+                None => {}
+            }
+
             diag = diag.with_notes(display);
         }
         self.env.add_diag(diag);
     }
 
+    /// Renders the counterexample of a verification error as a unit test which publishes the
+    /// initial global memory found in the model, calls the verified function with the arguments
+    /// found in the model, and checks that the function behaves as in the counterexample: if it
+    /// aborted, the test expects this failure, otherwise the test asserts the returned values
+    /// and the global memory at the point of failure. The test hence passes as long as the
+    /// counterexample can be reproduced. As the test may pack structs of the verified function's
+    /// module, it needs to be added to this module, whose name is returned along with the test.
+    fn counterexample_test(
+        &self,
+        error: &BoogieError,
+        global_mem_map: &BTreeMap<String, (NodeId, ModelValue, ModelValue)>,
+    ) -> Result<(String, String), String> {
+        let model = error.model.as_ref().ok_or("no model available")?;
+        let fun_env = error
+            .execution_trace
+            .iter()
+            .find_map(|entry| match entry {
+                TraceEntry::AtLocation(loc) => self.env.get_enclosing_function(loc),
+                _ => None,
+            })
+            .ok_or("verified function not found in the execution trace")?;
+        if fun_env.get_type_parameter_count() > 0 {
+            return Err("generic functions are not supported".to_string());
+        }
+        let fun_id = fun_env.get_qualified_id();
+        let module_id = fun_env.module_env.get_id();
+        let fun_target = self
+            .targets
+            .get_target(&fun_env, &FunctionVariant::Baseline);
+        let symbol_pool = self.env.symbol_pool();
+
+        // Signers of the test, along with the addresses they are created for.
+        let mut signers: Vec<(String, BigInt)> = vec![];
+        let mut stmts = vec![];
+        let mut args = vec![];
+        for idx in 0..fun_target.get_parameter_count() {
+            let name = fun_target
+                .get_local_name(idx)
+                .display(symbol_pool)
+                .to_string();
+            let ty = fun_target.get_local_type(idx);
+            let value = error
+                .execution_trace
+                .iter()
+                .find_map(|entry| match entry {
+                    TraceEntry::Temporary(fun, i, value) if *fun == fun_id && *i == idx => {
+                        Some(value)
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| format!("no value found for parameter `{}`", name))?;
+            let prefix = match ty {
+                Type::Reference(true, _) => "&mut ",
+                Type::Reference(false, _) => "&",
+                _ => "",
+            };
+            args.push(format!("{}{}", prefix, name));
+            if matches!(ty.skip_reference(), Type::Primitive(PrimitiveType::Signer)) {
+                let addr = value
+                    .extract_signer_address()
+                    .ok_or_else(|| format!("invalid signer for parameter `{}`", name))?;
+                signers.push((name, addr));
+            } else {
+                let literal = value
+                    .move_literal(self, model, module_id, ty.skip_reference())
+                    .ok_or_else(|| format!("cannot render value of parameter `{}`", name))?;
+                stmts.push(format!("let {} = {};", name, self.test_literal(literal)));
+            }
+        }
+
+        // Publish the resources found in the initial global memory before the call. The memory
+        // accessed by the function is taken from the usage analysis, completed by the memory
+        // reported at the point of failure.
+        let domain_info = extract_domain(model);
+        let mut memory = fun_target
+            .get_annotations()
+            .get::<UsageState>()
+            .map(|usage| usage.accessed.all.iter().cloned().collect::<BTreeSet<_>>())
+            .unwrap_or_default();
+        for (id, _, _) in global_mem_map.values() {
+            if let Type::Struct(mid, sid, inst) = self.env.get_node_type(*id) {
+                memory.insert(mid.qualified_inst(sid, inst));
+            }
+        }
+        let mut publish_stmts = vec![];
+        for mem in &memory {
+            let struct_env = self.env.get_struct_qid(mem.to_qualified_id());
+            let struct_name = struct_env.get_full_name_str();
+            let values = self
+                .initial_memory(model, mem, &domain_info)
+                .ok_or_else(|| {
+                    format!("cannot extract initial global memory of `{}`", struct_name)
+                })?;
+            if values.values.is_empty() {
+                continue;
+            }
+            if mem.module_id != module_id {
+                return Err(format!(
+                    "resource `{}` is not declared in the module of the verified function",
+                    struct_name
+                ));
+            }
+            let ty = mem.to_type();
+            for (addr, value) in values.values {
+                let addr = BigInt::from(addr);
+                let literal = value
+                    .move_literal(self, model, module_id, &ty)
+                    .ok_or_else(|| format!("cannot render resource `{}`", struct_name))?;
+                let signer = match signers.iter().find(|(_, a)| *a == addr) {
+                    Some((name, _)) => name.clone(),
+                    None => {
+                        let name = format!("account_{}", signers.len().saturating_add(1));
+                        signers.push((name.clone(), addr));
+                        name
+                    }
+                };
+                publish_stmts.push(format!(
+                    "move_to(&{}, {});",
+                    signer,
+                    self.test_literal(literal)
+                ));
+            }
+        }
+
+        let fun_name = fun_env.get_name().display(symbol_pool).to_string();
+        let call = format!("{}({})", fun_name, args.join(", "));
+        let abort = error.execution_trace.iter().find_map(|entry| match entry {
+            TraceEntry::Abort(fun, value) => Some((*fun, value)),
+            _ => None,
+        });
+        let mut attrs = vec![];
+        let mut check_stmts = vec![];
+        let mut acquires = vec![];
+        if let Some((abort_fun, value)) = abort {
+            // The call is expected to abort, as it did in the counterexample.
+            check_stmts.push(format!("{};", call));
+            let abort_module = self
+                .env
+                .get_module(abort_fun.module_id)
+                .get_name()
+                .display_full(symbol_pool)
+                .to_string();
+            match value.extract_i128() {
+                Some(code) if code >= 0 => attrs.push(format!(
+                    "#[expected_failure(abort_code = {}, location = {})]",
+                    code, abort_module
+                )),
+                // An execution failure, like an arithmetic error, whose kind is not reported
+                // by the model.
+                _ => attrs.push("#[expected_failure]".to_string()),
+            }
+        } else {
+            // The call is expected to return the values of the counterexample, and to leave
+            // global memory as it was at the point of failure.
+            let n = fun_target.get_return_count();
+            let results = (0..n)
+                .map(|idx| {
+                    if n > 1 {
+                        format!("result_{}", idx.saturating_add(1))
+                    } else {
+                        "result".to_string()
+                    }
+                })
+                .collect::<Vec<_>>();
+            check_stmts.push(match n {
+                0 => format!("{};", call),
+                1 => format!("let {} = {};", results[0], call),
+                _ => format!("let ({}) = {};", results.join(", "), call),
+            });
+            for (idx, result) in results.iter().enumerate() {
+                let ty = fun_target.get_return_type(idx);
+                let value = error
+                    .execution_trace
+                    .iter()
+                    .rev()
+                    .find_map(|entry| match entry {
+                        TraceEntry::Result(fun, i, value) if *fun == fun_id && *i == idx => {
+                            Some(value)
+                        }
+                        _ => None,
+                    });
+                if let Some(literal) = value
+                    .filter(|_| Self::is_comparable(ty))
+                    .and_then(|value| value.move_literal(self, model, module_id, ty))
+                {
+                    check_stmts.push(format!(
+                        "assert!({} == {}, {});",
+                        result,
+                        self.test_literal(literal),
+                        check_stmts.len()
+                    ));
+                }
+            }
+            for (id, domain_value, mem_value) in global_mem_map.values() {
+                let (mid, sid, inst) = match self.env.get_node_type(*id) {
+                    Type::Struct(mid, sid, inst) if mid == module_id => (mid, sid, inst),
+                    _ => continue,
+                };
+                let values = match mem_value.extract_mem_vector(model, domain_value, &domain_info) {
+                    Some(values) => values,
+                    None => continue,
+                };
+                let struct_env = self.env.get_struct_qid(mid.qualified(sid));
+                let struct_name = struct_env.get_name().display(symbol_pool).to_string();
+                for (addr, value) in values.values {
+                    let addr =
+                        self.test_literal(format!("@0x{}", BigInt::from(addr).to_str_radix(16)));
+                    check_stmts.push(format!(
+                        "assert!(exists<{}>({}), {});",
+                        struct_name,
+                        addr,
+                        check_stmts.len()
+                    ));
+                    let boogie_name = &boogie_struct_name(&struct_env, &inst);
+                    let fields = match value
+                        .extract_list(boogie_name)
+                        .or_else(|| value.extract_list(&format!("|{}|", boogie_name)))
+                    {
+                        Some(fields) => fields,
+                        None => continue,
+                    };
+                    for (field, field_value) in struct_env.get_fields().zip(fields) {
+                        let field_ty = field.get_type().instantiate(&inst);
+                        let field_name = field.get_name().display(symbol_pool).to_string();
+                        if field_name == "dummy_field" || !Self::is_comparable(&field_ty) {
+                            continue;
+                        }
+                        if let Some(literal) =
+                            field_value.move_literal(self, model, module_id, &field_ty)
+                        {
+                            check_stmts.push(format!(
+                                "assert!(borrow_global<{}>({}).{} == {}, {});",
+                                struct_name,
+                                addr,
+                                field_name,
+                                self.test_literal(literal),
+                                check_stmts.len()
+                            ));
+                            if !acquires.contains(&struct_name) {
+                                acquires.push(struct_name.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if signers.is_empty() {
+            attrs.insert(0, "#[test]".to_string());
+        } else {
+            attrs.insert(
+                0,
+                format!(
+                    "#[test({})]",
+                    signers
+                        .iter()
+                        .map(|(name, addr)| format!(
+                            "{} = {}",
+                            name,
+                            self.test_literal(format!("@0x{}", addr.to_str_radix(16)))
+                        ))
+                        .join(", ")
+                ),
+            );
+        }
+        let mut lines = attrs;
+        lines.push(format!(
+            "fun {}_counterexample({}){} {{",
+            fun_name,
+            signers
+                .iter()
+                .map(|(name, _)| format!("{}: signer", name))
+                .join(", "),
+            if acquires.is_empty() {
+                "".to_string()
+            } else {
+                format!(" acquires {}", acquires.join(", "))
+            }
+        ));
+        lines.push(format!("    // {}", error.message));
+        lines.extend(
+            publish_stmts
+                .into_iter()
+                .chain(stmts)
+                .chain(check_stmts)
+                .map(|s| "    ".to_string() + &s),
+        );
+        lines.push("}".to_string());
+        let module_name = fun_env
+            .module_env
+            .get_name()
+            .display(symbol_pool)
+            .to_string();
+        Ok((module_name, lines.join("\n")))
+    }
+
+    /// Extracts the given memory at the entry of the verified procedure from the model. This is
+    /// the value of the memory's Boogie variable before its first incarnation.
+    fn initial_memory(
+        &self,
+        model: &Model,
+        mem: &QualifiedInstId<StructId>,
+        domain_info: &Option<(
+            BTreeMap<ModelValue, BTreeMap<usize, bool>>,
+            Option<ModelValue>,
+            bool,
+        )>,
+    ) -> Option<ModelValueVector> {
+        let name = boogie_resource_memory_name(self.env, mem, &None);
+        let value = model
+            .vars
+            .get(&ModelValue::literal(&name))
+            .or_else(|| model.vars.get(&ModelValue::literal(&format!("|{}|", name))));
+        match value {
+            // The memory takes the form `($Memory_<n> <domain> <contents>)`, see the
+            // handling of `TraceEntry::GlobalMem`.
+            Some(ModelValue::List(elems)) if elems.len() == 3 => {
+                elems[2].extract_mem_vector(model, &elems[1], domain_info)
+            }
+            // Memory which is not constrained by the model is empty.
+            None => Some(ModelValueVector {
+                size: 0,
+                values: BTreeMap::new(),
+                default: ModelValue::error(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the given literal of a counterexample test, redacted for stable test output.
+    fn test_literal(&self, literal: String) -> String {
+        if self.options.stable_test_output {
+            "<redacted>".to_string()
+        } else {
+            literal
+        }
+    }
+
+    /// Returns true if values of the given type can be compared with `==` in a unit test.
+    fn is_comparable(ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(prim) => !matches!(prim, PrimitiveType::Signer),
+            Type::Vector(elem_ty) => Self::is_comparable(elem_ty),
+            _ => false,
+        }
+    }
+
     fn get_abbreviated_source(&self, node_id: NodeId) -> String {
         let loc = self.env.get_node_loc(node_id);
         let res = if let Ok(src) = self.env.get_source(&loc) {
@@ -1359,6 +1798,109 @@ impl ModelValue {
         }
     }
 
+    /// Extract the address of a signer.
+    fn extract_signer_address(&self) -> Option<BigInt> {
+        let l = self.extract_list("$signer")?;
+        BigInt::parse_bytes(l.first()?.extract_literal()?.as_bytes(), 10)
+    }
+
+    /// Renders the given model value which has given type as a Move expression which can be
+    /// used in the given module. Returns `None` if the value cannot be expressed in Move, e.g.
+    /// because it is undefined or is a struct of another module.
+    pub fn move_literal(
+        &self,
+        wrapper: &BoogieWrapper,
+        model: &Model,
+        module_id: ModuleId,
+        ty: &Type,
+    ) -> Option<String> {
+        /// Maximal size of a vector rendered as a literal.
+        const MAX_VECTOR_LITERAL_SIZE: usize = 1024;
+
+        if self.extract_list("Error").is_some() {
+            return None;
+        }
+        match ty {
+            Type::Primitive(prim) => {
+                let lit = self.extract_literal()?;
+                match prim {
+                    PrimitiveType::Bool => Some(lit.parse::<bool>().ok()?.to_string()),
+                    PrimitiveType::U8 => Some(format!("{}u8", lit.parse::<u8>().ok()?)),
+                    PrimitiveType::U16 => Some(format!("{}u16", lit.parse::<u16>().ok()?)),
+                    PrimitiveType::U32 => Some(format!("{}u32", lit.parse::<u32>().ok()?)),
+                    PrimitiveType::U64 => Some(format!("{}u64", lit.parse::<u64>().ok()?)),
+                    PrimitiveType::U128 => Some(format!("{}u128", lit.parse::<u128>().ok()?)),
+                    PrimitiveType::U256 => {
+                        let n = BigInt::parse_bytes(lit.as_bytes(), 10)?;
+                        (n.bits() <= 256 && n >= BigInt::from(0)).then(|| format!("{}u256", n))
+                    }
+                    PrimitiveType::Address => {
+                        let addr = BigInt::parse_bytes(lit.as_bytes(), 10)?;
+                        Some(format!("@0x{}", addr.to_str_radix(16)))
+                    }
+                    PrimitiveType::Signer
+                    | PrimitiveType::Num
+                    | PrimitiveType::Range
+                    | PrimitiveType::EventStore => None,
+                }
+            }
+            Type::Vector(elem_ty) => {
+                let values = self.extract_vector(model, elem_ty)?;
+                if values.size > MAX_VECTOR_LITERAL_SIZE {
+                    return None;
+                }
+                let elems = (0..values.size)
+                    .map(|idx| {
+                        values
+                            .values
+                            .get(&idx)
+                            .unwrap_or(&values.default)
+                            .extract_box()
+                            .move_literal(wrapper, model, module_id, elem_ty)
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("vector[{}]", elems.join(", ")))
+            }
+            Type::Struct(mid, sid, inst) if *mid == module_id => {
+                let struct_env = wrapper.env.get_struct_qid(mid.qualified(*sid));
+                if struct_env.is_native_or_intrinsic() {
+                    return None;
+                }
+                let boogie_name = &boogie_struct_name(&struct_env, inst);
+                let values = self
+                    .extract_list(boogie_name)
+                    .or_else(|| self.extract_list(&format!("|{}|", boogie_name)))?;
+                let symbol_pool = struct_env.symbol_pool();
+                let fields = struct_env
+                    .get_fields()
+                    .enumerate()
+                    .map(|(i, f)| {
+                        let name = f.get_name().display(symbol_pool).to_string();
+                        let ty = f.get_type().instantiate(inst);
+                        let value = values
+                            .get(i)?
+                            .move_literal(wrapper, model, module_id, &ty)?;
+                        Some((name, value))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let struct_name = struct_env.get_name().display(symbol_pool).to_string();
+                match fields.as_slice() {
+                    // Structs declared without fields have a compiler generated dummy field.
+                    [(name, _)] if name == "dummy_field" => Some(format!("{} {{}}", struct_name)),
+                    _ => Some(format!(
+                        "{} {{ {} }}",
+                        struct_name,
+                        fields
+                            .iter()
+                            .map(|(name, value)| format!("{}: {}", name, value))
+                            .join(", ")
+                    )),
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Pretty prints the given model value which has given type. If printing fails, falls
     /// back to print the debug value.
     pub fn pretty_or_raw(&self, wrapper: &BoogieWrapper, model: &Model, ty: &Type) -> PrettyDoc {
@@ -55,6 +55,7 @@ mod boogie_helpers;
 pub mod boogie_wrapper;
 pub mod bytecode_translator;
 pub mod options;
+pub mod proof_cache;
 mod prover_task_runner;
 mod spec_translator;
 
@@ -145,6 +145,11 @@ pub struct BoogieOptions {
     pub loop_unroll: Option<u64>,
     /// Optional aggregate function names for native methods implementing mutable borrow semantics
     pub borrow_aggregates: Vec<BorrowAggregate>,
+    /// Whether to render counterexamples of verification errors as Move unit tests replaying them.
+    pub counterexample_tests: bool,
+    /// Directory in which results of verification conditions are cached across runs. If not
+    /// set, every verification condition is sent to the solver.
+    pub proof_cache_dir: Option<String>,
 }
 
 impl Default for BoogieOptions {
@@ -182,6 +187,8 @@ impl Default for BoogieOptions {
             custom_natives: None,
             loop_unroll: None,
             borrow_aggregates: vec![],
+            counterexample_tests: false,
+            proof_cache_dir: None,
         }
     }
 }
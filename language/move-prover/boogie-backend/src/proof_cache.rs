@@ -0,0 +1,455 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Caching of verification results across prover runs.
+//!
+//! Each verification condition is represented by a `$verify` procedure in the generated Boogie
+//! program. Its cache key is a hash over the procedure together with all top-level Boogie
+//! declarations it transitively depends on (including axioms mentioning any of them) and the
+//! Boogie options. A procedure whose key was recorded as verified in a previous run does not
+//! need to be sent to the solver again.
+
+use crate::options::BoogieOptions;
+use move_command_line_common::files::FileHash;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the file in the cache directory which stores the hashes of verified conditions.
+const CACHE_FILE: &str = "verified.json";
+
+/// Name of the file in the cache directory to which Boogie writes its XML log.
+const XML_LOG_FILE: &str = "boogie.xml";
+
+/// Keywords starting a top-level Boogie declaration.
+const DECL_KEYWORDS: &[&str] = &[
+    "axiom",
+    "const",
+    "datatype",
+    "function",
+    "implementation",
+    "procedure",
+    "type",
+    "var",
+];
+
+static IDENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z_$#'.~^?\\][A-Za-z0-9_$#'.~^?\\@!]*").unwrap());
+
+/// Matches source location markers and comments, which do not contribute to the meaning
+/// of a declaration and are erased before hashing.
+static NOISE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\$at\([0-9,]*\)|[ \t]*//.*"#).unwrap());
+
+static XML_METHOD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?s)<method name="([^"]*)".*?</method>"#).unwrap());
+
+static XML_OUTCOME: Lazy<Regex> = Lazy::new(|| Regex::new(r#"outcome="([^"]*)""#).unwrap());
+
+/// A verification condition found in a Boogie program.
+#[derive(Debug, Clone)]
+pub struct VerificationCondition {
+    /// The name of the Boogie procedure representing the condition.
+    pub procedure: String,
+    /// The cache key of the condition.
+    pub hash: String,
+}
+
+impl VerificationCondition {
+    /// Returns true if a successful result for this condition can be cached. This is not the
+    /// case for inconsistency checks, for which successful verification indicates an error.
+    pub fn is_cacheable(&self) -> bool {
+        !self.procedure.contains("$verify_inconsistency")
+    }
+}
+
+/// How many of the verification conditions of a prover run were found in the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofCacheStats {
+    pub cached: usize,
+    pub total: usize,
+}
+
+impl fmt::Display for ProofCacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} verification conditions cached",
+            self.cached, self.total
+        )
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheContent {
+    verified: BTreeSet<String>,
+}
+
+/// A cache of verification conditions which have been successfully verified.
+#[derive(Debug)]
+pub struct ProofCache {
+    dir: PathBuf,
+    content: CacheContent,
+}
+
+impl ProofCache {
+    /// Loads the cache from the given directory. A missing or unreadable cache is treated
+    /// as empty.
+    pub fn load(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref().to_path_buf();
+        let content = fs::read_to_string(dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { dir, content }
+    }
+
+    /// Stores the cache in its directory, creating the directory if needed.
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            self.dir.join(CACHE_FILE),
+            serde_json::to_string_pretty(&self.content)?,
+        )?;
+        Ok(())
+    }
+
+    /// Returns the path of the file to which Boogie should write its XML log.
+    pub fn xml_log_file(&self) -> PathBuf {
+        self.dir.join(XML_LOG_FILE)
+    }
+
+    /// Returns true if the condition is known to be verified.
+    pub fn is_verified(&self, vc: &VerificationCondition) -> bool {
+        vc.is_cacheable() && self.content.verified.contains(&vc.hash)
+    }
+
+    /// Determines which of the conditions are not known to be verified, together with
+    /// statistics about how many were found in the cache.
+    pub fn pending(
+        &self,
+        vcs: Vec<VerificationCondition>,
+    ) -> (ProofCacheStats, Vec<VerificationCondition>) {
+        let total = vcs.len();
+        let pending: Vec<_> = vcs.into_iter().filter(|vc| !self.is_verified(vc)).collect();
+        let stats = ProofCacheStats {
+            cached: total - pending.len(),
+            total,
+        };
+        (stats, pending)
+    }
+
+    /// Records the condition as verified.
+    pub fn record_verified(&mut self, vc: &VerificationCondition) {
+        if vc.is_cacheable() {
+            self.content.verified.insert(vc.hash.clone());
+        }
+    }
+}
+
+/// Determines the verification conditions in the given Boogie program, together with their
+/// cache keys under the given options.
+pub fn verification_conditions(
+    boogie: &str,
+    options: &BoogieOptions,
+) -> Vec<VerificationCondition> {
+    let decls = split_declarations(boogie);
+
+    // Index declarations by the names they define. Axioms are indexed by every declared name
+    // they mention, so they become part of the closure of any declaration mentioning one of
+    // those.
+    let mut index: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, decl) in decls.iter().enumerate() {
+        if let Some(name) = decl.name {
+            index.entry(name).or_default().push(i);
+        }
+    }
+    for (i, decl) in decls.iter().enumerate() {
+        if decl.keyword == "axiom" {
+            for name in &decl.refs {
+                if let Some(entries) = index.get_mut(name) {
+                    entries.push(i)
+                }
+            }
+        }
+    }
+
+    let options_key = {
+        let mut options = options.clone();
+        options.proof_cache_dir = None;
+        FileHash::new(&serde_json::to_string(&options).expect("serializable options")).to_string()
+    };
+
+    decls
+        .iter()
+        .enumerate()
+        .filter_map(|(i, decl)| {
+            let name = decl.name?;
+            if decl.keyword != "procedure" || !name.contains("$verify") {
+                return None;
+            }
+            let mut closure = BTreeSet::new();
+            let mut todo = vec![i];
+            while let Some(j) = todo.pop() {
+                if closure.insert(j) {
+                    for r in &decls[j].refs {
+                        todo.extend(index.get(r).into_iter().flatten())
+                    }
+                }
+            }
+            let mut content = options_key.clone();
+            for j in closure {
+                content.push('\n');
+                content.push_str(&decls[j].hash);
+            }
+            Some(VerificationCondition {
+                procedure: name.to_string(),
+                hash: FileHash::new(&content).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Determines the procedures reported as verified in a Boogie XML log.
+pub fn verified_procedures(xml: &str) -> BTreeSet<String> {
+    XML_METHOD
+        .captures_iter(xml)
+        .filter(|cap| {
+            matches!(
+                XML_OUTCOME.captures_iter(cap.get(0).unwrap().as_str()).last(),
+                Some(outcome) if &outcome[1] == "correct"
+            )
+        })
+        .map(|cap| {
+            cap[1]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .collect()
+}
+
+/// A top-level Boogie declaration.
+struct Declaration<'a> {
+    keyword: &'a str,
+    name: Option<&'a str>,
+    refs: BTreeSet<&'a str>,
+    /// Hash of the declaration text, excluding noise.
+    hash: String,
+}
+
+/// Splits a Boogie program into its top-level declarations. The generated Boogie code starts
+/// every declaration at the beginning of a line, which makes this possible without parsing.
+fn split_declarations(boogie: &str) -> Vec<Declaration<'_>> {
+    let mut starts = vec![];
+    let mut offset = 0;
+    for line in boogie.split_inclusive('\n') {
+        if let Some(keyword) = DECL_KEYWORDS.iter().find(|k| {
+            line.starts_with(**k)
+                && matches!(line[k.len()..].chars().next(), Some(c) if c.is_whitespace() || c == '{')
+        }) {
+            starts.push((offset, *keyword))
+        }
+        offset += line.len();
+    }
+    let mut result = vec![];
+    for (i, (start, keyword)) in starts.iter().enumerate() {
+        let end = starts
+            .get(i + 1)
+            .map(|(end, _)| *end)
+            .unwrap_or(boogie.len());
+        let source = &boogie[*start..end];
+        let hash = FileHash::new(&NOISE.replace_all(source, "")).to_string();
+        let name = declared_name(&source[keyword.len()..]);
+        let refs = IDENT.find_iter(source).map(|m| m.as_str()).collect();
+        result.push(Declaration {
+            keyword,
+            name,
+            refs,
+            hash,
+        })
+    }
+    result
+}
+
+/// Determines the name declared by a declaration, given the text following its keyword.
+fn declared_name(mut rest: &str) -> Option<&str> {
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("{:") {
+            // Skip an attribute. Attributes do not contain nested braces in generated code.
+            rest = &rest[rest.find('}')? + 1..];
+        } else if let Some(r) = rest.strip_prefix("unique ") {
+            rest = r;
+        } else {
+            return IDENT.find(rest).map(|m| m.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = r#"function $f(x: int): int { x + 1 }
+
+function $g(x: int): int { x * 2 }
+
+axiom (forall x: int :: $g(x) > x);
+
+procedure {:timeLimit 40} $1_M_f$verify(x: int) returns (r: int)
+{
+    // a comment, at $at(1,2,3)
+    r := $f(x);
+}
+
+procedure {:timeLimit 40} $1_M_g$verify(x: int) returns (r: int)
+{
+    r := $g(x);
+}
+"#;
+
+    fn condition(boogie: &str, procedure: &str) -> VerificationCondition {
+        verification_conditions(boogie, &BoogieOptions::default())
+            .into_iter()
+            .find(|vc| vc.procedure == procedure)
+            .unwrap()
+    }
+
+    #[test]
+    fn hit_after_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let vc = condition(PROGRAM, "$1_M_f$verify");
+
+        let mut cache = ProofCache::load(dir.path());
+        assert!(!cache.is_verified(&vc));
+        cache.record_verified(&vc);
+        cache.save().unwrap();
+
+        let cache = ProofCache::load(dir.path());
+        assert!(cache.is_verified(&vc));
+        assert!(cache.is_verified(&condition(
+            &PROGRAM.replace("at $at(1,2,3)", "at $at(4,5,6)"),
+            "$1_M_f$verify"
+        )));
+    }
+
+    #[test]
+    fn miss_for_unrecorded_condition() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = ProofCache::load(dir.path());
+        cache.record_verified(&condition(PROGRAM, "$1_M_f$verify"));
+
+        assert!(!cache.is_verified(&condition(PROGRAM, "$1_M_g$verify")));
+    }
+
+    #[test]
+    fn invalidated_by_changed_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = ProofCache::load(dir.path());
+        for vc in verification_conditions(PROGRAM, &BoogieOptions::default()) {
+            cache.record_verified(&vc);
+        }
+
+        // Changing the function `$g` invalidates the condition using it, but not the other.
+        let changed = PROGRAM.replace("x * 2", "x * 3");
+        assert!(cache.is_verified(&condition(&changed, "$1_M_f$verify")));
+        assert!(!cache.is_verified(&condition(&changed, "$1_M_g$verify")));
+
+        // Changing an axiom about `$g` has the same effect.
+        let changed = PROGRAM.replace("$g(x) > x", "$g(x) >= x");
+        assert!(cache.is_verified(&condition(&changed, "$1_M_f$verify")));
+        assert!(!cache.is_verified(&condition(&changed, "$1_M_g$verify")));
+
+        // Changing the options invalidates everything.
+        let options = BoogieOptions {
+            vc_timeout: 1,
+            ..BoogieOptions::default()
+        };
+        assert!(verification_conditions(PROGRAM, &options)
+            .iter()
+            .all(|vc| !cache.is_verified(vc)));
+    }
+
+    #[test]
+    fn stats_count_cached_conditions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = ProofCache::load(dir.path());
+        let vcs = verification_conditions(PROGRAM, &BoogieOptions::default());
+
+        let (stats, pending) = cache.pending(vcs.clone());
+        assert_eq!(
+            stats,
+            ProofCacheStats {
+                cached: 0,
+                total: 2
+            }
+        );
+        assert_eq!(pending.len(), 2);
+
+        cache.record_verified(&condition(PROGRAM, "$1_M_f$verify"));
+        let (stats, pending) = cache.pending(vcs);
+        assert_eq!(
+            stats,
+            ProofCacheStats {
+                cached: 1,
+                total: 2
+            }
+        );
+        assert_eq!(stats.to_string(), "1 of 2 verification conditions cached");
+        assert_eq!(
+            pending
+                .iter()
+                .map(|vc| vc.procedure.as_str())
+                .collect::<Vec<_>>(),
+            vec!["$1_M_g$verify"]
+        );
+    }
+
+    #[test]
+    fn corrupt_cache_file_is_treated_as_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CACHE_FILE), "{ not json").unwrap();
+        let vc = condition(PROGRAM, "$1_M_f$verify");
+
+        let mut cache = ProofCache::load(dir.path());
+        assert!(!cache.is_verified(&vc));
+        cache.record_verified(&vc);
+        cache.save().unwrap();
+
+        assert!(ProofCache::load(dir.path()).is_verified(&vc));
+    }
+
+    #[test]
+    fn inconsistency_checks_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let vc = condition(
+            &PROGRAM.replace("$1_M_g$verify", "$1_M_g$verify_inconsistency"),
+            "$1_M_g$verify_inconsistency",
+        );
+
+        let mut cache = ProofCache::load(dir.path());
+        cache.record_verified(&vc);
+        assert!(!cache.is_verified(&vc));
+    }
+
+    #[test]
+    fn verified_procedures_from_xml_log() {
+        let xml = r#"<method name="$1_M_f$verify" startTime="0">
+  <conclusion endTime="1" duration="1" outcome="correct" />
+</method>
+<method name="$1_M_g$verify" startTime="0">
+  <conclusion endTime="1" duration="1" outcome="errors" />
+</method>"#;
+        assert_eq!(
+            verified_procedures(xml),
+            BTreeSet::from(["$1_M_f$verify".to_string()])
+        );
+    }
+}
@@ -9,9 +9,10 @@
 //! It emits instructions in bytecode format, but with changes made
 //! Note that this mutation does nothing if mutation flags are not enabled
 
+use ethnum::U256;
 use move_model::{
     exp_generator::ExpGenerator,
-    model::{FunctionEnv, GlobalEnv},
+    model::{FunctionEnv, GlobalEnv, Loc},
 };
 
 use crate::{
@@ -19,17 +20,30 @@ use crate::{
     function_target::FunctionData,
     function_target_pipeline::{FunctionTargetProcessor, FunctionTargetsHolder},
     options::ProverOptions,
-    stackless_bytecode::{Bytecode, Operation},
+    stackless_bytecode::{AssignKind, Bytecode, Constant, Operation},
 };
 
 pub struct MutationTester {}
 
+/// Selects the mutation to apply. Each counter gives the index (starting from 1) of the
+/// instruction to mutate among the instructions its mutation applies to, or is 0 if the mutation
+/// is not used. Counters are decremented as instructions are visited, so they carry over from one
+/// function to the next.
+#[derive(Debug, Clone, Default)]
 pub struct MutationManager {
     pub mutated: bool,
     pub add_sub: usize,
     pub sub_add: usize,
     pub mul_div: usize,
     pub div_mul: usize,
+    pub cmp_flip: usize,
+    pub bool_negation: usize,
+    pub off_by_one: usize,
+    pub assert_removal: usize,
+    pub if_swap: usize,
+    pub stmt_deletion: usize,
+    /// The location and description of the applied mutation
+    pub mutation: Option<(Loc, String)>,
 }
 
 impl MutationTester {
@@ -38,50 +52,148 @@ impl MutationTester {
     }
 }
 
-fn mutate_arith(
-    call: Bytecode,
-    mutation_value: usize,
-    global_env: &GlobalEnv,
-    mutation_manager: MutationManager,
-    bc: Bytecode,
-) -> Bytecode {
-    if mutation_value > 1 {
-        global_env.set_extension(MutationManager {
-            mutated: mutation_manager.mutated,
-            add_sub: mutation_manager.add_sub,
-            sub_add: mutation_manager.sub_add,
-            mul_div: mutation_manager.mul_div,
-            div_mul: mutation_manager.div_mul,
-        });
+/// Returns whether the current instruction is the one selected by `counter`.
+fn select(counter: &mut usize) -> bool {
+    match *counter {
+        0 => false,
+        1 => {
+            *counter = 0;
+            true
+        }
+        _ => {
+            *counter -= 1;
+            false
+        }
     }
-    if mutation_value == 1 {
-        global_env.set_extension(MutationManager {
-            mutated: true,
-            add_sub: mutation_manager.add_sub,
-            sub_add: mutation_manager.sub_add,
-            mul_div: mutation_manager.mul_div,
-            div_mul: mutation_manager.div_mul,
-        });
-        call
-    } else {
-        bc
+}
+
+fn op_symbol(op: &Operation) -> &'static str {
+    match op {
+        Operation::Add => "+",
+        Operation::Sub => "-",
+        Operation::Mul => "*",
+        Operation::Div => "/",
+        Operation::Lt => "<",
+        Operation::Gt => ">",
+        Operation::Le => "<=",
+        Operation::Ge => ">=",
+        Operation::Eq => "==",
+        Operation::Neq => "!=",
+        Operation::And => "&&",
+        Operation::Or => "||",
+        _ => unreachable!("operation is not mutated"),
+    }
+}
+
+/// Returns the constant changed by one, along with its description.
+fn off_by_one(constant: &Constant) -> Option<(Constant, String)> {
+    macro_rules! change {
+        ($x:expr, $ctor:path, $one:expr) => {{
+            let y = $x.checked_add($one).unwrap_or_else(|| $x - $one);
+            Some(($ctor(y), format!("replaced `{}` with `{}`", $x, y)))
+        }};
+    }
+    match constant {
+        Constant::U8(x) => change!(*x, Constant::U8, 1),
+        Constant::U16(x) => change!(*x, Constant::U16, 1),
+        Constant::U32(x) => change!(*x, Constant::U32, 1),
+        Constant::U64(x) => change!(*x, Constant::U64, 1),
+        Constant::U128(x) => change!(*x, Constant::U128, 1),
+        Constant::U256(x) => change!(*x, Constant::U256, U256::ONE),
+        _ => None,
+    }
+}
+
+impl MutationManager {
+    /// Returns the mutated instruction if `bc` is the instruction selected by a counter.
+    /// `next` is the instruction following `bc`.
+    fn mutate(&mut self, bc: &Bytecode, next: Option<&Bytecode>) -> Option<(Bytecode, String)> {
+        use Bytecode::*;
+        use Operation::*;
+
+        match bc {
+            Call(attr, dests, op, srcs, aa) => {
+                let (counter, new_op) = match op {
+                    Add => (&mut self.add_sub, Sub),
+                    Sub => (&mut self.sub_add, Add),
+                    Mul => (&mut self.mul_div, Div),
+                    Div => (&mut self.div_mul, Mul),
+                    Lt => (&mut self.cmp_flip, Ge),
+                    Ge => (&mut self.cmp_flip, Lt),
+                    Gt => (&mut self.cmp_flip, Le),
+                    Le => (&mut self.cmp_flip, Gt),
+                    Eq => (&mut self.cmp_flip, Neq),
+                    Neq => (&mut self.cmp_flip, Eq),
+                    And => (&mut self.bool_negation, Or),
+                    Or => (&mut self.bool_negation, And),
+                    Not => {
+                        return select(&mut self.bool_negation).then(|| {
+                            (
+                                Assign(*attr, dests[0], srcs[0], AssignKind::Copy),
+                                "removed `!`".to_string(),
+                            )
+                        })
+                    }
+                    WriteRef | MoveTo(..) | Function(..) if dests.is_empty() => {
+                        return select(&mut self.stmt_deletion)
+                            .then(|| (Nop(*attr), "deleted statement".to_string()))
+                    }
+                    _ => return None,
+                };
+                let description =
+                    format!("replaced `{}` with `{}`", op_symbol(op), op_symbol(&new_op));
+                select(counter).then(|| {
+                    (
+                        Call(*attr, dests.clone(), new_op, srcs.clone(), aa.clone()),
+                        description,
+                    )
+                })
+            }
+            Load(attr, dest, Constant::Bool(b)) => select(&mut self.bool_negation).then(|| {
+                (
+                    Load(*attr, *dest, Constant::Bool(!b)),
+                    format!("replaced `{}` with `{}`", b, !b),
+                )
+            }),
+            Load(attr, dest, constant) => {
+                let (constant, description) = off_by_one(constant)?;
+                select(&mut self.off_by_one).then_some((Load(*attr, *dest, constant), description))
+            }
+            Branch(attr, then_label, else_label, cond) => select(&mut self.if_swap).then(|| {
+                (
+                    Branch(*attr, *else_label, *then_label, *cond),
+                    "swapped the branches".to_string(),
+                )
+            }),
+            // only aborts after which execution can continue at a label, as for `assert!`
+            Abort(attr, _) => match next {
+                Some(Label(_, label)) => select(&mut self.assert_removal)
+                    .then(|| (Jump(*attr, *label), "removed `abort`".to_string())),
+                _ => None,
+            },
+            _ => None,
+        }
     }
 }
 
 impl FunctionTargetProcessor for MutationTester {
     fn initialize(&self, global_env: &GlobalEnv, _targets: &mut FunctionTargetsHolder) {
         let options = ProverOptions::get(global_env);
-        let m = global_env.get_extension::<MutationManager>();
-        match m {
-            Some(x) => global_env.set_extension(MutationManager { ..*x }),
-            None => global_env.set_extension(MutationManager {
-                mutated: false,
+        if global_env.get_extension::<MutationManager>().is_none() {
+            global_env.set_extension(MutationManager {
                 add_sub: options.mutation_add_sub,
                 sub_add: options.mutation_sub_add,
                 mul_div: options.mutation_mul_div,
                 div_mul: options.mutation_div_mul,
-            }),
-        };
+                cmp_flip: options.mutation_cmp_flip,
+                bool_negation: options.mutation_bool_negation,
+                off_by_one: options.mutation_off_by_one,
+                assert_removal: options.mutation_assert_removal,
+                if_swap: options.mutation_if_swap,
+                stmt_deletion: options.mutation_stmt_deletion,
+                ..MutationManager::default()
+            });
+        }
     }
 
     fn process(
@@ -91,8 +203,6 @@ impl FunctionTargetProcessor for MutationTester {
         data: FunctionData,
         _scc_opt: Option<&[FunctionEnv]>,
     ) -> FunctionData {
-        use Bytecode::*;
-
         if fun_env.is_native() {
             // Nothing to do
             return data;
@@ -108,82 +218,29 @@ impl FunctionTargetProcessor for MutationTester {
 
         builder.set_loc(builder.fun_env.get_loc().at_start());
         let global_env = fun_env.module_env.env;
-        let m = global_env.get_extension::<MutationManager>().unwrap();
-
-        for bc in code {
-            match bc {
-                Call(ref attrid, ref indices, Operation::Add, ref srcs, ref dests) => {
-                    let call = Call(
-                        *attrid,
-                        (*indices).clone(),
-                        Operation::Sub,
-                        (*srcs).clone(),
-                        (*dests).clone(),
-                    );
-                    let mv = m.add_sub;
-
-                    let result = if mv > 0 { mv - 1 } else { mv };
-                    let mm = MutationManager {
-                        add_sub: result,
-                        ..*m
-                    };
-                    builder.emit(mutate_arith(call, mv, global_env, mm, bc));
-                }
-                Call(ref attrid, ref indices, Operation::Sub, ref srcs, ref dests) => {
-                    let call = Call(
-                        *attrid,
-                        (*indices).clone(),
-                        Operation::Add,
-                        (*srcs).clone(),
-                        (*dests).clone(),
-                    );
-                    let mv = m.sub_add;
-
-                    let result = if mv > 0 { mv - 1 } else { mv };
-                    let mm = MutationManager {
-                        sub_add: result,
-                        ..*m
-                    };
-                    builder.emit(mutate_arith(call, mv, global_env, mm, bc));
-                }
-                Call(ref attrid, ref indices, Operation::Mul, ref srcs, ref dests) => {
-                    let call = Call(
-                        *attrid,
-                        (*indices).clone(),
-                        Operation::Div,
-                        (*srcs).clone(),
-                        (*dests).clone(),
-                    );
-                    let mv = m.mul_div;
-
-                    let result = if mv > 0 { mv - 1 } else { mv };
-                    let mm = MutationManager {
-                        mul_div: result,
-                        ..*m
-                    };
-                    builder.emit(mutate_arith(call, mv, global_env, mm, bc));
-                }
-                Call(ref attrid, ref indices, Operation::Div, ref srcs, ref dests) => {
-                    let call = Call(
-                        *attrid,
-                        (*indices).clone(),
-                        Operation::Mul,
-                        (*srcs).clone(),
-                        (*dests).clone(),
-                    );
-                    let mv = m.mul_div;
-                    let result = if mv > 0 { mv - 1 } else { mv };
-                    let mm = MutationManager {
-                        div_mul: result,
-                        ..*m
-                    };
-                    builder.emit(mutate_arith(call, mv, global_env, mm, bc));
-                }
-                _ => {
-                    builder.emit(bc);
+        let mut m = global_env
+            .get_extension::<MutationManager>()
+            .unwrap()
+            .as_ref()
+            .clone();
+
+        for (i, bc) in code.iter().enumerate() {
+            match m.mutate(bc, code.get(i + 1)) {
+                Some((mutated, description)) => {
+                    let loc = builder
+                        .data
+                        .locations
+                        .get(&bc.get_attr_id())
+                        .cloned()
+                        .unwrap_or_else(|| fun_env.get_loc());
+                    m.mutated = true;
+                    m.mutation = Some((loc, description));
+                    builder.emit(mutated);
                 }
+                None => builder.emit(bc.clone()),
             }
         }
+        global_env.set_extension(m);
 
         builder.data
     }
@@ -60,6 +60,20 @@ pub struct ProverOptions {
     pub mutation_mul_div: usize,
     /// Indicates that we should use the divide-multiply mutation on the given block
     pub mutation_div_mul: usize,
+    /// Indicates that we should negate the comparison (e.g. `<` to `>=`) on the given block
+    pub mutation_cmp_flip: usize,
+    /// Indicates that we should use the boolean negation mutation (removing `!`, flipping a
+    /// boolean constant, or swapping `&&` and `||`) on the given block
+    pub mutation_bool_negation: usize,
+    /// Indicates that we should change the integer constant by one on the given block
+    pub mutation_off_by_one: usize,
+    /// Indicates that we should remove the abort (e.g. of an `assert!`) on the given block
+    pub mutation_assert_removal: usize,
+    /// Indicates that we should swap the branches of the conditional on the given block
+    pub mutation_if_swap: usize,
+    /// Indicates that we should delete the statement (a call without results, `move_to` or
+    /// write through a reference) on the given block
+    pub mutation_stmt_deletion: usize,
     /// Whether to use the polymorphic boogie backend.
     pub boogie_poly: bool,
     /// Whether pack/unpack should recurse over the structure.
@@ -109,6 +123,12 @@ impl Default for ProverOptions {
             mutation_sub_add: 0,
             mutation_mul_div: 0,
             mutation_div_mul: 0,
+            mutation_cmp_flip: 0,
+            mutation_bool_negation: 0,
+            mutation_off_by_one: 0,
+            mutation_assert_removal: 0,
+            mutation_if_swap: 0,
+            mutation_stmt_deletion: 0,
             boogie_poly: false,
             deep_pack_unpack: false,
             auto_trace_level: AutoTraceLevel::Off,
@@ -15,6 +15,7 @@ use move_core_types::{
     identifier::IdentStr,
     language_storage::{StructTag, TypeTag},
 };
+use move_errmapgen::{ErrmapGen, ErrmapOptions};
 use move_model::{
     model::{FunctionEnv, GlobalEnv, ModuleEnv},
     ty,
@@ -22,6 +23,8 @@ use move_model::{
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, io::Read, path::PathBuf};
 
+use crate::module_abi::{module_abi, ModuleABI};
+
 /// Options passed into the ABI generator.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -32,6 +35,10 @@ pub struct AbigenOptions {
     pub in_memory_bytes: Option<BTreeMap<String, Vec<u8>>>,
     /// In which directory to store output.
     pub output_directory: String,
+    /// Whether to also generate a JSON ABI for each module, describing all its public and entry
+    /// functions, structs, constants, friends and error codes. Module ABIs are written to
+    /// `<address>_<module>.json`, so that same-named modules at different addresses don't collide.
+    pub module_abis: bool,
 }
 
 impl Default for AbigenOptions {
@@ -40,6 +47,7 @@ impl Default for AbigenOptions {
             compiled_script_directory: ".".to_string(),
             in_memory_bytes: None,
             output_directory: "abi".to_string(),
+            module_abis: false,
         }
     }
 }
@@ -52,6 +60,8 @@ pub struct Abigen<'env> {
     env: &'env GlobalEnv,
     /// Map from file name to generated script ABI (if any).
     output: BTreeMap<String, ScriptABI>,
+    /// Map from file name to generated module ABI.
+    module_output: BTreeMap<String, ModuleABI>,
 }
 
 impl<'env> Abigen<'env> {
@@ -61,25 +71,54 @@ impl<'env> Abigen<'env> {
             options,
             env,
             output: Default::default(),
+            module_output: Default::default(),
         }
     }
 
     /// Returns the result of ABI generation, a vector of pairs of filenames
-    /// and JSON content.
+    /// and content: BCS for script ABIs and JSON for module ABIs.
     pub fn into_result(mut self) -> Vec<(String, Vec<u8>)> {
+        let module_abis = std::mem::take(&mut self.module_output)
+            .into_iter()
+            .map(|(path, abi)| {
+                let mut content =
+                    serde_json::to_vec_pretty(&abi).expect("ABI serialization should not fail");
+                content.push(b'\n');
+                (path, content)
+            });
         std::mem::take(&mut self.output)
             .into_iter()
             .map(|(path, abi)| {
                 let content = bcs::to_bytes(&abi).expect("ABI serialization should not fail");
                 (path, content)
             })
+            .chain(module_abis)
             .collect()
     }
 
     /// Generates ABIs for all script modules in the environment (excluding the dependency set).
     pub fn gen(&mut self) {
+        let errmap_options = ErrmapOptions::default();
+        let mut errmap = ErrmapGen::new(self.env, &errmap_options);
         for module in self.env.get_modules() {
             if module.is_target() {
+                if self.options.module_abis && !module.is_script_module() {
+                    if let Err(err) = errmap.build_error_map(&module) {
+                        warn!(
+                            "cannot compute error codes of module `{}`: {}",
+                            module.get_full_name_str(),
+                            err
+                        );
+                    }
+                    let module_id = module.get_verified_module().self_id();
+                    let abi =
+                        module_abi(&module, errmap.result().module_error_maps.get(&module_id));
+                    let mut path = PathBuf::from(&self.options.output_directory);
+                    path.push(format!("{}_{}.json", abi.address, abi.name));
+                    self.module_output
+                        .insert(path.to_str().unwrap().to_string(), abi);
+                }
+
                 let mut path = PathBuf::from(&self.options.output_directory);
                 // We make a directory for all of the script function ABIs in a module. But, if
                 // it's a script, we don't create a directory.
@@ -0,0 +1,317 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of typed client bindings for the modules of a package.
+//!
+//! For each module, the bindings contain types mirroring the layouts of its structs together
+//! with their BCS encoding, builders for the payloads of its entry functions, and decoders for
+//! its resources. Bindings are generated for the target modules, and for the modules of
+//! dependencies which define types used by them. `0x1::string::String`, `0x1::ascii::String`
+//! and `0x1::option::Option` are mapped to the native string and option types of the language,
+//! which have the same BCS encoding.
+//!
+//! Bindings are generated from the move model rather than from the layouts of
+//! `move-bytecode-utils::layout`: layouts only exist for fully instantiated types, while the
+//! bindings are generic over the type parameters of structs, and need the doc comments, phantom
+//! parameters and entry functions which only the model carries. The `bindings_compile` test
+//! checks that the encoding of the generated types agrees with those layouts.
+
+mod rust;
+mod typescript;
+
+use move_model::{
+    model::{FunctionEnv, GlobalEnv, ModuleEnv, ModuleId, StructEnv, StructId},
+    ty::{PrimitiveType, Type},
+};
+use num::BigUint;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+const MAX_LINE_LENGTH: usize = 100;
+
+/// The language to generate bindings in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingsLanguage {
+    Rust,
+    TypeScript,
+}
+
+impl FromStr for BindingsLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rust" => Ok(BindingsLanguage::Rust),
+            "typescript" | "ts" => Ok(BindingsLanguage::TypeScript),
+            _ => Err(format!(
+                "Unknown bindings language `{}`, expected `rust` or `typescript`",
+                s
+            )),
+        }
+    }
+}
+
+/// Generates bindings for the target modules of `env`, returning pairs of file names, relative
+/// to the output directory, and generated contents.
+pub fn generate_bindings(env: &GlobalEnv, language: BindingsLanguage) -> Vec<(String, String)> {
+    let ctx = BindingsContext::new(env);
+    match language {
+        BindingsLanguage::Rust => rust::generate(&ctx),
+        BindingsLanguage::TypeScript => typescript::generate(&ctx),
+    }
+}
+
+/// Structs which are mapped to native types of the target language.
+enum NativeStruct {
+    String,
+    Option,
+}
+
+struct BindingsContext<'env> {
+    env: &'env GlobalEnv,
+    /// The modules to generate bindings for, with the names of their bindings.
+    modules: BTreeMap<ModuleId, String>,
+}
+
+impl<'env> BindingsContext<'env> {
+    fn new(env: &'env GlobalEnv) -> Self {
+        let mut ctx = Self {
+            env,
+            modules: BTreeMap::new(),
+        };
+        let mut todo = env
+            .get_modules()
+            .filter(|m| m.is_target() && !m.is_script_module())
+            .map(|m| m.get_id())
+            .collect::<Vec<_>>();
+        let mut included = BTreeSet::new();
+        while let Some(id) = todo.pop() {
+            if !included.insert(id) {
+                continue;
+            }
+            let module_env = env.get_module(id);
+            let mut used = BTreeSet::new();
+            for struct_env in module_env.get_structs() {
+                for field_env in struct_env.get_fields() {
+                    ctx.collect_modules(&field_env.get_type(), &mut used);
+                }
+            }
+            for fun_env in ctx.entry_functions(&module_env) {
+                for ty in ctx.arguments(&fun_env).iter().map(|(_, ty)| ty) {
+                    ctx.collect_modules(ty, &mut used);
+                }
+            }
+            todo.extend(used);
+        }
+        // Name the bindings of modules after the modules, qualified by the address if the
+        // name is ambiguous.
+        let name_of = |id: &ModuleId| {
+            let module_env = env.get_module(*id);
+            env.symbol_pool()
+                .string(module_env.get_name().name())
+                .to_string()
+        };
+        let mut counts = BTreeMap::new();
+        for id in &included {
+            *counts.entry(name_of(id)).or_insert(0) += 1;
+        }
+        for id in included {
+            let name = name_of(&id);
+            let binding_name = if counts[&name] > 1 {
+                format!("{}_{:x}", name, env.get_module(id).get_name().addr())
+            } else {
+                name
+            };
+            ctx.modules.insert(id, binding_name);
+        }
+        ctx
+    }
+
+    /// Collects the modules defining the structs used in `ty`.
+    fn collect_modules(&self, ty: &Type, modules: &mut BTreeSet<ModuleId>) {
+        match ty {
+            Type::Vector(elem) | Type::Reference(_, elem) => self.collect_modules(elem, modules),
+            Type::Struct(mid, sid, type_args) => {
+                if self.native_struct(*mid, *sid).is_none() {
+                    modules.insert(*mid);
+                }
+                for ty in type_args {
+                    self.collect_modules(ty, modules);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the modules to generate bindings for.
+    fn modules(&self) -> impl Iterator<Item = (ModuleEnv<'env>, &str)> {
+        let mut modules = self.modules.iter().collect::<Vec<_>>();
+        modules.sort_by_key(|(_, name)| name.as_str());
+        modules
+            .into_iter()
+            .map(|(id, name)| (self.env.get_module(*id), name.as_str()))
+    }
+
+    fn binding_name(&self, id: ModuleId) -> &str {
+        self.modules
+            .get(&id)
+            .map(String::as_str)
+            .expect("bindings for module")
+    }
+
+    fn native_struct(&self, mid: ModuleId, sid: StructId) -> Option<NativeStruct> {
+        let module_env = self.env.get_module(mid);
+        let struct_env = module_env.get_struct(sid);
+        let pool = self.env.symbol_pool();
+        let name = module_env.get_name();
+        if name.addr() != &BigUint::from(1u8) {
+            return None;
+        }
+        match (
+            pool.string(name.name()).as_str(),
+            pool.string(struct_env.get_name()).as_str(),
+        ) {
+            ("string", "String") | ("ascii", "String") => Some(NativeStruct::String),
+            ("option", "Option") => Some(NativeStruct::Option),
+            _ => None,
+        }
+    }
+
+    /// Returns the entry functions of a module for which payloads can be built: those whose
+    /// arguments, after the signers, have types with a binding.
+    fn entry_functions<'a>(&self, module_env: &'a ModuleEnv<'env>) -> Vec<FunctionEnv<'a>> {
+        module_env
+            .get_functions()
+            .filter(|fun_env| {
+                fun_env.is_entry()
+                    && self
+                        .arguments(fun_env)
+                        .iter()
+                        .all(|(_, ty)| is_encodable(ty))
+            })
+            .collect()
+    }
+
+    /// Returns the parameters of an entry function which are passed as arguments, i.e. all
+    /// but the leading signers.
+    fn arguments(&self, fun_env: &FunctionEnv<'_>) -> Vec<(String, Type)> {
+        let pool = self.env.symbol_pool();
+        fun_env
+            .get_parameters()
+            .into_iter()
+            .skip_while(|param| is_signer(&param.1))
+            .map(|param| {
+                // Leading underscores only mark parameters unused in the Move code.
+                let name = pool.string(param.0);
+                let name = match name.trim_start_matches('_') {
+                    "" => name.to_string(),
+                    trimmed => trimmed.to_string(),
+                };
+                (name, param.1)
+            })
+            .collect()
+    }
+
+    /// Returns the names of the type parameters of a struct, and whether they are phantom.
+    /// Phantom type parameters do not affect the layout, and are omitted from the types of
+    /// the bindings.
+    fn struct_type_params(&self, struct_env: &StructEnv<'_>) -> Vec<(String, bool)> {
+        let pool = self.env.symbol_pool();
+        struct_env
+            .get_named_type_parameters()
+            .iter()
+            .enumerate()
+            .map(|(i, tp)| {
+                (
+                    pool.string(tp.0).to_string(),
+                    struct_env.is_phantom_parameter(i),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the type arguments of a struct type which are not for phantom parameters.
+    fn layout_type_args<'a>(
+        &self,
+        mid: ModuleId,
+        sid: StructId,
+        type_args: &'a [Type],
+    ) -> Vec<&'a Type> {
+        let struct_env = self.env.get_module(mid).into_struct(sid);
+        type_args
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !struct_env.is_phantom_parameter(*i))
+            .map(|(_, ty)| ty)
+            .collect()
+    }
+
+    /// Returns the fully qualified Move name of a struct.
+    fn struct_tag_name(&self, struct_env: &StructEnv<'_>) -> String {
+        format!(
+            "{}::{}",
+            struct_env.module_env.get_full_name_str(),
+            self.env.symbol_pool().string(struct_env.get_name())
+        )
+    }
+}
+
+fn is_signer(ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(PrimitiveType::Signer) => true,
+        Type::Reference(_, inner) => matches!(**inner, Type::Primitive(PrimitiveType::Signer)),
+        _ => false,
+    }
+}
+
+/// Returns whether a value of the type can be encoded by the bindings, without knowing the
+/// instantiation of type parameters.
+fn is_encodable(ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(prim) => !matches!(
+            prim,
+            PrimitiveType::Signer
+                | PrimitiveType::Num
+                | PrimitiveType::Range
+                | PrimitiveType::EventStore
+        ),
+        Type::Vector(elem) => is_encodable(elem),
+        Type::Struct(_, _, type_args) => type_args.iter().all(is_encodable),
+        _ => false,
+    }
+}
+
+/// Formats documentation as comment lines with the given prefix.
+fn doc_comment(doc: &str, prefix: &str) -> String {
+    doc.lines()
+        .map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with(' ') {
+                format!("{}{}\n", prefix, line)
+            } else {
+                format!("{} {}\n", prefix, line)
+            }
+        })
+        .collect()
+}
+
+/// Formats a call or declaration with its parameters, breaking the parameters into one per
+/// line if the declaration does not fit on a line.
+fn signature(head: &str, params: &[String], tail: &str) -> String {
+    let line = format!("{}({}){}", head, params.join(", "), tail);
+    if line.len() <= MAX_LINE_LENGTH {
+        line
+    } else {
+        format!(
+            "{}(\n{}){}",
+            head,
+            params
+                .iter()
+                .map(|param| format!("    {},\n", param))
+                .collect::<String>(),
+            tail
+        )
+    }
+}
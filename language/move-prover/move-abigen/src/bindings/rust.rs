@@ -0,0 +1,349 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rust bindings. Every module is bound in a Rust module of its own, named in snake case, and
+//! the generated files form a module tree rooted in `mod.rs`. The bindings depend on the
+//! `move-core-types`, `serde` and `bcs` crates. Generated files start with the license header of
+//! the code they are derived from.
+
+use super::{doc_comment, signature, BindingsContext, NativeStruct};
+use heck::SnakeCase;
+use move_model::{
+    code_writer::CodeWriter,
+    emit, emitln,
+    model::{ModuleEnv, ModuleId, StructEnv, StructId},
+    ty::{PrimitiveType, Type},
+};
+
+const HEADER: &str = "// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `move generate bindings`. Do not edit.";
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+pub(crate) fn generate(ctx: &BindingsContext<'_>) -> Vec<(String, String)> {
+    let mut result = vec![("mod.rs".to_string(), generate_root(ctx))];
+    for (module_env, name) in ctx.modules() {
+        result.push((
+            format!("{}.rs", name.to_snake_case()),
+            generate_module(ctx, &module_env),
+        ));
+    }
+    result
+}
+
+fn generate_root(ctx: &BindingsContext<'_>) -> String {
+    let writer = CodeWriter::new(ctx.env.unknown_loc());
+    emitln!(writer, HEADER);
+    emitln!(writer);
+    emitln!(writer, "use move_core_types::{");
+    emitln!(writer, "    identifier::Identifier,");
+    emitln!(writer, "    language_storage::{ModuleId, TypeTag},");
+    emitln!(writer, "};");
+    emitln!(writer);
+    for (_, name) in ctx.modules() {
+        emitln!(writer, "pub mod {};", ident(&name.to_snake_case()));
+    }
+    emitln!(writer);
+    emitln!(
+        writer,
+        "/// A call to an entry function, with BCS encoded arguments."
+    );
+    emitln!(writer, "#[derive(Clone, Debug, PartialEq, Eq)]");
+    emitln!(writer, "pub struct EntryFunctionCall {");
+    writer.with_indent(|| {
+        emitln!(writer, "pub module: ModuleId,");
+        emitln!(writer, "pub function: Identifier,");
+        emitln!(writer, "pub ty_args: Vec<TypeTag>,");
+        emitln!(writer, "pub args: Vec<Vec<u8>>,");
+    });
+    emitln!(writer, "}");
+    writer.extract_result()
+}
+
+fn generate_module(ctx: &BindingsContext<'_>, module_env: &ModuleEnv<'_>) -> String {
+    let pool = ctx.env.symbol_pool();
+    let writer = CodeWriter::new(module_env.get_loc());
+    emitln!(writer, HEADER);
+    emitln!(writer);
+    let doc = module_env.get_doc();
+    if !doc.is_empty() {
+        emit!(writer, &doc_comment(doc, "//!"));
+        emitln!(writer);
+    }
+    emitln!(writer, "#![allow(dead_code, unused_imports)]");
+    emitln!(writer);
+    emitln!(writer, "use move_core_types::{");
+    writer.with_indent(|| {
+        emitln!(writer, "account_address::AccountAddress,");
+        emitln!(writer, "identifier::Identifier,");
+        emitln!(writer, "language_storage::{ModuleId, StructTag, TypeTag},");
+        emitln!(writer, "u256::U256,");
+    });
+    emitln!(writer, "};");
+    emitln!(
+        writer,
+        "use serde::{de::DeserializeOwned, Deserialize, Serialize};"
+    );
+    emitln!(writer);
+    emitln!(
+        writer,
+        "pub const ADDRESS: &str = \"0x{}\";",
+        module_env.get_name().addr().to_str_radix(16)
+    );
+    emitln!(
+        writer,
+        "pub const MODULE_NAME: &str = \"{}\";",
+        pool.string(module_env.get_name().name())
+    );
+    emitln!(writer);
+    emitln!(writer, "pub fn module_id() -> ModuleId {");
+    writer.with_indent(|| {
+        emitln!(writer, "ModuleId::new(");
+        writer.with_indent(|| {
+            emitln!(
+                writer,
+                "AccountAddress::from_hex_literal(ADDRESS).unwrap(),"
+            );
+            emitln!(writer, "Identifier::new(MODULE_NAME).unwrap(),");
+        });
+        emitln!(writer, ")");
+    });
+    emitln!(writer, "}");
+
+    for struct_env in module_env.get_structs().filter(|s| !s.is_native()) {
+        emitln!(writer);
+        generate_struct(ctx, &writer, &struct_env);
+    }
+    for fun_env in ctx.entry_functions(module_env) {
+        let name = pool.string(fun_env.get_name()).to_string();
+        let type_params = fun_env
+            .get_named_type_parameters()
+            .iter()
+            .map(|tp| format!("{}_type", pool.string(tp.0).to_snake_case()))
+            .collect::<Vec<_>>();
+        let args = ctx.arguments(&fun_env);
+        let params = type_params
+            .iter()
+            .map(|tp| format!("{}: TypeTag", tp))
+            .chain(args.iter().map(|(arg, ty)| {
+                format!("{}: {}", ident(arg), type_name(ctx, module_env, &[], ty))
+            }))
+            .collect::<Vec<_>>();
+        emitln!(writer);
+        emit!(writer, &doc_comment(fun_env.get_doc(), "///"));
+        emitln!(
+            writer,
+            &signature(
+                &format!("pub fn {}", ident(&name)),
+                &params,
+                " -> super::EntryFunctionCall {"
+            )
+        );
+        writer.with_indent(|| {
+            emitln!(writer, "super::EntryFunctionCall {");
+            writer.with_indent(|| {
+                emitln!(writer, "module: module_id(),");
+                emitln!(writer, "function: Identifier::new(\"{}\").unwrap(),", name);
+                emitln!(writer, "ty_args: vec![{}],", type_params.join(", "));
+                emitln!(writer, "args: vec![");
+                writer.with_indent(|| {
+                    for (arg, _) in &args {
+                        emitln!(writer, "bcs::to_bytes(&{}).unwrap(),", ident(arg));
+                    }
+                });
+                emitln!(writer, "],");
+            });
+            emitln!(writer, "}");
+        });
+        emitln!(writer, "}");
+    }
+    writer.extract_result()
+}
+
+fn generate_struct(ctx: &BindingsContext<'_>, writer: &CodeWriter, struct_env: &StructEnv<'_>) {
+    let pool = ctx.env.symbol_pool();
+    let module_env = &struct_env.module_env;
+    let name = pool.string(struct_env.get_name()).to_string();
+    let type_params = ctx.struct_type_params(struct_env);
+    let type_param_names = type_params
+        .iter()
+        .map(|(tp, _)| tp.clone())
+        .collect::<Vec<_>>();
+    let generics = generics(
+        type_params
+            .iter()
+            .filter(|(_, is_phantom)| !is_phantom)
+            .map(|(tp, _)| tp.clone())
+            .collect(),
+    );
+    emit!(writer, &doc_comment(struct_env.get_doc(), "///"));
+    emitln!(
+        writer,
+        "#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]"
+    );
+    emitln!(writer, "pub struct {}{} {{", name, generics);
+    writer.with_indent(|| {
+        for field_env in struct_env.get_fields() {
+            emit!(writer, &doc_comment(field_env.get_doc(), "///"));
+            emitln!(
+                writer,
+                "pub {}: {},",
+                ident(&pool.string(field_env.get_name())),
+                type_name(ctx, module_env, &type_param_names, &field_env.get_type())
+            );
+        }
+    });
+    emitln!(writer, "}");
+    emitln!(writer);
+    emitln!(writer, "impl{} {}{} {{", generics, name, generics);
+    writer.with_indent(|| {
+        emitln!(writer, "pub fn from_bcs(bytes: &[u8]) -> bcs::Result<Self>");
+        emitln!(writer, "where");
+        writer.with_indent(|| emitln!(writer, "Self: DeserializeOwned,"));
+        emitln!(writer, "{");
+        writer.with_indent(|| emitln!(writer, "bcs::from_bytes(bytes)"));
+        emitln!(writer, "}");
+        emitln!(writer);
+        emitln!(writer, "pub fn to_bcs(&self) -> Vec<u8>");
+        emitln!(writer, "where");
+        writer.with_indent(|| emitln!(writer, "Self: Serialize,"));
+        emitln!(writer, "{");
+        writer.with_indent(|| emitln!(writer, "bcs::to_bytes(self).unwrap()"));
+        emitln!(writer, "}");
+    });
+    emitln!(writer, "}");
+    emitln!(writer);
+
+    // The struct tag includes all type arguments, phantom or not.
+    let tag_params = type_params
+        .iter()
+        .map(|(tp, _)| tp.to_snake_case())
+        .collect::<Vec<_>>();
+    emitln!(
+        writer,
+        "/// Returns the tag of `{}`{}.",
+        ctx.struct_tag_name(struct_env),
+        if tag_params.is_empty() {
+            ""
+        } else {
+            ", instantiated with the given type arguments"
+        }
+    );
+    emitln!(
+        writer,
+        "pub fn {}_struct_tag({}) -> StructTag {{",
+        name.to_snake_case(),
+        tag_params
+            .iter()
+            .map(|tp| format!("{}: TypeTag", ident(tp)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    writer.with_indent(|| {
+        emitln!(writer, "StructTag {");
+        writer.with_indent(|| {
+            emitln!(
+                writer,
+                "address: AccountAddress::from_hex_literal(ADDRESS).unwrap(),"
+            );
+            emitln!(writer, "module: Identifier::new(MODULE_NAME).unwrap(),");
+            emitln!(writer, "name: Identifier::new(\"{}\").unwrap(),", name);
+            emitln!(
+                writer,
+                "type_params: vec![{}],",
+                tag_params
+                    .iter()
+                    .map(|tp| ident(tp))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        });
+        emitln!(writer, "}");
+    });
+    emitln!(writer, "}");
+}
+
+/// Returns the Rust type bound to a Move type, in the context of `module_env` and the given
+/// type parameters.
+fn type_name(
+    ctx: &BindingsContext<'_>,
+    module_env: &ModuleEnv<'_>,
+    type_params: &[String],
+    ty: &Type,
+) -> String {
+    match ty {
+        Type::Primitive(prim) => match prim {
+            PrimitiveType::Bool => "bool",
+            PrimitiveType::U8 => "u8",
+            PrimitiveType::U16 => "u16",
+            PrimitiveType::U32 => "u32",
+            PrimitiveType::U64 => "u64",
+            PrimitiveType::U128 => "u128",
+            PrimitiveType::U256 => "U256",
+            PrimitiveType::Address => "AccountAddress",
+            _ => unreachable!("type cannot be bound: {:?}", prim),
+        }
+        .to_string(),
+        Type::Vector(elem) => format!("Vec<{}>", type_name(ctx, module_env, type_params, elem)),
+        Type::Struct(mid, sid, type_args) => {
+            let type_args = ctx
+                .layout_type_args(*mid, *sid, type_args)
+                .into_iter()
+                .map(|ty| type_name(ctx, module_env, type_params, ty))
+                .collect::<Vec<_>>();
+            match ctx.native_struct(*mid, *sid) {
+                Some(NativeStruct::String) => "String".to_string(),
+                Some(NativeStruct::Option) => format!("Option<{}>", type_args[0]),
+                None => format!(
+                    "{}{}{}",
+                    module_path(ctx, module_env, *mid),
+                    struct_name(ctx, *mid, *sid),
+                    generics(type_args)
+                ),
+            }
+        }
+        Type::TypeParameter(idx) => type_params[*idx as usize].clone(),
+        _ => unreachable!("type cannot be bound: {:?}", ty),
+    }
+}
+
+fn module_path(ctx: &BindingsContext<'_>, module_env: &ModuleEnv<'_>, mid: ModuleId) -> String {
+    if mid == module_env.get_id() {
+        String::new()
+    } else {
+        format!("super::{}::", ident(&ctx.binding_name(mid).to_snake_case()))
+    }
+}
+
+fn struct_name(ctx: &BindingsContext<'_>, mid: ModuleId, sid: StructId) -> String {
+    let struct_env = ctx.env.get_module(mid).into_struct(sid);
+    ctx.env
+        .symbol_pool()
+        .string(struct_env.get_name())
+        .to_string()
+}
+
+fn generics(type_args: Vec<String>) -> String {
+    if type_args.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", type_args.join(", "))
+    }
+}
+
+/// Escapes identifiers which are Rust keywords.
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,712 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! TypeScript bindings. Every module is bound in a TypeScript module of its own, which
+//! exports interfaces for its structs together with codecs of the same name. The codecs are
+//! built on a small BCS runtime in `bcs.ts`, and the modules are re-exported from `index.ts`.
+
+use super::{doc_comment, signature, BindingsContext, NativeStruct};
+use heck::MixedCase;
+use move_core_types::account_address::AccountAddress;
+use move_model::{
+    code_writer::CodeWriter,
+    emit, emitln,
+    model::{ModuleEnv, ModuleId, StructEnv, StructId},
+    ty::{PrimitiveType, Type},
+};
+use std::collections::BTreeSet;
+
+const HEADER: &str = "// Generated by `move generate bindings`. Do not edit.";
+
+const RUNTIME: &str = r#"
+export interface Codec<T> {
+    serialize(s: Serializer, value: T): void;
+    deserialize(d: Deserializer): T;
+}
+
+/** The payload of a transaction calling an entry function, with BCS encoded arguments. */
+export interface EntryFunctionPayload {
+    function: string;
+    typeArguments: string[];
+    arguments: Uint8Array[];
+}
+
+export class Serializer {
+    private bytes: number[] = [];
+
+    uleb128(value: number): void {
+        do {
+            let byte = value & 0x7f;
+            value = Math.floor(value / 128);
+            if (value > 0) {
+                byte |= 0x80;
+            }
+            this.bytes.push(byte);
+        } while (value > 0);
+    }
+
+    fixed(value: bigint, size: number): void {
+        if (value < 0n || value >= 1n << BigInt(8 * size)) {
+            throw new RangeError(`${value} does not fit in ${size} bytes`);
+        }
+        for (let i = 0; i < size; i++) {
+            this.bytes.push(Number(value & 0xffn));
+            value >>= 8n;
+        }
+    }
+
+    append(bytes: Uint8Array): void {
+        bytes.forEach((byte) => this.bytes.push(byte));
+    }
+
+    toBytes(): Uint8Array {
+        return Uint8Array.from(this.bytes);
+    }
+}
+
+export class Deserializer {
+    private offset = 0;
+
+    constructor(private readonly bytes: Uint8Array) {}
+
+    uleb128(): number {
+        let value = 0;
+        let shift = 1;
+        for (;;) {
+            const byte = this.take(1)[0];
+            value += (byte & 0x7f) * shift;
+            if ((byte & 0x80) === 0) {
+                return value;
+            }
+            shift *= 128;
+        }
+    }
+
+    fixed(size: number): bigint {
+        const bytes = this.take(size);
+        let value = 0n;
+        for (let i = size - 1; i >= 0; i--) {
+            value = (value << 8n) | BigInt(bytes[i]);
+        }
+        return value;
+    }
+
+    take(length: number): Uint8Array {
+        if (this.offset + length > this.bytes.length) {
+            throw new RangeError("unexpected end of input");
+        }
+        const bytes = this.bytes.subarray(this.offset, this.offset + length);
+        this.offset += length;
+        return bytes;
+    }
+
+    remaining(): number {
+        return this.bytes.length - this.offset;
+    }
+}
+
+function fixedNumber(size: number): Codec<number> {
+    return {
+        serialize: (s, value) => s.fixed(BigInt(value), size),
+        deserialize: (d) => Number(d.fixed(size)),
+    };
+}
+
+function fixedBigint(size: number): Codec<bigint> {
+    return {
+        serialize: (s, value) => s.fixed(value, size),
+        deserialize: (d) => d.fixed(size),
+    };
+}
+
+export const bool: Codec<boolean> = {
+    serialize: (s, value) => s.fixed(value ? 1n : 0n, 1),
+    deserialize: (d) => {
+        const value = d.fixed(1);
+        if (value > 1n) {
+            throw new RangeError(`invalid bool ${value}`);
+        }
+        return value === 1n;
+    },
+};
+export const u8 = fixedNumber(1);
+export const u16 = fixedNumber(2);
+export const u32 = fixedNumber(4);
+export const u64 = fixedBigint(8);
+export const u128 = fixedBigint(16);
+export const u256 = fixedBigint(32);
+
+/** Addresses, as hex strings prefixed with `0x`. */
+export const address: Codec<string> = {
+    serialize: (s, value) => {
+        const hex = value.replace(/^0x/, "").padStart(2 * ADDRESS_LENGTH, "0");
+        if (hex.length !== 2 * ADDRESS_LENGTH || !/^[0-9a-fA-F]*$/.test(hex)) {
+            throw new RangeError(`invalid address ${value}`);
+        }
+        for (let i = 0; i < hex.length; i += 2) {
+            s.fixed(BigInt(parseInt(hex.slice(i, i + 2), 16)), 1);
+        }
+    },
+    deserialize: (d) =>
+        "0x" +
+        Array.from(d.take(ADDRESS_LENGTH), (byte) => byte.toString(16).padStart(2, "0")).join(""),
+};
+
+export const string: Codec<string> = {
+    serialize: (s, value) => {
+        const bytes = new TextEncoder().encode(value);
+        s.uleb128(bytes.length);
+        s.append(bytes);
+    },
+    deserialize: (d) => new TextDecoder().decode(d.take(d.uleb128())),
+};
+
+export function vector<T>(codec: Codec<T>): Codec<T[]> {
+    return {
+        serialize: (s, value) => {
+            s.uleb128(value.length);
+            value.forEach((elem) => codec.serialize(s, elem));
+        },
+        deserialize: (d) => {
+            const length = d.uleb128();
+            const value: T[] = [];
+            for (let i = 0; i < length; i++) {
+                value.push(codec.deserialize(d));
+            }
+            return value;
+        },
+    };
+}
+
+export function option<T>(codec: Codec<T>): Codec<T | null> {
+    return {
+        serialize: (s, value) => {
+            s.uleb128(value === null ? 0 : 1);
+            if (value !== null) {
+                codec.serialize(s, value);
+            }
+        },
+        deserialize: (d) => {
+            const tag = d.uleb128();
+            if (tag > 1) {
+                throw new RangeError(`invalid option tag ${tag}`);
+            }
+            return tag === 0 ? null : codec.deserialize(d);
+        },
+    };
+}
+
+/** A struct codec, given its fields in order. Fields are resolved lazily, on first use. */
+export function struct<T>(fields: () => [string, Codec<any>][]): Codec<T> {
+    let resolved: [string, Codec<any>][] | undefined;
+    const get = () => (resolved = resolved ?? fields());
+    return {
+        serialize: (s, value) => {
+            for (const [name, codec] of get()) {
+                codec.serialize(s, (value as any)[name]);
+            }
+        },
+        deserialize: (d) => {
+            const value: any = {};
+            for (const [name, codec] of get()) {
+                value[name] = codec.deserialize(d);
+            }
+            return value as T;
+        },
+    };
+}
+
+export function encode<T>(codec: Codec<T>, value: T): Uint8Array {
+    const s = new Serializer();
+    codec.serialize(s, value);
+    return s.toBytes();
+}
+
+export function decode<T>(codec: Codec<T>, bytes: Uint8Array): T {
+    const d = new Deserializer(bytes);
+    const value = codec.deserialize(d);
+    if (d.remaining() > 0) {
+        throw new RangeError(`${d.remaining()} unexpected trailing bytes`);
+    }
+    return value;
+}
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "let",
+    "static",
+    "yield",
+    "await",
+    "implements",
+    "interface",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "bcs",
+];
+
+pub(crate) fn generate(ctx: &BindingsContext<'_>) -> Vec<(String, String)> {
+    let mut runtime = format!(
+        "{}\n\n/** The length of addresses, in bytes. */\nexport const ADDRESS_LENGTH = {};\n",
+        HEADER,
+        AccountAddress::LENGTH
+    );
+    runtime.push_str(RUNTIME);
+    let mut index = format!("{}\n\n", HEADER);
+    for (_, name) in ctx.modules() {
+        index.push_str(&format!(
+            "export * as {} from \"./{}\";\n",
+            ident(name),
+            name
+        ));
+    }
+    let mut result = vec![
+        ("bcs.ts".to_string(), runtime),
+        ("index.ts".to_string(), index),
+    ];
+    for (module_env, name) in ctx.modules() {
+        result.push((format!("{}.ts", name), generate_module(ctx, &module_env)));
+    }
+    result
+}
+
+fn generate_module(ctx: &BindingsContext<'_>, module_env: &ModuleEnv<'_>) -> String {
+    let pool = ctx.env.symbol_pool();
+    let writer = CodeWriter::new(module_env.get_loc());
+    emitln!(writer, HEADER);
+    emitln!(writer);
+    let doc = module_env.get_doc();
+    if !doc.is_empty() {
+        emit!(writer, &doc_comment(doc, "//"));
+        emitln!(writer);
+    }
+    emitln!(writer, "import * as bcs from \"./bcs\";");
+    let mut imports = BTreeSet::new();
+    for struct_env in module_env.get_structs() {
+        for field_env in struct_env.get_fields() {
+            ctx.collect_modules(&field_env.get_type(), &mut imports);
+        }
+    }
+    for fun_env in ctx.entry_functions(module_env) {
+        for (_, ty) in ctx.arguments(&fun_env) {
+            ctx.collect_modules(&ty, &mut imports);
+        }
+    }
+    imports.remove(&module_env.get_id());
+    for mid in imports {
+        let name = ctx.binding_name(mid);
+        emitln!(writer, "import * as {} from \"./{}\";", ident(name), name);
+    }
+    emitln!(writer);
+    emitln!(
+        writer,
+        "export const ADDRESS = \"0x{}\";",
+        module_env.get_name().addr().to_str_radix(16)
+    );
+    emitln!(
+        writer,
+        "export const MODULE_NAME = \"{}\";",
+        pool.string(module_env.get_name().name())
+    );
+
+    for struct_env in module_env.get_structs().filter(|s| !s.is_native()) {
+        emitln!(writer);
+        generate_struct(ctx, &writer, &struct_env);
+    }
+    for fun_env in ctx.entry_functions(module_env) {
+        let name = pool.string(fun_env.get_name()).to_string();
+        let type_params = fun_env
+            .get_named_type_parameters()
+            .iter()
+            .map(|tp| format!("{}Type", pool.string(tp.0).to_mixed_case()))
+            .collect::<Vec<_>>();
+        let args = ctx
+            .arguments(&fun_env)
+            .into_iter()
+            .map(|(arg, ty)| (ident(&arg.to_mixed_case()), ty))
+            .collect::<Vec<_>>();
+        let params = type_params
+            .iter()
+            .map(|tp| format!("{}: string", tp))
+            .chain(
+                args.iter()
+                    .map(|(arg, ty)| format!("{}: {}", arg, type_name(ctx, module_env, &[], ty))),
+            )
+            .collect::<Vec<_>>();
+        emitln!(writer);
+        emit!(writer, &doc_block(fun_env.get_doc()));
+        emitln!(
+            writer,
+            &signature(
+                &format!("export function {}", ident(&name.to_mixed_case())),
+                &params,
+                ": bcs.EntryFunctionPayload {"
+            )
+        );
+        writer.with_indent(|| {
+            emitln!(writer, "return {");
+            writer.with_indent(|| {
+                emitln!(
+                    writer,
+                    "function: `${{ADDRESS}}::${{MODULE_NAME}}::{}`,",
+                    name
+                );
+                emitln!(writer, "typeArguments: [{}],", type_params.join(", "));
+                emitln!(writer, "arguments: [");
+                writer.with_indent(|| {
+                    for (arg, ty) in &args {
+                        emitln!(
+                            writer,
+                            "bcs.encode({}, {}),",
+                            codec(ctx, module_env, &[], ty),
+                            arg
+                        );
+                    }
+                });
+                emitln!(writer, "],");
+            });
+            emitln!(writer, "};");
+        });
+        emitln!(writer, "}");
+    }
+    writer.extract_result()
+}
+
+fn generate_struct(ctx: &BindingsContext<'_>, writer: &CodeWriter, struct_env: &StructEnv<'_>) {
+    let pool = ctx.env.symbol_pool();
+    let module_env = &struct_env.module_env;
+    let name = pool.string(struct_env.get_name()).to_string();
+    let type_params = ctx.struct_type_params(struct_env);
+    let type_param_names = type_params
+        .iter()
+        .map(|(tp, _)| tp.clone())
+        .collect::<Vec<_>>();
+    let layout_params = type_params
+        .iter()
+        .filter(|(_, is_phantom)| !is_phantom)
+        .map(|(tp, _)| tp.clone())
+        .collect::<Vec<_>>();
+    let generics = generics(&layout_params);
+    let codec_params = layout_params
+        .iter()
+        .map(|tp| format!("{}: bcs.Codec<{}>", codec_param(tp), tp))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    emit!(writer, &doc_block(struct_env.get_doc()));
+    emitln!(writer, "export interface {}{} {{", name, generics);
+    writer.with_indent(|| {
+        for field_env in struct_env.get_fields() {
+            emit!(writer, &doc_block(field_env.get_doc()));
+            emitln!(
+                writer,
+                "{}: {};",
+                pool.string(field_env.get_name()),
+                type_name(ctx, module_env, &type_param_names, &field_env.get_type())
+            );
+        }
+    });
+    emitln!(writer, "}");
+    emitln!(writer);
+    let fields = || {
+        writer.with_indent(|| {
+            for field_env in struct_env.get_fields() {
+                emitln!(
+                    writer,
+                    "[\"{}\", {}],",
+                    pool.string(field_env.get_name()),
+                    codec(ctx, module_env, &type_param_names, &field_env.get_type())
+                );
+            }
+        })
+    };
+    if layout_params.is_empty() {
+        emitln!(
+            writer,
+            "export const {}: bcs.Codec<{}> = bcs.struct(() => [",
+            name,
+            name
+        );
+        fields();
+        emitln!(writer, "]);");
+    } else {
+        emitln!(
+            writer,
+            "export function {}{}({}): bcs.Codec<{}{}> {{",
+            name,
+            generics,
+            codec_params,
+            name,
+            generics
+        );
+        writer.with_indent(|| {
+            emitln!(writer, "return bcs.struct(() => [");
+            fields();
+            emitln!(writer, "]);");
+        });
+        emitln!(writer, "}");
+    }
+    emitln!(writer);
+
+    // The struct tag includes all type arguments, phantom or not.
+    let tag_params = type_params
+        .iter()
+        .map(|(tp, _)| ident(&tp.to_mixed_case()))
+        .collect::<Vec<_>>();
+    emitln!(
+        writer,
+        "/** Returns the tag of `{}`{}. */",
+        ctx.struct_tag_name(struct_env),
+        if tag_params.is_empty() {
+            ""
+        } else {
+            ", instantiated with the given type arguments"
+        }
+    );
+    emitln!(
+        writer,
+        "export function {}StructTag({}): string {{",
+        name.to_mixed_case(),
+        tag_params
+            .iter()
+            .map(|tp| format!("{}: string", tp))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    writer.with_indent(|| {
+        if tag_params.is_empty() {
+            emitln!(writer, "return `${{ADDRESS}}::${{MODULE_NAME}}::{}`;", name);
+        } else {
+            emitln!(
+                writer,
+                "return `${{ADDRESS}}::${{MODULE_NAME}}::{}<{}>`;",
+                name,
+                tag_params
+                    .iter()
+                    .map(|tp| format!("${{{}}}", tp))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    });
+    emitln!(writer, "}");
+
+    if struct_env.get_abilities().has_key() {
+        emitln!(writer);
+        emitln!(writer, "/** Decodes a `{}` resource. */", name);
+        let mut params = codec_params.clone();
+        if !params.is_empty() {
+            params.push_str(", ");
+        }
+        emitln!(
+            writer,
+            "export function decode{}{}({}bytes: Uint8Array): {}{} {{",
+            name,
+            generics,
+            params,
+            name,
+            generics
+        );
+        writer.with_indent(|| {
+            let codec = if layout_params.is_empty() {
+                name.clone()
+            } else {
+                format!(
+                    "{}({})",
+                    name,
+                    layout_params
+                        .iter()
+                        .map(|tp| codec_param(tp))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            emitln!(writer, "return bcs.decode({}, bytes);", codec);
+        });
+        emitln!(writer, "}");
+    }
+}
+
+/// Returns the TypeScript type bound to a Move type, in the context of `module_env` and the
+/// given type parameters.
+fn type_name(
+    ctx: &BindingsContext<'_>,
+    module_env: &ModuleEnv<'_>,
+    type_params: &[String],
+    ty: &Type,
+) -> String {
+    match ty {
+        Type::Primitive(prim) => match prim {
+            PrimitiveType::Bool => "boolean",
+            PrimitiveType::U8 | PrimitiveType::U16 | PrimitiveType::U32 => "number",
+            PrimitiveType::U64 | PrimitiveType::U128 | PrimitiveType::U256 => "bigint",
+            PrimitiveType::Address => "string",
+            _ => unreachable!("type cannot be bound: {:?}", prim),
+        }
+        .to_string(),
+        Type::Vector(elem) => {
+            let elem = type_name(ctx, module_env, type_params, elem);
+            if elem.contains(' ') {
+                format!("({})[]", elem)
+            } else {
+                format!("{}[]", elem)
+            }
+        }
+        Type::Struct(mid, sid, type_args) => {
+            let type_args = ctx
+                .layout_type_args(*mid, *sid, type_args)
+                .into_iter()
+                .map(|ty| type_name(ctx, module_env, type_params, ty))
+                .collect::<Vec<_>>();
+            match ctx.native_struct(*mid, *sid) {
+                Some(NativeStruct::String) => "string".to_string(),
+                Some(NativeStruct::Option) => format!("{} | null", type_args[0]),
+                None => format!(
+                    "{}{}{}",
+                    module_prefix(ctx, module_env, *mid),
+                    struct_name(ctx, *mid, *sid),
+                    generics(&type_args)
+                ),
+            }
+        }
+        Type::TypeParameter(idx) => type_params[*idx as usize].clone(),
+        _ => unreachable!("type cannot be bound: {:?}", ty),
+    }
+}
+
+/// Returns an expression for the codec of a Move type.
+fn codec(
+    ctx: &BindingsContext<'_>,
+    module_env: &ModuleEnv<'_>,
+    type_params: &[String],
+    ty: &Type,
+) -> String {
+    match ty {
+        Type::Primitive(prim) => match prim {
+            PrimitiveType::Bool => "bcs.bool",
+            PrimitiveType::U8 => "bcs.u8",
+            PrimitiveType::U16 => "bcs.u16",
+            PrimitiveType::U32 => "bcs.u32",
+            PrimitiveType::U64 => "bcs.u64",
+            PrimitiveType::U128 => "bcs.u128",
+            PrimitiveType::U256 => "bcs.u256",
+            PrimitiveType::Address => "bcs.address",
+            _ => unreachable!("type cannot be bound: {:?}", prim),
+        }
+        .to_string(),
+        Type::Vector(elem) => format!("bcs.vector({})", codec(ctx, module_env, type_params, elem)),
+        Type::Struct(mid, sid, type_args) => {
+            let type_args = ctx
+                .layout_type_args(*mid, *sid, type_args)
+                .into_iter()
+                .map(|ty| codec(ctx, module_env, type_params, ty))
+                .collect::<Vec<_>>();
+            match ctx.native_struct(*mid, *sid) {
+                Some(NativeStruct::String) => "bcs.string".to_string(),
+                Some(NativeStruct::Option) => format!("bcs.option({})", type_args[0]),
+                None => {
+                    let name = format!(
+                        "{}{}",
+                        module_prefix(ctx, module_env, *mid),
+                        struct_name(ctx, *mid, *sid)
+                    );
+                    if type_args.is_empty() {
+                        name
+                    } else {
+                        format!("{}({})", name, type_args.join(", "))
+                    }
+                }
+            }
+        }
+        Type::TypeParameter(idx) => codec_param(&type_params[*idx as usize]),
+        _ => unreachable!("type cannot be bound: {:?}", ty),
+    }
+}
+
+fn module_prefix(ctx: &BindingsContext<'_>, module_env: &ModuleEnv<'_>, mid: ModuleId) -> String {
+    if mid == module_env.get_id() {
+        String::new()
+    } else {
+        format!("{}.", ident(ctx.binding_name(mid)))
+    }
+}
+
+fn struct_name(ctx: &BindingsContext<'_>, mid: ModuleId, sid: StructId) -> String {
+    let struct_env = ctx.env.get_module(mid).into_struct(sid);
+    ctx.env
+        .symbol_pool()
+        .string(struct_env.get_name())
+        .to_string()
+}
+
+/// The name of the parameter passing the codec for a type parameter.
+fn codec_param(type_param: &str) -> String {
+    format!("{}Codec", type_param.to_mixed_case())
+}
+
+fn generics(type_args: &[String]) -> String {
+    if type_args.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", type_args.join(", "))
+    }
+}
+
+/// Formats documentation as a JSDoc comment.
+fn doc_block(doc: &str) -> String {
+    let doc = doc.trim().replace("*/", "*\\/");
+    if doc.is_empty() {
+        String::new()
+    } else if !doc.contains('\n') {
+        format!("/** {} */\n", doc)
+    } else {
+        format!("/**\n{} */\n", doc_comment(&doc, " *"))
+    }
+}
+
+/// Escapes identifiers which are reserved in TypeScript, or clash with the import of the
+/// runtime.
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
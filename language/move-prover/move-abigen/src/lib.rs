@@ -7,5 +7,7 @@
 extern crate core;
 
 mod abigen;
+mod bindings;
+mod module_abi;
 
-pub use crate::abigen::*;
+pub use crate::{abigen::*, bindings::*, module_abi::*};
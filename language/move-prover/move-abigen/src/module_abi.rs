@@ -0,0 +1,271 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! JSON ABIs of modules, describing everything a client needs to interact with a module: its
+//! public and entry functions, struct layouts, constants, friends and error codes.
+
+use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
+use move_core_types::errmap::ErrorDescription;
+use move_model::{
+    ast::Value,
+    model::{FunctionEnv, ModuleEnv, StructEnv, TypeParameter},
+    symbol::Symbol,
+    ty::{PrimitiveType, Type},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleABI {
+    pub address: String,
+    pub name: String,
+    pub doc: String,
+    /// The fully qualified names of the friend modules.
+    pub friends: Vec<String>,
+    pub structs: Vec<StructABI>,
+    /// The public, friend and entry functions.
+    pub functions: Vec<FunctionABI>,
+    pub constants: Vec<ConstantABI>,
+    /// The error codes of the module, as found by the error map generator.
+    pub errors: Vec<ErrorABI>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructABI {
+    pub name: String,
+    pub doc: String,
+    pub abilities: Vec<String>,
+    pub type_parameters: Vec<TypeParameterABI>,
+    /// The fields in declaration order. Native structs have no fields.
+    pub fields: Vec<FieldABI>,
+    pub is_native: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldABI {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeParameterABI {
+    pub name: String,
+    pub constraints: Vec<String>,
+    pub is_phantom: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionABI {
+    pub name: String,
+    pub doc: String,
+    /// One of `public`, `friend` or `private`. Private functions are only included if they
+    /// are entry functions.
+    pub visibility: String,
+    pub is_entry: bool,
+    pub type_parameters: Vec<TypeParameterABI>,
+    pub parameters: Vec<FieldABI>,
+    pub return_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantABI {
+    pub name: String,
+    pub doc: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The value, where numbers are given as decimal strings, addresses and byte vectors as
+    /// hex strings, and vectors as arrays.
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorABI {
+    pub code: u64,
+    pub name: String,
+    pub description: String,
+}
+
+/// Computes the ABI of a module, given its error codes.
+pub(crate) fn module_abi(
+    module_env: &ModuleEnv<'_>,
+    errors: Option<&BTreeMap<u64, ErrorDescription>>,
+) -> ModuleABI {
+    let pool = module_env.symbol_pool();
+    let name = module_env.get_name();
+    ModuleABI {
+        address: format!("0x{:x}", name.addr()),
+        name: pool.string(name.name()).to_string(),
+        doc: module_env.get_doc().to_string(),
+        friends: module_env
+            .get_friend_modules()
+            .into_iter()
+            .map(|id| module_env.env.get_module(id).get_full_name_str())
+            .collect(),
+        structs: module_env
+            .get_structs()
+            .map(|struct_env| struct_abi(&struct_env))
+            .collect(),
+        functions: module_env
+            .get_functions()
+            .filter(|fun_env| fun_env.is_entry() || fun_env.visibility() != Visibility::Private)
+            .map(|fun_env| function_abi(&fun_env))
+            .collect(),
+        constants: module_env
+            .get_named_constants()
+            .map(|const_env| ConstantABI {
+                name: pool.string(const_env.get_name()).to_string(),
+                doc: const_env.get_doc().to_string(),
+                ty: type_string(module_env, &[], &const_env.get_type()),
+                value: value_json(&const_env.get_value()),
+            })
+            .collect(),
+        errors: errors
+            .into_iter()
+            .flatten()
+            .map(|(code, desc)| ErrorABI {
+                code: *code,
+                name: desc.code_name.clone(),
+                description: desc.code_description.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn struct_abi(struct_env: &StructEnv<'_>) -> StructABI {
+    let module_env = &struct_env.module_env;
+    let pool = module_env.symbol_pool();
+    let type_params = struct_env.get_named_type_parameters();
+    let type_param_names = type_params.iter().map(|tp| tp.0).collect::<Vec<_>>();
+    StructABI {
+        name: pool.string(struct_env.get_name()).to_string(),
+        doc: struct_env.get_doc().to_string(),
+        abilities: ability_strings(struct_env.get_abilities()),
+        type_parameters: type_params
+            .iter()
+            .enumerate()
+            .map(|(i, tp)| type_parameter_abi(module_env, tp, struct_env.is_phantom_parameter(i)))
+            .collect(),
+        fields: struct_env
+            .get_fields()
+            .map(|field_env| FieldABI {
+                name: pool.string(field_env.get_name()).to_string(),
+                ty: type_string(module_env, &type_param_names, &field_env.get_type()),
+            })
+            .collect(),
+        is_native: struct_env.is_native(),
+    }
+}
+
+fn function_abi(fun_env: &FunctionEnv<'_>) -> FunctionABI {
+    let module_env = &fun_env.module_env;
+    let pool = module_env.symbol_pool();
+    let type_params = fun_env.get_named_type_parameters();
+    let type_param_names = type_params.iter().map(|tp| tp.0).collect::<Vec<_>>();
+    FunctionABI {
+        name: pool.string(fun_env.get_name()).to_string(),
+        doc: fun_env.get_doc().to_string(),
+        visibility: match fun_env.visibility() {
+            Visibility::Public => "public",
+            Visibility::Friend => "friend",
+            Visibility::Private => "private",
+        }
+        .to_string(),
+        is_entry: fun_env.is_entry(),
+        type_parameters: type_params
+            .iter()
+            .map(|tp| type_parameter_abi(module_env, tp, false))
+            .collect(),
+        parameters: fun_env
+            .get_parameters()
+            .iter()
+            .map(|param| FieldABI {
+                name: pool.string(param.0).to_string(),
+                ty: type_string(module_env, &type_param_names, &param.1),
+            })
+            .collect(),
+        return_types: fun_env
+            .get_return_types()
+            .iter()
+            .map(|ty| type_string(module_env, &type_param_names, ty))
+            .collect(),
+    }
+}
+
+fn type_parameter_abi(
+    module_env: &ModuleEnv<'_>,
+    tp: &TypeParameter,
+    is_phantom: bool,
+) -> TypeParameterABI {
+    TypeParameterABI {
+        name: module_env.symbol_pool().string(tp.0).to_string(),
+        constraints: ability_strings((tp.1).0),
+        is_phantom,
+    }
+}
+
+fn ability_strings(abilities: AbilitySet) -> Vec<String> {
+    abilities
+        .into_iter()
+        .map(|ability| {
+            match ability {
+                Ability::Copy => "copy",
+                Ability::Drop => "drop",
+                Ability::Store => "store",
+                Ability::Key => "key",
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+/// Displays a type in Move syntax, with structs qualified by the address of their module.
+fn type_string(module_env: &ModuleEnv<'_>, type_param_names: &[Symbol], ty: &Type) -> String {
+    let display = |ty| type_string(module_env, type_param_names, ty);
+    match ty {
+        Type::Primitive(PrimitiveType::Signer) => "signer".to_string(),
+        Type::Primitive(prim) => prim.to_string(),
+        Type::Vector(elem) => format!("vector<{}>", display(elem)),
+        Type::Reference(is_mut, inner) => {
+            format!("&{}{}", if *is_mut { "mut " } else { "" }, display(inner))
+        }
+        Type::Struct(mid, sid, type_args) => {
+            let struct_module = module_env.env.get_module(*mid);
+            let struct_env = struct_module.get_struct(*sid);
+            let mut s = format!(
+                "{}::{}",
+                struct_module.get_full_name_str(),
+                struct_module.symbol_pool().string(struct_env.get_name())
+            );
+            if !type_args.is_empty() {
+                s.push('<');
+                s.push_str(&type_args.iter().map(display).collect::<Vec<_>>().join(", "));
+                s.push('>');
+            }
+            s
+        }
+        Type::TypeParameter(idx) => match type_param_names.get(*idx as usize) {
+            Some(name) => module_env.symbol_pool().string(*name).to_string(),
+            None => format!("T{}", idx),
+        },
+        Type::Tuple(elems) => format!(
+            "({})",
+            elems.iter().map(display).collect::<Vec<_>>().join(", ")
+        ),
+        _ => ty.display(&module_env.env.get_type_display_ctx()).to_string(),
+    }
+}
+
+fn value_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Address(addr) => format!("0x{:x}", addr).into(),
+        Value::Number(n) => n.to_string().into(),
+        Value::Bool(b) => (*b).into(),
+        Value::ByteArray(bytes) => format!("0x{}", hex::encode(bytes)).into(),
+        Value::AddressArray(addrs) => addrs
+            .iter()
+            .map(|addr| serde_json::Value::from(format!("0x{:x}", addr)))
+            .collect(),
+        Value::Vector(values) => values.iter().map(value_json).collect(),
+    }
+}
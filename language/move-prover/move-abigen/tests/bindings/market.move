@@ -0,0 +1,61 @@
+address 0x42 {
+/// A toy marketplace for tokens.
+module market {
+    use std::fixed_point32::FixedPoint32;
+    use std::option::Option;
+    use std::string::String;
+    use 0x42::token::Token;
+
+    /// A token listed for sale.
+    struct Listing<phantom Currency, T: store> has store, drop {
+        item: T,
+        /// The price, in units of `Currency`.
+        price: u64,
+        seller: address,
+        note: Option<String>,
+    }
+
+    /// The marketplace for a currency, published under the account of its operator.
+    struct Market<phantom Currency> has key {
+        listings: vector<Listing<Currency, Token>>,
+        fee: FixedPoint32,
+        volume: u128,
+        supply: u256,
+    }
+
+    /// Lists a token for sale.
+    public entry fun list<Currency>(
+        _seller: &signer,
+        _name: String,
+        _price: u64,
+        _royalties: vector<u8>,
+        _expiry: Option<u64>,
+    ) {
+        abort 0
+    }
+
+    /// Buys the listed token at `index`.
+    public entry fun buy<Currency>(_buyer: signer, _market: address, _index: u64, _accept: bool) {
+        abort 0
+    }
+
+    public entry fun with_reference(_account: &signer, _amount: &u64) {
+        abort 0
+    }
+
+    public entry fun with_type_parameter<T: drop>(_value: T) {
+        abort 0
+    }
+}
+
+module token {
+    struct Token has store, drop {
+        id: u64,
+        uri: vector<u8>,
+    }
+
+    public entry fun mint_batch(_to: address, _uris: vector<vector<u8>>, _flags: vector<bool>) {
+        abort 0
+    }
+}
+}
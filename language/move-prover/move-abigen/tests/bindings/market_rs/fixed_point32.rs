@@ -0,0 +1,55 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `move generate bindings`. Do not edit.
+
+#![allow(dead_code, unused_imports)]
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    u256::U256,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub const ADDRESS: &str = "0x1";
+pub const MODULE_NAME: &str = "fixed_point32";
+
+pub fn module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        Identifier::new(MODULE_NAME).unwrap(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixedPoint32 {
+    pub value: u64,
+}
+
+impl FixedPoint32 {
+    pub fn from_bcs(bytes: &[u8]) -> bcs::Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        bcs::from_bytes(bytes)
+    }
+
+    pub fn to_bcs(&self) -> Vec<u8>
+    where
+        Self: Serialize,
+    {
+        bcs::to_bytes(self).unwrap()
+    }
+}
+
+/// Returns the tag of `0x1::fixed_point32::FixedPoint32`.
+pub fn fixed_point32_struct_tag() -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        module: Identifier::new(MODULE_NAME).unwrap(),
+        name: Identifier::new("FixedPoint32").unwrap(),
+        type_params: vec![],
+    }
+}
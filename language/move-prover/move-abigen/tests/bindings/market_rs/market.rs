@@ -0,0 +1,137 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `move generate bindings`. Do not edit.
+
+//! A toy marketplace for tokens.
+
+#![allow(dead_code, unused_imports)]
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    u256::U256,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub const ADDRESS: &str = "0x42";
+pub const MODULE_NAME: &str = "market";
+
+pub fn module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        Identifier::new(MODULE_NAME).unwrap(),
+    )
+}
+
+/// A token listed for sale.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Listing<T> {
+    pub item: T,
+    /// The price, in units of `Currency`.
+    pub price: u64,
+    pub seller: AccountAddress,
+    pub note: Option<String>,
+}
+
+impl<T> Listing<T> {
+    pub fn from_bcs(bytes: &[u8]) -> bcs::Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        bcs::from_bytes(bytes)
+    }
+
+    pub fn to_bcs(&self) -> Vec<u8>
+    where
+        Self: Serialize,
+    {
+        bcs::to_bytes(self).unwrap()
+    }
+}
+
+/// Returns the tag of `0x42::market::Listing`, instantiated with the given type arguments.
+pub fn listing_struct_tag(currency: TypeTag, t: TypeTag) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        module: Identifier::new(MODULE_NAME).unwrap(),
+        name: Identifier::new("Listing").unwrap(),
+        type_params: vec![currency, t],
+    }
+}
+
+/// The marketplace for a currency, published under the account of its operator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Market {
+    pub listings: Vec<Listing<super::token::Token>>,
+    pub fee: super::fixed_point32::FixedPoint32,
+    pub volume: u128,
+    pub supply: U256,
+}
+
+impl Market {
+    pub fn from_bcs(bytes: &[u8]) -> bcs::Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        bcs::from_bytes(bytes)
+    }
+
+    pub fn to_bcs(&self) -> Vec<u8>
+    where
+        Self: Serialize,
+    {
+        bcs::to_bytes(self).unwrap()
+    }
+}
+
+/// Returns the tag of `0x42::market::Market`, instantiated with the given type arguments.
+pub fn market_struct_tag(currency: TypeTag) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        module: Identifier::new(MODULE_NAME).unwrap(),
+        name: Identifier::new("Market").unwrap(),
+        type_params: vec![currency],
+    }
+}
+
+/// Buys the listed token at `index`.
+pub fn buy(
+    currency_type: TypeTag,
+    market: AccountAddress,
+    index: u64,
+    accept: bool,
+) -> super::EntryFunctionCall {
+    super::EntryFunctionCall {
+        module: module_id(),
+        function: Identifier::new("buy").unwrap(),
+        ty_args: vec![currency_type],
+        args: vec![
+            bcs::to_bytes(&market).unwrap(),
+            bcs::to_bytes(&index).unwrap(),
+            bcs::to_bytes(&accept).unwrap(),
+        ],
+    }
+}
+
+/// Lists a token for sale.
+pub fn list(
+    currency_type: TypeTag,
+    name: String,
+    price: u64,
+    royalties: Vec<u8>,
+    expiry: Option<u64>,
+) -> super::EntryFunctionCall {
+    super::EntryFunctionCall {
+        module: module_id(),
+        function: Identifier::new("list").unwrap(),
+        ty_args: vec![currency_type],
+        args: vec![
+            bcs::to_bytes(&name).unwrap(),
+            bcs::to_bytes(&price).unwrap(),
+            bcs::to_bytes(&royalties).unwrap(),
+            bcs::to_bytes(&expiry).unwrap(),
+        ],
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `move generate bindings`. Do not edit.
+
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+
+pub mod fixed_point32;
+pub mod market;
+pub mod token;
+
+/// A call to an entry function, with BCS encoded arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryFunctionCall {
+    pub module: ModuleId,
+    pub function: Identifier,
+    pub ty_args: Vec<TypeTag>,
+    pub args: Vec<Vec<u8>>,
+}
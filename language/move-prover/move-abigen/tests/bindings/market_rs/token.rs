@@ -0,0 +1,73 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `move generate bindings`. Do not edit.
+
+#![allow(dead_code, unused_imports)]
+
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    u256::U256,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub const ADDRESS: &str = "0x42";
+pub const MODULE_NAME: &str = "token";
+
+pub fn module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        Identifier::new(MODULE_NAME).unwrap(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub id: u64,
+    pub uri: Vec<u8>,
+}
+
+impl Token {
+    pub fn from_bcs(bytes: &[u8]) -> bcs::Result<Self>
+    where
+        Self: DeserializeOwned,
+    {
+        bcs::from_bytes(bytes)
+    }
+
+    pub fn to_bcs(&self) -> Vec<u8>
+    where
+        Self: Serialize,
+    {
+        bcs::to_bytes(self).unwrap()
+    }
+}
+
+/// Returns the tag of `0x42::token::Token`.
+pub fn token_struct_tag() -> StructTag {
+    StructTag {
+        address: AccountAddress::from_hex_literal(ADDRESS).unwrap(),
+        module: Identifier::new(MODULE_NAME).unwrap(),
+        name: Identifier::new("Token").unwrap(),
+        type_params: vec![],
+    }
+}
+
+pub fn mint_batch(
+    to: AccountAddress,
+    uris: Vec<Vec<u8>>,
+    flags: Vec<bool>,
+) -> super::EntryFunctionCall {
+    super::EntryFunctionCall {
+        module: module_id(),
+        function: Identifier::new("mint_batch").unwrap(),
+        ty_args: vec![],
+        args: vec![
+            bcs::to_bytes(&to).unwrap(),
+            bcs::to_bytes(&uris).unwrap(),
+            bcs::to_bytes(&flags).unwrap(),
+        ],
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builds the generated Rust bindings of `tests/bindings`, and checks their BCS encoding against
+//! the layouts computed from the bytecode by `move-bytecode-utils`.
+
+#[allow(dead_code)]
+#[path = "bindings/market_rs/mod.rs"]
+mod market_bindings;
+
+use market_bindings::{fixed_point32::FixedPoint32, market, token::Token};
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::{
+    layout::{StructLayoutBuilder, TypeLayoutBuilder},
+    module_cache::GetModule,
+};
+use move_compiler::shared::{NumericalAddress, PackagePaths};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+    u256::U256,
+    value::{MoveStruct, MoveValue},
+};
+use std::collections::BTreeMap;
+
+/// The compiled modules of `tests/bindings/market.move` and the standard library.
+struct Modules(BTreeMap<ModuleId, CompiledModule>);
+
+impl GetModule for Modules {
+    type Error = anyhow::Error;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<CompiledModule>> {
+        Ok(self.0.get(id).cloned())
+    }
+}
+
+fn modules() -> Modules {
+    let named_address_map = BTreeMap::from([(
+        "std".to_string(),
+        NumericalAddress::parse_str("0x1").unwrap(),
+    )]);
+    let env = move_model::run_model_builder(
+        vec![PackagePaths {
+            name: None,
+            paths: vec!["tests/bindings/market.move".to_string()],
+            named_address_map: named_address_map.clone(),
+        }],
+        vec![PackagePaths {
+            name: None,
+            paths: vec!["../../move-stdlib/sources".to_string()],
+            named_address_map,
+        }],
+    )
+    .unwrap();
+    assert!(!env.has_errors(), "model has errors");
+    Modules(
+        env.get_modules()
+            .map(|module_env| {
+                let module = module_env.get_verified_module();
+                (module.self_id(), module.clone())
+            })
+            .collect(),
+    )
+}
+
+fn currency() -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::from_hex_literal("0x1").unwrap(),
+        module: Identifier::new("string").unwrap(),
+        name: Identifier::new("String").unwrap(),
+        type_params: vec![],
+    }))
+}
+
+#[test]
+fn structs_match_bytecode_layouts() {
+    let modules = modules();
+    let market = market::Market {
+        listings: vec![market::Listing {
+            item: Token {
+                id: 7,
+                uri: b"ipfs://token".to_vec(),
+            },
+            price: 100,
+            seller: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            note: Some("first sale".to_string()),
+        }],
+        fee: FixedPoint32 { value: 1 << 31 },
+        volume: u128::MAX,
+        supply: U256::from(42u64),
+    };
+    let bytes = market.to_bcs();
+
+    let layout =
+        StructLayoutBuilder::build_with_fields(&market::market_struct_tag(currency()), &modules)
+            .unwrap();
+    let value = MoveStruct::simple_deserialize(&bytes, &layout).unwrap();
+    let fields = match &value {
+        MoveStruct::WithFields(fields) => fields,
+        _ => panic!("expected a struct with fields, got {:?}", value),
+    };
+    assert_eq!(
+        fields
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        ["listings", "fee", "volume", "supply"],
+    );
+    assert_eq!(fields[2].1, MoveValue::U128(u128::MAX));
+    assert_eq!(value.simple_serialize().unwrap(), bytes);
+    assert_eq!(market::Market::from_bcs(&bytes).unwrap(), market);
+}
+
+#[test]
+fn entry_function_arguments_match_bytecode_layouts() {
+    let modules = modules();
+    let call = market::list(currency(), "gm".to_string(), 5, vec![1, 2], Some(3600));
+    assert_eq!(call.module, market::module_id());
+    assert_eq!(call.function.as_str(), "list");
+    assert_eq!(call.ty_args, [currency()]);
+
+    // Strings are encoded as `0x1::string::String`, and options as `0x1::option::Option`.
+    let option_u64 = TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::from_hex_literal("0x1").unwrap(),
+        module: Identifier::new("option").unwrap(),
+        name: Identifier::new("Option").unwrap(),
+        type_params: vec![TypeTag::U64],
+    }));
+    let arg_types = [
+        currency(),
+        TypeTag::U64,
+        TypeTag::Vector(Box::new(TypeTag::U8)),
+        option_u64,
+    ];
+    assert_eq!(call.args.len(), arg_types.len());
+    for (arg, ty) in call.args.iter().zip(arg_types) {
+        let layout = TypeLayoutBuilder::build_runtime(&ty, &modules).unwrap();
+        let value = MoveValue::simple_deserialize(arg, &layout).unwrap();
+        assert_eq!(&value.simple_serialize().unwrap(), arg);
+    }
+}
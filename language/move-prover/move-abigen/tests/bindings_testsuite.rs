@@ -0,0 +1,48 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_abigen::{generate_bindings, BindingsLanguage};
+use move_compiler::shared::{NumericalAddress, PackagePaths};
+use move_prover_test_utils::baseline_test::verify_or_update_baseline;
+use std::{collections::BTreeMap, path::Path};
+
+fn test_runner(path: &Path) -> datatest_stable::Result<()> {
+    let named_address_map = BTreeMap::from([(
+        "std".to_string(),
+        NumericalAddress::parse_str("0x1").unwrap(),
+    )]);
+    let env = move_model::run_model_builder(
+        vec![PackagePaths {
+            name: None,
+            paths: vec![path.to_string_lossy().to_string()],
+            named_address_map: named_address_map.clone(),
+        }],
+        vec![PackagePaths {
+            name: None,
+            paths: vec!["../../move-stdlib/sources".to_string()],
+            named_address_map,
+        }],
+    )?;
+    assert!(!env.has_errors(), "model has errors");
+
+    // The Rust bindings are compared file by file, so that `bindings_compile` can build them.
+    let rust_dir = path.with_file_name(format!(
+        "{}_rs",
+        path.file_stem().unwrap().to_string_lossy()
+    ));
+    for (file, contents) in generate_bindings(&env, BindingsLanguage::Rust) {
+        verify_or_update_baseline(&rust_dir.join(file), &contents)?;
+    }
+
+    // The BCS runtime of TypeScript bindings does not depend on the sources.
+    let contents = generate_bindings(&env, BindingsLanguage::TypeScript)
+        .into_iter()
+        .filter(|(file, _)| file != "bcs.ts")
+        .map(|(file, contents)| format!("==> {} <==\n{}", file, contents))
+        .collect::<Vec<_>>()
+        .join("\n");
+    verify_or_update_baseline(&path.with_extension("ts.exp"), &contents)?;
+    Ok(())
+}
+
+datatest_stable::harness!(test_runner, "tests/bindings", r".*\.move$");
@@ -33,5 +33,12 @@ module ScriptFunInModule {
     public fun foo() { }
 
     fun bar() { }
+
+    /// The value is out of range
+    const EOUT_OF_RANGE: u64 = 1;
+
+    struct Container<phantom M, T: store> has copy, store { value: T }
+
+    public fun unwrap<M, T: copy + store>(c: &Container<M, T>): T { c.value }
 }
 }
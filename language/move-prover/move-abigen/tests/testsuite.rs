@@ -27,6 +27,7 @@ fn test_runner(path: &Path) -> datatest_stable::Result<()> {
     let mut options = Options::create_from_args(&args)?;
     options.setup_logging_for_test();
     options.abigen.compiled_script_directory = "tests/sources".to_string();
+    options.abigen.module_abis = true;
     options.move_deps.push("../../move-stdlib".to_string());
     options
         .move_named_address_values
@@ -45,7 +46,7 @@ fn get_generated_abis(dir: &Path) -> std::io::Result<Vec<String>> {
             let path = entry.path();
             if path.is_dir() {
                 abi_paths.append(&mut get_generated_abis(&path)?);
-            } else if let Some("abi") = path.extension().and_then(OsStr::to_str) {
+            } else if let Some("abi" | "json") = path.extension().and_then(OsStr::to_str) {
                 abi_paths.push(path.to_str().unwrap().to_string());
             }
         }
@@ -5,6 +5,7 @@
 #[allow(unused_imports)]
 use log::{debug, info, warn};
 
+use crate::html::{self, NavEntry, SearchEntry};
 use codespan::{ByteIndex, Span};
 use itertools::Itertools;
 use move_compiler::parser::keywords::{BUILTINS, CONTEXTUAL_KEYWORDS, KEYWORDS};
@@ -32,6 +33,7 @@ use std::{
     path::{Path, PathBuf},
     process::{Command, Stdio},
     rc::Rc,
+    str::FromStr,
 };
 
 /// The maximum number of subheadings that are allowed
@@ -94,6 +96,35 @@ pub struct DocgenOptions {
     pub include_call_diagrams: bool,
     /// If this is being compiled relative to a different place where it will be stored (output directory).
     pub compile_relative_to_output_dir: bool,
+    /// The format of the generated documentation.
+    pub output_format: DocFormat,
+}
+
+/// The format of the generated documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocFormat {
+    /// A Markdown file per module or root template.
+    Markdown,
+    /// A static HTML site with a page per module or root template, a navigation sidebar and a
+    /// search index. Dependencies are documented as well, so that all references resolve
+    /// within the site.
+    Html,
+}
+
+impl FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(DocFormat::Markdown),
+            "html" => Ok(DocFormat::Html),
+            _ => Err(format!(
+                "Unknown documentation format `{}`, expected `markdown` or `html`",
+                s
+            )),
+        }
+    }
 }
 
 impl Default for DocgenOptions {
@@ -113,6 +144,7 @@ impl Default for DocgenOptions {
             references_file: None,
             include_dep_diagrams: false,
             include_call_diagrams: false,
+            output_format: DocFormat::Markdown,
         }
     }
 }
@@ -235,7 +267,7 @@ impl<'env> Docgen<'env> {
         // Generate documentation for standalone modules which are not included in the templates.
         for (id, info) in self.infos.clone() {
             let m = self.env.get_module(id);
-            if !info.is_included && m.is_target() {
+            if !info.is_included && (m.is_target() || self.is_html()) {
                 self.gen_module(&m, &info);
                 let path = self.make_file_in_out_dir(&info.target_file);
                 self.output.push((path, self.writer.extract_result()));
@@ -265,9 +297,100 @@ impl<'env> Docgen<'env> {
             }
         }
 
+        if self.is_html() {
+            self.gen_html_site();
+        }
+
         self.output
     }
 
+    fn is_html(&self) -> bool {
+        self.options.output_format == DocFormat::Html
+    }
+
+    /// Converts the generated Markdown into HTML pages, and adds the index page, the search
+    /// index and the assets of the site.
+    fn gen_html_site(&mut self) {
+        let nav = self
+            .infos
+            .keys()
+            .map(|id| self.env.get_module(*id))
+            .sorted_by_key(|m| m.get_full_name_str())
+            .map(|m| NavEntry {
+                title: m.get_full_name_str(),
+                href: self.ref_for_module(&m),
+                summary: m.get_doc().lines().next().unwrap_or_default().to_string(),
+            })
+            .collect_vec();
+        let search_index = self.html_search_index();
+        let image_dir = PathBuf::from(&self.options.output_directory);
+
+        let mut pages = std::mem::take(&mut self.output)
+            .into_iter()
+            .map(|(file, content)| {
+                (
+                    PathBuf::from(file)
+                        .with_extension("html")
+                        .to_string_lossy()
+                        .to_string(),
+                    html::page(&content, &nav, &image_dir),
+                )
+            })
+            .collect_vec();
+        let index = self.make_file_in_out_dir("index.html");
+        if !pages.iter().any(|(file, _)| file == &index) {
+            pages.push((index, html::index_page(&nav)));
+        }
+        pages.push((
+            self.make_file_in_out_dir("search-index.js"),
+            html::search_index_script(&search_index),
+        ));
+        pages.push((
+            self.make_file_in_out_dir("search.js"),
+            html::SEARCH_SCRIPT.to_string(),
+        ));
+        pages.push((
+            self.make_file_in_out_dir("style.css"),
+            html::STYLE_SHEET.to_string(),
+        ));
+        self.output = pages;
+    }
+
+    /// Computes the entries of the search index: all documented modules, and their structs,
+    /// functions and constants.
+    fn html_search_index(&self) -> Vec<SearchEntry> {
+        let mut entries = vec![];
+        for id in self.infos.keys() {
+            let module_env = self.env.get_module(*id);
+            let module_name = module_env.get_full_name_str();
+            entries.push(SearchEntry {
+                name: module_name.clone(),
+                kind: "module",
+                href: self.ref_for_module(&module_env),
+            });
+            let mut add_item = |kind, name: Symbol| {
+                entries.push(SearchEntry {
+                    name: format!("{}::{}", module_name, self.name_string(name)),
+                    kind,
+                    href: self.ref_for_module_item(&module_env, name),
+                })
+            };
+            for struct_env in module_env.get_structs() {
+                add_item("struct", struct_env.get_name());
+            }
+            for fun_env in module_env
+                .get_functions()
+                .filter(|f| self.options.include_private_fun || f.is_exposed())
+            {
+                add_item("function", fun_env.get_name());
+            }
+            for const_env in module_env.get_named_constants() {
+                add_item("constant", const_env.get_name());
+            }
+        }
+        entries
+    }
+
     /// Compute the schemas declared in all modules. This information is currently not directly
     /// in the environment, but can be derived from it.
     fn compute_declared_schemas(&mut self) {
@@ -451,6 +574,11 @@ impl<'env> Docgen<'env> {
     /// Computes file location for a module. This considers if the module is a dependency
     /// and if so attempts to locate already generated documentation for it.
     fn compute_output_file(&self, module_env: &ModuleEnv<'env>) -> Option<String> {
+        if self.is_html() {
+            // The site documents dependencies alongside the targets, so name the files after
+            // the modules, which are unique across packages.
+            return Some(format!("{}.md", self.make_label_for_module(module_env)));
+        }
         let output_path = PathBuf::from(&self.options.output_directory);
         let file_name = PathBuf::from(module_env.get_source_path())
             .with_extension("md")
@@ -882,7 +1010,7 @@ impl<'env> Docgen<'env> {
         self.begin_items();
         for (id, _) in sorted_infos {
             let module_env = self.env.get_module(*id);
-            if !module_env.is_target() {
+            if !module_env.is_target() && !self.is_html() {
                 // Do not include modules which are not target (outside of the package)
                 // into the index.
                 continue;
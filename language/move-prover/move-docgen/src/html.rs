@@ -0,0 +1,387 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering of the generated documentation as a static HTML site.
+//!
+//! The generator produces Markdown with embedded HTML for code, which is converted here into
+//! HTML pages. The conversion supports the subset of Markdown emitted by the generator and
+//! commonly used in doc comments: headers, paragraphs, lists, block quotes, fenced code,
+//! images, links, strong emphasis and inline HTML.
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+/// An entry of the navigation sidebar and of the index page.
+pub(crate) struct NavEntry {
+    pub title: String,
+    pub href: String,
+    /// The first line of the module documentation.
+    pub summary: String,
+}
+
+/// An entry of the search index.
+#[derive(Serialize)]
+pub(crate) struct SearchEntry {
+    pub name: String,
+    pub kind: &'static str,
+    pub href: String,
+}
+
+/// The script searching the index as the user types into the search box.
+pub(crate) const SEARCH_SCRIPT: &str = r#"(function () {
+  const input = document.getElementById("search");
+  const results = document.getElementById("search-results");
+  input.addEventListener("input", function () {
+    const query = input.value.trim().toLowerCase();
+    results.innerHTML = "";
+    if (query.length === 0) {
+      return;
+    }
+    const matches = SEARCH_INDEX
+      .filter((entry) => entry.name.toLowerCase().includes(query))
+      .slice(0, 50);
+    for (const entry of matches) {
+      const link = document.createElement("a");
+      link.href = entry.href;
+      link.textContent = entry.name;
+      const kind = document.createElement("span");
+      kind.className = "kind";
+      kind.textContent = entry.kind;
+      const item = document.createElement("li");
+      item.append(link, " ", kind);
+      results.append(item);
+    }
+  });
+})();
+"#;
+
+pub(crate) const STYLE_SHEET: &str = r#"body {
+  display: flex;
+  margin: 0;
+  font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif;
+  line-height: 1.5;
+  color: #24292f;
+}
+nav.sidebar {
+  position: sticky;
+  top: 0;
+  height: 100vh;
+  overflow-y: auto;
+  flex: 0 0 18em;
+  padding: 1em;
+  box-sizing: border-box;
+  background: #f6f8fa;
+  border-right: 1px solid #d0d7de;
+}
+nav.sidebar ul {
+  list-style: none;
+  padding-left: 0;
+}
+nav.sidebar input {
+  width: 100%;
+  box-sizing: border-box;
+}
+main {
+  flex: 1;
+  min-width: 0;
+  max-width: 60em;
+  padding: 1em 2em;
+}
+a {
+  color: #0969da;
+  text-decoration: none;
+}
+pre {
+  padding: 0.8em;
+  overflow-x: auto;
+  background: #f6f8fa;
+  border-radius: 6px;
+}
+code {
+  font-family: ui-monospace, Menlo, Consolas, monospace;
+  font-size: 90%;
+}
+details {
+  margin: 0.5em 0;
+}
+summary {
+  cursor: pointer;
+  font-weight: 600;
+}
+dt {
+  font-weight: 600;
+}
+.kind {
+  color: #57606a;
+  font-size: 80%;
+}
+.diagram svg {
+  max-width: 100%;
+  height: auto;
+}
+"#;
+
+/// Renders a page of the site from the Markdown generated for it. Images of diagrams are
+/// looked up relative to `image_dir` and embedded into the page.
+pub(crate) fn page(markdown: &str, nav: &[NavEntry], image_dir: &Path) -> String {
+    let content = markdown_to_html(markdown, image_dir);
+    let title = page_title(&content);
+    layout(&title, nav, &content)
+}
+
+/// Renders the index page, listing all documented modules.
+pub(crate) fn index_page(nav: &[NavEntry]) -> String {
+    let mut content = String::from("<h1>Modules</h1>\n<dl>\n");
+    for entry in nav {
+        content.push_str(&format!(
+            "<dt><a href=\"{}\"><code>{}</code></a></dt>\n<dd>{}</dd>\n",
+            entry.href,
+            entry.title,
+            escape(&entry.summary)
+        ));
+    }
+    content.push_str("</dl>\n");
+    layout("Modules", nav, &content)
+}
+
+/// Renders the search index as a script defining `SEARCH_INDEX`.
+pub(crate) fn search_index_script(entries: &[SearchEntry]) -> String {
+    let entries = entries
+        .iter()
+        .map(|entry| SearchEntry {
+            name: entry.name.clone(),
+            kind: entry.kind,
+            href: html_href(&entry.href),
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "const SEARCH_INDEX = {};\n",
+        serde_json::to_string_pretty(&entries).expect("search index is serializable")
+    )
+}
+
+fn layout(title: &str, nav: &[NavEntry], content: &str) -> String {
+    let mut modules = String::new();
+    for entry in nav {
+        modules.push_str(&format!(
+            "<li><a href=\"{}\"><code>{}</code></a></li>\n",
+            entry.href, entry.title
+        ));
+    }
+    let page = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{}</title>
+<link rel="stylesheet" href="style.css">
+<script src="search-index.js"></script>
+<script src="search.js" defer></script>
+</head>
+<body>
+<nav class="sidebar">
+<input id="search" type="search" placeholder="Search" autocomplete="off">
+<ul id="search-results"></ul>
+<h3><a href="index.html">Modules</a></h3>
+<ul>
+{}</ul>
+</nav>
+<main>
+{}</main>
+</body>
+</html>
+"#,
+        escape(title),
+        modules,
+        content
+    );
+    rewrite_links(&page)
+}
+
+/// Returns the text of the first header of the page.
+fn page_title(content: &str) -> String {
+    static HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"<h\d>(.*?)</h\d>").unwrap());
+    static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+    HEADER
+        .captures(content)
+        .map(|cap| TAG.replace_all(&cap[1], "").to_string())
+        .unwrap_or_else(|| "Documentation".to_string())
+}
+
+/// Rewrites references to the generated Markdown files into references to their HTML pages.
+fn rewrite_links(html: &str) -> String {
+    static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r#"href="([^":]*)\.md(#[^"]*)?""#).unwrap());
+    LINK.replace_all(html, r#"href="${1}.html${2}""#)
+        .to_string()
+}
+
+fn html_href(href: &str) -> String {
+    static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([^:]*)\.md(#.*)?$").unwrap());
+    LINK.replace(href, "${1}.html${2}").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The kind of block which is currently open in the conversion.
+#[derive(PartialEq, Eq)]
+enum Block {
+    None,
+    Paragraph,
+    Quote,
+    List(&'static str),
+}
+
+struct Converter<'a> {
+    image_dir: &'a Path,
+    out: String,
+    block: Block,
+    lines: Vec<String>,
+}
+
+/// Converts Markdown into HTML.
+fn markdown_to_html(markdown: &str, image_dir: &Path) -> String {
+    static HTML_BLOCK: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"^</?(a|details|summary|dl|dt|dd|div|table|p|ul|ol|li|blockquote|h[1-6]|hr|br)\b",
+        )
+        .unwrap()
+    });
+    static HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").unwrap());
+    static IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^!\[([^\]]*)\]\(([^)\s]+)\)$").unwrap());
+    static UNORDERED_ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[-*+]\s+(.*)$").unwrap());
+    static ORDERED_ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+[.)]\s+(.*)$").unwrap());
+
+    let mut conv = Converter {
+        image_dir,
+        out: String::new(),
+        block: Block::None,
+        lines: vec![],
+    };
+    let mut lines = markdown.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("<pre>") {
+            // Code generated by the documentation generator, which is already HTML.
+            conv.close_block();
+            conv.push_line(line);
+            if !line.contains("</pre>") {
+                for line in lines.by_ref() {
+                    conv.push_line(line);
+                    if line.contains("</pre>") {
+                        break;
+                    }
+                }
+            }
+        } else if trimmed.starts_with("```") {
+            conv.close_block();
+            conv.out.push_str("<pre><code>");
+            for line in lines.by_ref() {
+                if line.trim().starts_with("```") {
+                    break;
+                }
+                conv.out.push_str(&escape(line));
+                conv.out.push('\n');
+            }
+            conv.out.push_str("</code></pre>\n");
+        } else if trimmed.is_empty() {
+            conv.close_block();
+        } else if let Some(cap) = HEADER.captures(trimmed) {
+            conv.close_block();
+            let level = cap[1].len();
+            let header = format!("<h{}>{}</h{}>", level, inline(&cap[2]), level);
+            conv.push_line(&header);
+        } else if HTML_BLOCK.is_match(trimmed) {
+            conv.close_block();
+            conv.push_line(trimmed);
+        } else if let Some(cap) = IMAGE.captures(trimmed) {
+            conv.close_block();
+            let image = conv.image(&cap[1], &cap[2]);
+            conv.push_line(&image);
+        } else if let Some(cap) = UNORDERED_ITEM.captures(trimmed) {
+            conv.list_item("ul", &cap[1]);
+        } else if let Some(cap) = ORDERED_ITEM.captures(trimmed) {
+            conv.list_item("ol", &cap[1]);
+        } else if let Some(quoted) = trimmed.strip_prefix('>') {
+            conv.open_block(Block::Quote);
+            conv.lines.push(quoted.trim().to_string());
+        } else {
+            conv.open_block(Block::Paragraph);
+            conv.lines.push(trimmed.to_string());
+        }
+    }
+    conv.close_block();
+    conv.out
+}
+
+impl<'a> Converter<'a> {
+    fn push_line(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    fn open_block(&mut self, block: Block) {
+        if self.block != block {
+            self.close_block();
+            if let Block::List(tag) = block {
+                self.push_line(&format!("<{}>", tag));
+            }
+            self.block = block;
+        }
+    }
+
+    fn close_block(&mut self) {
+        let text = inline(&self.lines.join("\n"));
+        self.lines.clear();
+        match std::mem::replace(&mut self.block, Block::None) {
+            Block::None => {}
+            Block::Paragraph => self.push_line(&format!("<p>{}</p>", text)),
+            Block::Quote => self.push_line(&format!("<blockquote><p>{}</p></blockquote>", text)),
+            Block::List(tag) => self.push_line(&format!("</{}>", tag)),
+        }
+    }
+
+    fn list_item(&mut self, tag: &'static str, text: &str) {
+        self.open_block(Block::List(tag));
+        let item = format!("<li>{}</li>", inline(text));
+        self.push_line(&item);
+    }
+
+    /// Embeds SVG diagrams into the page, so they are styled and linked along with it, and
+    /// references other images.
+    fn image(&self, alt: &str, path: &str) -> String {
+        static PROLOG: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"(?s)<\?xml.*?\?>|<!DOCTYPE.*?>|<!--.*?-->").unwrap());
+        if path.ends_with(".svg") {
+            if let Ok(svg) = fs::read_to_string(self.image_dir.join(path)) {
+                return format!(
+                    "<div class=\"diagram\">\n{}\n</div>",
+                    PROLOG.replace_all(&svg, "").trim()
+                );
+            }
+        }
+        format!("<img src=\"{}\" alt=\"{}\">", path, escape(alt))
+    }
+}
+
+/// Converts inline Markdown: code, images, links and strong emphasis. HTML in the text is kept.
+fn inline(text: &str) -> String {
+    static CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r"`([^`]+)`").unwrap());
+    static IMAGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+    static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap());
+    static STRONG: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*\*([^*]+)\*\*").unwrap());
+    let text = CODE.replace_all(text, |cap: &Captures| {
+        format!("<code>{}</code>", escape(&cap[1]))
+    });
+    let text = IMAGE.replace_all(&text, r#"<img src="$2" alt="$1">"#);
+    let text = LINK.replace_all(&text, |cap: &Captures| {
+        format!("<a href=\"{}\">{}</a>", &cap[2], &cap[1])
+    });
+    STRONG.replace_all(&text, "<strong>$1</strong>").to_string()
+}
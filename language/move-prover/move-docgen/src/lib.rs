@@ -5,5 +5,6 @@
 #![forbid(unsafe_code)]
 
 mod docgen;
+mod html;
 
 pub use crate::docgen::*;
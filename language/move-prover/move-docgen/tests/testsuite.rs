@@ -6,6 +6,7 @@ use std::path::Path;
 
 use codespan_reporting::term::termcolor::Buffer;
 
+use move_docgen::DocFormat;
 use move_prover::{cli::Options, run_move_prover};
 use move_prover_test_utils::baseline_test::verify_or_update_baseline;
 use std::path::PathBuf;
@@ -67,7 +68,13 @@ fn test_runner(path: &Path) -> datatest_stable::Result<()> {
 
     options.docgen.specs_inlined = true;
     options.docgen.collapsed_sections = false;
-    test_docgen(path, options, "spec_inline_no_fold.md")?;
+    test_docgen(path, options.clone(), "spec_inline_no_fold.md")?;
+
+    if is_root_template {
+        options.docgen.collapsed_sections = true;
+        options.docgen.output_format = DocFormat::Html;
+        test_docgen(path, options, "html")?;
+    }
 
     Ok(())
 }
@@ -75,13 +82,19 @@ fn test_runner(path: &Path) -> datatest_stable::Result<()> {
 fn test_docgen(path: &Path, mut options: Options, suffix: &str) -> anyhow::Result<()> {
     let mut temp_path = PathBuf::from(TempDir::new()?.path());
     options.docgen.output_directory = temp_path.to_string_lossy().to_string();
+    let extension = if options.docgen.output_format == DocFormat::Html {
+        "html"
+    } else {
+        "md"
+    };
     let base_name = format!(
-        "{}.md",
+        "{}.{}",
         path.file_stem()
             .unwrap()
             .to_str()
             .unwrap()
-            .replace("_template", "")
+            .replace("_template", ""),
+        extension
     );
     temp_path.push(&base_name);
 
@@ -63,6 +63,11 @@ impl<'env> ErrmapGen<'env> {
         self.output.to_file(&self.options.output_file);
     }
 
+    /// Returns the error mapping generated so far.
+    pub fn result(&self) -> &ErrorMapping {
+        &self.output
+    }
+
     pub fn gen(&mut self) {
         for module in self.env.get_modules() {
             if !module.is_script_module() {
@@ -71,7 +76,8 @@ impl<'env> ErrmapGen<'env> {
         }
     }
 
-    fn build_error_map(&mut self, module: &ModuleEnv<'_>) -> Result<()> {
+    /// Adds the error codes of a single module to the error mapping.
+    pub fn build_error_map(&mut self, module: &ModuleEnv<'_>) -> Result<()> {
         let module_id = self.get_module_id_for_name(module);
         if module_id == self.options.error_category_module {
             self.build_error_categories(module)?
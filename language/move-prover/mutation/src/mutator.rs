@@ -10,7 +10,7 @@ use itertools::Itertools;
 use log::LevelFilter;
 use move_compiler::shared::PackagePaths;
 use move_model::{
-    model::{FunctionEnv, GlobalEnv, VerificationScope},
+    model::{FunctionEnv, GlobalEnv, Loc, VerificationScope},
     options::ModelBuilderOptions,
     parse_addresses_from_options, run_model_builder_with_options,
 };
@@ -19,6 +19,7 @@ use move_prover::{
 };
 use move_stackless_bytecode::{mutation_tester::MutationManager, options::ProverOptions};
 use std::{
+    collections::BTreeMap,
     io::Write,
     path::PathBuf,
     time::{Duration, Instant},
@@ -30,6 +31,7 @@ use std::{
 struct Runner {
     options: Options,
     error_writer: StandardStream,
+    mutants: Vec<Mutant>,
 }
 
 pub fn mutate(args: &[String]) {
@@ -158,94 +160,88 @@ fn apply_mutation(
 
     println!("Starting mutations with config `{}`.", config_descr);
 
-    let mut i = 0;
-    let mut mutation_applied = true;
-
     let mut runner = Runner {
         options,
         error_writer,
+        mutants: vec![],
     };
 
-    while mutation_applied {
-        i += 1;
-        println!("Applying add-sub mutation {}", i);
-        runner.options.prover.mutation_add_sub = i;
-        env.set_extension(MutationManager {
-            mutated: false,
-            add_sub: i,
-            sub_add: 0,
-            mul_div: 0,
-            div_mul: 0,
-        });
-        mutation_applied = runner.mutate(&env)?;
-        if !mutation_applied {
-            println!("No mutations applied");
-        }
-    }
-    i = 0;
-    mutation_applied = true;
-    while mutation_applied {
-        i += 1;
-        println!("Applying sub-add mutation {}", i);
-        runner.options.prover.mutation_sub_add = i;
-        env.set_extension(MutationManager {
-            mutated: false,
-            add_sub: 0,
-            sub_add: i,
-            mul_div: 0,
-            div_mul: 0,
-        });
-        mutation_applied = runner.mutate(&env)?;
-        if !mutation_applied {
-            println!("No mutations applied");
-        }
-    }
-    i = 0;
-    mutation_applied = true;
-    while mutation_applied {
-        i += 1;
-        println!("Applying mul-div mutation {}", i);
-        runner.options.prover.mutation_mul_div = i;
-        env.set_extension(MutationManager {
-            mutated: false,
-            add_sub: 0,
-            sub_add: 0,
-            mul_div: i,
-            div_mul: 0,
-        });
-        mutation_applied = runner.mutate(&env)?;
-        if !mutation_applied {
-            println!("No mutations applied");
-        }
-    }
-    i = 0;
-    mutation_applied = true;
-    while mutation_applied {
-        i += 1;
-        println!("Applying div-mul mutation {}", i);
-        runner.options.prover.mutation_div_mul = i;
-        env.set_extension(MutationManager {
-            mutated: false,
-            add_sub: 0,
-            sub_add: 0,
-            mul_div: 0,
-            div_mul: i,
-        });
-        mutation_applied = runner.mutate(&env)?;
-        if !mutation_applied {
-            println!("No mutations applied");
+    for operator in OPERATORS {
+        let mut i = 0;
+        let mut mutation_applied = true;
+        while mutation_applied {
+            i += 1;
+            println!("Applying {} mutation {}", operator, i);
+            env.set_extension(mutation_manager(operator, i));
+            mutation_applied = runner.mutate(&env, operator)?;
+            if !mutation_applied {
+                println!("No mutations applied");
+            }
         }
     }
+    runner.report(&env);
     Ok(())
 }
 
+/// The mutation operators, which are applied one at a time.
+const OPERATORS: &[&str] = &[
+    "add-sub",
+    "sub-add",
+    "mul-div",
+    "div-mul",
+    "cmp-flip",
+    "bool-negation",
+    "off-by-one",
+    "assert-removal",
+    "if-swap",
+    "stmt-deletion",
+];
+
+/// Selects the `i`-th application of `operator` as the mutation to make.
+fn mutation_manager(operator: &str, i: usize) -> MutationManager {
+    let mut manager = MutationManager::default();
+    let counter = match operator {
+        "add-sub" => &mut manager.add_sub,
+        "sub-add" => &mut manager.sub_add,
+        "mul-div" => &mut manager.mul_div,
+        "div-mul" => &mut manager.div_mul,
+        "cmp-flip" => &mut manager.cmp_flip,
+        "bool-negation" => &mut manager.bool_negation,
+        "off-by-one" => &mut manager.off_by_one,
+        "assert-removal" => &mut manager.assert_removal,
+        "if-swap" => &mut manager.if_swap,
+        "stmt-deletion" => &mut manager.stmt_deletion,
+        _ => unreachable!("unknown mutation operator"),
+    };
+    *counter = i;
+    manager
+}
+
+/// A mutant together with the outcome of verifying it.
+struct Mutant {
+    module_name: String,
+    function_name: String,
+    operator: &'static str,
+    loc: Loc,
+    description: String,
+    status: String,
+}
+
+impl Mutant {
+    /// A mutant is killed if the prover reports errors for it. Mutants which verify, or whose
+    /// verification timed out, survive.
+    fn is_killed(&self) -> bool {
+        self.status == "errors"
+    }
+}
+
 impl Runner {
-    fn mutate(&mut self, env: &GlobalEnv) -> anyhow::Result<bool> {
+    fn mutate(&mut self, env: &GlobalEnv, operator: &'static str) -> anyhow::Result<bool> {
         let mut mutated = false;
         for module in env.get_modules() {
             if module.is_target() {
                 for fun in module.get_functions() {
-                    mutated = self.mutate_function(fun)?;
+                    mutated = self.mutate_function(fun, operator)?;
                     if mutated {
                         break;
                     }
@@ -255,25 +251,79 @@ impl Runner {
         Ok(mutated)
     }
 
-    fn mutate_function(&mut self, fun: FunctionEnv<'_>) -> anyhow::Result<bool> {
+    fn mutate_function(
+        &mut self,
+        fun: FunctionEnv<'_>,
+        operator: &'static str,
+    ) -> anyhow::Result<bool> {
         // Scope verification to the given function
         let env = fun.module_env.env;
         self.options.prover.verify_scope = VerificationScope::Only(fun.get_full_name_str());
         ProverOptions::set(env, self.options.prover.clone());
         let (duration, status) = self.run_mutated_function(fun.module_env.env)?;
 
-        let mutated = env
+        let mutation = env
             .get_extension::<MutationManager>()
-            .map(|e| e.mutated)
-            .unwrap_or(false);
-        if mutated {
+            .and_then(|e| e.mutation.clone());
+        let mutated = mutation.is_some();
+        if let Some((loc, description)) = mutation {
             print!("mutated function {} ..", fun.get_full_name_str());
             std::io::stdout().flush()?;
             println!("\x08\x08{:.3}s {}.", duration.as_secs_f64(), status);
+            self.mutants.push(Mutant {
+                module_name: fun.module_env.get_full_name_str(),
+                function_name: fun.get_name_str(),
+                operator,
+                loc,
+                description,
+                status,
+            });
         }
         Ok(mutated)
     }
 
+    /// Prints the mutation score of each module, and the surviving mutants against the source.
+    fn report(&self, env: &GlobalEnv) {
+        let mut by_module: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for mutant in &self.mutants {
+            let (killed, total) = by_module.entry(&mutant.module_name).or_default();
+            *total += 1;
+            if mutant.is_killed() {
+                *killed += 1;
+            }
+        }
+        println!("\nMutation score by module:");
+        for (module_name, (killed, total)) in by_module {
+            println!(
+                "  {}: {}/{} mutants killed ({:.1}%)",
+                module_name,
+                killed,
+                total,
+                killed as f64 * 100.0 / total as f64
+            );
+        }
+
+        let survivors = self.mutants.iter().filter(|mutant| !mutant.is_killed());
+        for (i, mutant) in survivors.enumerate() {
+            if i == 0 {
+                println!("\nSurviving mutants:");
+            }
+            let position = match env.get_file_and_location(&mutant.loc) {
+                Some((file, location)) => {
+                    format!("{}:{}:{}", file, location.line.0 + 1, location.column.0 + 1)
+                }
+                None => mutant.module_name.clone(),
+            };
+            println!(
+                "  {}: {} in `{}` ({}, {})",
+                position, mutant.description, mutant.function_name, mutant.operator, mutant.status
+            );
+            if let Ok(source) = env.get_source(&mutant.loc) {
+                println!("      {}", source.lines().next().unwrap_or_default());
+            }
+        }
+    }
+
     fn run_mutated_function(&mut self, env: &GlobalEnv) -> anyhow::Result<(Duration, String)> {
         // Create and process bytecode.
         let targets = create_and_process_bytecode(&self.options, env);
@@ -392,6 +392,72 @@ impl Options {
                         specifically by modifyig the \"nth\" such operation",
                     ),
             )
+            .arg(
+                Arg::new("mutation-cmp-flip")
+                    .long("mutation-cmp-flip")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should negate the indicated comparison (e.g. `<` to `>=`) \
+                        specifically by modifying the \"nth\" such operation",
+                    ),
+            )
+            .arg(
+                Arg::new("mutation-bool-negation")
+                    .long("mutation-bool-negation")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should negate the indicated boolean expression (removing `!`, \
+                        flipping a constant, or swapping `&&` and `||`) specifically by modifying the \"nth\" such operation",
+                    ),
+            )
+            .arg(
+                Arg::new("mutation-off-by-one")
+                    .long("mutation-off-by-one")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should change the indicated integer constant by one \
+                        specifically by modifying the \"nth\" such constant",
+                    ),
+            )
+            .arg(
+                Arg::new("mutation-assert-removal")
+                    .long("mutation-assert-removal")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should remove the indicated abort (e.g. of an `assert!`) \
+                        specifically by modifying the \"nth\" such abort",
+                    ),
+            )
+            .arg(
+                Arg::new("mutation-if-swap")
+                    .long("mutation-if-swap")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should swap the branches of the indicated conditional \
+                        specifically by modifying the \"nth\" such conditional",
+                    ),
+            )
+            .arg(
+                Arg::new("mutation-stmt-deletion")
+                    .long("mutation-stmt-deletion")
+                    .takes_value(true)
+                    .value_name("COUNT")
+                    .validator(is_number)
+                    .help(
+                        "indicates that this program should delete the indicated statement (a call without results, \
+                        `move_to` or write through a reference) specifically by modifying the \"nth\" such statement",
+                    ),
+            )
             .arg(
                 Arg::new("dependencies")
                     .long("dependency")
@@ -465,6 +531,13 @@ impl Options {
                      and suitable for baseline tests. This redacts values in diagnosis which might\
                      be non-deterministic, and may do other things to keep output stable.")
             )
+            .arg(
+                Arg::new("counterexample-tests")
+                    .long("counterexample-tests")
+                    .help("renders counterexamples of verification errors as unit tests which \
+                     replay them when added to the module of the verified function and run via \
+                     `move test`")
+            )
             .arg(
                 Arg::new("use-cvc5")
                     .long("use-cvc5")
@@ -644,6 +717,42 @@ impl Options {
                 .unwrap()
                 .parse::<usize>()?;
         }
+        if matches.is_present("mutation-cmp-flip") {
+            options.prover.mutation_cmp_flip = matches
+                .value_of("mutation-cmp-flip")
+                .unwrap()
+                .parse::<usize>()?;
+        }
+        if matches.is_present("mutation-bool-negation") {
+            options.prover.mutation_bool_negation = matches
+                .value_of("mutation-bool-negation")
+                .unwrap()
+                .parse::<usize>()?;
+        }
+        if matches.is_present("mutation-off-by-one") {
+            options.prover.mutation_off_by_one = matches
+                .value_of("mutation-off-by-one")
+                .unwrap()
+                .parse::<usize>()?;
+        }
+        if matches.is_present("mutation-assert-removal") {
+            options.prover.mutation_assert_removal = matches
+                .value_of("mutation-assert-removal")
+                .unwrap()
+                .parse::<usize>()?;
+        }
+        if matches.is_present("mutation-if-swap") {
+            options.prover.mutation_if_swap = matches
+                .value_of("mutation-if-swap")
+                .unwrap()
+                .parse::<usize>()?;
+        }
+        if matches.is_present("mutation-stmt-deletion") {
+            options.prover.mutation_stmt_deletion = matches
+                .value_of("mutation-stmt-deletion")
+                .unwrap()
+                .parse::<usize>()?;
+        }
         if matches.is_present("verify") {
             options.prover.verify_scope = match matches.value_of("verify").unwrap() {
                 "public" => VerificationScope::Public,
@@ -715,6 +824,9 @@ impl Options {
             //options.prover.stable_test_output = true;
             options.backend.stable_test_output = true;
         }
+        if matches.is_present("counterexample-tests") {
+            options.backend.counterexample_tests = true;
+        }
         if matches.is_present("keep") {
             options.backend.keep_artifacts = true;
         }
@@ -0,0 +1,23 @@
+// flag: --counterexample-tests
+module 0x42::TestCounterexampleTests {
+
+    spec module {
+        pragma verify = true;
+    }
+
+    // fails, because the abort is not covered by the specification.
+    fun abort_incorrect(x: u64) {
+        if (x == 7) abort 3
+    }
+    spec abort_incorrect {
+        aborts_if false;
+    }
+
+    // fails, because the result is not incremented.
+    fun inc_incorrect(x: u64): u64 {
+        x
+    }
+    spec inc_incorrect {
+        ensures result == x + 1;
+    }
+}
@@ -16,6 +16,16 @@ use move_vm_types::{
 use smallvec::smallvec;
 use std::{collections::VecDeque, sync::Arc};
 
+/// Runs `f`, collecting the output of `debug::print` and `debug::print_stack_trace` calls made
+/// on the current thread in the meantime. The output is still printed as well.
+#[cfg(feature = "testing")]
+pub fn capture_output<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = testing::CAPTURED_OUTPUT.with(|c| c.replace(Some(String::new())));
+    let result = f();
+    let captured = testing::CAPTURED_OUTPUT.with(|c| c.replace(outer));
+    (result, captured.unwrap_or_default())
+}
+
 /***************************************************************************************************
  * native fun print
  *
@@ -61,7 +71,7 @@ fn native_print(
             single_line,
             include_int_types,
         )?;
-        println!("{}", out);
+        testing::emit_output(&out);
     }
 
     Ok(NativeResult::ok(gas_params.base_cost, smallvec![]))
@@ -103,7 +113,7 @@ fn native_print_stack_trace(
     {
         let mut s = String::new();
         context.print_stack_trace(&mut s)?;
-        println!("{}", s);
+        testing::emit_output(&s);
     }
 
     Ok(NativeResult::ok(gas_params.base_cost, smallvec![]))
@@ -152,7 +162,22 @@ mod testing {
     };
     use move_vm_runtime::native_functions::NativeContext;
     use move_vm_types::{loaded_data::runtime_types::Type, values::Value};
-    use std::{fmt, fmt::Write};
+    use std::{cell::RefCell, fmt, fmt::Write};
+
+    thread_local! {
+        /// Output collected by `super::capture_output`, if it is active on this thread.
+        pub(super) static CAPTURED_OUTPUT: RefCell<Option<String>> = const { RefCell::new(None) };
+    }
+
+    pub(super) fn emit_output(out: &str) {
+        println!("{}", out);
+        CAPTURED_OUTPUT.with(|c| {
+            if let Some(captured) = c.borrow_mut().as_mut() {
+                captured.push_str(out);
+                captured.push('\n');
+            }
+        })
+    }
 
     const VECTOR_BEGIN: &str = "[";
 
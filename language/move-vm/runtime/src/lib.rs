@@ -21,6 +21,8 @@ mod runtime;
 pub mod session;
 #[macro_use]
 mod tracing;
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub use tracing::collect_executed_instructions;
 pub mod config;
 
 // Only include debugging functionality in debug builds
@@ -11,6 +11,8 @@ use ::{
     move_vm_types::values::Locals,
     once_cell::sync::Lazy,
     std::{
+        cell::RefCell,
+        collections::BTreeSet,
         env,
         fs::{File, OpenOptions},
         io::Write,
@@ -56,6 +58,12 @@ static LOGGING_FILE: Lazy<Mutex<File>> = Lazy::new(|| {
     )
 });
 
+#[cfg(any(debug_assertions, feature = "debugging"))]
+thread_local! {
+    static EXECUTED_INSTRUCTIONS: RefCell<Option<BTreeSet<(String, u16)>>> =
+        const { RefCell::new(None) };
+}
+
 #[cfg(any(debug_assertions, feature = "debugging"))]
 static DEBUG_CONTEXT: Lazy<Mutex<DebugContext>> = Lazy::new(|| Mutex::new(DebugContext::new()));
 
@@ -82,6 +90,11 @@ pub(crate) fn trace(
         )
         .unwrap();
     }
+    EXECUTED_INSTRUCTIONS.with(|executed| {
+        if let Some(executed) = executed.borrow_mut().as_mut() {
+            executed.insert((function_desc.pretty_string(), pc));
+        }
+    });
     if *DEBUGGING_ENABLED {
         DEBUG_CONTEXT
             .lock()
@@ -90,6 +103,19 @@ pub(crate) fn trace(
     }
 }
 
+/// Runs `f` and returns the instructions the VM executed on the current thread meanwhile, as the
+/// fully qualified name of their function and their code offset. Unlike the trace file, this is
+/// meant for tools which need coverage feedback while they run, such as fuzzers.
+#[cfg(any(debug_assertions, feature = "debugging"))]
+pub fn collect_executed_instructions<T>(f: impl FnOnce() -> T) -> (T, BTreeSet<(String, u16)>) {
+    let previous = EXECUTED_INSTRUCTIONS.with(|executed| executed.replace(Some(BTreeSet::new())));
+    let result = f();
+    let executed = EXECUTED_INSTRUCTIONS
+        .with(|executed| executed.replace(previous))
+        .unwrap_or_default();
+    (result, executed)
+}
+
 #[macro_export]
 macro_rules! trace {
     ($function_desc:expr, $locals:expr, $pc:expr, $instr:tt, $resolver:expr, $interp:expr) => {
@@ -113,6 +113,7 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    instructions_executed: u64,
 }
 
 impl<'a> GasStatus<'a> {
@@ -125,6 +126,7 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            instructions_executed: 0,
         }
     }
 
@@ -137,6 +139,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            instructions_executed: 0,
         }
     }
 
@@ -150,6 +153,16 @@ impl<'a> GasStatus<'a> {
         self.gas_left.to_unit_round_down()
     }
 
+    /// Return the gas left, in internal gas units.
+    pub fn remaining_internal_gas(&self) -> InternalGas {
+        self.gas_left
+    }
+
+    /// Return the number of instructions charged for so far.
+    pub fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
     /// Charge a given amount of gas and fail if not enough gas units are left.
     pub fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
         if !self.charge {
@@ -169,6 +182,7 @@ impl<'a> GasStatus<'a> {
     }
 
     fn charge_instr(&mut self, opcode: Opcodes) -> PartialVMResult<()> {
+        self.instructions_executed += 1;
         self.deduct_gas(
             self.cost_table
                 .instruction_cost(opcode as u8)
@@ -183,6 +197,7 @@ impl<'a> GasStatus<'a> {
         opcode: Opcodes,
         size: AbstractMemorySize,
     ) -> PartialVMResult<()> {
+        self.instructions_executed += 1;
         // Make sure that the size is always non-zero
         let size = std::cmp::max(1.into(), size);
         debug_assert!(size > 0.into());
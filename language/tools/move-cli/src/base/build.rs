@@ -9,7 +9,11 @@ use std::path::PathBuf;
 /// Build the package at `path`. If no path is provided defaults to current directory.
 #[derive(Parser)]
 #[clap(name = "build")]
-pub struct Build;
+pub struct Build {
+    /// Build all members of the workspace containing the package, in dependency order.
+    #[clap(long = "workspace")]
+    pub workspace: bool,
+}
 
 impl Build {
     pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
@@ -25,10 +29,18 @@ impl Build {
         let architecture = config.architecture.unwrap_or(Architecture::Move);
 
         match architecture {
+            Architecture::Move | Architecture::AsyncMove if self.workspace => {
+                config.compile_workspace(&rerooted_path, &mut std::io::stdout())?;
+            }
+
             Architecture::Move | Architecture::AsyncMove => {
                 config.compile_package(&rerooted_path, &mut std::io::stdout())?;
             }
 
+            Architecture::Ethereum if self.workspace => {
+                anyhow::bail!("Workspaces cannot be built for the Ethereum architecture");
+            }
+
             Architecture::Ethereum => {
                 #[cfg(feature = "evm-backend")]
                 config.compile_package_evm(&rerooted_path, &mut std::io::stderr())?;
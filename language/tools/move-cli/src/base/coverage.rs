@@ -5,12 +5,19 @@ use super::reroot_path;
 use clap::*;
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_coverage::{
-    coverage_map::CoverageMap, format_csv_summary, format_human_summary,
-    source_coverage::SourceCoverageBuilder, summary::summarize_inst_cov,
+    coverage_map::CoverageMap,
+    coverage_report::{render_cobertura, render_lcov, ModuleLineCoverage},
+    format_csv_summary, format_human_summary,
+    source_coverage::SourceCoverageBuilder,
+    summary::summarize_inst_cov,
 };
 use move_disassembler::disassembler::Disassembler;
-use move_package::BuildConfig;
-use std::path::PathBuf;
+use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 #[derive(Parser)]
 pub enum CoverageSummaryOptions {
@@ -36,6 +43,24 @@ pub enum CoverageSummaryOptions {
         #[clap(long = "module")]
         module_name: String,
     },
+    /// Export line and branch coverage of all modules in this package in the LCOV format.
+    /// Branch hit counts are derived from instruction execution counts, and are approximate
+    /// where both targets of a branch are also reached from elsewhere
+    #[clap(name = "lcov")]
+    Lcov {
+        /// Write the report to this file instead of stdout
+        #[clap(long = "output", short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Export line and branch coverage of all modules in this package in the Cobertura XML
+    /// format. Branch coverage is derived as for `lcov`, and is approximate where both targets
+    /// of a branch are also reached from elsewhere
+    #[clap(name = "cobertura")]
+    Cobertura {
+        /// Write the report to this file instead of stdout
+        #[clap(long = "output", short = 'o')]
+        output: Option<PathBuf>,
+    },
 }
 
 /// Inspect test coverage for this package. A previous test run with the `--coverage` flag must
@@ -104,7 +129,59 @@ impl Coverage {
                 disassembler.add_coverage_map(coverage_map.to_unified_exec_map());
                 println!("{}", disassembler.disassemble()?);
             }
+            CoverageSummaryOptions::Lcov { output } => {
+                let modules = line_coverage(&package, &coverage_map)?;
+                write_report(output.as_deref(), &render_lcov(&modules))?;
+            }
+            CoverageSummaryOptions::Cobertura { output } => {
+                let modules = line_coverage(&package, &coverage_map)?;
+                let timestamp = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)?
+                    .as_millis() as u64;
+                let report = render_cobertura(
+                    &modules,
+                    package.compiled_package_info.package_name.as_str(),
+                    &std::env::current_dir()?,
+                    timestamp,
+                );
+                write_report(output.as_deref(), &report)?;
+            }
         }
         Ok(())
     }
 }
+
+/// Maps the coverage of the modules of the root package to the lines of their sources.
+fn line_coverage(
+    package: &CompiledPackage,
+    coverage_map: &CoverageMap,
+) -> anyhow::Result<Vec<ModuleLineCoverage>> {
+    let coverage_map = coverage_map.to_unified_exec_map();
+    package
+        .root_modules()
+        .filter_map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => Some((unit, module, source_map)),
+            _ => None,
+        })
+        .map(|(unit, module, source_map)| {
+            let source = fs::read_to_string(&unit.source_path)?;
+            ModuleLineCoverage::new(
+                module,
+                source_map,
+                &unit.source_path,
+                &source,
+                &coverage_map,
+            )
+        })
+        .collect()
+}
+
+fn write_report(output: Option<&Path>, report: &str) -> anyhow::Result<()> {
+    match output {
+        Some(path) => fs::write(path, report)?,
+        None => print!("{}", report),
+    }
+    Ok(())
+}
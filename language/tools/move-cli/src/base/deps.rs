@@ -0,0 +1,58 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use move_package::BuildConfig;
+use move_symbol_pool::Symbol;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub enum DepsCommand {
+    /// Print the dependency graph of this package as a tree, with the source of each dependency
+    /// and the digest of its sources.
+    #[clap(name = "tree")]
+    Tree,
+    /// Print every path from this package to one of its transitive dependencies.
+    #[clap(name = "why")]
+    Why {
+        /// The name of the dependency
+        #[clap(value_name = "PACKAGE")]
+        package: String,
+    },
+    /// List the git dependencies whose commit pinned in Move.lock is behind the latest commit of
+    /// the branch they track, in the local copy of their repository.
+    #[clap(name = "outdated")]
+    Outdated {
+        /// Fetch the latest commits of the tracked branches before comparing against them
+        #[clap(long = "fetch")]
+        fetch: bool,
+    },
+}
+
+/// Inspect the dependencies of this package.
+#[derive(Parser)]
+#[clap(name = "deps")]
+pub struct Deps {
+    #[clap(subcommand)]
+    pub cmd: DepsCommand,
+}
+
+impl Deps {
+    pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let mut stdout = std::io::stdout();
+
+        match self.cmd {
+            DepsCommand::Tree => config
+                .dependency_graph_for_package(&rerooted_path, &mut stdout)?
+                .print_tree(&mut stdout),
+            DepsCommand::Why { package } => config
+                .dependency_graph_for_package(&rerooted_path, &mut stdout)?
+                .print_why(Symbol::from(package), &mut stdout),
+            DepsCommand::Outdated { fetch } => {
+                config.print_outdated_dependencies(&rerooted_path, fetch, &mut stdout)
+            }
+        }
+    }
+}
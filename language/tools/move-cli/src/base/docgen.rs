@@ -3,7 +3,7 @@
 
 use super::reroot_path;
 use clap::*;
-use move_docgen::DocgenOptions;
+use move_docgen::{DocFormat, DocgenOptions};
 use move_package::{BuildConfig, ModelConfig};
 use std::{fs, path::PathBuf};
 
@@ -53,6 +53,10 @@ pub struct Docgen {
     /// If this is being compiled relative to a different place where it will be stored (output directory)
     #[clap(long = "compile-relative-to-output-dir")]
     pub compile_relative_to_output_dir: bool,
+    /// The format of the generated documentation: `markdown`, or `html` for a static site
+    /// which also documents the dependencies
+    #[clap(long = "format", value_name = "FORMAT", default_value = "markdown")]
+    pub format: DocFormat,
 }
 
 impl Docgen {
@@ -101,6 +105,7 @@ impl Docgen {
         if self.compile_relative_to_output_dir {
             options.compile_relative_to_output_dir = true;
         }
+        options.output_format = self.format;
 
         // We are using the full namespace, since we already use `Docgen` here.
         // Docgen is the most suitable name for both: this Docgen subcommand,
@@ -0,0 +1,727 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use crate::{
+    sandbox::utils::{get_gas_status, module, on_disk_state_view::OnDiskStateView, PackageContext},
+    NativeFunctionRecord, DEFAULT_STORAGE_DIR,
+};
+use anyhow::{bail, Result};
+use clap::*;
+use move_binary_format::{
+    access::ModuleAccess,
+    errors::{Location, VMError},
+    file_format::{SignatureToken, Visibility},
+    CompiledModule,
+};
+use move_command_line_common::env::get_bytecode_version_from_env;
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Op},
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
+};
+use move_package::BuildConfig;
+use move_unit_test::random_test::{generate_value, shrink_value};
+use move_vm_runtime::{collect_executed_instructions, move_vm::MoveVM};
+use move_vm_test_utils::{gas_schedule::CostTable, DeltaStorage};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::BTreeSet, path::PathBuf};
+
+/// The maximal number of call sequences executed while minimizing a failure.
+const MAX_SHRINK_ATTEMPTS: usize = 2000;
+
+/// Fuzz the public and entry functions of this package. Sequences of calls with generated
+/// arguments and signers are executed against the sandbox storage, guided by the instructions they
+/// cover. A call sequence fails if a call ends with an unexpected status code (anything but an
+/// abort or running out of gas, by default) or if a call leaves the state violating an invariant.
+/// The state in the storage directory is never modified.
+#[derive(Parser)]
+#[clap(name = "fuzz")]
+pub struct Fuzz {
+    /// Directory storing the Move resources and module bytecodes to fuzz against.
+    #[clap(long, default_value = DEFAULT_STORAGE_DIR, parse(from_os_str))]
+    pub storage_dir: PathBuf,
+    /// Number of call sequences to execute
+    #[clap(long = "runs", default_value = "10000")]
+    pub runs: u64,
+    /// Maximal number of calls in a sequence
+    #[clap(long = "max-calls", default_value = "8")]
+    pub max_calls: usize,
+    /// Seed for the generation of call sequences
+    #[clap(long = "seed", default_value = "0")]
+    pub seed: u64,
+    /// Only call functions whose `module::function` name contains this string
+    #[clap(name = "filter", long = "filter", short = 'f')]
+    pub filter: Option<String>,
+    /// Addresses of the signers passed to the called functions. Address arguments are usually
+    /// drawn from these as well.
+    #[clap(
+        long = "signers",
+        parse(try_from_str = AccountAddress::from_hex_literal),
+        default_values = &["0xA", "0xB", "0xC"],
+        takes_value(true),
+        multiple_values(true),
+        multiple_occurrences(true)
+    )]
+    pub signers: Vec<AccountAddress>,
+    /// A function `<module>::<function>` of this package without parameters, returning nothing
+    /// or a `bool`, which is checked after every successful call. The invariant is violated if it
+    /// aborts or returns `false`.
+    #[clap(
+        long = "invariant",
+        takes_value(true),
+        multiple_values(true),
+        multiple_occurrences(true)
+    )]
+    pub invariants: Vec<String>,
+    /// Status codes (e.g. `ARITHMETIC_ERROR`) which are expected outcomes of a call, in addition
+    /// to `ABORTED` and `OUT_OF_GAS`
+    #[clap(
+        long = "allow-status",
+        parse(try_from_str = parse_status_code),
+        takes_value(true),
+        multiple_values(true),
+        multiple_occurrences(true)
+    )]
+    pub allowed_statuses: Vec<StatusCode>,
+    /// Maximum number of gas units to be consumed by a call. Calls are unmetered if not set.
+    #[clap(long = "gas-budget")]
+    pub gas_budget: Option<u64>,
+}
+
+/// A parameter of a fuzzed function.
+enum Param {
+    Signer,
+    SignerRef,
+    Value(MoveTypeLayout),
+}
+
+/// A function which can be called by the fuzzer.
+struct Target {
+    module: ModuleId,
+    function: Identifier,
+    params: Vec<Param>,
+    is_private_entry: bool,
+}
+
+struct Invariant {
+    module: ModuleId,
+    function: Identifier,
+    returns_bool: bool,
+}
+
+#[derive(Clone)]
+struct Call {
+    target: usize,
+    args: Vec<MoveValue>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FailureKind {
+    UnexpectedStatus(StatusCode, Location),
+    InvariantViolated(usize),
+}
+
+struct Failure {
+    /// The index of the call in the sequence after which the failure occurred
+    call: usize,
+    kind: FailureKind,
+    message: String,
+}
+
+struct Fuzzer<'a> {
+    vm: MoveVM,
+    state: &'a OnDiskStateView,
+    cost_table: &'a CostTable,
+    gas_budget: Option<u64>,
+    /// Publishes the modules of the package on top of the storage
+    modules: ChangeSet,
+    targets: Vec<Target>,
+    invariants: Vec<Invariant>,
+    signers: Vec<AccountAddress>,
+    allowed_statuses: Vec<StatusCode>,
+    max_calls: usize,
+}
+
+impl Fuzz {
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        config: BuildConfig,
+        natives: Vec<NativeFunctionRecord>,
+        cost_table: &CostTable,
+    ) -> Result<()> {
+        let path = reroot_path(path)?;
+        if self.signers.is_empty() {
+            bail!("At least one signer is required")
+        }
+        if self.max_calls == 0 {
+            bail!("--max-calls must be at least 1")
+        }
+        // reject an invalid budget upfront rather than on the first call
+        get_gas_status(cost_table, self.gas_budget)?;
+
+        let context = PackageContext::new(&Some(path), &config)?;
+        let state = context.prepare_state(&self.storage_dir)?;
+        let bytecode_version = get_bytecode_version_from_env();
+        let root_modules = context
+            .package()
+            .root_modules()
+            .map(|unit| module(&unit.unit))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut modules = ChangeSet::new();
+        for module in &root_modules {
+            let mut bytes = vec![];
+            module.serialize_for_version(bytecode_version, &mut bytes)?;
+            modules.add_module_op(module.self_id(), Op::New(bytes))?;
+        }
+        let invariants = self
+            .invariants
+            .iter()
+            .map(|name| find_invariant(&root_modules, name))
+            .collect::<Result<Vec<_>>>()?;
+        let targets: Vec<_> = root_modules
+            .iter()
+            .flat_map(|module| fuzz_targets(module))
+            .filter(|target| {
+                !invariants
+                    .iter()
+                    .any(|inv| inv.module == target.module && inv.function == target.function)
+            })
+            .filter(|target| match &self.filter {
+                Some(filter) => format!("{}::{}", target.module.name(), target.function)
+                    .contains(filter.as_str()),
+                None => true,
+            })
+            .collect();
+        if targets.is_empty() {
+            bail!(
+                "No functions to fuzz. Only public or entry functions without type parameters, \
+                 whose parameters are signers or primitive values, can be fuzzed"
+            )
+        }
+
+        let fuzzer = Fuzzer {
+            vm: MoveVM::new(natives)?,
+            state: &state,
+            cost_table,
+            gas_budget: self.gas_budget,
+            modules,
+            targets,
+            invariants,
+            signers: self.signers,
+            allowed_statuses: self.allowed_statuses,
+            max_calls: self.max_calls,
+        };
+        fuzzer.run(self.runs, self.seed)
+    }
+}
+
+impl<'a> Fuzzer<'a> {
+    fn run(&self, runs: u64, seed: u64) -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut corpus: Vec<Vec<Call>> = vec![];
+        let mut covered = BTreeSet::new();
+        for _ in 0..runs {
+            let calls = if corpus.is_empty() || rng.gen_bool(0.2) {
+                self.generate_sequence(&mut rng)
+            } else {
+                let calls = corpus[rng.gen_range(0..corpus.len())].clone();
+                let other = &corpus[rng.gen_range(0..corpus.len())];
+                self.mutate(calls, other, &mut rng)
+            };
+            let (failure, coverage) = self.run_sequence(&calls)?;
+            if let Some(failure) = failure {
+                let (calls, failure) = self.minimize(calls, failure)?;
+                self.report(&calls, &failure);
+                bail!("Fuzzing found a failing call sequence (seed {})", seed)
+            }
+            let previously_covered = covered.len();
+            covered.extend(coverage);
+            if covered.len() > previously_covered {
+                corpus.push(calls);
+            }
+        }
+        println!(
+            "Executed {} call sequences without failures, covering {} instructions \
+             ({} sequences in the corpus)",
+            runs,
+            covered.len(),
+            corpus.len()
+        );
+        Ok(())
+    }
+
+    /// Executes `calls` in order, discarding the effects of calls which abort, and returns the
+    /// first failure along with the instructions executed by the calls.
+    fn run_sequence(&self, calls: &[Call]) -> Result<(Option<Failure>, BTreeSet<(String, u16)>)> {
+        let mut delta = self.modules.clone();
+        let mut coverage = BTreeSet::new();
+        for (i, call) in calls.iter().enumerate() {
+            let (result, executed) = collect_executed_instructions(|| self.execute(&delta, call));
+            coverage.extend(executed);
+            match result {
+                Ok(changes) => delta.squash(changes)?,
+                Err(err) if self.is_expected(&err) => continue,
+                Err(err) => {
+                    let failure = Failure {
+                        call: i,
+                        kind: FailureKind::UnexpectedStatus(
+                            err.major_status(),
+                            err.location().clone(),
+                        ),
+                        message: format!("The call {}", describe_error(&err)),
+                    };
+                    return Ok((Some(failure), coverage));
+                }
+            }
+            for (j, invariant) in self.invariants.iter().enumerate() {
+                if let Some(violation) = self.check_invariant(&delta, invariant) {
+                    let failure = Failure {
+                        call: i,
+                        kind: FailureKind::InvariantViolated(j),
+                        message: format!(
+                            "The invariant {}::{} was violated after the call: it {}",
+                            invariant.module.name(),
+                            invariant.function,
+                            violation
+                        ),
+                    };
+                    return Ok((Some(failure), coverage));
+                }
+            }
+        }
+        Ok((None, coverage))
+    }
+
+    fn execute(&self, delta: &ChangeSet, call: &Call) -> Result<ChangeSet, VMError> {
+        let storage = DeltaStorage::new(self.state, delta);
+        let mut session = self.vm.new_session(&storage);
+        let mut gas_status =
+            get_gas_status(self.cost_table, self.gas_budget).expect("gas budget was validated");
+        let target = &self.targets[call.target];
+        let args: Vec<_> = call
+            .args
+            .iter()
+            .map(|arg| {
+                arg.simple_serialize()
+                    .expect("generated arguments must serialize")
+            })
+            .collect();
+        session.execute_function_bypass_visibility(
+            &target.module,
+            &target.function,
+            vec![],
+            args,
+            &mut gas_status,
+        )?;
+        let (changes, _events) = session.finish()?;
+        Ok(changes)
+    }
+
+    /// Returns how the invariant was violated, if it was.
+    fn check_invariant(&self, delta: &ChangeSet, invariant: &Invariant) -> Option<String> {
+        let storage = DeltaStorage::new(self.state, delta);
+        let mut session = self.vm.new_session(&storage);
+        let mut gas_status =
+            get_gas_status(self.cost_table, None).expect("unmetered gas status is always valid");
+        match session.execute_function_bypass_visibility(
+            &invariant.module,
+            &invariant.function,
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            &mut gas_status,
+        ) {
+            Err(err) => Some(describe_error(&err)),
+            Ok(values) if invariant.returns_bool => {
+                match bcs::from_bytes::<bool>(&values.return_values[0].0) {
+                    Ok(true) => None,
+                    _ => Some("returned false".to_string()),
+                }
+            }
+            Ok(_) => None,
+        }
+    }
+
+    fn is_expected(&self, err: &VMError) -> bool {
+        let status = err.major_status();
+        matches!(status, StatusCode::ABORTED | StatusCode::OUT_OF_GAS)
+            || self.allowed_statuses.contains(&status)
+    }
+
+    fn generate_sequence(&self, rng: &mut StdRng) -> Vec<Call> {
+        let len = rng.gen_range(1..=self.max_calls);
+        (0..len).map(|_| self.generate_call(rng)).collect()
+    }
+
+    fn generate_call(&self, rng: &mut StdRng) -> Call {
+        let target = rng.gen_range(0..self.targets.len());
+        let args = self.targets[target]
+            .params
+            .iter()
+            .map(|param| self.generate_arg(param, rng))
+            .collect();
+        Call { target, args }
+    }
+
+    fn generate_arg(&self, param: &Param, rng: &mut StdRng) -> MoveValue {
+        match param {
+            Param::Signer | Param::SignerRef => MoveValue::Signer(self.pick_signer(rng)),
+            // addresses drawn from the signers are far more likely to hit existing resources
+            Param::Value(MoveTypeLayout::Address) if rng.gen_bool(0.8) => {
+                MoveValue::Address(self.pick_signer(rng))
+            }
+            Param::Value(layout) => generate_value(layout, rng),
+        }
+    }
+
+    fn pick_signer(&self, rng: &mut StdRng) -> AccountAddress {
+        self.signers[rng.gen_range(0..self.signers.len())]
+    }
+
+    fn mutate(&self, mut calls: Vec<Call>, other: &[Call], rng: &mut StdRng) -> Vec<Call> {
+        match rng.gen_range(0..5) {
+            0 if calls.len() < self.max_calls => {
+                let i = rng.gen_range(0..=calls.len());
+                calls.insert(i, self.generate_call(rng));
+            }
+            1 if calls.len() > 1 => {
+                calls.remove(rng.gen_range(0..calls.len()));
+            }
+            2 => {
+                let len = calls.len();
+                let call = &mut calls[rng.gen_range(0..len)];
+                let params = &self.targets[call.target].params;
+                if !params.is_empty() {
+                    let i = rng.gen_range(0..params.len());
+                    call.args[i] = self.generate_arg(&params[i], rng);
+                }
+            }
+            3 => {
+                let len = calls.len();
+                let call = &mut calls[rng.gen_range(0..len)];
+                if !call.args.is_empty() {
+                    let i = rng.gen_range(0..call.args.len());
+                    let candidates = shrink_value(&call.args[i]);
+                    if !candidates.is_empty() {
+                        call.args[i] = candidates[rng.gen_range(0..candidates.len())].clone();
+                    }
+                }
+            }
+            _ => {
+                let i = rng.gen_range(0..=calls.len());
+                let j = rng.gen_range(0..=other.len());
+                calls.truncate(i);
+                calls.extend(other[j..].iter().cloned());
+                calls.truncate(self.max_calls);
+                if calls.is_empty() {
+                    calls.push(self.generate_call(rng));
+                }
+            }
+        }
+        calls
+    }
+
+    /// Simplifies a failing call sequence, first by removing calls and then by shrinking
+    /// arguments, as long as it still fails in the same way.
+    fn minimize(&self, mut calls: Vec<Call>, mut failure: Failure) -> Result<(Vec<Call>, Failure)> {
+        calls.truncate(failure.call + 1);
+        let mut attempts = 0;
+        'improve: loop {
+            for candidate in self.simplifications(&calls) {
+                if attempts >= MAX_SHRINK_ATTEMPTS {
+                    break 'improve;
+                }
+                attempts += 1;
+                if let (Some(candidate_failure), _) = self.run_sequence(&candidate)? {
+                    if candidate_failure.kind == failure.kind {
+                        calls = candidate;
+                        calls.truncate(candidate_failure.call + 1);
+                        failure = candidate_failure;
+                        continue 'improve;
+                    }
+                }
+            }
+            break;
+        }
+        Ok((calls, failure))
+    }
+
+    fn simplifications(&self, calls: &[Call]) -> Vec<Vec<Call>> {
+        let removals = (0..calls.len()).filter(|_| calls.len() > 1).map(|i| {
+            let mut calls = calls.to_vec();
+            calls.remove(i);
+            calls
+        });
+        let shrunk_args = calls.iter().enumerate().flat_map(|(i, call)| {
+            call.args.iter().enumerate().flat_map(move |(j, arg)| {
+                shrink_value(arg).into_iter().map(move |candidate| {
+                    let mut calls = calls.to_vec();
+                    calls[i].args[j] = candidate;
+                    calls
+                })
+            })
+        });
+        removals.chain(shrunk_args).collect()
+    }
+
+    fn report(&self, calls: &[Call], failure: &Failure) {
+        println!("Found a failing sequence of {} call(s):", calls.len());
+        for call in calls {
+            println!(
+                "  {}",
+                self.render_call(call, &|addr| format!("@0x{}", addr.short_str_lossless()))
+            );
+        }
+        println!("{}", failure.message);
+        println!();
+        println!("Reproducer:");
+        println!("{}", self.render_reproducer(calls));
+
+        if self.state.resource_paths().next().is_some() {
+            println!(
+                "Note: the calls were executed against the resources in the storage directory, \
+                 while the reproducer starts from an empty storage"
+            );
+        }
+        let private_entries: BTreeSet<_> = calls
+            .iter()
+            .map(|call| &self.targets[call.target])
+            .filter(|target| target.is_private_entry)
+            .map(|target| format!("{}::{}", target.module.name(), target.function))
+            .collect();
+        for name in private_entries {
+            println!(
+                "Note: `{}` is a private entry function, which can only be called from a test \
+                 in its own module",
+                name
+            );
+        }
+    }
+
+    /// Renders the call sequence as a Move unit test.
+    fn render_reproducer(&self, calls: &[Call]) -> String {
+        let mut signers = vec![];
+        for call in calls {
+            for arg in &call.args {
+                if let MoveValue::Signer(addr) = arg {
+                    if !signers.contains(addr) {
+                        signers.push(*addr);
+                    }
+                }
+            }
+        }
+        let signer_name = |addr: &AccountAddress| {
+            let i = signers.iter().position(|s| s == addr).unwrap();
+            format!("signer_{}", i)
+        };
+
+        let mut out = String::new();
+        let attribute_args: Vec<_> = signers
+            .iter()
+            .map(|addr| format!("{} = @0x{}", signer_name(addr), addr.short_str_lossless()))
+            .collect();
+        if attribute_args.is_empty() {
+            out.push_str("#[test]\n");
+        } else {
+            out.push_str(&format!("#[test({})]\n", attribute_args.join(", ")));
+        }
+        let params: Vec<_> = signers
+            .iter()
+            .map(|addr| format!("{}: signer", signer_name(addr)))
+            .collect();
+        out.push_str(&format!("fun fuzz_reproducer({}) {{\n", params.join(", ")));
+        for call in calls {
+            out.push_str(&format!("    {};\n", self.render_call(call, &signer_name)));
+        }
+        out.push('}');
+        out
+    }
+
+    fn render_call(&self, call: &Call, signer: &dyn Fn(&AccountAddress) -> String) -> String {
+        let target = &self.targets[call.target];
+        let args: Vec<_> = target
+            .params
+            .iter()
+            .zip(&call.args)
+            .map(|(param, arg)| match (param, arg) {
+                (Param::SignerRef, MoveValue::Signer(addr)) => format!("&{}", signer(addr)),
+                (Param::Signer, MoveValue::Signer(addr)) => signer(addr),
+                (_, value) => render_value(value),
+            })
+            .collect();
+        format!(
+            "{}::{}({})",
+            target.module.short_str_lossless(),
+            target.function,
+            args.join(", ")
+        )
+    }
+}
+
+/// The functions of `module` which can be fuzzed: public or entry functions without type
+/// parameters, whose parameters can all be generated.
+fn fuzz_targets(module: &CompiledModule) -> Vec<Target> {
+    module
+        .function_defs()
+        .iter()
+        .filter(|def| def.visibility == Visibility::Public || def.is_entry)
+        .filter_map(|def| {
+            let handle = module.function_handle_at(def.function);
+            if !handle.type_parameters.is_empty() {
+                return None;
+            }
+            let params = module
+                .signature_at(handle.parameters)
+                .0
+                .iter()
+                .map(param)
+                .collect::<Option<Vec<_>>>()?;
+            Some(Target {
+                module: module.self_id(),
+                function: module.identifier_at(handle.name).to_owned(),
+                params,
+                is_private_entry: def.is_entry && def.visibility == Visibility::Private,
+            })
+        })
+        .collect()
+}
+
+fn param(token: &SignatureToken) -> Option<Param> {
+    match token {
+        SignatureToken::Signer => Some(Param::Signer),
+        SignatureToken::Reference(inner) if **inner == SignatureToken::Signer => {
+            Some(Param::SignerRef)
+        }
+        _ => value_layout(token).map(Param::Value),
+    }
+}
+
+fn value_layout(token: &SignatureToken) -> Option<MoveTypeLayout> {
+    use SignatureToken as S;
+    Some(match token {
+        S::Bool => MoveTypeLayout::Bool,
+        S::U8 => MoveTypeLayout::U8,
+        S::U16 => MoveTypeLayout::U16,
+        S::U32 => MoveTypeLayout::U32,
+        S::U64 => MoveTypeLayout::U64,
+        S::U128 => MoveTypeLayout::U128,
+        S::U256 => MoveTypeLayout::U256,
+        S::Address => MoveTypeLayout::Address,
+        S::Vector(elem) => MoveTypeLayout::Vector(Box::new(value_layout(elem)?)),
+        S::Signer
+        | S::Struct(_)
+        | S::StructInstantiation(_, _)
+        | S::Reference(_)
+        | S::MutableReference(_)
+        | S::TypeParameter(_) => return None,
+    })
+}
+
+/// Resolves an invariant given as `<module>::<function>` among the modules of the package.
+fn find_invariant(modules: &[&CompiledModule], name: &str) -> Result<Invariant> {
+    let (module_name, function_name) = match name.rsplit_once("::") {
+        Some(names) => names,
+        None => bail!(
+            "Invalid invariant `{}`. Expected `<module>::<function>`",
+            name
+        ),
+    };
+    let module = match modules
+        .iter()
+        .find(|module| module.self_id().name().as_str() == module_name)
+    {
+        Some(module) => module,
+        None => bail!(
+            "Unable to find module `{}` of invariant `{}`",
+            module_name,
+            name
+        ),
+    };
+    let handle = match module.function_defs().iter().find_map(|def| {
+        let handle = module.function_handle_at(def.function);
+        (module.identifier_at(handle.name).as_str() == function_name).then_some(handle)
+    }) {
+        Some(handle) => handle,
+        None => bail!("Unable to find function `{}`", name),
+    };
+    let returns_bool = match &module.signature_at(handle.return_).0[..] {
+        [] => false,
+        [SignatureToken::Bool] => true,
+        _ => bail!("Invariant `{}` must return nothing or a `bool`", name),
+    };
+    if !handle.type_parameters.is_empty() || !module.signature_at(handle.parameters).is_empty() {
+        bail!(
+            "Invariant `{}` must not have parameters or type parameters",
+            name
+        )
+    }
+    Ok(Invariant {
+        module: module.self_id(),
+        function: module.identifier_at(handle.name).to_owned(),
+        returns_bool,
+    })
+}
+
+fn describe_error(err: &VMError) -> String {
+    let location = match err.location() {
+        Location::Module(id) => format!(" in module {}", id.short_str_lossless()),
+        Location::Script | Location::Undefined => String::new(),
+    };
+    match err.major_status() {
+        StatusCode::ABORTED => format!(
+            "aborted with code {}{}",
+            err.sub_status().unwrap_or_default(),
+            location
+        ),
+        status => format!("failed with status {:?}{}", status, location),
+    }
+}
+
+/// Renders a generated value as a Move expression.
+fn render_value(value: &MoveValue) -> String {
+    match value {
+        MoveValue::Bool(b) => b.to_string(),
+        MoveValue::U8(x) => format!("{}u8", x),
+        MoveValue::U16(x) => format!("{}u16", x),
+        MoveValue::U32(x) => format!("{}u32", x),
+        MoveValue::U64(x) => format!("{}u64", x),
+        MoveValue::U128(x) => format!("{}u128", x),
+        MoveValue::U256(x) => format!("{}u256", x),
+        MoveValue::Address(addr) | MoveValue::Signer(addr) => {
+            format!("@0x{}", addr.short_str_lossless())
+        }
+        MoveValue::Vector(elems) if !elems.is_empty() => {
+            if let Some(bytes) = elems
+                .iter()
+                .map(|elem| match elem {
+                    MoveValue::U8(byte) => Some(format!("{:02x}", byte)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+            {
+                format!("x\"{}\"", bytes.concat())
+            } else {
+                let elems: Vec<_> = elems.iter().map(render_value).collect();
+                format!("vector[{}]", elems.join(", "))
+            }
+        }
+        MoveValue::Vector(_) => "vector[]".to_string(),
+        MoveValue::Struct(_) => unreachable!("structs are never generated"),
+    }
+}
+
+fn parse_status_code(name: &str) -> Result<StatusCode> {
+    // status codes are grouped into ranges below 5000
+    match (0..5000)
+        .filter_map(|code| StatusCode::try_from(code).ok())
+        .find(|status| format!("{:?}", status) == name)
+    {
+        Some(status) => Ok(status),
+        None => bail!("Unknown status code `{}`", name),
+    }
+}
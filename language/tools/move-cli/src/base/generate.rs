@@ -0,0 +1,69 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use move_abigen::BindingsLanguage;
+use move_package::{BuildConfig, ModelConfig};
+use std::{fs, path::PathBuf};
+
+#[derive(Parser)]
+pub enum GenerateCommand {
+    /// Generate typed client bindings for the modules of this package: types mirroring the
+    /// layouts of structs with their BCS encoding, builders for entry function payloads, and
+    /// resource decoders.
+    #[clap(name = "bindings")]
+    Bindings {
+        /// The language of the bindings, `rust` or `typescript`
+        #[clap(long = "lang", value_name = "LANGUAGE")]
+        language: BindingsLanguage,
+        /// The directory to write the bindings to, relative to the package root
+        #[clap(
+            long = "output-directory",
+            value_name = "PATH",
+            default_value = "bindings"
+        )]
+        output_directory: PathBuf,
+    },
+}
+
+/// Generate code from the modules of this package.
+#[derive(Parser)]
+#[clap(name = "generate")]
+pub struct Generate {
+    #[clap(subcommand)]
+    pub cmd: GenerateCommand,
+}
+
+impl Generate {
+    pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let model = config.move_model_for_package(
+            &rerooted_path,
+            ModelConfig {
+                all_files_as_targets: false,
+                target_filter: None,
+            },
+        )?;
+        anyhow::ensure!(
+            !model.has_errors(),
+            "Errors encountered while building the model of the package!"
+        );
+
+        match self.cmd {
+            GenerateCommand::Bindings {
+                language,
+                output_directory,
+            } => {
+                let output_directory = rerooted_path.join(output_directory);
+                for (file, content) in move_abigen::generate_bindings(&model, language) {
+                    let path = output_directory.join(file);
+                    fs::create_dir_all(path.parent().unwrap())?;
+                    fs::write(&path, content)?;
+                    println!("Generated {:?}", path);
+                }
+            }
+        }
+        Ok(())
+    }
+}
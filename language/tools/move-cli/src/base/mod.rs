@@ -3,13 +3,19 @@
 
 pub mod build;
 pub mod coverage;
+pub mod deps;
 pub mod disassemble;
 pub mod docgen;
 pub mod errmap;
+pub mod fuzz;
+pub mod generate;
 pub mod info;
 pub mod new;
+pub mod package;
 pub mod prove;
 pub mod test;
+pub mod vendor;
+pub mod verify_bytecode;
 
 use move_package::source_package::layout::SourcePackageLayout;
 use std::path::PathBuf;
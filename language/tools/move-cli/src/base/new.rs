@@ -1,6 +1,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{bail, Context};
 use clap::*;
 use move_package::source_package::layout::SourcePackageLayout;
 use std::{
@@ -18,6 +19,89 @@ pub const MOVE_STDLIB_PACKAGE_PATH: &str = "{ \
 pub const MOVE_STDLIB_ADDR_NAME: &str = "std";
 pub const MOVE_STDLIB_ADDR_VALUE: &str = "0x1";
 
+/// Variable substituted with the name of the package in templates.
+pub const TEMPLATE_PACKAGE_NAME_VAR: &str = "{{package_name}}";
+/// Variable substituted with the named address of the package in templates.
+pub const TEMPLATE_ADDRESS_NAME_VAR: &str = "{{address_name}}";
+
+/// Built-in templates by name, each given as the path of its files relative to the package root,
+/// along with their contents.
+pub const BUILTIN_TEMPLATES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "library",
+        &[
+            (
+                "Move.toml",
+                include_str!("../../templates/library/Move.toml"),
+            ),
+            (
+                "sources/math.move",
+                include_str!("../../templates/library/sources/math.move"),
+            ),
+            (
+                "tests/math_tests.move",
+                include_str!("../../templates/library/tests/math_tests.move"),
+            ),
+        ],
+    ),
+    (
+        "resource-account",
+        &[
+            (
+                "Move.toml",
+                include_str!("../../templates/resource-account/Move.toml"),
+            ),
+            (
+                "sources/vault.move",
+                include_str!("../../templates/resource-account/sources/vault.move"),
+            ),
+            (
+                "tests/vault_tests.move",
+                include_str!("../../templates/resource-account/tests/vault_tests.move"),
+            ),
+        ],
+    ),
+    (
+        "prover",
+        &[
+            (
+                "Move.toml",
+                include_str!("../../templates/prover/Move.toml"),
+            ),
+            (
+                "sources/counter.move",
+                include_str!("../../templates/prover/sources/counter.move"),
+            ),
+        ],
+    ),
+    (
+        "async-actor",
+        &[
+            (
+                "Move.toml",
+                include_str!("../../templates/async-actor/Move.toml"),
+            ),
+            (
+                "sources/counter.move",
+                include_str!("../../templates/async-actor/sources/counter.move"),
+            ),
+        ],
+    ),
+    (
+        "evm-contract",
+        &[
+            (
+                "Move.toml",
+                include_str!("../../templates/evm-contract/Move.toml"),
+            ),
+            (
+                "sources/counter.move",
+                include_str!("../../templates/evm-contract/sources/counter.move"),
+            ),
+        ],
+    ),
+];
+
 /// Create a new Move package with name `name` at `path`. If `path` is not provided the package
 /// will be created in the directory `name`.
 #[derive(Parser)]
@@ -25,6 +109,16 @@ pub const MOVE_STDLIB_ADDR_VALUE: &str = "0x1";
 pub struct New {
     /// The name of the package to be created.
     pub name: String,
+    /// Create the package from a template: either one of the built-in templates (`library`,
+    /// `resource-account`, `prover`, `async-actor` or `evm-contract`), or a directory laid out
+    /// like the package to create. `{{package_name}}` and `{{address_name}}` are replaced with the
+    /// name of the package and of its named address in the paths and contents of its files.
+    #[clap(long = "template", value_name = "TEMPLATE")]
+    pub template: Option<String>,
+    /// The named address of the modules of the package created from a template. Defaults to the
+    /// name of the package in snake case.
+    #[clap(long = "address-name", value_name = "NAME", requires = "template")]
+    pub address_name: Option<String>,
 }
 
 impl New {
@@ -47,7 +141,11 @@ impl New {
         custom: &str, // anything else that needs to end up being in Move.toml (or empty string)
     ) -> anyhow::Result<()> {
         // TODO warn on build config flags
-        let Self { name } = self;
+        let Self {
+            name,
+            template,
+            address_name,
+        } = self;
         let p: PathBuf;
         let path: &Path = match path {
             Some(path) => {
@@ -57,6 +155,34 @@ impl New {
             None => Path::new(&name),
         };
         create_dir_all(path.join(SourcePackageLayout::Sources.path()))?;
+
+        let mut own_address = None;
+        if let Some(template) = template {
+            let address_name = address_name.unwrap_or_else(|| snake_case(&name));
+            let substitute = |text: &str| {
+                text.replace(TEMPLATE_PACKAGE_NAME_VAR, &name)
+                    .replace(TEMPLATE_ADDRESS_NAME_VAR, &address_name)
+            };
+
+            let files = template_files(&template)?;
+            for (file, contents) in &files {
+                let target = path.join(substitute(file));
+                if let Some(parent) = target.parent() {
+                    create_dir_all(parent)?;
+                }
+                std::fs::write(&target, substitute(contents))
+                    .with_context(|| format!("Writing '{}'", target.to_string_lossy()))?;
+            }
+
+            // Templates that come with a manifest are complete, otherwise the default one is
+            // written, with the named address of the package left to be assigned at build time.
+            let manifest = SourcePackageLayout::Manifest.path();
+            if files.iter().any(|(file, _)| Path::new(file) == manifest) {
+                return Ok(());
+            }
+            own_address = Some(address_name);
+        }
+
         let mut w = std::fs::File::create(path.join(SourcePackageLayout::Manifest.path()))?;
         writeln!(
             &mut w,
@@ -78,9 +204,75 @@ version = \"{version}\"
         for (addr_name, addr_val) in addrs {
             writeln!(w, "{addr_name} =  \"{addr_val}\"")?;
         }
+        if let Some(addr_name) = own_address {
+            writeln!(w, "{addr_name} =  \"_\"")?;
+        }
         if !custom.is_empty() {
             writeln!(w, "{}", custom)?;
         }
         Ok(())
     }
 }
+
+/// The files of `template`, as their path relative to the package root along with their contents.
+fn template_files(template: &str) -> anyhow::Result<Vec<(String, String)>> {
+    if let Some((_, files)) = BUILTIN_TEMPLATES.iter().find(|(name, _)| *name == template) {
+        return Ok(files
+            .iter()
+            .map(|(file, contents)| (file.to_string(), contents.to_string()))
+            .collect());
+    }
+
+    let root = Path::new(template);
+    if !root.is_dir() {
+        bail!(
+            "Unknown template '{}'. Expected one of {} or a template directory",
+            template,
+            BUILTIN_TEMPLATES
+                .iter()
+                .map(|(name, _)| format!("'{}'", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let mut files = vec![];
+    for entry in walkdir::WalkDir::new(root).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let contents = std::fs::read_to_string(entry.path()).with_context(|| {
+            format!("Reading template file '{}'", entry.path().to_string_lossy())
+        })?;
+        let file = entry
+            .path()
+            .strip_prefix(root)?
+            .to_string_lossy()
+            .to_string();
+        files.push((file, contents));
+    }
+    Ok(files)
+}
+
+/// `name` in snake case, e.g. `my_package` for `MyPackage` or `my-package`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut after_word = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if after_word {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+            after_word = false;
+        } else if c == '-' {
+            snake.push('_');
+            after_word = false;
+        } else {
+            snake.push(c);
+            after_word = c.is_ascii_alphanumeric();
+        }
+    }
+    snake
+}
@@ -0,0 +1,70 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use move_package::{
+    compilation::{
+        package_bundle::PACKAGE_BUNDLE_EXTENSION, package_layout::CompiledPackageLayout,
+    },
+    BuildConfig,
+};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+pub enum PackageCommand {
+    /// Build this package and write it, along with its dependencies, source maps, sources,
+    /// manifest, ABIs and build metadata, to a single self-contained bundle.
+    #[clap(name = "bundle")]
+    Bundle {
+        /// The file to write the bundle to. Defaults to `<package>.mvb` in the build directory.
+        #[clap(long = "output", short = 'o', parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Produce portable artifacts of this package.
+#[derive(Parser)]
+#[clap(name = "package")]
+pub struct Package {
+    #[clap(subcommand)]
+    pub cmd: PackageCommand,
+}
+
+impl Package {
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        verbose: bool,
+        config: BuildConfig,
+    ) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let mut stdout = std::io::stdout();
+
+        match self.cmd {
+            PackageCommand::Bundle { output } => {
+                let build_dir = config
+                    .install_dir
+                    .clone()
+                    .unwrap_or_else(|| rerooted_path.clone())
+                    .join(CompiledPackageLayout::Root.path());
+                let bundle = config.bundle_package(&rerooted_path, &mut stdout)?;
+                let output = output.unwrap_or_else(|| {
+                    build_dir
+                        .join(bundle.package_name.as_str())
+                        .with_extension(PACKAGE_BUNDLE_EXTENSION)
+                });
+                bundle.write(&output)?;
+                println!(
+                    "Bundled package '{}' to '{}'",
+                    bundle.package_name,
+                    output.to_string_lossy()
+                );
+                if verbose {
+                    println!("Content hash: {}", bundle.content_hash);
+                }
+                Ok(())
+            }
+        }
+    }
+}
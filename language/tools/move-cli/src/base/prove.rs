@@ -6,8 +6,13 @@ use anyhow::bail;
 use clap::Parser;
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
 use colored::Colorize;
-use move_package::{BuildConfig, ModelConfig};
+use move_package::{
+    compilation::{model_builder::ModelBuilder, package_layout::CompiledPackageLayout},
+    source_package::layout::SourcePackageLayout,
+    BuildConfig, ModelConfig,
+};
 use move_prover::run_move_prover_with_model;
+use move_prover_boogie_backend::proof_cache::ProofCacheStats;
 use std::{
     io::Write,
     path::{Path, PathBuf},
@@ -15,6 +20,9 @@ use std::{
 };
 use tempfile::TempDir;
 
+/// Directory in the package's build directory where verification results are cached.
+const PROOF_CACHE_DIR: &str = "prover_cache";
+
 #[derive(Parser, Debug)]
 pub enum ProverOptions {
     // Pass through unknown commands to the prover Clap parser
@@ -36,6 +44,14 @@ pub struct Prove {
     /// this string will be part of verification.
     #[clap(short = 't', long = "target")]
     pub target_filter: Option<String>,
+    /// Verify all verification conditions, instead of skipping those which are unchanged since
+    /// they were last verified.
+    #[clap(long = "no-cache")]
+    pub no_cache: bool,
+    /// Verify all members of the workspace containing the package, in dependency order. The
+    /// `Prover.toml` and the verification cache of the workspace are found at its root.
+    #[clap(long = "workspace")]
+    pub workspace: bool,
     /// Internal field indicating that this prover run is for a test.
     #[clap(skip)]
     pub for_test: bool,
@@ -46,12 +62,18 @@ pub struct Prove {
 
 impl Prove {
     pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
-        let rerooted_path = reroot_path(path)?;
         let Self {
             target_filter,
+            no_cache,
+            workspace,
             for_test,
             options,
         } = self;
+        let rerooted_path = if workspace {
+            SourcePackageLayout::try_find_workspace_root(&reroot_path(path)?)?
+        } else {
+            reroot_path(path)?
+        };
         let opts = match options {
             Some(ProverOptions::Options(opts)) => opts,
             _ => vec![],
@@ -62,14 +84,29 @@ impl Prove {
             args.push(format!("--config={}", prover_toml.to_string_lossy()));
         }
         args.extend(opts.iter().cloned());
-        let options = move_prover::cli::Options::create_from_args(&args)?;
+        let mut options = move_prover::cli::Options::create_from_args(&args)?;
+        if no_cache || for_test {
+            options.backend.proof_cache_dir = None;
+        } else if options.backend.proof_cache_dir.is_none() {
+            options.backend.proof_cache_dir = Some(
+                rerooted_path
+                    .join(CompiledPackageLayout::Root.path())
+                    .join(PROOF_CACHE_DIR)
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
         if for_test {
             options.setup_logging_for_test();
         } else {
             options.setup_logging();
         }
 
-        run_move_prover(config, &rerooted_path, &target_filter, for_test, options)
+        if workspace {
+            run_move_prover_for_workspace(config, &rerooted_path, &target_filter, options)
+        } else {
+            run_move_prover(config, &rerooted_path, &target_filter, for_test, options)
+        }
     }
 }
 
@@ -124,6 +161,8 @@ impl ProverTest {
         let pkg_path = path_in_crate(std::mem::take(&mut self.path));
         let cmd = Prove {
             target_filter: None,
+            no_cache: true,
+            workspace: false,
             for_test: true,
             options: Some(ProverOptions::Options(std::mem::take(&mut self.options))),
         };
@@ -163,26 +202,7 @@ pub fn run_move_prover(
 ) -> anyhow::Result<()> {
     // Always run the prover in dev mode, so addresses get default assignments
     config.dev_mode = true;
-
-    if !options.move_sources.is_empty() {
-        bail!(
-            "move prover options must not specify sources as those are given \
-                     by the package system. Did you meant to prefix `{}` with `-t`?",
-            &options.move_sources[0]
-        );
-    }
-    if !options.move_deps.is_empty() {
-        bail!(
-            "move prover options must not specify dependencies as those are given \
-                     by the package system"
-        );
-    }
-    if !options.move_named_address_values.is_empty() {
-        bail!(
-            "move prover options must not specify named addresses as those are given \
-                     by the package system"
-        );
-    }
+    check_prover_options(&options)?;
 
     let mut message_writer = StandardStream::stdout(ColorChoice::Auto);
     let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
@@ -229,6 +249,84 @@ pub fn run_move_prover(
             basedir,
             now.elapsed().as_secs_f64()
         )?;
+    } else {
+        report_proof_cache_stats(
+            model.get_extension::<ProofCacheStats>().as_deref(),
+            &mut message_writer,
+        )?;
     }
     res
 }
+
+/// Runs the prover on each member of the workspace containing `path`, in dependency order,
+/// stopping at the first member which fails to verify.
+pub fn run_move_prover_for_workspace(
+    mut config: BuildConfig,
+    path: &Path,
+    target_filter: &Option<String>,
+    options: move_prover::cli::Options,
+) -> anyhow::Result<()> {
+    config.dev_mode = true;
+    check_prover_options(&options)?;
+
+    let mut message_writer = StandardStream::stdout(ColorChoice::Auto);
+    let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
+    for resolution_graph in config.resolution_graphs_for_workspace(path, &mut Vec::new())? {
+        let now = Instant::now();
+        writeln!(
+            message_writer,
+            "{} {}",
+            "PROVING".bold().green(),
+            resolution_graph.root_package.package.name
+        )?;
+        let model = ModelBuilder::create(
+            resolution_graph,
+            ModelConfig {
+                all_files_as_targets: false,
+                target_filter: target_filter.clone(),
+            },
+        )
+        .build_model()?;
+        let res = run_move_prover_with_model(&model, &mut error_writer, options.clone(), Some(now));
+        report_proof_cache_stats(
+            model.get_extension::<ProofCacheStats>().as_deref(),
+            &mut message_writer,
+        )?;
+        res?;
+    }
+    Ok(())
+}
+
+/// Reports how many verification conditions were found in the proof cache, if it was used.
+fn report_proof_cache_stats<W: Write>(
+    stats: Option<&ProofCacheStats>,
+    writer: &mut W,
+) -> anyhow::Result<()> {
+    if let Some(stats) = stats {
+        writeln!(writer, "{}", stats)?;
+    }
+    Ok(())
+}
+
+fn check_prover_options(options: &move_prover::cli::Options) -> anyhow::Result<()> {
+    if !options.move_sources.is_empty() {
+        bail!(
+            "move prover options must not specify sources as those are given \
+                     by the package system. Did you meant to prefix `{}` with `-t`?",
+            &options.move_sources[0]
+        );
+    }
+    if !options.move_deps.is_empty() {
+        bail!(
+            "move prover options must not specify dependencies as those are given \
+                     by the package system"
+        );
+    }
+    if !options.move_named_address_values.is_empty() {
+        bail!(
+            "move prover options must not specify named addresses as those are given \
+                     by the package system"
+        );
+    }
+    Ok(())
+}
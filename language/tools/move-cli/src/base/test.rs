@@ -13,8 +13,12 @@ use move_compiler::{
     PASS_CFGIR,
 };
 use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
-use move_package::{compilation::build_plan::BuildPlan, BuildConfig};
-use move_unit_test::UnitTestingConfig;
+use move_package::{
+    compilation::build_plan::BuildPlan, resolution::resolution_graph::ResolvedGraph, BuildConfig,
+};
+use move_unit_test::{
+    gas_snapshot::GAS_SNAPSHOT_FILE, test_reporter::TestReport, UnitTestingConfig,
+};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     collections::HashMap,
@@ -55,7 +59,8 @@ pub struct Test {
         long = "threads"
     )]
     pub num_threads: usize,
-    /// Report test statistics at the end of testing
+    /// Report test statistics at the end of testing, including the gas used by each test in
+    /// internal gas units, the unit of JSON and JUnit reports and gas snapshots
     #[clap(name = "report_statistics", short = 's', long = "statistics")]
     pub report_statistics: bool,
     /// Show the storage state at the end of execution of a failing test
@@ -76,6 +81,38 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Number of times a #[random_test] is run with generated arguments.
+    #[clap(name = "rand_num_iters", long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+    /// Seed for generating the arguments of #[random_test]s. A failing random test reports the
+    /// seed to reproduce it.
+    #[clap(name = "seed", long = "seed")]
+    pub seed: Option<u64>,
+    /// Write a machine-readable report of the test results to a file, given as `junit=<path>`
+    /// for JUnit XML or `json=<path>` for JSON. Can be given multiple times.
+    #[clap(name = "report", long = "report")]
+    pub reports: Vec<TestReport>,
+    /// Record the gas used by each passing test in the `.gas-snapshot` file of the package.
+    /// Random tests are not recorded, as the gas they use depends on the seed.
+    #[clap(name = "gas_snapshot", long = "gas-snapshot")]
+    pub gas_snapshot: bool,
+    /// Instead of updating the `.gas-snapshot` file, fail if the gas used by a test differs
+    /// from it by more than the gas tolerance, and print the differences.
+    #[clap(name = "check", long = "check", requires = "gas_snapshot")]
+    pub check_gas_snapshot: bool,
+    /// The change in gas used by a test, in percent, which is accepted by `--check`.
+    #[clap(name = "gas_tolerance", long = "gas-tolerance", default_value = "0")]
+    pub gas_tolerance: f64,
+    /// After the tests passed, run them against mutants of the modules of this package, e.g.
+    /// with `+` replaced by `-` or an `assert!` removed, and report the mutation score of each
+    /// module along with the mutants no test detected.
+    #[clap(name = "mutate", long = "mutate")]
+    pub mutate: bool,
+    /// Run the tests of all members of the workspace containing the package, in dependency
+    /// order. Each member records gas in its own `.gas-snapshot` file, and reports are written
+    /// per member, with the name of the member prepended to their file names.
+    #[clap(name = "workspace", long = "workspace")]
+    pub workspace: bool,
 
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
@@ -104,6 +141,14 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            rand_num_iters,
+            seed,
+            reports,
+            gas_snapshot,
+            check_gas_snapshot,
+            gas_tolerance,
+            mutate,
+            workspace,
             #[cfg(feature = "evm-backend")]
             evm,
         } = self;
@@ -117,20 +162,39 @@ impl Test {
             check_stackless_vm,
             verbose: verbose_mode,
             ignore_compile_warnings,
+            rand_num_iters,
+            seed,
+            reports,
+            gas_snapshot: gas_snapshot.then(|| rerooted_path.join(GAS_SNAPSHOT_FILE)),
+            check_gas_snapshot,
+            gas_tolerance,
+            mutate,
             #[cfg(feature = "evm-backend")]
             evm,
 
             ..UnitTestingConfig::default_with_bound(None)
         };
-        let result = run_move_unit_tests(
-            &rerooted_path,
-            config,
-            unit_test_config,
-            natives,
-            cost_table,
-            compute_coverage,
-            &mut std::io::stdout(),
-        )?;
+        let result = if workspace {
+            run_move_unit_tests_for_workspace(
+                &rerooted_path,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+                compute_coverage,
+                &mut std::io::stdout(),
+            )?
+        } else {
+            run_move_unit_tests(
+                &rerooted_path,
+                config,
+                unit_test_config,
+                natives,
+                cost_table,
+                compute_coverage,
+                &mut std::io::stdout(),
+            )?
+        };
 
         // Return a non-zero exit code if any test failed
         if let UnitTestResult::Failure = result {
@@ -150,19 +214,88 @@ pub enum UnitTestResult {
 pub fn run_move_unit_tests<W: Write + Send>(
     pkg_path: &Path,
     mut build_config: move_package::BuildConfig,
-    mut unit_test_config: UnitTestingConfig,
+    unit_test_config: UnitTestingConfig,
     natives: Vec<NativeFunctionRecord>,
     cost_table: Option<CostTable>,
     compute_coverage: bool,
     writer: &mut W,
 ) -> Result<UnitTestResult> {
-    let mut test_plan = None;
     build_config.test_mode = true;
     build_config.dev_mode = true;
 
     // Build the resolution graph (resolution graph diagnostics are only needed for CLI commands so
     // ignore them by passing a vector as the writer)
     let resolution_graph = build_config.resolution_graph_for_package(pkg_path, &mut Vec::new())?;
+    run_move_unit_tests_for_graph(
+        pkg_path,
+        resolution_graph,
+        unit_test_config,
+        natives,
+        cost_table,
+        compute_coverage,
+        writer,
+    )
+}
+
+/// Runs the unit tests of each member of the workspace containing `pkg_path`, in dependency
+/// order, failing if the tests of any member fail. The gas snapshot and reports of
+/// `unit_test_config` are taken per member.
+pub fn run_move_unit_tests_for_workspace<W: Write + Send>(
+    pkg_path: &Path,
+    mut build_config: move_package::BuildConfig,
+    unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> Result<UnitTestResult> {
+    build_config.test_mode = true;
+    build_config.dev_mode = true;
+
+    let mut result = UnitTestResult::Success;
+    for resolution_graph in
+        build_config.resolution_graphs_for_workspace(pkg_path, &mut Vec::new())?
+    {
+        let member = resolution_graph.root_package.package.name;
+        let member_path = resolution_graph.get_package(&member).package_path.clone();
+        let mut unit_test_config = unit_test_config.clone();
+        if unit_test_config.gas_snapshot.is_some() {
+            unit_test_config.gas_snapshot = Some(member_path.join(GAS_SNAPSHOT_FILE));
+        }
+        for report in &mut unit_test_config.reports {
+            let file_name = report
+                .path
+                .file_name()
+                .map(|name| format!("{}-{}", member, name.to_string_lossy()))
+                .unwrap_or_else(|| member.to_string());
+            report.path.set_file_name(file_name);
+        }
+        if run_move_unit_tests_for_graph(
+            &member_path,
+            resolution_graph,
+            unit_test_config,
+            natives.clone(),
+            cost_table.clone(),
+            compute_coverage,
+            writer,
+        )? == UnitTestResult::Failure
+        {
+            result = UnitTestResult::Failure;
+        }
+    }
+    Ok(result)
+}
+
+fn run_move_unit_tests_for_graph<W: Write + Send>(
+    pkg_path: &Path,
+    resolution_graph: ResolvedGraph,
+    mut unit_test_config: UnitTestingConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: Option<CostTable>,
+    compute_coverage: bool,
+    writer: &mut W,
+) -> Result<UnitTestResult> {
+    let mut test_plan = None;
 
     // Note: unit_test_config.named_address_values is always set to vec![] (the default value) before
     // being passed in.
@@ -0,0 +1,20 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use move_package::BuildConfig;
+use std::path::PathBuf;
+
+/// Copy the git and custom dependencies of the package into its `vendor` directory, so that it
+/// builds without fetching them.
+#[derive(Parser)]
+#[clap(name = "vendor")]
+pub struct Vendor;
+
+impl Vendor {
+    pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        config.vendor_dependencies(&rerooted_path, &mut std::io::stdout())
+    }
+}
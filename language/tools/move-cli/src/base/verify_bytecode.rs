@@ -0,0 +1,42 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use crate::DEFAULT_STORAGE_DIR;
+use clap::*;
+use move_package::BuildConfig;
+use std::path::PathBuf;
+
+/// Rebuild the package and check that each of its modules matches the deployed bytecode,
+/// ignoring module metadata.
+#[derive(Parser)]
+#[clap(name = "verify-bytecode")]
+pub struct VerifyBytecode {
+    /// Directory storing Move resources, events, and module bytecodes produced by module publishing
+    /// in the sandbox, to read the deployed modules from.
+    #[clap(long = "storage-dir", default_value = DEFAULT_STORAGE_DIR, parse(from_os_str))]
+    pub storage_dir: PathBuf,
+    /// Directory of `.mv` files to read the deployed modules from, instead of the sandbox storage.
+    #[clap(long = "bytecode-dir", parse(from_os_str))]
+    pub bytecode_dir: Option<PathBuf>,
+}
+
+impl VerifyBytecode {
+    pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
+        // Resolve the bytecode directory before moving to the root of the package.
+        let bytecode_dir = self
+            .bytecode_dir
+            .unwrap_or(self.storage_dir)
+            .canonicalize()?;
+        let rerooted_path = reroot_path(path)?;
+
+        let mut stdout = std::io::stdout();
+        let report = config.verify_bytecode(&rerooted_path, &bytecode_dir, &mut stdout)?;
+        report.print(&mut stdout)?;
+
+        if !report.is_verified() {
+            anyhow::bail!("Deployed bytecode does not match the package");
+        }
+        Ok(())
+    }
+}
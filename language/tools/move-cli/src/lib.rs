@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use base::{
-    build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, errmap::Errmap,
-    info::Info, new::New, prove::Prove, test::Test,
+    build::Build, coverage::Coverage, deps::Deps, disassemble::Disassemble, docgen::Docgen,
+    errmap::Errmap, fuzz::Fuzz, generate::Generate, info::Info, new::New, package::Package,
+    prove::Prove, test::Test, vendor::Vendor, verify_bytecode::VerifyBytecode,
 };
 use move_package::BuildConfig;
 
@@ -64,13 +65,19 @@ pub struct MoveCLI {
 pub enum Command {
     Build(Build),
     Coverage(Coverage),
+    Deps(Deps),
     Disassemble(Disassemble),
     Docgen(Docgen),
     Errmap(Errmap),
+    Fuzz(Fuzz),
+    Generate(Generate),
     Info(Info),
     New(New),
+    Package(Package),
     Prove(Prove),
     Test(Test),
+    Vendor(Vendor),
+    VerifyBytecode(VerifyBytecode),
     /// Execute a sandbox command.
     #[clap(name = "sandbox")]
     Sandbox {
@@ -106,11 +113,24 @@ pub fn run_cli(
     match cmd {
         Command::Build(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Coverage(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Deps(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Disassemble(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Docgen(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Errmap(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::Fuzz(c) => c.execute(
+            move_args.package_path,
+            move_args.build_config,
+            natives,
+            cost_table,
+        ),
+        Command::Generate(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Info(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::New(c) => c.execute_with_defaults(move_args.package_path),
+        Command::Package(c) => c.execute(
+            move_args.package_path,
+            move_args.verbose,
+            move_args.build_config,
+        ),
         Command::Prove(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Test(c) => c.execute(
             move_args.package_path,
@@ -118,6 +138,8 @@ pub fn run_cli(
             natives,
             Some(cost_table.clone()),
         ),
+        Command::Vendor(c) => c.execute(move_args.package_path, move_args.build_config),
+        Command::VerifyBytecode(c) => c.execute(move_args.package_path, move_args.build_config),
         Command::Sandbox { storage_dir, cmd } => cmd.handle_command(
             natives,
             cost_table,
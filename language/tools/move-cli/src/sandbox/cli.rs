@@ -15,7 +15,9 @@ use move_core_types::{
     errmap::ErrorMapping, language_storage::TypeTag, parser,
     transaction_argument::TransactionArgument,
 };
-use move_package::compilation::package_layout::CompiledPackageLayout;
+use move_package::compilation::{
+    package_bundle::PackageBundle, package_layout::CompiledPackageLayout,
+};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     fs,
@@ -44,6 +46,10 @@ pub enum SandboxCommand {
         /// modules sequentially.
         #[clap(long = "bundle")]
         bundle: bool,
+        /// Publish the modules in this package bundle file, as produced by
+        /// `move package bundle`, at once instead of compiling this package.
+        #[clap(long = "bundle-file", value_name = "BUNDLE_FILE", parse(from_os_str))]
+        bundle_file: Option<PathBuf>,
         /// Manually specify the publishing order of modules.
         #[clap(
             long = "override-ordering",
@@ -209,8 +215,27 @@ impl SandboxCommand {
                 ignore_breaking_changes,
                 with_deps,
                 bundle,
+                bundle_file,
                 override_ordering,
             } => {
+                if let Some(bundle_file) = bundle_file {
+                    let build_dir = move_args
+                        .build_config
+                        .install_dir
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(DEFAULT_BUILD_DIR));
+                    let state = OnDiskStateView::create(build_dir.as_path(), storage_dir)?;
+                    return sandbox::commands::publish_bundle(
+                        natives,
+                        cost_table,
+                        &state,
+                        &PackageBundle::read(bundle_file)?,
+                        *no_republish,
+                        *ignore_breaking_changes,
+                        move_args.verbose,
+                    );
+                }
+
                 let context =
                     PackageContext::new(&move_args.package_path, &move_args.build_config)?;
                 let state = context.prepare_state(storage_dir)?;
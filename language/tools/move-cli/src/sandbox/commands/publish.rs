@@ -12,7 +12,7 @@ use crate::{
 use anyhow::{bail, Result};
 use move_binary_format::errors::Location;
 use move_command_line_common::env::get_bytecode_version_from_env;
-use move_package::compilation::compiled_package::CompiledPackage;
+use move_package::compilation::{compiled_package::CompiledPackage, package_bundle::PackageBundle};
 use move_vm_runtime::move_vm::MoveVM;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::collections::BTreeMap;
@@ -177,3 +177,90 @@ pub fn publish(
 
     Ok(())
 }
+
+/// Publish the modules of the package in `bundle` at once, along with the modules of its
+/// dependencies that do not exist in storage yet.
+pub fn publish_bundle(
+    natives: impl IntoIterator<Item = NativeFunctionRecord>,
+    cost_table: &CostTable,
+    state: &OnDiskStateView,
+    bundle: &PackageBundle,
+    no_republish: bool,
+    ignore_breaking_changes: bool,
+    verbose: bool,
+) -> Result<()> {
+    let mut dependency_modules = vec![];
+    for unit in &bundle.modules {
+        if unit.package_name == bundle.package_name {
+            continue;
+        }
+        let id = unit.id()?;
+        if !state.has_module(&id) {
+            dependency_modules.push((id, unit.bytecode.clone()));
+        }
+    }
+
+    let mut modules_to_publish = vec![];
+    for unit in bundle.package_modules() {
+        modules_to_publish.push((unit.id()?, unit.bytecode.clone()));
+    }
+    if verbose {
+        println!(
+            "Found {} modules in the bundle of package '{}', and {} modules of its dependencies \
+             to publish",
+            modules_to_publish.len(),
+            bundle.package_name,
+            dependency_modules.len()
+        );
+    }
+
+    if no_republish {
+        let republished = modules_to_publish
+            .iter()
+            .filter(|(id, _)| state.has_module(id))
+            .map(|(id, _)| format!("{}", id))
+            .collect::<Vec<_>>();
+
+        if !republished.is_empty() {
+            eprintln!("Failed to republish modules since the --no-republish flag is set. Tried to republish the following modules: {}",
+                republished.join(", "));
+            return Ok(());
+        }
+    }
+
+    // NOTE: as in `publish`, breaking changes can only be forced through by skipping the VM and
+    // overriding the on-disk state directly
+    if ignore_breaking_changes {
+        dependency_modules.extend(modules_to_publish);
+        return state.save_modules(&dependency_modules);
+    }
+
+    let (sender, module_bytes_vec) = bundle.package_module_bundle()?;
+    let vm = MoveVM::new(natives).unwrap();
+    let mut gas_status = get_gas_status(cost_table, None)?;
+    let mut session = vm.new_session(state);
+    // dependencies are published one at a time, in dependency order, as they may live at
+    // different addresses
+    for (id, module_bytes) in dependency_modules {
+        let sender = *id.address();
+        if let Err(err) = session.publish_module(module_bytes, sender, &mut gas_status) {
+            println!("Invalid publishing of dependency module {}: {}", id, err);
+            return Ok(());
+        }
+    }
+    if let Err(err) = session.publish_module_bundle(module_bytes_vec, sender, &mut gas_status) {
+        println!("Invalid multi-module publishing: {}", err);
+        return Ok(());
+    }
+
+    let (changeset, events) = session.finish().map_err(|e| e.into_vm_status())?;
+    assert!(events.is_empty());
+    if verbose {
+        explain_publish_changeset(&changeset);
+    }
+    let modules: Vec<_> = changeset
+        .into_modules()
+        .map(|(module_id, blob_opt)| (module_id, blob_opt.ok().expect("must be non-deletion")))
+        .collect();
+    state.save_modules(&modules)
+}
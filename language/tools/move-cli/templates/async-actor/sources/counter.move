@@ -0,0 +1,23 @@
+#[actor]
+/// An actor holding a counter, which other actors can increment by sending it messages.
+module {{address_name}}::counter {
+    #[state]
+    struct Counter {
+        value: u64,
+    }
+
+    #[init]
+    fun init(): Counter {
+        Counter { value: 0 }
+    }
+
+    #[message]
+    fun increment(this: &mut Counter, by: u64) {
+        this.value = this.value + by;
+    }
+
+    #[message]
+    fun reset(this: &mut Counter) {
+        this.value = 0;
+    }
+}
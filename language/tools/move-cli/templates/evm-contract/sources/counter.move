@@ -0,0 +1,26 @@
+#[evm_contract]
+/// A contract holding a counter, which anyone can increment.
+module {{address_name}}::counter {
+    use Evm::Evm::{sign, self};
+    use Evm::U256::{Self, U256};
+
+    struct Counter has key {
+        value: U256,
+    }
+
+    #[create(sig=b"constructor()")]
+    public fun create() {
+        move_to(&sign(self()), Counter { value: U256::zero() });
+    }
+
+    #[callable(sig=b"increment()")]
+    public fun increment() acquires Counter {
+        let counter = borrow_global_mut<Counter>(self());
+        counter.value = U256::add(counter.value, U256::one());
+    }
+
+    #[callable(sig=b"value() returns (uint256)"), view]
+    public fun value(): U256 acquires Counter {
+        borrow_global<Counter>(self()).value
+    }
+}
@@ -0,0 +1,17 @@
+/// Math utilities for integers.
+module {{address_name}}::math {
+    /// Return the larger of `a` and `b`.
+    public fun max(a: u64, b: u64): u64 {
+        if (a >= b) a else b
+    }
+
+    /// Return `base` raised to the power of `exponent`.
+    public fun pow(base: u64, exponent: u64): u64 {
+        let result = 1;
+        while (exponent > 0) {
+            result = result * base;
+            exponent = exponent - 1;
+        };
+        result
+    }
+}
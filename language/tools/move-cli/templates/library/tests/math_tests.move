@@ -0,0 +1,16 @@
+#[test_only]
+module {{address_name}}::math_tests {
+    use {{address_name}}::math;
+
+    #[test]
+    fun test_max() {
+        assert!(math::max(1, 2) == 2, 0);
+        assert!(math::max(2, 1) == 2, 1);
+    }
+
+    #[test]
+    fun test_pow() {
+        assert!(math::pow(2, 10) == 1024, 0);
+        assert!(math::pow(7, 0) == 1, 1);
+    }
+}
@@ -0,0 +1,51 @@
+/// A counter with specifications, to be checked with `move prove`.
+module {{address_name}}::counter {
+    use std::signer;
+
+    /// The maximum value of a counter.
+    const MAX: u64 = 1000;
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    spec Counter {
+        invariant value <= MAX;
+    }
+
+    /// Publish a counter starting at zero under `account`.
+    public fun publish(account: &signer) {
+        move_to(account, Counter { value: 0 });
+    }
+
+    spec publish {
+        let addr = signer::address_of(account);
+        aborts_if exists<Counter>(addr);
+        ensures global<Counter>(addr).value == 0;
+    }
+
+    /// Increment the counter of `owner`, up to `MAX`.
+    public fun increment(owner: address) acquires Counter {
+        let counter = borrow_global_mut<Counter>(owner);
+        if (counter.value < MAX) {
+            counter.value = counter.value + 1;
+        }
+    }
+
+    spec increment {
+        aborts_if !exists<Counter>(owner);
+        let old_value = global<Counter>(owner).value;
+        ensures old_value < MAX ==> global<Counter>(owner).value == old_value + 1;
+        ensures old_value == MAX ==> global<Counter>(owner).value == MAX;
+    }
+
+    /// The value of the counter of `owner`.
+    public fun value(owner: address): u64 acquires Counter {
+        borrow_global<Counter>(owner).value
+    }
+
+    spec value {
+        aborts_if !exists<Counter>(owner);
+        ensures result == global<Counter>(owner).value;
+    }
+}
@@ -0,0 +1,44 @@
+/// A vault holding a balance, stored as a resource under the account that owns it.
+module {{address_name}}::vault {
+    use std::error;
+    use std::signer;
+
+    /// The account already has a vault.
+    const EVAULT_EXISTS: u64 = 0;
+    /// The account has no vault.
+    const ENO_VAULT: u64 = 1;
+    /// The vault does not hold enough to withdraw the requested amount.
+    const EINSUFFICIENT_BALANCE: u64 = 2;
+
+    struct Vault has key {
+        balance: u64,
+    }
+
+    /// Create an empty vault under `account`.
+    public fun create(account: &signer) {
+        assert!(!exists<Vault>(signer::address_of(account)), error::already_exists(EVAULT_EXISTS));
+        move_to(account, Vault { balance: 0 });
+    }
+
+    /// Add `amount` to the vault of `owner`.
+    public fun deposit(owner: address, amount: u64) acquires Vault {
+        assert!(exists<Vault>(owner), error::not_found(ENO_VAULT));
+        let vault = borrow_global_mut<Vault>(owner);
+        vault.balance = vault.balance + amount;
+    }
+
+    /// Take `amount` out of the vault of `account`.
+    public fun withdraw(account: &signer, amount: u64) acquires Vault {
+        let owner = signer::address_of(account);
+        assert!(exists<Vault>(owner), error::not_found(ENO_VAULT));
+        let vault = borrow_global_mut<Vault>(owner);
+        assert!(vault.balance >= amount, error::out_of_range(EINSUFFICIENT_BALANCE));
+        vault.balance = vault.balance - amount;
+    }
+
+    /// The balance of the vault of `owner`.
+    public fun balance(owner: address): u64 acquires Vault {
+        assert!(exists<Vault>(owner), error::not_found(ENO_VAULT));
+        borrow_global<Vault>(owner).balance
+    }
+}
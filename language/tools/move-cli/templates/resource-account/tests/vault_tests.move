@@ -0,0 +1,28 @@
+#[test_only]
+module {{address_name}}::vault_tests {
+    use std::signer;
+    use {{address_name}}::vault;
+
+    #[test(account = @0xCAFE)]
+    fun test_deposit_and_withdraw(account: signer) {
+        let owner = signer::address_of(&account);
+        vault::create(&account);
+        vault::deposit(owner, 10);
+        vault::withdraw(&account, 4);
+        assert!(vault::balance(owner) == 6, 0);
+    }
+
+    #[test(account = @0xCAFE)]
+    #[expected_failure]
+    fun test_create_twice(account: signer) {
+        vault::create(&account);
+        vault::create(&account);
+    }
+
+    #[test(account = @0xCAFE)]
+    #[expected_failure]
+    fun test_overdraw(account: signer) {
+        vault::create(&account);
+        vault::withdraw(&account, 1);
+    }
+}
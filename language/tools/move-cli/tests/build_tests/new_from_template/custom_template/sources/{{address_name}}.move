@@ -0,0 +1,2 @@
+/// Created from a user-defined template for package {{package_name}}.
+module {{address_name}}::{{address_name}} {}
@@ -0,0 +1,3 @@
+module 0x42::b {
+    public fun value(): u64 { 2 }
+}
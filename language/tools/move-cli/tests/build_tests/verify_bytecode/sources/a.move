@@ -0,0 +1,3 @@
+module 0x42::a {
+    public fun value(): u64 { 1 }
+}
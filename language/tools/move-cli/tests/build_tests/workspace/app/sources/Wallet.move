@@ -0,0 +1,5 @@
+module app::Wallet {
+    use core::Coin::Coin;
+
+    struct Wallet has key { coin: Coin }
+}
@@ -0,0 +1,3 @@
+module core::Coin {
+    struct Coin has store { value: u64 }
+}
@@ -0,0 +1,45 @@
+module 0x2::Bank {
+    use std::signer;
+
+    const MAX_BALANCE: u8 = 100;
+    const MAX_DEPOSIT: u8 = 200;
+
+    const ENO_BALANCE: u64 = 1;
+    const EINSUFFICIENT_BALANCE: u64 = 2;
+    const EABOVE_LIMIT: u64 = 3;
+
+    struct Balance has key {
+        value: u8,
+    }
+
+    public entry fun open(account: &signer) {
+        if (!exists<Balance>(signer::address_of(account))) {
+            move_to(account, Balance { value: 0 });
+        }
+    }
+
+    // Only checks the amount, not the resulting balance
+    public entry fun deposit(account: &signer, amount: u8) acquires Balance {
+        assert!(amount <= MAX_DEPOSIT, EABOVE_LIMIT);
+        let addr = signer::address_of(account);
+        assert!(exists<Balance>(addr), ENO_BALANCE);
+        let balance = borrow_global_mut<Balance>(addr);
+        balance.value = balance.value + amount;
+    }
+
+    public entry fun withdraw(account: &signer, amount: u8) acquires Balance {
+        let addr = signer::address_of(account);
+        assert!(exists<Balance>(addr), ENO_BALANCE);
+        let balance = borrow_global_mut<Balance>(addr);
+        assert!(balance.value >= amount, EINSUFFICIENT_BALANCE);
+        balance.value = balance.value - amount;
+    }
+
+    public fun balance(addr: address): u8 acquires Balance {
+        if (exists<Balance>(addr)) borrow_global<Balance>(addr).value else 0
+    }
+
+    public fun balances_within_limit(): bool acquires Balance {
+        balance(@0xA) <= MAX_BALANCE && balance(@0xB) <= MAX_BALANCE
+    }
+}
@@ -0,0 +1,3 @@
+module Dep::dep {
+    public fun value(): u64 { 1 }
+}
@@ -0,0 +1,7 @@
+script {
+    use App::app;
+
+    fun check() {
+        assert!(app::value() == 2, 0);
+    }
+}
@@ -0,0 +1,5 @@
+module App::app {
+    use Dep::dep;
+
+    public fun value(): u64 { dep::value() + 1 }
+}
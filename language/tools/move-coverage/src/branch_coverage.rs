@@ -0,0 +1,169 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::coverage_map::FunctionCoverage;
+use move_binary_format::{
+    control_flow_graph::{BlockId, ControlFlowGraph, VMControlFlowGraph},
+    file_format::{Bytecode, CodeOffset},
+};
+use serde::Serialize;
+use std::{cmp::min, collections::BTreeMap};
+
+/// The coverage of a conditional branch instruction (`BrTrue` or `BrFalse`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchCoverage {
+    /// The offset of the branch instruction
+    pub offset: CodeOffset,
+    /// The jump target and the fall-through target of the branch, with the number of times
+    /// each of them was taken from the branch
+    pub targets: [(CodeOffset, u64); 2],
+}
+
+impl BranchCoverage {
+    /// Whether the branch instruction was executed at all.
+    pub fn is_executed(&self) -> bool {
+        self.targets.iter().any(|(_, count)| *count > 0)
+    }
+
+    /// The number of targets of the branch which were taken.
+    pub fn targets_taken(&self) -> usize {
+        self.targets.iter().filter(|(_, count)| *count > 0).count()
+    }
+}
+
+/// Computes the coverage of the conditional branches in `code`.
+///
+/// The coverage map only records how often each instruction was executed, so how often a target
+/// was taken from a branch is derived from the control-flow graph: it is the execution count of
+/// the target if the branch is the only executed way to reach it, and otherwise follows from the
+/// count of the other target. If both targets are also reached from elsewhere, a target is
+/// counted as taken as often as it was executed, bounded by the executions of the branch.
+pub fn compute_branch_coverage(
+    code: &[Bytecode],
+    coverage: &FunctionCoverage,
+) -> Vec<BranchCoverage> {
+    let cfg = VMControlFlowGraph::new(code);
+    let count = |offset: CodeOffset| coverage.get(&(offset as u64)).copied().unwrap_or(0);
+
+    // the predecessors of each block, identified by the offset of their last instruction
+    let mut predecessors: BTreeMap<BlockId, Vec<CodeOffset>> = BTreeMap::new();
+    for block_id in cfg.blocks() {
+        for succ_block_id in cfg.successors(block_id) {
+            predecessors
+                .entry(*succ_block_id)
+                .or_default()
+                .push(cfg.block_end(block_id));
+        }
+    }
+    let entry_block_id = cfg.entry_block_id();
+
+    code.iter()
+        .enumerate()
+        .filter_map(|(offset, instr)| {
+            let offset = offset as CodeOffset;
+            let target = match instr {
+                Bytecode::BrTrue(target) | Bytecode::BrFalse(target) => *target,
+                _ => return None,
+            };
+            let fall_through = offset + 1;
+            if target == fall_through {
+                // both outcomes continue at the same instruction
+                return None;
+            }
+
+            let only_reached_from_branch = |block_id: CodeOffset| {
+                block_id != entry_block_id
+                    && predecessors.get(&block_id).map_or(true, |preds| {
+                        preds
+                            .iter()
+                            .all(|pred| *pred == offset || count(*pred) == 0)
+                    })
+            };
+            let executed = count(offset);
+            let (jumped, fell_through) = match (
+                only_reached_from_branch(target),
+                only_reached_from_branch(fall_through),
+            ) {
+                (true, true) => (count(target), count(fall_through)),
+                (true, false) => (count(target), executed.saturating_sub(count(target))),
+                (false, true) => (
+                    executed.saturating_sub(count(fall_through)),
+                    count(fall_through),
+                ),
+                (false, false) => (
+                    min(executed, count(target)),
+                    min(executed, count(fall_through)),
+                ),
+            };
+            Some(BranchCoverage {
+                offset,
+                targets: [(target, jumped), (fall_through, fell_through)],
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::Bytecode::*;
+
+    fn branch_coverage(code: &[Bytecode], counts: &[u64]) -> Vec<BranchCoverage> {
+        let coverage = counts
+            .iter()
+            .enumerate()
+            .map(|(offset, count)| (offset as u64, *count))
+            .collect();
+        compute_branch_coverage(code, &coverage)
+    }
+
+    #[test]
+    fn test_if_else() {
+        // if (b) 1 else 2, run once with `b` true and twice with `b` false
+        let code = [LdTrue, BrFalse(4), LdU64(1), Branch(5), LdU64(2), Ret];
+        assert_eq!(
+            branch_coverage(&code, &[3, 3, 1, 1, 2, 3]),
+            vec![BranchCoverage {
+                offset: 1,
+                targets: [(4, 2), (2, 1)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_not_executed() {
+        let code = [LdTrue, BrFalse(4), LdU64(1), Branch(5), LdU64(2), Ret];
+        let branches = branch_coverage(&code, &[0; 6]);
+        assert_eq!(branches[0].targets, [(4, 0), (2, 0)]);
+        assert!(!branches[0].is_executed());
+        assert_eq!(branches[0].targets_taken(), 0);
+    }
+
+    #[test]
+    fn test_target_reached_from_elsewhere() {
+        // The loop body at 2 is entered from the branch at 1 and from the back edge at 5, whose
+        // count follows from the exit at 6 being only reached from it
+        let code = [LdTrue, BrFalse(4), Pop, LdTrue, LdTrue, BrTrue(2), Ret];
+        let branches = branch_coverage(&code, &[1, 1, 2, 2, 2, 2, 1]);
+        assert_eq!(branches[1].offset, 5);
+        assert_eq!(branches[1].targets, [(2, 1), (6, 1)]);
+    }
+
+    #[test]
+    fn test_approximation_when_both_targets_reached_from_elsewhere() {
+        // The branch at 1 fell through once, but both of its targets were also reached from
+        // elsewhere, so each is counted as taken as often as the branch was executed
+        let code = [LdTrue, BrFalse(4), Pop, LdTrue, LdTrue, BrTrue(2), Ret];
+        let branches = branch_coverage(&code, &[1, 1, 2, 2, 2, 2, 1]);
+        assert_eq!(branches[0].offset, 1);
+        assert_eq!(branches[0].targets, [(4, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_branch_to_fall_through_is_ignored() {
+        let code = [LdTrue, BrTrue(2), Ret];
+        assert!(branch_coverage(&code, &[1, 1, 1]).is_empty());
+    }
+}
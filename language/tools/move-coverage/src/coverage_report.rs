@@ -0,0 +1,518 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Line and branch coverage of Move source files, rendered in the LCOV tracefile and Cobertura
+//! XML formats.
+
+use crate::{
+    branch_coverage::{compute_branch_coverage, BranchCoverage},
+    coverage_map::{ExecCoverageMap, FunctionCoverage},
+};
+use anyhow::{bail, Result};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use serde::Serialize;
+use std::{
+    cmp::max,
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// The coverage of a module, mapped to the lines of its source file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleLineCoverage {
+    /// The name of the module, e.g. `0x1::vector`
+    pub module_name: String,
+    pub source_path: PathBuf,
+    pub functions: Vec<FunctionLineCoverage>,
+}
+
+/// The coverage of a function. Lines are numbered from 1.
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionLineCoverage {
+    pub name: String,
+    /// The line the function is defined at
+    pub line: u32,
+    /// The number of times the function was called
+    pub calls: u64,
+    /// The number of times each line with instructions of the function was executed
+    pub lines: BTreeMap<u32, u64>,
+    /// The conditional branches of the function, with the line they are at
+    pub branches: Vec<(u32, BranchCoverage)>,
+}
+
+impl ModuleLineCoverage {
+    /// Maps the coverage of `module` in `coverage_map` to the lines of `source`, the contents of
+    /// the file at `source_path` the module was compiled from.
+    pub fn new(
+        module: &CompiledModule,
+        source_map: &SourceMap,
+        source_path: &Path,
+        source: &str,
+        coverage_map: &ExecCoverageMap,
+    ) -> Result<Self> {
+        if !source_map.check(source) {
+            bail!(
+                "File contents of {} out of sync with source map",
+                source_path.display()
+            )
+        }
+        let line_starts: Vec<_> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i as u32 + 1))
+            .collect();
+        let line_of = |byte_index: u32| line_starts.partition_point(|start| *start <= byte_index);
+
+        let module_id = module.self_id();
+        let module_map = coverage_map
+            .module_maps
+            .get(&(*module_id.address(), module_id.name().to_owned()));
+        let empty_coverage = FunctionCoverage::new();
+
+        let mut functions = vec![];
+        for (function_def_idx, function_def) in module.function_defs().iter().enumerate() {
+            let code_unit = match &function_def.code {
+                // native functions have no lines to cover
+                None => continue,
+                Some(code_unit) => code_unit,
+            };
+            let fn_name =
+                module.identifier_at(module.function_handle_at(function_def.function).name);
+            let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+            let coverage = module_map
+                .and_then(|module_map| module_map.get_function_coverage(fn_name))
+                .unwrap_or(&empty_coverage);
+            let count = |offset: CodeOffset| coverage.get(&(offset as u64)).copied().unwrap_or(0);
+
+            let mut lines = BTreeMap::new();
+            for offset in 0..code_unit.code.len() as CodeOffset {
+                let loc = source_map.get_code_location(function_def_idx, offset)?;
+                let line_count = lines.entry(line_of(loc.start()) as u32).or_insert(0);
+                *line_count = max(*line_count, count(offset));
+            }
+            let branches = compute_branch_coverage(&code_unit.code, coverage)
+                .into_iter()
+                .map(|branch| {
+                    let loc = source_map.get_code_location(function_def_idx, branch.offset)?;
+                    Ok((line_of(loc.start()) as u32, branch))
+                })
+                .collect::<Result<_>>()?;
+            let definition_location = source_map
+                .get_function_source_map(function_def_idx)?
+                .definition_location;
+
+            functions.push(FunctionLineCoverage {
+                name: fn_name.to_string(),
+                line: line_of(definition_location.start()) as u32,
+                calls: count(0),
+                lines,
+                branches,
+            });
+        }
+
+        Ok(Self {
+            module_name: format!(
+                "0x{}::{}",
+                module_id.address().short_str_lossless(),
+                module_id.name()
+            ),
+            source_path: source_path.to_path_buf(),
+            functions,
+        })
+    }
+
+    /// The number of times each line with instructions of the module was executed.
+    fn lines(&self) -> BTreeMap<u32, u64> {
+        let mut lines = BTreeMap::new();
+        for function in &self.functions {
+            merge_lines(&mut lines, &function.lines)
+        }
+        lines
+    }
+}
+
+fn merge_lines(lines: &mut BTreeMap<u32, u64>, other: &BTreeMap<u32, u64>) {
+    for (line, count) in other {
+        let line_count = lines.entry(*line).or_insert(0);
+        *line_count = max(*line_count, *count);
+    }
+}
+
+/// Renders the coverage in the LCOV tracefile format, with one record per source file.
+pub fn render_lcov(modules: &[ModuleLineCoverage]) -> String {
+    let mut files: BTreeMap<&Path, Vec<&ModuleLineCoverage>> = BTreeMap::new();
+    for module in modules {
+        files.entry(&module.source_path).or_default().push(module);
+    }
+
+    let mut out = String::new();
+    for (source_path, modules) in files {
+        writeln!(out, "SF:{}", display_path(source_path)).unwrap();
+
+        let mut functions: Vec<_> = modules
+            .iter()
+            .flat_map(|module| {
+                module
+                    .functions
+                    .iter()
+                    .map(move |function| (&module.module_name, function))
+            })
+            .collect();
+        functions.sort_by_key(|(_, function)| function.line);
+        for (module_name, function) in &functions {
+            writeln!(
+                out,
+                "FN:{},{}::{}",
+                function.line, module_name, function.name
+            )
+            .unwrap();
+        }
+        for (module_name, function) in &functions {
+            writeln!(
+                out,
+                "FNDA:{},{}::{}",
+                function.calls, module_name, function.name
+            )
+            .unwrap();
+        }
+        writeln!(out, "FNF:{}", functions.len()).unwrap();
+        writeln!(
+            out,
+            "FNH:{}",
+            functions.iter().filter(|(_, f)| f.calls > 0).count()
+        )
+        .unwrap();
+
+        let mut branches_found = 0;
+        let mut branches_hit = 0;
+        for (block, (line, branch)) in functions
+            .iter()
+            .flat_map(|(_, function)| &function.branches)
+            .enumerate()
+        {
+            for (index, (_, taken)) in branch.targets.iter().enumerate() {
+                if branch.is_executed() {
+                    writeln!(out, "BRDA:{},{},{},{}", line, block, index, taken).unwrap();
+                } else {
+                    writeln!(out, "BRDA:{},{},{},-", line, block, index).unwrap();
+                }
+            }
+            branches_found += branch.targets.len();
+            branches_hit += branch.targets_taken();
+        }
+        writeln!(out, "BRF:{}", branches_found).unwrap();
+        writeln!(out, "BRH:{}", branches_hit).unwrap();
+
+        let mut lines = BTreeMap::new();
+        for module in &modules {
+            merge_lines(&mut lines, &module.lines());
+        }
+        for (line, count) in &lines {
+            writeln!(out, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(out, "LF:{}", lines.len()).unwrap();
+        writeln!(
+            out,
+            "LH:{}",
+            lines.values().filter(|count| **count > 0).count()
+        )
+        .unwrap();
+        writeln!(out, "end_of_record").unwrap();
+    }
+    out
+}
+
+/// Renders the coverage in the Cobertura XML format, with one class per module. Source file
+/// names are relative to the package root, which is given as the only source directory.
+pub fn render_cobertura(
+    modules: &[ModuleLineCoverage],
+    package_name: &str,
+    package_root: &Path,
+    timestamp: u64,
+) -> String {
+    let all_lines: Vec<_> = modules.iter().map(|module| module.lines()).collect();
+    let (branches_valid, branches_covered) = branch_counts(
+        modules
+            .iter()
+            .flat_map(|module| &module.functions)
+            .flat_map(|function| &function.branches)
+            .map(|(_, branch)| branch),
+    );
+    let (lines_valid, lines_covered) = line_counts(all_lines.iter().flatten());
+    let line_rate = rate(lines_covered, lines_valid);
+    let branch_rate = rate(branches_covered, branches_valid);
+
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" ?>"#).unwrap();
+    writeln!(
+        out,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="0.1" timestamp="{}">"#,
+        line_rate, branch_rate, lines_covered, lines_valid, branches_covered, branches_valid, timestamp
+    )
+    .unwrap();
+    writeln!(out, "  <sources>").unwrap();
+    writeln!(
+        out,
+        "    <source>{}</source>",
+        xml_escape(&display_path(package_root))
+    )
+    .unwrap();
+    writeln!(out, "  </sources>").unwrap();
+    writeln!(out, "  <packages>").unwrap();
+    writeln!(
+        out,
+        r#"    <package name="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+        xml_escape(package_name),
+        line_rate,
+        branch_rate
+    )
+    .unwrap();
+    writeln!(out, "      <classes>").unwrap();
+    for (module, lines) in modules.iter().zip(&all_lines) {
+        let module_branches: Vec<_> = module
+            .functions
+            .iter()
+            .flat_map(|function| function.branches.iter().cloned())
+            .collect();
+        writeln!(
+            out,
+            r#"        <class name="{}" filename="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            xml_escape(&module.module_name),
+            xml_escape(&display_path(&module.source_path)),
+            rate_of_lines(lines),
+            rate_of_branches(module_branches.iter().map(|(_, b)| b)),
+        )
+        .unwrap();
+        writeln!(out, "          <methods>").unwrap();
+        for function in &module.functions {
+            writeln!(
+                out,
+                r#"            <method name="{}" signature="" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                xml_escape(&function.name),
+                rate_of_lines(&function.lines),
+                rate_of_branches(function.branches.iter().map(|(_, b)| b)),
+            )
+            .unwrap();
+            render_cobertura_lines(
+                &mut out,
+                "              ",
+                &function.lines,
+                &function.branches,
+            );
+            writeln!(out, "            </method>").unwrap();
+        }
+        writeln!(out, "          </methods>").unwrap();
+        render_cobertura_lines(&mut out, "          ", lines, &module_branches);
+        writeln!(out, "        </class>").unwrap();
+    }
+    writeln!(out, "      </classes>").unwrap();
+    writeln!(out, "    </package>").unwrap();
+    writeln!(out, "  </packages>").unwrap();
+    writeln!(out, "</coverage>").unwrap();
+    out
+}
+
+fn render_cobertura_lines(
+    out: &mut String,
+    indent: &str,
+    lines: &BTreeMap<u32, u64>,
+    branches: &[(u32, BranchCoverage)],
+) {
+    writeln!(out, "{}<lines>", indent).unwrap();
+    for (line, hits) in lines {
+        let (valid, covered) = branch_counts(
+            branches
+                .iter()
+                .filter(|(branch_line, _)| branch_line == line)
+                .map(|(_, branch)| branch),
+        );
+        if valid == 0 {
+            writeln!(
+                out,
+                r#"{}  <line number="{}" hits="{}" branch="false"/>"#,
+                indent, line, hits
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                out,
+                r#"{}  <line number="{}" hits="{}" branch="true" condition-coverage="{}% ({}/{})"/>"#,
+                indent,
+                line,
+                hits,
+                covered * 100 / valid,
+                covered,
+                valid
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "{}</lines>", indent).unwrap();
+}
+
+/// Returns the number of branch targets and the number of those taken.
+fn branch_counts<'a>(branches: impl Iterator<Item = &'a BranchCoverage>) -> (usize, usize) {
+    branches.fold((0, 0), |(valid, covered), branch| {
+        (
+            valid + branch.targets.len(),
+            covered + branch.targets_taken(),
+        )
+    })
+}
+
+/// Returns the number of lines and the number of those executed.
+fn line_counts<'a>(lines: impl Iterator<Item = (&'a u32, &'a u64)>) -> (usize, usize) {
+    lines.fold((0, 0), |(valid, covered), (_, count)| {
+        (valid + 1, covered + usize::from(*count > 0))
+    })
+}
+
+fn rate_of_lines(lines: &BTreeMap<u32, u64>) -> String {
+    let (valid, covered) = line_counts(lines.iter());
+    rate(covered, valid)
+}
+
+fn rate_of_branches<'a>(branches: impl Iterator<Item = &'a BranchCoverage>) -> String {
+    let (valid, covered) = branch_counts(branches);
+    rate(covered, valid)
+}
+
+/// The fraction of covered items, where nothing to cover counts as fully covered.
+fn rate(covered: usize, valid: usize) -> String {
+    if valid == 0 {
+        "1".to_string()
+    } else {
+        format!("{:.4}", covered as f64 / valid as f64)
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    if path.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_coverage() -> ModuleLineCoverage {
+        ModuleLineCoverage {
+            module_name: "0x1::M".to_string(),
+            source_path: PathBuf::from("sources/M.move"),
+            functions: vec![
+                FunctionLineCoverage {
+                    name: "f".to_string(),
+                    line: 3,
+                    calls: 3,
+                    lines: BTreeMap::from([(4, 3), (5, 0), (6, 3)]),
+                    branches: vec![(
+                        4,
+                        BranchCoverage {
+                            offset: 1,
+                            targets: [(4, 3), (2, 0)],
+                        },
+                    )],
+                },
+                FunctionLineCoverage {
+                    name: "g".to_string(),
+                    line: 9,
+                    calls: 0,
+                    lines: BTreeMap::from([(10, 0)]),
+                    branches: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lcov() {
+        assert_eq!(
+            render_lcov(&[module_coverage()]),
+            "\
+SF:sources/M.move
+FN:3,0x1::M::f
+FN:9,0x1::M::g
+FNDA:3,0x1::M::f
+FNDA:0,0x1::M::g
+FNF:2
+FNH:1
+BRDA:4,0,0,3
+BRDA:4,0,1,0
+BRF:2
+BRH:1
+DA:4,3
+DA:5,0
+DA:6,3
+DA:10,0
+LF:4
+LH:2
+end_of_record
+"
+        );
+    }
+
+    #[test]
+    fn test_cobertura() {
+        assert_eq!(
+            render_cobertura(&[module_coverage()], "P", Path::new("/pkg"), 42),
+            r#"<?xml version="1.0" ?>
+<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+<coverage line-rate="0.5000" branch-rate="0.5000" lines-covered="2" lines-valid="4" branches-covered="1" branches-valid="2" complexity="0" version="0.1" timestamp="42">
+  <sources>
+    <source>/pkg</source>
+  </sources>
+  <packages>
+    <package name="P" line-rate="0.5000" branch-rate="0.5000" complexity="0">
+      <classes>
+        <class name="0x1::M" filename="sources/M.move" line-rate="0.5000" branch-rate="0.5000" complexity="0">
+          <methods>
+            <method name="f" signature="" line-rate="0.6667" branch-rate="0.5000" complexity="0">
+              <lines>
+                <line number="4" hits="3" branch="true" condition-coverage="50% (1/2)"/>
+                <line number="5" hits="0" branch="false"/>
+                <line number="6" hits="3" branch="false"/>
+              </lines>
+            </method>
+            <method name="g" signature="" line-rate="0.0000" branch-rate="1" complexity="0">
+              <lines>
+                <line number="10" hits="0" branch="false"/>
+              </lines>
+            </method>
+          </methods>
+          <lines>
+            <line number="4" hits="3" branch="true" condition-coverage="50% (1/2)"/>
+            <line number="5" hits="0" branch="false"/>
+            <line number="6" hits="3" branch="false"/>
+            <line number="10" hits="0" branch="false"/>
+          </lines>
+        </class>
+      </classes>
+    </package>
+  </packages>
+</coverage>
+"#
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A record of everything that determines the bytecode compiled from a package: the compiler and
+//! bytecode versions, the options and features the package was built with, its named address
+//! assignments, and the digests of its sources and of the sources of its dependencies.  Building the same sources
+//! with the same build metadata produces the same bytecode, on any machine.
+
+use crate::{
+    compilation::compiled_package::CompiledPackageInfo,
+    resolution::resolution_graph::{ResolvedGraph, ResolvedPackage, ResolvedTable},
+    source_package::parsed_manifest::{FeatureName, PackageDigest, PackageName},
+    Architecture,
+};
+use anyhow::{bail, Context, Result};
+use move_binary_format::{
+    file_format::CompiledModule,
+    file_format_common::{VERSION_5, VERSION_MAX},
+};
+use move_command_line_common::env::get_bytecode_version_from_env;
+use move_compiler::COMPILER_VERSION;
+use move_core_types::{account_address::AccountAddress, metadata::Metadata};
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Key of the module metadata entry that the build metadata of its package is embedded in.
+pub const BUILD_METADATA_KEY: &[u8] = b"move_build_metadata";
+
+/// The build metadata of a compiled package, saved alongside it in the build directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildMetadata {
+    /// The version of the compiler the package was built with
+    pub compiler_version: String,
+    /// The bytecode version that modules and scripts were serialized with
+    pub bytecode_version: u32,
+    /// The build options that affect compilation
+    pub build_options: BuildOptions,
+    /// The instantiations for all named addresses that were used for compilation
+    pub address_alias_instantiation: ResolvedTable,
+    /// The digest of the sources of the package
+    pub source_digest: PackageDigest,
+    /// The digest of the sources of each transitive dependency of the package
+    pub dependency_digests: BTreeMap<PackageName, PackageDigest>,
+}
+
+/// The subset of `BuildConfig` that affects the bytecode compiled from a package.  Options that
+/// depend on the machine the package is built on (e.g. the installation directory) are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildOptions {
+    pub dev_mode: bool,
+    pub test_mode: bool,
+    pub architecture: Option<Architecture>,
+    pub additional_named_addresses: BTreeMap<String, AccountAddress>,
+    pub profile: Option<String>,
+    /// The features enabled in the package and in each of its transitive dependencies, for those
+    /// with any features enabled
+    #[serde(default)]
+    pub features: BTreeMap<PackageName, BTreeSet<FeatureName>>,
+}
+
+impl BuildMetadata {
+    /// The build metadata of `package`, built as part of `resolution_graph`, along with its
+    /// transitive `dependencies`.
+    pub(crate) fn new(
+        resolution_graph: &ResolvedGraph,
+        package: &ResolvedPackage,
+        dependencies: impl IntoIterator<Item = PackageName>,
+    ) -> Self {
+        let options = &resolution_graph.build_options;
+        let dependencies: Vec<_> = dependencies.into_iter().collect();
+        let mut features = resolution_graph.features_by_package();
+        features.retain(|name, enabled| {
+            !enabled.is_empty()
+                && (*name == package.source_package.package.name || dependencies.contains(name))
+        });
+        BuildMetadata {
+            compiler_version: COMPILER_VERSION.to_string(),
+            bytecode_version: get_bytecode_version_from_env().unwrap_or(VERSION_MAX),
+            build_options: BuildOptions {
+                dev_mode: options.dev_mode,
+                test_mode: options.test_mode,
+                architecture: options.architecture,
+                additional_named_addresses: options.additional_named_addresses.clone(),
+                profile: options.profile.clone(),
+                features,
+            },
+            address_alias_instantiation: package.resolution_table.clone(),
+            source_digest: package.source_digest,
+            dependency_digests: dependencies
+                .into_iter()
+                .map(|dep| (dep, resolution_graph.package_table[&dep].source_digest))
+                .collect(),
+        }
+    }
+
+    /// The build metadata of a package that was saved to disk before its build metadata was
+    /// recorded, as far as it can be recovered from the package's build `info`.  The compiler
+    /// version, the features enabled in its packages, the digests of its dependencies, and its own
+    /// digest if it had no sources, are not known, and are left empty.
+    pub(crate) fn from_package_info(info: &CompiledPackageInfo, bytecode_version: u32) -> Self {
+        let options = &info.build_flags;
+        BuildMetadata {
+            compiler_version: String::new(),
+            bytecode_version,
+            build_options: BuildOptions {
+                dev_mode: options.dev_mode,
+                test_mode: options.test_mode,
+                architecture: options.architecture,
+                additional_named_addresses: options.additional_named_addresses.clone(),
+                profile: options.profile.clone(),
+                features: BTreeMap::new(),
+            },
+            address_alias_instantiation: info.address_alias_instantiation.clone(),
+            source_digest: info.source_digest.unwrap_or_else(|| Symbol::from("")),
+            dependency_digests: BTreeMap::new(),
+        }
+    }
+
+    /// Embed this build metadata into the metadata of `module`, replacing any build metadata it
+    /// already has.
+    pub fn embed_in(&self, module: &mut CompiledModule) -> Result<()> {
+        if self.bytecode_version < VERSION_5 {
+            bail!(
+                "Build metadata can only be embedded in modules of bytecode version {} or above, \
+                 not {}",
+                VERSION_5,
+                self.bytecode_version
+            );
+        }
+
+        let value = bcs::to_bytes(self).context("Serializing build metadata")?;
+        module
+            .metadata
+            .retain(|entry| entry.key != BUILD_METADATA_KEY);
+        module.metadata.push(Metadata {
+            key: BUILD_METADATA_KEY.to_vec(),
+            value,
+        });
+        Ok(())
+    }
+
+    /// The build metadata embedded in `module`, if any.
+    pub fn from_module(module: &CompiledModule) -> Result<Option<Self>> {
+        module
+            .metadata
+            .iter()
+            .find(|entry| entry.key == BUILD_METADATA_KEY)
+            .map(|entry| {
+                bcs::from_bytes(&entry.value).context("Deserializing embedded build metadata")
+            })
+            .transpose()
+    }
+}
@@ -3,15 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    compilation::compiled_package::CompiledPackage, resolution::resolution_graph::ResolvedGraph,
+    compilation::compiled_package::{make_source_and_deps_for_compiler, CompiledPackage},
+    resolution::resolution_graph::{ResolvedGraph, ResolvedTable},
     source_package::parsed_manifest::PackageName,
 };
 use anyhow::Result;
 use move_compiler::{
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::{report_diagnostics_to_color_buffer, report_warnings, FilesSourceText},
+    shared::PackagePaths,
     Compiler,
 };
+use move_symbol_pool::Symbol;
 use petgraph::algo::toposort;
 use std::{collections::BTreeSet, io::Write, path::Path};
 
@@ -141,9 +144,54 @@ impl BuildPlan {
             Some(under_path) => under_path.clone(),
             None => self.resolution_graph.root_package_path.clone(),
         };
+        let transitive_dependencies = self.transitive_dependencies();
+
+        let compiled = CompiledPackage::build_all(
+            writer,
+            &project_root,
+            root_package.clone(),
+            transitive_dependencies,
+            &self.resolution_graph,
+            &mut compiler_driver,
+        )?;
+
+        Self::clean(
+            &project_root.join(CompiledPackageLayout::Root.path()),
+            self.sorted_deps.iter().copied().collect(),
+        )?;
+        Ok(compiled)
+    }
+
+    /// Returns the paths of the root package sources and of the sources of each of its transitive
+    /// dependencies, along with their named address mappings, in the form expected by the compiler.
+    pub fn source_and_dep_package_paths(&self) -> Result<(PackagePaths, Vec<PackagePaths>)> {
+        let root_package = &self.resolution_graph.package_table[&self.root];
+        let transitive_dependencies = self
+            .transitive_dependencies()
+            .into_iter()
+            .map(|(name, _is_immediate, source_paths, address_mapping)| {
+                (name, source_paths, address_mapping)
+            })
+            .collect();
+        make_source_and_deps_for_compiler(
+            &self.resolution_graph,
+            root_package,
+            transitive_dependencies,
+        )
+    }
+
+    fn transitive_dependencies(
+        &self,
+    ) -> Vec<(
+        /* name */ Symbol,
+        /* is immediate */ bool,
+        /* source paths */ Vec<Symbol>,
+        /* address mapping */ &ResolvedTable,
+    )> {
+        let root_package = &self.resolution_graph.package_table[&self.root];
         let immediate_dependencies_names =
             root_package.immediate_dependencies(&self.resolution_graph);
-        let transitive_dependencies = root_package
+        root_package
             .transitive_dependencies(&self.resolution_graph)
             .into_iter()
             .map(|package_name| {
@@ -162,22 +210,7 @@ impl BuildPlan {
                     &dep_package.resolution_table,
                 )
             })
-            .collect();
-
-        let compiled = CompiledPackage::build_all(
-            writer,
-            &project_root,
-            root_package.clone(),
-            transitive_dependencies,
-            &self.resolution_graph,
-            &mut compiler_driver,
-        )?;
-
-        Self::clean(
-            &project_root.join(CompiledPackageLayout::Root.path()),
-            self.sorted_deps.iter().copied().collect(),
-        )?;
-        Ok(compiled)
+            .collect()
     }
 
     #[cfg(feature = "evm-backend")]
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    compilation::package_layout::CompiledPackageLayout,
+    compilation::{build_metadata::BuildMetadata, package_layout::CompiledPackageLayout},
     resolution::resolution_graph::{Renaming, ResolvedGraph, ResolvedPackage, ResolvedTable},
     source_package::{
         layout::{SourcePackageLayout, REFERENCE_TEMPLATE_FILENAME},
@@ -14,7 +14,10 @@ use crate::{
 use anyhow::{ensure, Result};
 use colored::Colorize;
 use move_abigen::{Abigen, AbigenOptions};
-use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_binary_format::{
+    file_format::{CompiledModule, CompiledScript},
+    file_format_common::VERSION_MAX,
+};
 use move_bytecode_source_map::utils::source_map_from_file;
 use move_bytecode_utils::Modules;
 use move_command_line_common::{
@@ -76,6 +79,8 @@ pub struct CompiledPackageInfo {
 pub struct CompiledPackage {
     /// Meta information about the compilation of this `CompiledPackage`
     pub compiled_package_info: CompiledPackageInfo,
+    /// The record of everything that determines the bytecode compiled from this package
+    pub build_metadata: BuildMetadata,
     /// The output compiled bytecode in the root package (both module, and scripts) along with its
     /// source file
     pub root_compiled_units: Vec<CompiledUnitWithSource>,
@@ -181,7 +186,7 @@ impl OnDiskCompiledPackage {
         let compiled_abis = if abi_path.is_dir() {
             Some(
                 find_filenames(&[abi_path.to_string_lossy().to_string()], |path| {
-                    extension_equals(path, "abi")
+                    extension_equals(path, "abi") || extension_equals(path, "json")
                 })?
                 .into_iter()
                 .map(|path| {
@@ -194,8 +199,28 @@ impl OnDiskCompiledPackage {
             None
         };
 
+        let build_metadata_path = self
+            .root_path
+            .join(CompiledPackageLayout::BuildMetadata.path());
+        let build_metadata = if build_metadata_path.is_file() {
+            serde_yaml::from_slice(&std::fs::read(&build_metadata_path)?)?
+        } else {
+            // Packages built before build metadata was recorded only have their build info
+            let bytecode_version = root_compiled_units
+                .iter()
+                .find_map(|unit| match &unit.unit {
+                    CompiledUnit::Module(NamedCompiledModule { module, .. }) => {
+                        Some(module.version)
+                    }
+                    CompiledUnit::Script(_) => None,
+                })
+                .unwrap_or(VERSION_MAX);
+            BuildMetadata::from_package_info(&self.package.compiled_package_info, bytecode_version)
+        };
+
         Ok(CompiledPackage {
             compiled_package_info: self.package.compiled_package_info.clone(),
+            build_metadata,
             root_compiled_units,
             deps_compiled_units,
             compiled_docs,
@@ -292,6 +317,13 @@ impl OnDiskCompiledPackage {
         }
     }
 
+    #[allow(unused)]
+    pub(crate) fn has_build_metadata(&self) -> bool {
+        self.root_path
+            .join(CompiledPackageLayout::BuildMetadata.path())
+            .is_file()
+    }
+
     #[allow(unused)]
     pub(crate) fn are_build_flags_different(&self, build_config: &BuildConfig) -> bool {
         build_config != &self.package.compiled_package_info.build_flags
@@ -514,6 +546,8 @@ impl CompiledPackage {
     ) -> bool {
         // TODO: add more tests for the different caching cases
         !(package.has_source_changed_since_last_compile(resolved_package) // recompile if source has changed
+            // Recompile if the package was built before build metadata was recorded
+                || !package.has_build_metadata()
             // Recompile if the flags are different
                 || package.are_build_flags_different(&resolution_graph.build_options)
                 // Force root package recompilation in test mode
@@ -564,6 +598,12 @@ impl CompiledPackage {
         let root_package_name = resolved_package.source_package.package.name;
         writeln!(w, "{} {}", "BUILDING".bold().green(), root_package_name)?;
 
+        let build_metadata = BuildMetadata::new(
+            resolution_graph,
+            &resolved_package,
+            transitive_dependencies.iter().map(|(name, _, _)| *name),
+        );
+
         // gather source/dep files with their address mappings
         let (sources_package_paths, deps_package_paths) = make_source_and_deps_for_compiler(
             resolution_graph,
@@ -574,7 +614,8 @@ impl CompiledPackage {
             Flags::testing()
         } else {
             Flags::empty()
-        };
+        }
+        .set_features(resolution_graph.features_by_package());
         // invoke the compiler
         let mut paths = deps_package_paths.clone();
         paths.push(sources_package_paths.clone());
@@ -600,6 +641,14 @@ impl CompiledPackage {
             }
         }
 
+        if resolution_graph.build_options.embed_build_metadata {
+            for unit in &mut root_compiled_units {
+                if let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &mut unit.unit {
+                    build_metadata.embed_in(module)?;
+                }
+            }
+        }
+
         let mut compiled_docs = None;
         let mut compiled_abis = None;
         if resolution_graph.build_options.generate_docs
@@ -637,6 +686,7 @@ impl CompiledPackage {
                 source_digest: Some(resolved_package.source_digest),
                 build_flags: resolution_graph.build_options.clone(),
             },
+            build_metadata,
             root_compiled_units,
             deps_compiled_units,
             compiled_docs,
@@ -751,10 +801,7 @@ impl CompiledPackage {
         if let Some(abis) = &self.compiled_abis {
             for (filename, abi_bytes) in abis {
                 on_disk_package.save_under(
-                    CompiledPackageLayout::CompiledABIs
-                        .path()
-                        .join(filename)
-                        .with_extension("abi"),
+                    CompiledPackageLayout::CompiledABIs.path().join(filename),
                     abi_bytes,
                 )?;
             }
@@ -765,6 +812,11 @@ impl CompiledPackage {
             serde_yaml::to_string(&on_disk_package.package)?.as_bytes(),
         )?;
 
+        on_disk_package.save_under(
+            CompiledPackageLayout::BuildMetadata.path(),
+            serde_yaml::to_string(&self.build_metadata)?.as_bytes(),
+        )?;
+
         Ok(on_disk_package)
     }
 
@@ -789,6 +841,7 @@ impl CompiledPackage {
         let abi_options = AbigenOptions {
             in_memory_bytes: Some(bytecode_map),
             output_directory: "".to_string(),
+            module_abis: true,
             ..AbigenOptions::default()
         };
         let mut abigen = Abigen::new(model, &abi_options);
@@ -2,7 +2,10 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod build_metadata;
 pub mod build_plan;
 pub mod compiled_package;
 pub mod model_builder;
+pub mod package_bundle;
 pub mod package_layout;
+pub mod verification;
@@ -0,0 +1,215 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A self-contained, portable artifact of a built package: the bytecode of its modules and of the
+//! modules of its dependencies, in the order they can be published in, along with their source
+//! maps and sources, the manifest, ABIs and build metadata of the package, and a hash of all of
+//! it.  The same bundle can be handed to reviewers, verifiers and deployers, and published without
+//! access to the package or its build directory.
+
+use crate::{
+    compilation::{build_metadata::BuildMetadata, compiled_package::CompiledPackage},
+    source_package::parsed_manifest::PackageName,
+};
+use anyhow::{bail, ensure, Context, Result};
+use move_binary_format::file_format::CompiledModule;
+use move_command_line_common::env::get_bytecode_version_from_env;
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, path::Path};
+
+/// Version of the bundle format, to be bumped on any change to it.
+pub const PACKAGE_BUNDLE_VERSION: u32 = 1;
+
+/// Extension of files holding a package bundle.
+pub const PACKAGE_BUNDLE_EXTENSION: &str = "mvb";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageBundle {
+    /// The version of the bundle format
+    pub version: u32,
+    /// The name of the bundled package
+    pub package_name: PackageName,
+    /// The contents of the manifest of the package
+    pub manifest: String,
+    /// The record of everything that determines the bytecode of the package
+    pub build_metadata: BuildMetadata,
+    /// The modules of the package and of its dependencies, in dependency order
+    pub modules: Vec<BundledModule>,
+    /// The ABIs of the package by file name, if they were generated
+    pub abis: BTreeMap<String, Vec<u8>>,
+    /// The hash of all other contents of the bundle
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledModule {
+    /// The name of the package the module belongs to
+    pub package_name: PackageName,
+    /// The name of the module
+    pub name: Symbol,
+    /// The serialized bytecode of the module
+    pub bytecode: Vec<u8>,
+    /// The serialized source map of the module
+    pub source_map: Vec<u8>,
+    /// The contents of the source file the module was compiled from
+    pub source: String,
+}
+
+impl PackageBundle {
+    /// Bundle `package`, whose manifest has contents `manifest`.
+    pub fn new(package: &CompiledPackage, manifest: String) -> Result<Self> {
+        let bytecode_version = get_bytecode_version_from_env();
+        let units: BTreeMap<_, _> = package
+            .root_modules()
+            .map(|unit| (package.compiled_package_info.package_name, unit))
+            .chain(
+                package
+                    .deps_compiled_units
+                    .iter()
+                    .map(|(package_name, unit)| (*package_name, unit)),
+            )
+            .filter_map(|(package_name, unit)| match &unit.unit {
+                CompiledUnit::Module(NamedCompiledModule { module, .. }) => {
+                    Some((module.self_id(), (package_name, unit)))
+                }
+                CompiledUnit::Script(_) => None,
+            })
+            .collect();
+
+        let all_modules = package.all_modules_map();
+        let dependency_graph = all_modules.compute_dependency_graph();
+        let mut modules = vec![];
+        for module in dependency_graph.compute_topological_order()? {
+            let (package_name, unit) = units[&module.self_id()];
+            modules.push(BundledModule {
+                package_name,
+                name: unit.unit.name(),
+                bytecode: unit.unit.serialize(bytecode_version),
+                source_map: unit.unit.serialize_source_map(),
+                source: std::fs::read_to_string(&unit.source_path).with_context(|| {
+                    format!(
+                        "Reading the source of module '{}' from '{}'",
+                        unit.unit.name(),
+                        unit.source_path.to_string_lossy()
+                    )
+                })?,
+            });
+        }
+
+        let mut bundle = PackageBundle {
+            version: PACKAGE_BUNDLE_VERSION,
+            package_name: package.compiled_package_info.package_name,
+            manifest,
+            build_metadata: package.build_metadata.clone(),
+            modules,
+            abis: package.compiled_abis.iter().flatten().cloned().collect(),
+            content_hash: String::new(),
+        };
+        bundle.content_hash = bundle.compute_content_hash()?;
+        Ok(bundle)
+    }
+
+    /// Deserialize a bundle from `bytes`, checking that its contents match its hash.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bundle: PackageBundle =
+            bcs::from_bytes(bytes).context("Deserializing package bundle")?;
+        bundle.verify()?;
+        Ok(bundle)
+    }
+
+    /// Read the bundle at `path`, checking that its contents match its hash.
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Reading package bundle '{}'", path.to_string_lossy()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).context("Serializing package bundle")
+    }
+
+    /// Write the bundle to `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_bytes()?)
+            .with_context(|| format!("Writing package bundle '{}'", path.to_string_lossy()))
+    }
+
+    /// Check that the bundle is of a supported version, and that its contents match its hash.
+    pub fn verify(&self) -> Result<()> {
+        ensure!(
+            self.version == PACKAGE_BUNDLE_VERSION,
+            "Unsupported package bundle version {}, expected {}",
+            self.version,
+            PACKAGE_BUNDLE_VERSION
+        );
+
+        let content_hash = self.compute_content_hash()?;
+        if content_hash != self.content_hash {
+            bail!(
+                "Contents of the bundle of package '{}' do not match its hash: expected {}, \
+                 found {}",
+                self.package_name,
+                self.content_hash,
+                content_hash
+            )
+        }
+        Ok(())
+    }
+
+    /// The modules of the bundled package itself, in dependency order.
+    pub fn package_modules(&self) -> impl Iterator<Item = &BundledModule> {
+        self.modules
+            .iter()
+            .filter(move |module| module.package_name == self.package_name)
+    }
+
+    /// The serialized modules of the bundled package itself, in the order they can be published
+    /// in, along with the address they are published at, e.g. to hand to
+    /// `Session::publish_module_bundle`.
+    pub fn package_module_bundle(&self) -> Result<(AccountAddress, Vec<Vec<u8>>)> {
+        let mut sender = None;
+        let mut bytecodes = vec![];
+        for module in self.package_modules() {
+            let address = *module.id()?.address();
+            match sender {
+                None => sender = Some(address),
+                Some(sender) if sender != address => bail!(
+                    "All modules of package '{}' must share the same address to be published \
+                     together",
+                    self.package_name
+                ),
+                Some(_) => (),
+            }
+            bytecodes.push(module.bytecode.clone());
+        }
+
+        match sender {
+            Some(sender) => Ok((sender, bytecodes)),
+            None => bail!("Package '{}' has no modules to publish", self.package_name),
+        }
+    }
+
+    fn compute_content_hash(&self) -> Result<String> {
+        let contents = bcs::to_bytes(&PackageBundle {
+            content_hash: String::new(),
+            ..self.clone()
+        })
+        .context("Serializing package bundle")?;
+        Ok(format!("{:X}", Sha256::digest(&contents)))
+    }
+}
+
+impl BundledModule {
+    pub fn module(&self) -> Result<CompiledModule> {
+        CompiledModule::deserialize(&self.bytecode)
+            .with_context(|| format!("Deserializing bundled module '{}'", self.name))
+    }
+
+    pub fn id(&self) -> Result<ModuleId> {
+        Ok(self.module()?.self_id())
+    }
+}
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 #[derive(Debug, Clone)]
 pub enum CompiledPackageLayout {
     BuildInfo,
+    BuildMetadata,
     Root,
     Dependencies,
     Sources,
@@ -22,6 +23,7 @@ impl CompiledPackageLayout {
     pub fn path(&self) -> &Path {
         let path = match self {
             Self::BuildInfo => "BuildInfo.yaml",
+            Self::BuildMetadata => "BuildMetadata.yaml",
             Self::Root => "build",
             Self::Dependencies => "dependencies",
             Self::Sources => "sources",
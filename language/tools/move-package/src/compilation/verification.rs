@@ -0,0 +1,214 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification that deployed modules were compiled from the sources of a package, by comparing
+//! them with the modules rebuilt from the package.  Modules are compared after being serialized
+//! with the same bytecode version, ignoring their declared metadata, which does not affect their
+//! behaviour.
+
+use crate::compilation::compiled_package::CompiledPackage;
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use move_binary_format::file_format::CompiledModule;
+use move_command_line_common::{
+    env::get_bytecode_version_from_env,
+    files::{extension_equals, find_filenames, MOVE_COMPILED_EXTENSION},
+};
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_core_types::language_storage::ModuleId;
+use std::{collections::BTreeMap, fs, io::Write, path::Path};
+
+/// The deployed modules to verify a package against, keyed by their ids.
+pub type DeployedModules = BTreeMap<ModuleId, CompiledModule>;
+
+/// The outcome of comparing a module rebuilt from a package with its deployed counterpart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleVerification {
+    /// The deployed module is identical to the rebuilt module, ignoring metadata.
+    Matches,
+    /// No module with the same id is deployed.
+    Missing,
+    /// The deployed module diverges from the rebuilt module, starting at entry `index` of `table`
+    /// (or at `table` itself if it is not a table, e.g. the bytecode version).
+    Differs {
+        table: &'static str,
+        index: Option<usize>,
+    },
+}
+
+/// The result of verifying each module of a package against deployed bytecode.
+#[derive(Debug, Clone)]
+pub struct VerificationReport {
+    pub modules: Vec<(ModuleId, ModuleVerification)>,
+}
+
+impl VerificationReport {
+    /// Whether every module of the package matches its deployed counterpart.
+    pub fn is_verified(&self) -> bool {
+        self.modules
+            .iter()
+            .all(|(_, result)| *result == ModuleVerification::Matches)
+    }
+
+    /// Print whether each module matches, and where the modules that do not match diverge.
+    pub fn print<W: Write>(&self, writer: &mut W) -> Result<()> {
+        for (id, result) in &self.modules {
+            let name = format!("0x{}::{}", id.address().short_str_lossless(), id.name());
+            match result {
+                ModuleVerification::Matches => {
+                    writeln!(writer, "{} {}", "MATCHES".bold().green(), name)?
+                }
+                ModuleVerification::Missing => writeln!(
+                    writer,
+                    "{} {}: no deployed module found",
+                    "MISSING".bold().red(),
+                    name
+                )?,
+                ModuleVerification::Differs { table, index } => {
+                    let at = match index {
+                        Some(index) => format!("{}[{}]", table, index),
+                        None => table.to_string(),
+                    };
+                    writeln!(
+                        writer,
+                        "{} {}: first difference in {}",
+                        "DIFFERS".bold().red(),
+                        name,
+                        at
+                    )?
+                }
+            }
+        }
+
+        let matching = self
+            .modules
+            .iter()
+            .filter(|(_, result)| *result == ModuleVerification::Matches)
+            .count();
+        writeln!(
+            writer,
+            "{} of {} modules match the deployed bytecode",
+            matching,
+            self.modules.len()
+        )?;
+        Ok(())
+    }
+}
+
+/// Read the modules stored as `.mv` files anywhere under `dir`, e.g. the storage directory of the
+/// sandbox or a directory of bytecode fetched from chain.  Fails if a file does not hold a valid
+/// module, or if the same module is found more than once.
+pub fn read_deployed_modules(dir: &Path) -> Result<DeployedModules> {
+    if !dir.is_dir() {
+        bail!("Unable to find deployed bytecode directory {:?}", dir);
+    }
+
+    let mut modules = DeployedModules::new();
+    let paths = find_filenames(&[dir], |path| {
+        extension_equals(path, MOVE_COMPILED_EXTENSION)
+    })?;
+
+    for path in paths {
+        let bytes = fs::read(&path).with_context(|| format!("Reading {}", path))?;
+        let module = CompiledModule::deserialize(&bytes)
+            .with_context(|| format!("Deserializing module at {}", path))?;
+
+        let id = module.self_id();
+        if modules.insert(id.clone(), module).is_some() {
+            bail!(
+                "Found more than one deployed copy of module {} (the last at {})",
+                id,
+                path
+            );
+        }
+    }
+
+    Ok(modules)
+}
+
+/// Compare each module in the root of `package` with its counterpart in `deployed`.
+pub fn verify_package(
+    package: &CompiledPackage,
+    deployed: &DeployedModules,
+) -> Result<VerificationReport> {
+    let bytecode_version = get_bytecode_version_from_env();
+    let mut modules = Vec::new();
+
+    for unit in package.root_modules() {
+        let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &unit.unit else {
+            continue;
+        };
+
+        // Round-trip the rebuilt module through the serializer, to compare it with the deployed
+        // module as it would have been deployed.
+        let rebuilt = CompiledModule::deserialize(&unit.unit.serialize(bytecode_version))
+            .with_context(|| format!("Deserializing rebuilt module {}", module.self_id()))?;
+
+        let id = rebuilt.self_id();
+        let result = match deployed.get(&id) {
+            None => ModuleVerification::Missing,
+            Some(deployed) => match first_difference(&rebuilt, deployed) {
+                None => ModuleVerification::Matches,
+                Some((table, index)) => ModuleVerification::Differs { table, index },
+            },
+        };
+
+        modules.push((id, result));
+    }
+
+    Ok(VerificationReport { modules })
+}
+
+/// The first table where `rebuilt` and `deployed` differ, in the order they are serialized, and the
+/// index of the first entry that differs in it.  Metadata is not compared.
+fn first_difference(
+    rebuilt: &CompiledModule,
+    deployed: &CompiledModule,
+) -> Option<(&'static str, Option<usize>)> {
+    if rebuilt.version != deployed.version {
+        return Some(("version", None));
+    }
+
+    if rebuilt.self_module_handle_idx != deployed.self_module_handle_idx {
+        return Some(("self_module_handle_idx", None));
+    }
+
+    macro_rules! compare_tables {
+        ($($table:ident),* $(,)?) => {
+            $(
+                if let Some(index) = first_mismatch(&rebuilt.$table, &deployed.$table) {
+                    return Some((stringify!($table), Some(index)));
+                }
+            )*
+        };
+    }
+
+    compare_tables!(
+        module_handles,
+        struct_handles,
+        function_handles,
+        field_handles,
+        friend_decls,
+        struct_def_instantiations,
+        function_instantiations,
+        field_instantiations,
+        signatures,
+        identifiers,
+        address_identifiers,
+        constant_pool,
+        struct_defs,
+        function_defs,
+    );
+
+    None
+}
+
+/// The index of the first entry that differs between `rebuilt` and `deployed`, counting a missing
+/// entry as a difference.
+fn first_mismatch<T: PartialEq>(rebuilt: &[T], deployed: &[T]) -> Option<usize> {
+    rebuilt
+        .iter()
+        .zip(deployed)
+        .position(|(r, d)| r != d)
+        .or_else(|| (rebuilt.len() != deployed.len()).then(|| rebuilt.len().min(deployed.len())))
+}
@@ -9,11 +9,14 @@ pub mod package_hooks;
 pub mod resolution;
 pub mod source_package;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::*;
 use move_core_types::account_address::AccountAddress;
 use move_model::model::GlobalEnv;
-use resolution::{dependency_graph::DependencyGraph, lock_file::LockFile};
+use resolution::{
+    dependency_graph::DependencyGraph,
+    lock_file::{schema, LockFile},
+};
 use serde::{Deserialize, Serialize};
 use source_package::layout::SourcePackageLayout;
 use std::{
@@ -25,10 +28,14 @@ use std::{
 
 use crate::{
     compilation::{
-        build_plan::BuildPlan, compiled_package::CompiledPackage, model_builder::ModelBuilder,
+        build_plan::BuildPlan,
+        compiled_package::CompiledPackage,
+        model_builder::ModelBuilder,
+        package_bundle::PackageBundle,
+        verification::{self, VerificationReport},
     },
     package_lock::PackageLock,
-    resolution::resolution_graph::{ResolutionGraph, ResolvedGraph},
+    resolution::resolution_graph::{address_profile, ResolutionGraph, ResolvedGraph},
     source_package::manifest_parser,
 };
 
@@ -109,8 +116,14 @@ pub struct BuildConfig {
     #[clap(name = "generate-docs", long = "doc", global = true)]
     pub generate_docs: bool,
 
-    /// Generate ABIs for packages
-    #[clap(name = "generate-abis", long = "abi", global = true)]
+    /// Generate ABIs for packages: BCS-encoded ABIs of scripts and entry functions, and a JSON
+    /// ABI of each module
+    #[clap(
+        name = "generate-abis",
+        long = "abi",
+        alias = "generate-abis",
+        global = true
+    )]
     pub generate_abis: bool,
 
     /// Installation directory for compiled artifacts. Defaults to current directory.
@@ -139,6 +152,32 @@ pub struct BuildConfig {
     /// Skip fetching latest git dependencies
     #[clap(long = "skip-fetch-latest-git-deps", global = true)]
     pub skip_fetch_latest_git_deps: bool,
+
+    /// Only read remote dependencies from their copies vendored into the package by `move vendor`,
+    /// failing instead of fetching them
+    #[clap(long = "vendored-only", global = true)]
+    pub vendored_only: bool,
+
+    /// Embed the build metadata of the package (compiler version, build options, named addresses
+    /// and source digests) in the metadata of each of its modules
+    #[clap(long = "embed-build-metadata", global = true)]
+    pub embed_build_metadata: bool,
+
+    /// Features to enable, on top of the default features of each package. Features of the root
+    /// package are given by name, features of its dependencies as `<package>/<feature>`
+    #[clap(
+        long = "features",
+        global = true,
+        use_value_delimiter = true,
+        multiple_occurrences = true
+    )]
+    pub features: Vec<String>,
+
+    /// Named address profile to build with, from the '[addresses.profile.<name>]' sections of the
+    /// manifest. Its addresses override the ones packages assign themselves, and additional named
+    /// addresses take precedence over them
+    #[clap(long = "profile", global = true)]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
@@ -175,6 +214,59 @@ impl BuildConfig {
         ret
     }
 
+    /// Compile the members of the workspace containing `path`, in dependency order, into the build
+    /// directory of the workspace, returning the compiled members. Failures to resolve the
+    /// workspace or to build a member are returned as errors, but like `compile_package`, compiler
+    /// diagnostics in a member exit the process.
+    pub fn compile_workspace<W: Write>(
+        self,
+        path: &Path,
+        writer: &mut W,
+    ) -> Result<Vec<CompiledPackage>> {
+        let resolved_graphs = self.resolution_graphs_for_workspace(path, writer)?;
+        let mutx = PackageLock::lock();
+        let ret = resolved_graphs
+            .into_iter()
+            .map(|resolved_graph| BuildPlan::create(resolved_graph)?.compile(writer))
+            .collect();
+        mutx.unlock();
+        ret
+    }
+
+    /// Rebuild the package at `path` from scratch, and compare each of its modules with the
+    /// deployed modules stored as `.mv` files under `bytecode_dir`, ignoring their metadata.
+    pub fn verify_bytecode<W: Write>(
+        self,
+        path: &Path,
+        bytecode_dir: &Path,
+        writer: &mut W,
+    ) -> Result<VerificationReport> {
+        let deployed = verification::read_deployed_modules(bytecode_dir)?;
+        let config = BuildConfig {
+            test_mode: false,
+            force_recompilation: true,
+            ..self
+        };
+        let compiled = config.compile_package_no_exit(path, writer)?;
+        verification::verify_package(&compiled, &deployed)
+    }
+
+    /// Build the package at `path` along with its ABIs, and bundle it with its dependencies into
+    /// a single portable artifact.
+    pub fn bundle_package<W: Write>(self, path: &Path, writer: &mut W) -> Result<PackageBundle> {
+        let root = SourcePackageLayout::try_find_root(path)?;
+        let manifest_path = root.join(SourcePackageLayout::Manifest.path());
+        let manifest = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Reading manifest '{}'", manifest_path.to_string_lossy()))?;
+
+        let config = BuildConfig {
+            generate_abis: true,
+            ..self
+        };
+        let compiled = config.compile_package_no_exit(&root, writer)?;
+        PackageBundle::new(&compiled, manifest)
+    }
+
     #[cfg(feature = "evm-backend")]
     pub fn compile_package_evm<W: Write>(self, path: &Path, writer: &mut W) -> Result<()> {
         // resolution graph diagnostics are only needed for CLI commands so ignore them by passing a
@@ -218,6 +310,77 @@ impl BuildConfig {
         Ok(())
     }
 
+    /// Build the dependency graph of the package at `path`, including its dev-dependencies.
+    pub fn dependency_graph_for_package<W: Write>(
+        &self,
+        path: &Path,
+        writer: &mut W,
+    ) -> Result<DependencyGraph> {
+        let path = SourcePackageLayout::try_find_root(path)?;
+        let toml_manifest =
+            self.parse_toml_manifest(path.join(SourcePackageLayout::Manifest.path()))?;
+        let mutx = PackageLock::lock();
+        // This should be locked as it inspects the environment for `MOVE_HOME` which could
+        // possibly be set by a different process in parallel.
+        let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
+        let dependency_graph = DependencyGraph::build(
+            &manifest,
+            path,
+            self.skip_fetch_latest_git_deps,
+            self.vendored_only,
+            writer,
+        )?;
+        mutx.unlock();
+        Ok(dependency_graph)
+    }
+
+    /// Print the git dependencies of the package at `path` whose commit pinned in its lock file is
+    /// behind the latest commit of the branch they track, fetching their repositories first if
+    /// `fetch_latest_git_deps` is set.  The lock file is read from `lock_file` if it is set, and
+    /// from the root of the package otherwise.
+    pub fn print_outdated_dependencies<W: Write>(
+        &self,
+        path: &Path,
+        fetch_latest_git_deps: bool,
+        writer: &mut W,
+    ) -> Result<()> {
+        let path = SourcePackageLayout::try_find_root(path)?;
+        let lock_path = self
+            .lock_file
+            .clone()
+            .unwrap_or_else(|| path.join(SourcePackageLayout::Lock.path()));
+        let mut lock = std::fs::File::open(&lock_path)
+            .with_context(|| format!("Reading lock file '{}'", lock_path.to_string_lossy()))?;
+        let mutx = PackageLock::lock();
+        // This should be locked as it inspects the environment for `MOVE_HOME` which could
+        // possibly be set by a different process in parallel.
+        let ret = DependencyGraph::print_outdated(&mut lock, fetch_latest_git_deps, writer);
+        mutx.unlock();
+        ret
+    }
+
+    /// Copy the remote dependencies of the package at `path` into its vendor directory, so that
+    /// it can be built without fetching them.
+    pub fn vendor_dependencies<W: Write>(&self, path: &Path, writer: &mut W) -> Result<()> {
+        let path = SourcePackageLayout::try_find_root(path)?;
+        let toml_manifest =
+            self.parse_toml_manifest(path.join(SourcePackageLayout::Manifest.path()))?;
+        let mutx = PackageLock::lock();
+        // This should be locked as it inspects the environment for `MOVE_HOME` which could
+        // possibly be set by a different process in parallel.
+        let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
+        let dependency_graph = DependencyGraph::build(
+            &manifest,
+            path.clone(),
+            self.skip_fetch_latest_git_deps,
+            self.vendored_only,
+            writer,
+        )?;
+        dependency_graph.vendor(writer)?;
+        mutx.unlock();
+        Ok(())
+    }
+
     pub fn resolution_graph_for_package<W: Write>(
         mut self,
         path: &Path,
@@ -235,26 +398,127 @@ impl BuildConfig {
         // possibly be set by a different process in parallel.
         let mut lock = LockFile::new(&path)?;
         let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
+        let profile_addresses = address_profile(&self, [&manifest])?;
 
-        let dependency_graph = DependencyGraph::new(
+        let dependency_graph = DependencyGraph::build(
             &manifest,
             path.clone(),
             self.skip_fetch_latest_git_deps,
+            self.vendored_only,
             writer,
         )?;
 
         dependency_graph.write_to_lock(&mut lock)?;
+        if let (Some(profile), Some(addresses)) = (&self.profile, &profile_addresses) {
+            schema::write_address_profile(&mut *lock, profile, addresses)?;
+        }
         if let Some(lock_path) = &self.lock_file {
             lock.commit(lock_path)?;
         }
 
-        let resolution_graph = ResolutionGraph::new(manifest, path, self, writer)?;
+        let resolution_graph = ResolutionGraph::new_with_dependency_graph(
+            manifest,
+            path,
+            &dependency_graph,
+            self,
+            writer,
+        )?;
         let ret = resolution_graph.resolve()?;
 
         mutx.unlock();
         Ok(ret)
     }
 
+    /// Resolve the members of the workspace containing `path` together, writing a single lock file
+    /// for the workspace, and return the resolution graph rooted at each member, in dependency
+    /// order.
+    pub fn resolution_graphs_for_workspace<W: Write>(
+        mut self,
+        path: &Path,
+        writer: &mut W,
+    ) -> Result<Vec<ResolvedGraph>> {
+        if self.test_mode {
+            self.dev_mode = true;
+        }
+        let path = SourcePackageLayout::try_find_workspace_root(path)?;
+        let toml_manifest =
+            self.parse_toml_manifest(path.join(SourcePackageLayout::Manifest.path()))?;
+        let workspace = manifest_parser::parse_workspace_manifest(&toml_manifest)?
+            .expect("The root of a workspace has a workspace manifest");
+        let members = workspace
+            .members
+            .into_iter()
+            .map(|member| {
+                let manifest = self
+                    .parse_toml_manifest(
+                        path.join(&member)
+                            .join(SourcePackageLayout::Manifest.path()),
+                    )
+                    .and_then(manifest_parser::parse_source_manifest)
+                    .with_context(|| {
+                        format!(
+                            "Error parsing manifest of workspace member '{}'",
+                            member.to_string_lossy()
+                        )
+                    })?;
+                Ok((member, manifest))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let profile_addresses =
+            address_profile(&self, members.iter().map(|(_, manifest)| manifest))?;
+        let mutx = PackageLock::lock();
+
+        // This should be locked as it inspects the environment for `MOVE_HOME` which could
+        // possibly be set by a different process in parallel.
+        let mut lock = LockFile::new(&path)?;
+
+        let dependency_graph = DependencyGraph::new_workspace(
+            &members,
+            path.clone(),
+            self.skip_fetch_latest_git_deps,
+            self.vendored_only,
+            writer,
+        )?;
+        let order = dependency_graph.topological_order();
+
+        dependency_graph.write_to_lock(&mut lock)?;
+        if let (Some(profile), Some(addresses)) = (&self.profile, &profile_addresses) {
+            schema::write_address_profile(&mut *lock, profile, addresses)?;
+        }
+        if let Some(lock_path) = &self.lock_file {
+            lock.commit(lock_path)?;
+        }
+
+        let mut members = members
+            .into_iter()
+            .map(|(member, manifest)| {
+                let member_path = if member.as_os_str().is_empty() {
+                    path.clone()
+                } else {
+                    path.join(member)
+                };
+                (manifest, member_path)
+            })
+            .collect::<Vec<_>>();
+        members.sort_by_key(|(manifest, _)| {
+            order.iter().position(|name| *name == manifest.package.name)
+        });
+        let member_names = members
+            .iter()
+            .map(|(manifest, _)| manifest.package.name)
+            .collect::<Vec<_>>();
+
+        let resolution_graph =
+            ResolutionGraph::new_workspace(members, path, &dependency_graph, self, writer)?;
+        let resolved_graph = resolution_graph.resolve()?;
+
+        mutx.unlock();
+        Ok(member_names
+            .into_iter()
+            .map(|member| resolved_graph.rooted_at(member))
+            .collect())
+    }
+
     fn parse_toml_manifest(&self, path: PathBuf) -> Result<toml::Value> {
         let manifest_string = std::fs::read_to_string(path)?;
         manifest_parser::parse_move_manifest_string(manifest_string)
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{bail, Context, Result};
+use colored::Colorize;
 use move_symbol_pool::Symbol;
 use petgraph::{algo, prelude::DiGraphMap, Direction};
+use ptree::{write_tree, TreeBuilder};
 use std::{
     collections::{btree_map::Entry, BTreeMap, BTreeSet},
-    fmt,
+    fmt, fs,
     io::{BufWriter, Read, Write},
     path::{Path, PathBuf},
 };
@@ -14,18 +16,23 @@ use std::{
 use crate::{
     package_hooks,
     source_package::{
+        layout::SourcePackageLayout,
         manifest_parser::parse_dependency,
         parsed_manifest::{
-            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, PackageName,
-            SourceManifest, SubstOrRename, Substitution,
+            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, PackageDigest,
+            PackageName, RegistryInfo, SourceManifest, SubstOrRename, Substitution,
         },
     },
 };
 
 use super::{
-    download_and_update_if_remote,
+    digest::compute_digest,
+    download_and_update_if_remote, git_branch_head, git_checked_out_commit, local_path,
     lock_file::{schema, LockFile},
     parse_package_manifest,
+    registry::VersionResolver,
+    repository_path,
+    vendor::{copy_package, VendoredPackages, VENDOR_DIR, VENDOR_MANIFEST},
 };
 
 /// A representation of the transitive dependency graph of a Move package.  If successfully created,
@@ -35,6 +42,9 @@ use super::{
 /// - mentions each package at most once (i.e. no duplicate packages), and
 /// - contains information about the source of every package (excluding the root package).
 ///
+/// The graph of a workspace has the members of the workspace as its roots, and each member is
+/// sourced from its path relative to the root of the workspace.
+///
 /// It can be built by recursively exploring a package's dependencies, fetching their sources if
 /// necessary, or by reading its serialized contents from a lock file.  Both these processes will
 /// fail if any of the criteria above cannot be met (e.g. if the graph contains a cycle, the same
@@ -43,23 +53,40 @@ use super::{
 ///
 /// In order to be `BuildConfig` agnostic, it contains `dev-dependencies` as well as `dependencies`
 /// and labels edges in the graph accordingly, as `DevOnly`, or `Always` dependencies.
+///
+/// Dependencies can require versions of the packages they depend on.  Dependencies on packages
+/// published to a registry are sourced from the latest version satisfying all the requirements on
+/// the package, and graph construction fails if no version does, or if the declared version of a
+/// package depended on by source does not.
+///
+/// Packages vendored into the root package are read from their vendored copies instead of being
+/// fetched, as long as they were vendored from the same source.
 #[derive(Debug)]
 pub struct DependencyGraph {
-    /// Path to the root package and its name (according to its manifest)
+    /// Path to the root package and its name (according to its manifest), or to the root of a
+    /// workspace and the names of its members.
     root_path: PathBuf,
-    root_package: PackageName,
+    root_packages: Vec<PackageName>,
 
     /// Transitive dependency graph, with dependency edges `P -> Q` labelled according to whether Q
     /// is always a dependency of P or only in dev-mode.
     package_graph: DiGraphMap<PackageName, DependencyMode>,
 
-    /// The dependency that each package (keyed by name) originates from.  The root package is the
-    /// only node in `package_graph` that does not have an entry in `package_table`.
+    /// The dependency that each package (keyed by name) originates from.  The root package of a
+    /// single package is the only node in `package_graph` that does not have an entry in
+    /// `package_table`.
     package_table: BTreeMap<PackageName, Dependency>,
 
     /// Packages that are transitive dependencies regardless of mode (the transitive closure of
     /// `DependencyMode::Always` edges in `package_graph`).
     pub always_deps: BTreeSet<PackageName>,
+
+    /// Requirements found on the versions of packages in the graph, and the versions picked for
+    /// registry packages.
+    versions: VersionResolver,
+
+    /// Copies of remote packages vendored into the root package.
+    vendored: VendoredPackages,
 }
 
 /// Edge label indicating whether one package always depends on another, or only in dev-mode.
@@ -88,28 +115,120 @@ impl DependencyGraph {
         root_path: PathBuf,
         skip_fetch_latest_git_deps: bool,
         progress_output: &mut Progress,
+    ) -> Result<DependencyGraph> {
+        Self::build(
+            root_package,
+            root_path,
+            skip_fetch_latest_git_deps,
+            /* vendored_only */ false,
+            progress_output,
+        )
+    }
+
+    /// Build a graph like `new`, failing instead of fetching remote dependencies if
+    /// `vendored_only` is set and they have no up to date copy vendored into the root package.
+    pub(crate) fn build<Progress: Write>(
+        root_package: &SourceManifest,
+        root_path: PathBuf,
+        skip_fetch_latest_git_deps: bool,
+        vendored_only: bool,
+        progress_output: &mut Progress,
     ) -> Result<DependencyGraph> {
         let mut graph = DependencyGraph {
+            vendored: VendoredPackages::read(&root_path, vendored_only)?,
             root_path,
-            root_package: root_package.package.name,
+            root_packages: vec![root_package.package.name],
             package_graph: DiGraphMap::new(),
             package_table: BTreeMap::new(),
             always_deps: BTreeSet::new(),
+            versions: VersionResolver::default(),
         };
 
-        graph
-            .extend_graph(
-                DependencyKind::default(),
-                root_package,
-                skip_fetch_latest_git_deps,
-                progress_output,
-            )
-            .with_context(|| {
-                format!(
-                    "Failed to resolve dependencies for package '{}'",
-                    graph.root_package
+        // Picking a newer version of a registry package can introduce requirements that an
+        // already picked version does not satisfy, in which case the graph is explored again.
+        loop {
+            let repinned = graph
+                .extend_graph(
+                    DependencyKind::default(),
+                    root_package,
+                    skip_fetch_latest_git_deps,
+                    progress_output,
                 )
-            })?;
+                .and_then(|_| graph.versions.repin())
+                .with_context(|| {
+                    format!(
+                        "Failed to resolve dependencies for package '{}'",
+                        root_package.package.name
+                    )
+                })?;
+
+            if !repinned {
+                break;
+            }
+
+            graph.clear();
+        }
+
+        graph.check_acyclic()?;
+        graph.discover_always_deps();
+
+        Ok(graph)
+    }
+
+    /// Build a graph from the transitive dependencies and dev-dependencies of the `members` of the
+    /// workspace rooted at `root_path`, given with their paths relative to `root_path`.  Members
+    /// can depend on each other, as long as they refer to each other by their paths.  Remote
+    /// dependencies without an up to date vendored copy are an error if `vendored_only` is set.
+    pub fn new_workspace<Progress: Write>(
+        members: &[(PathBuf, SourceManifest)],
+        root_path: PathBuf,
+        skip_fetch_latest_git_deps: bool,
+        vendored_only: bool,
+        progress_output: &mut Progress,
+    ) -> Result<DependencyGraph> {
+        let mut graph = DependencyGraph {
+            vendored: VendoredPackages::read(&root_path, vendored_only)?,
+            root_path,
+            root_packages: members.iter().map(|(_, m)| m.package.name).collect(),
+            package_graph: DiGraphMap::new(),
+            package_table: BTreeMap::new(),
+            always_deps: BTreeSet::new(),
+            versions: VersionResolver::default(),
+        };
+
+        loop {
+            for (path, member) in members {
+                let name = member.package.name;
+                let dep = Dependency {
+                    kind: DependencyKind::Local(path.clone()),
+                    subst: None,
+                    version: None,
+                    digest: None,
+                    features: vec![],
+                };
+
+                // Members without dependencies would not otherwise be part of the graph.
+                graph.package_graph.add_node(name);
+                graph
+                    .process_dependency(dep, name, skip_fetch_latest_git_deps, progress_output)
+                    .with_context(|| {
+                        format!(
+                            "Failed to resolve dependencies for workspace member '{}'",
+                            name
+                        )
+                    })?;
+            }
+
+            if !graph
+                .versions
+                .repin()
+                .context("Failed to resolve dependencies for workspace")?
+            {
+                break;
+            }
+
+            graph.clear();
+        }
 
         graph.check_acyclic()?;
         graph.discover_always_deps();
@@ -151,6 +270,7 @@ impl DependencyGraph {
             source,
             dependencies,
             dev_dependencies,
+            commit: _,
         } in schema::Dependencies::read(lock)?
         {
             let package = PackageName::from(name.as_str());
@@ -183,11 +303,13 @@ impl DependencyGraph {
         }
 
         let mut graph = DependencyGraph {
+            vendored: VendoredPackages::read(&root_path, /* vendored_only */ false)?,
             root_path,
-            root_package: root,
+            root_packages: vec![root],
             package_graph,
             package_table,
             always_deps: BTreeSet::new(),
+            versions: VersionResolver::default(),
         };
 
         graph.check_consistency()?;
@@ -196,17 +318,110 @@ impl DependencyGraph {
         Ok(graph)
     }
 
-    /// Serialize this dependency graph into a lock file, consuming it in the process.
+    /// Returns the packages in the graph in dependency order: each package comes after all of its
+    /// dependencies and dev-dependencies.
+    pub fn topological_order(&self) -> Vec<PackageName> {
+        let mut order = algo::toposort(&self.package_graph, None)
+            .expect("dependency graph is acyclic after construction");
+        order.reverse();
+        order
+    }
+
+    /// If `dep` is a dependency on package `dep_name` through a registry, returns it sourced from
+    /// the version of the package picked in this graph (keeping its address substitutions).
+    /// Other dependencies are returned unchanged.
+    pub fn pinned(&self, dep_name: PackageName, dep: Dependency) -> Result<Dependency> {
+        let DependencyKind::Registry(_) = dep.kind else {
+            return Ok(dep);
+        };
+
+        let Some(source) = self.package_table.get(&dep_name) else {
+            bail!("No version of package '{}' picked", dep_name);
+        };
+
+        Ok(Dependency {
+            kind: source.kind.clone(),
+            version: source.version.clone(),
+            ..dep
+        })
+    }
+
+    /// The path to the copy of package `name` vendored into the root package, if it was vendored
+    /// from the same source that it is depended on from in this graph.  Fails if the copy was
+    /// modified after it was vendored.
+    pub fn vendored_copy(&self, name: PackageName) -> Result<Option<PathBuf>> {
+        let Some(source) = self.package_table.get(&name) else {
+            return Ok(None);
+        };
+
+        self.vendored.copy_of(name, source)
+    }
+
+    /// Copy the packages in this graph that are fetched from remote (git or custom) sources into
+    /// the vendor directory of the root package, replacing its previous contents, along with a
+    /// record of their sources and the digests of their copies.
+    pub fn vendor<Progress: Write>(&self, progress_output: &mut Progress) -> Result<()> {
+        // Packages can currently be read from the existing vendor directory, so the new one is
+        // built alongside it, before replacing it.
+        let staging = tempfile::tempdir_in(&self.root_path)?;
+        let mut writer = BufWriter::new(fs::File::create(staging.path().join(VENDOR_MANIFEST))?);
+        writeln!(
+            writer,
+            "# @generated by Move, please check-in and do not edit manually."
+        )?;
+
+        for (pkg, dep) in &self.package_table {
+            if !matches!(dep.kind, DependencyKind::Git(_) | DependencyKind::Custom(_)) {
+                continue;
+            }
+
+            let from = match self.vendored.copy_of(*pkg, dep)? {
+                Some(path) => path,
+                None => local_path(&dep.kind),
+            };
+
+            writeln!(progress_output, "{} {}", "VENDORING".bold().green(), pkg)?;
+            let digest = copy_package(&from, &staging.path().join(pkg.as_str()))
+                .with_context(|| format!("Vendoring '{}'", pkg))?;
+
+            writeln!(writer, "\n[[package]]")?;
+            writeln!(writer, "name = {}", str_escape(pkg.as_str())?)?;
+            writeln!(writer, "source = {}", DependencyTOML(dep))?;
+            writeln!(writer, "digest = {}", str_escape(digest.as_str())?)?;
+        }
+
+        writer.flush()?;
+        drop(writer);
+
+        let vendor_path = self.root_path.join(VENDOR_DIR);
+        if vendor_path.exists() {
+            fs::remove_dir_all(&vendor_path)?;
+        }
+
+        fs::rename(staging.into_path(), &vendor_path)?;
+        Ok(())
+    }
+
+    /// Serialize this dependency graph into a lock file.
     ///
     /// This operation fails, writing nothing, if the graph contains a cycle, and can fail with an
     /// undefined output if it cannot be represented in a TOML file.
-    pub fn write_to_lock(self, lock: &mut LockFile) -> Result<()> {
+    pub fn write_to_lock(&self, lock: &mut LockFile) -> Result<()> {
         let mut writer = BufWriter::new(&**lock);
-        for (pkg, dep) in self.package_table {
+        for (pkg, dep) in &self.package_table {
+            let pkg = *pkg;
             writeln!(writer, "\n[[move.dependency]]")?;
 
             writeln!(writer, "name = {}", str_escape(pkg.as_str())?)?;
-            writeln!(writer, "source = {}", DependencyTOML(&dep))?;
+            writeln!(writer, "source = {}", DependencyTOML(dep))?;
+
+            // Pin git dependencies to the commit they are checked out at, so that
+            // `move deps outdated` can compare it against the branch they track.
+            if let DependencyKind::Git(git) = &dep.kind {
+                if let Some(commit) = git_checked_out_commit(git)? {
+                    writeln!(writer, "commit = {}", str_escape(&commit)?)?;
+                }
+            }
 
             let mut deps: Vec<_> = self
                 .package_graph
@@ -242,6 +457,118 @@ impl DependencyGraph {
         Ok(())
     }
 
+    /// Print the graph as a tree rooted at each root package, showing the source of every
+    /// dependency and the digest of its sources.  The dependencies of a package are only shown the
+    /// first time it appears, and it is marked with `(*)` wherever it appears again.
+    pub fn print_tree<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut expanded = BTreeSet::new();
+        for root in &self.root_packages {
+            let mut tree = TreeBuilder::new(root.to_string());
+            self.print_tree_dfs(*root, &mut expanded, &mut tree)?;
+            write_tree(&tree.build(), &mut *writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print every path from a root package to `package` through the graph, one per line.  Steps
+    /// through dev-dependencies are marked with `(dev)`.  Fails if `package` is not in the graph.
+    pub fn print_why<W: Write>(&self, package: PackageName, writer: &mut W) -> Result<()> {
+        if !self.package_graph.contains_node(package) {
+            bail!(
+                "Package '{}' is not a dependency of {}",
+                package,
+                self.root_packages
+                    .iter()
+                    .map(|root| format!("'{}'", root))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+
+        let mut paths = Vec::new();
+        for root in &self.root_packages {
+            self.paths_to(package, *root, &mut vec![root.to_string()], &mut paths);
+        }
+
+        for path in paths {
+            writeln!(writer, "{}", path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print the git dependencies in the lock file `lock` whose pinned commit is behind the latest
+    /// commit of the branch they track, in the local copy of their repository, which is fetched
+    /// first if `fetch_latest_git_deps` is set.  Dependencies on a commit or a tag, and
+    /// dependencies that have not been fetched (e.g. because they are read from vendored copies),
+    /// are skipped.
+    pub fn print_outdated<W: Write>(
+        lock: &mut impl Read,
+        fetch_latest_git_deps: bool,
+        writer: &mut W,
+    ) -> Result<()> {
+        // Packages from the same repository share a local copy, which only needs to be fetched
+        // once.
+        let mut fetched = BTreeSet::new();
+        let mut outdated = false;
+
+        for schema::Dependency {
+            name,
+            source,
+            commit,
+            ..
+        } in schema::Dependencies::read(lock)?
+        {
+            let pkg = PackageName::from(name.as_str());
+            let dep = parse_dependency(pkg.as_str(), source)
+                .with_context(|| format!("Deserializing dependency {}", pkg))?;
+            let DependencyKind::Git(git) = &dep.kind else {
+                continue;
+            };
+
+            let fetch = fetch_latest_git_deps && fetched.insert(repository_path(&dep.kind));
+            let Some(latest) = git_branch_head(pkg, git, fetch, writer)
+                .with_context(|| format!("Checking '{}' for updates", pkg))?
+            else {
+                continue;
+            };
+
+            match commit {
+                Some(pinned) if pinned == latest => (),
+                Some(pinned) => {
+                    outdated = true;
+                    writeln!(
+                        writer,
+                        "{} = {}: pinned at {}, latest commit on '{}' is {}",
+                        pkg,
+                        DependencyTOML(&dep),
+                        pinned,
+                        git.git_rev,
+                        latest,
+                    )?;
+                }
+                None => {
+                    outdated = true;
+                    writeln!(
+                        writer,
+                        "{} = {}: no commit pinned in the lock file, latest commit on '{}' is {}",
+                        pkg,
+                        DependencyTOML(&dep),
+                        git.git_rev,
+                        latest,
+                    )?;
+                }
+            }
+        }
+
+        if !outdated {
+            writeln!(writer, "All git dependencies are up to date")?;
+        }
+
+        Ok(())
+    }
+
     /// Add the transitive dependencies and dev-dependencies from `package` to the dependency graph.
     fn extend_graph<Progress: Write>(
         &mut self,
@@ -252,8 +579,14 @@ impl DependencyGraph {
     ) -> Result<()> {
         let from = package.package.name;
         for (to, dep) in &package.dependencies {
-            let mut dep = dep.clone();
-            dep.kind.reroot(&parent)?;
+            let dep = self.constrain(
+                package,
+                *to,
+                dep,
+                &parent,
+                skip_fetch_latest_git_deps,
+                progress_output,
+            )?;
 
             self.process_dependency(dep, *to, skip_fetch_latest_git_deps, progress_output)?;
 
@@ -262,8 +595,14 @@ impl DependencyGraph {
         }
 
         for (to, dep) in &package.dev_dependencies {
-            let mut dep = dep.clone();
-            dep.kind.reroot(&parent)?;
+            let dep = self.constrain(
+                package,
+                *to,
+                dep,
+                &parent,
+                skip_fetch_latest_git_deps,
+                progress_output,
+            )?;
 
             self.process_dependency(dep, *to, skip_fetch_latest_git_deps, progress_output)?;
 
@@ -274,6 +613,44 @@ impl DependencyGraph {
         Ok(())
     }
 
+    /// Re-root `dep`, a dependency of `package` on `dep_name` relative to `parent`, recording the
+    /// requirement it places on the version of `dep_name`, and sourcing it from the version picked
+    /// for `dep_name` if it is a registry dependency.
+    fn constrain<Progress: Write>(
+        &mut self,
+        package: &SourceManifest,
+        dep_name: PackageName,
+        dep: &Dependency,
+        parent: &DependencyKind,
+        skip_fetch_latest_git_deps: bool,
+        progress_output: &mut Progress,
+    ) -> Result<Dependency> {
+        let mut dep = dep.clone();
+        dep.kind.reroot(parent)?;
+
+        if let Some(requirement) = &dep.version {
+            self.versions
+                .require(dep_name, &package.package, requirement);
+        }
+
+        self.versions
+            .pin(
+                dep_name,
+                dep,
+                &self.root_path,
+                skip_fetch_latest_git_deps,
+                progress_output,
+            )
+            .with_context(|| format!("Picking a version of '{}'", dep_name))
+    }
+
+    /// Remove all packages from the graph, keeping the requirements on their versions, and the
+    /// versions picked for registry packages, to explore it again.
+    fn clear(&mut self) {
+        self.package_graph.clear();
+        self.package_table.clear();
+    }
+
     /// Ensures that package `dep_name` and all its transitive dependencies are present in the
     /// graph, all sourced from their respective `dep`endencies.  Fails if any of the packages in
     /// the dependency sub-graph rooted at `dep_name` are already present in `self` but sourced from
@@ -304,10 +681,26 @@ impl DependencyGraph {
             }
         };
 
-        download_and_update_if_remote(dep_name, dep, skip_fetch_latest_git_deps, progress_output)
-            .with_context(|| format!("Fetching '{}'", dep_name))?;
+        // Prefer the copy of the package vendored into the root package, over fetching it.
+        let fetched = match self.vendored.copy_of(dep_name, dep)? {
+            Some(path) => Dependency {
+                kind: DependencyKind::Local(path),
+                ..dep.clone()
+            },
 
-        let (manifest, _) = parse_package_manifest(dep, &dep_name, self.root_path.clone())
+            None => {
+                download_and_update_if_remote(
+                    dep_name,
+                    dep,
+                    skip_fetch_latest_git_deps,
+                    progress_output,
+                )
+                .with_context(|| format!("Fetching '{}'", dep_name))?;
+                dep.clone()
+            }
+        };
+
+        let (manifest, _) = parse_package_manifest(&fetched, &dep_name, self.root_path.clone())
             .with_context(|| format!("Parsing manifest for '{}'", dep_name))?;
 
         if dep_name != manifest.package.name {
@@ -319,16 +712,110 @@ impl DependencyGraph {
             )
         }
 
+        self.versions.declare(dep_name, manifest.package.version);
+
         let kind = dep.kind.clone();
         self.extend_graph(kind, &manifest, skip_fetch_latest_git_deps, progress_output)
             .with_context(|| format!("Resolving dependencies for package '{}'", dep_name))
     }
 
-    /// Check that every dependency in the graph, excluding the root package, is present in the
+    /// Add the dependencies of `package` to `tree`, expanding the dependencies of each one that
+    /// has not been `expanded` yet.
+    fn print_tree_dfs(
+        &self,
+        package: PackageName,
+        expanded: &mut BTreeSet<PackageName>,
+        tree: &mut TreeBuilder,
+    ) -> Result<()> {
+        let mut deps: Vec<_> = self
+            .package_graph
+            .edges(package)
+            .map(|(_, dep, mode)| (*mode, dep))
+            .collect();
+        deps.sort();
+
+        for (mode, dep) in deps {
+            let label = format!(
+                "{}{} = {}, digest {}",
+                dep,
+                if mode == DependencyMode::DevOnly {
+                    " (dev)"
+                } else {
+                    ""
+                },
+                DependencyTOML(&self.package_table[&dep]),
+                self.source_digest(dep)?,
+            );
+
+            if expanded.insert(dep) {
+                tree.begin_child(label);
+                self.print_tree_dfs(dep, expanded, tree)?;
+                tree.end_child();
+            } else {
+                tree.add_empty_child(format!("{} (*)", label));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add every path to `target` that extends `path`, which ends at `package`, to `paths`.
+    fn paths_to(
+        &self,
+        target: PackageName,
+        package: PackageName,
+        path: &mut Vec<String>,
+        paths: &mut Vec<String>,
+    ) {
+        if package == target {
+            paths.push(path.join(" -> "));
+            return;
+        }
+
+        let mut deps: Vec<_> = self
+            .package_graph
+            .edges(package)
+            .map(|(_, dep, mode)| (dep, *mode))
+            .collect();
+        deps.sort();
+
+        for (dep, mode) in deps {
+            path.push(match mode {
+                DependencyMode::Always => dep.to_string(),
+                DependencyMode::DevOnly => format!("{} (dev)", dep),
+            });
+            self.paths_to(target, dep, path, paths);
+            path.pop();
+        }
+    }
+
+    /// The digest of the sources and manifest of dependency `name`, as read from its vendored copy
+    /// or its fetched source (the digest that `digest` fields of dependencies are checked against
+    /// outside of dev-mode).
+    fn source_digest(&self, name: PackageName) -> Result<PackageDigest> {
+        let package_path = match self.vendored_copy(name)? {
+            Some(path) => path,
+            None => self
+                .root_path
+                .join(local_path(&self.package_table[&name].kind)),
+        };
+
+        let mut paths = vec![package_path.join(SourcePackageLayout::Manifest.path())];
+        for layout in [SourcePackageLayout::Sources, SourcePackageLayout::Scripts] {
+            let path = package_path.join(layout.path());
+            if path.exists() {
+                paths.push(path);
+            }
+        }
+
+        compute_digest(&paths)
+    }
+
+    /// Check that every dependency in the graph, excluding the root packages, is present in the
     /// package table.
     fn check_consistency(&self) -> Result<()> {
         for package in self.package_graph.nodes() {
-            if package == self.root_package {
+            if self.root_packages.contains(&package) {
                 continue;
             }
 
@@ -371,11 +858,11 @@ impl DependencyGraph {
         bail!("Found cycle between packages: {}", cycle.join(" -> "));
     }
 
-    /// Add the transitive closure of `DependencyMode::Always` edges reachable from the root package
+    /// Add the transitive closure of `DependencyMode::Always` edges reachable from the root packages
     /// to the `always_deps` set.  Assumes that if a package is already in the graph's `always_deps`
     /// set, then the sub-graph reachable from it has already been explored.
     fn discover_always_deps(&mut self) {
-        let mut frontier = vec![self.root_package];
+        let mut frontier = self.root_packages.clone();
         while let Some(package) = frontier.pop() {
             let new_frontier = self.always_deps.insert(package);
             if !new_frontier {
@@ -399,6 +886,7 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
             subst,
             version,
             digest,
+            features,
         } = self.0;
 
         f.write_str("{ ")?;
@@ -442,10 +930,16 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
                 write!(f, ", subdir = ")?;
                 f.write_str(&path_escape(subdir)?)?;
             }
+
+            DependencyKind::Registry(RegistryInfo { index }) => {
+                write!(f, "registry = ")?;
+                f.write_str(&str_escape(index.as_str())?)?;
+            }
         }
 
-        if let Some((major, minor, bugfix)) = version {
-            write!(f, ", version = \"{}.{}.{}\"", major, minor, bugfix)?;
+        if let Some(version) = version {
+            write!(f, ", version = ")?;
+            f.write_str(&str_escape(&version.to_string())?)?;
         }
 
         if let Some(digest) = digest {
@@ -457,6 +951,17 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
             write!(f, ", addr_subst = {}", SubstTOML(subst))?;
         }
 
+        if !features.is_empty() {
+            write!(f, ", features = [")?;
+            for (i, feature) in features.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                f.write_str(&str_escape(feature.as_str())?)?;
+            }
+            write!(f, "]")?;
+        }
+
         f.write_str(" }")?;
         Ok(())
     }
@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
 use toml::value::Value;
 
+use crate::source_package::parsed_manifest::DevAddressDeclarations;
+
 /// Lock file version written by this version of the compiler.  Backwards compatibility is
 /// guaranteed (the compiler can read lock files with older versions), forward compatibility is not
 /// (the compiler will fail to read lock files at newer versions).
@@ -38,6 +40,9 @@ pub struct Dependency {
     pub dependencies: Option<Vec<String>>,
     #[serde(rename = "dev-dependencies")]
     pub dev_dependencies: Option<Vec<String>>,
+
+    /// The commit that a git dependency was checked out at when the lock file was written.
+    pub commit: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -97,3 +102,23 @@ pub(crate) fn write_prologue(file: &mut NamedTempFile) -> Result<()> {
 
     Ok(())
 }
+
+/// Record the address profile that packages were resolved with, and the addresses it assigns.
+///
+/// The `[move.address-profile]` table is informational: it is never read back (readers of this
+/// version skip it), so it does not change the format of the lock file, and `VERSION` is not
+/// bumped for it.
+pub(crate) fn write_address_profile(
+    file: &mut impl Write,
+    name: &str,
+    addresses: &DevAddressDeclarations,
+) -> Result<()> {
+    writeln!(file, "\n[move.address-profile]")?;
+    writeln!(file, "name = {}", toml::to_string(name)?)?;
+    writeln!(file, "\n[move.address-profile.addresses]")?;
+    for (name, addr) in addresses {
+        writeln!(file, "{} = \"0x{}\"", name, addr.short_str_lossless())?;
+    }
+
+    Ok(())
+}
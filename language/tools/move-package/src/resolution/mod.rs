@@ -2,7 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use move_command_line_common::env::MOVE_HOME;
 use std::{
@@ -20,7 +20,7 @@ use crate::{
         manifest_parser::{parse_move_manifest_string, parse_source_manifest},
         parsed_manifest::{
             CustomDepInfo, Dependencies, Dependency, DependencyKind, GitInfo, PackageName,
-            SourceManifest,
+            RegistryInfo, SourceManifest,
         },
     },
     BuildConfig,
@@ -29,7 +29,9 @@ use crate::{
 pub mod dependency_graph;
 mod digest;
 pub mod lock_file;
+pub mod registry;
 pub mod resolution_graph;
+pub mod vendor;
 
 pub fn download_dependency_repos<Progress: Write>(
     manifest: &SourceManifest,
@@ -47,6 +49,12 @@ pub fn download_dependency_repos<Progress: Write>(
     };
 
     for (dep_name, dep) in manifest.dependencies.iter().chain(additional_deps.iter()) {
+        // The source of a registry dependency is only known once versions have been picked for
+        // the whole dependency graph, which fetches it.
+        if let DependencyKind::Registry(_) = dep.kind {
+            continue;
+        }
+
         download_and_update_if_remote(
             *dep_name,
             dep,
@@ -96,6 +104,12 @@ fn download_and_update_if_remote<Progress: Write>(
             package_hooks::resolve_custom_dependency(dep_name, node_info)
         }
 
+        DependencyKind::Registry(RegistryInfo { index }) => bail!(
+            "No version of package '{}' picked from registry '{}'",
+            dep_name,
+            index
+        ),
+
         kind @ DependencyKind::Git(GitInfo {
             git_url,
             git_rev,
@@ -254,6 +268,93 @@ fn download_and_update_if_remote<Progress: Write>(
     }
 }
 
+/// The commit checked out in the local copy of the git repository `git`, or `None` if it has not
+/// been fetched.
+fn git_checked_out_commit(git: &GitInfo) -> Result<Option<String>> {
+    let git_path = repository_path(&DependencyKind::Git(git.clone()));
+    if !git_path.exists() {
+        return Ok(None);
+    }
+
+    git_rev_parse(&git_path, "HEAD")
+}
+
+/// The latest commit of the branch that the revision of dependency `dep_name` tracks, in the local
+/// copy of its git repository, fetching the repository first if `fetch_latest_git_deps` is set.
+/// Does not update the checked out commit.
+///
+/// Returns `None` if the repository has not been fetched, or if its revision is not a branch (e.g.
+/// it is a commit or a tag).
+fn git_branch_head<Progress: Write>(
+    dep_name: PackageName,
+    git: &GitInfo,
+    fetch_latest_git_deps: bool,
+    progress_output: &mut Progress,
+) -> Result<Option<String>> {
+    let git_path = repository_path(&DependencyKind::Git(git.clone()));
+    if !git_path.exists() {
+        return Ok(None);
+    }
+
+    if fetch_latest_git_deps {
+        writeln!(
+            progress_output,
+            "{} {}",
+            "FETCHING GIT DEPENDENCY".bold().green(),
+            git.git_url,
+        )?;
+
+        let status = Command::new("git")
+            .args([
+                OsStr::new("-C"),
+                git_path.as_os_str(),
+                OsStr::new("fetch"),
+                OsStr::new("origin"),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Failed to fetch latest Git state for package '{}'",
+                    dep_name
+                )
+            })?;
+
+        if !status.success() {
+            bail!(
+                "Failed to fetch latest Git state for package '{}' | Exit status: {}",
+                dep_name,
+                status
+            );
+        }
+    }
+
+    // Only branches have a remote-tracking reference.
+    git_rev_parse(&git_path, &format!("refs/remotes/origin/{}", git.git_rev))
+        .with_context(|| format!("Reading Git state for package '{}'", dep_name))
+}
+
+/// The commit that `rev` refers to in the git repository at `git_path`, if any.
+fn git_rev_parse(git_path: &Path, rev: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args([
+            OsStr::new("-C"),
+            git_path.as_os_str(),
+            OsStr::new("rev-parse"),
+            OsStr::new("--verify"),
+            OsStr::new("--quiet"),
+            OsStr::new(rev),
+        ])
+        .output()
+        .map_err(|_| anyhow::anyhow!("Failed to read Git reference '{}'", rev))?;
+
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
 /// The local location of the repository containing the dependency of kind `kind` (and potentially
 /// other, related dependencies).
 fn repository_path(kind: &DependencyKind) -> PathBuf {
@@ -293,6 +394,10 @@ fn repository_path(kind: &DependencyKind) -> PathBuf {
         ]
         .iter()
         .collect(),
+
+        // Registry dependencies are replaced by the source of the version picked for them before
+        // they are fetched, and the registry index itself is found via its own source.
+        DependencyKind::Registry(RegistryInfo { index }) => PathBuf::from(index.as_str()),
     }
 }
 
@@ -0,0 +1,386 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution of the version requirements on dependencies, picking versions for dependencies on
+//! packages published to a registry index.
+//!
+//! A registry index is a directory, or a git repository, holding a file per published package,
+//! named after the package (e.g. `MoveStdlib.toml`), listing its versions and their sources:
+//!
+//! ```toml
+//! [[version]]
+//! version = "1.0.0"
+//! source = { git = "https://github.com/move-language/move.git", rev = "v1.0.0", subdir = "stdlib" }
+//!
+//! [[version]]
+//! version = "1.1.0"
+//! source = { local = "packages/MoveStdlib-1.1.0" }
+//! ```
+//!
+//! Local sources are relative to the root of the index.
+
+use anyhow::{bail, Context, Result};
+use semver::Version as SemVer;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::source_package::{
+    manifest_parser::parse_dependency,
+    parsed_manifest::{
+        normalize_path, Dependency, DependencyKind, GitInfo, PackageInfo, PackageName,
+        RegistryInfo, Version, VersionRequirement,
+    },
+};
+
+use super::{download_and_update_if_remote, local_path};
+
+/// The version requirements found on the packages of a dependency graph, and the versions picked
+/// for the packages depended on through a registry.
+#[derive(Debug, Default)]
+pub struct VersionResolver {
+    /// The requirements found on each package, from the versions of dependent packages that are
+    /// currently part of the graph.
+    requirements: BTreeMap<PackageName, Vec<Requirement>>,
+
+    /// The version picked for each package depended on through a registry.
+    pinned: BTreeMap<PackageName, Published>,
+
+    /// The versions of packages depended on through a registry that were picked and later
+    /// replaced.  They are not picked again, so that picking versions always terminates.
+    superseded: BTreeMap<PackageName, BTreeSet<SemVer>>,
+
+    /// The versions that packages depended on by source declare in their manifests.
+    declared: BTreeMap<PackageName, SemVer>,
+
+    /// The registry that each package depended on through a registry is picked from, and its
+    /// published versions in increasing order.
+    published: BTreeMap<PackageName, (RegistryInfo, Vec<Published>)>,
+}
+
+/// A requirement on the version of a package, from a version of a package depending on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Requirement {
+    dependent: PackageName,
+    dependent_version: SemVer,
+    requirement: VersionRequirement,
+}
+
+/// A version of a package published to a registry index.
+#[derive(Debug, Clone)]
+struct Published {
+    version: SemVer,
+    source: Dependency,
+}
+
+/// The file describing a package in a registry index.
+#[derive(Deserialize)]
+struct IndexFile {
+    #[serde(rename = "version", default)]
+    versions: Vec<IndexEntry>,
+}
+
+/// A published version of a package, as read from its file in the registry index.
+#[derive(Deserialize)]
+struct IndexEntry {
+    version: String,
+    source: toml::Value,
+}
+
+impl VersionResolver {
+    /// Record that `dependent` requires package `dep_name` at `requirement`.
+    pub fn require(
+        &mut self,
+        dep_name: PackageName,
+        dependent: &PackageInfo,
+        requirement: &VersionRequirement,
+    ) {
+        let requirement = Requirement {
+            dependent: dependent.name,
+            dependent_version: to_semver(dependent.version),
+            requirement: requirement.clone(),
+        };
+
+        let requirements = self.requirements.entry(dep_name).or_default();
+        if !requirements.contains(&requirement) {
+            requirements.push(requirement);
+        }
+    }
+
+    /// Record that package `dep_name`, depended on by source, declares `version` in its manifest.
+    pub fn declare(&mut self, dep_name: PackageName, version: Version) {
+        if !self.pinned.contains_key(&dep_name) {
+            self.declared.insert(dep_name, to_semver(version));
+        }
+    }
+
+    /// If `dep` is a dependency on package `dep_name` through a registry, replace it by the source
+    /// of the version picked for the package, picking the latest version satisfying its
+    /// requirements so far if no version has been picked yet.  Other dependencies are returned
+    /// unchanged.  The version picked is recorded in the dependency as an exact requirement.
+    ///
+    /// Registry indices given as paths are relative to `root_path`.
+    pub fn pin<Progress: Write>(
+        &mut self,
+        dep_name: PackageName,
+        dep: Dependency,
+        root_path: &Path,
+        skip_fetch_latest_git_deps: bool,
+        progress_output: &mut Progress,
+    ) -> Result<Dependency> {
+        let registry = match &dep.kind {
+            DependencyKind::Registry(registry) => registry.clone(),
+            _ => return Ok(dep),
+        };
+
+        match self.published.get(&dep_name) {
+            Some((other, _)) if *other != registry => bail!(
+                "Package '{}' is depended on from different registries: '{}' and '{}'",
+                dep_name,
+                other.index,
+                registry.index,
+            ),
+
+            Some(_) => (),
+
+            None => {
+                let versions = read_published_versions(
+                    dep_name,
+                    &registry,
+                    root_path,
+                    skip_fetch_latest_git_deps,
+                    progress_output,
+                )?;
+                self.published.insert(dep_name, (registry, versions));
+            }
+        }
+
+        if !self.pinned.contains_key(&dep_name) {
+            let published = self.pick(dep_name)?;
+            self.pinned.insert(dep_name, published);
+        }
+
+        let Published { version, source } = &self.pinned[&dep_name];
+        Ok(Dependency {
+            kind: source.kind.clone(),
+            version: Some(exact_requirement(version)),
+            ..dep
+        })
+    }
+
+    /// Check the versions of packages in the graph against all the requirements found on them,
+    /// picking a new version for registry packages whose version no longer satisfies them.  The
+    /// requirements of a replaced version are dropped, as it is no longer part of the graph.
+    ///
+    /// Returns whether any version was picked again, in which case the graph needs to be explored
+    /// again from the new versions, and fails if there is no version of a package satisfying all
+    /// the requirements on it.
+    pub fn repin(&mut self) -> Result<bool> {
+        let mut repinned = false;
+        let packages: Vec<_> = self.requirements.keys().copied().collect();
+        for package in packages {
+            if let Some(Published { version, .. }) = self.pinned.get(&package) {
+                if !self.satisfies(package, version) {
+                    let replaced = version.clone();
+                    self.supersede(package, replaced);
+                    let published = self.pick(package)?;
+                    self.pinned.insert(package, published);
+                    repinned = true;
+                }
+            } else if let Some(version) = self.declared.get(&package) {
+                if !self.satisfies(package, version) {
+                    bail!(
+                        "Version {} of package '{}' does not satisfy all the requirements on it:\n{}",
+                        version,
+                        package,
+                        self.explain(package),
+                    );
+                }
+            }
+        }
+
+        Ok(repinned)
+    }
+
+    /// Record that `version` of `package` is replaced, dropping the requirements it places on
+    /// other packages.
+    fn supersede(&mut self, package: PackageName, version: SemVer) {
+        for requirements in self.requirements.values_mut() {
+            requirements.retain(|req| req.dependent != package || req.dependent_version != version);
+        }
+        self.superseded.entry(package).or_default().insert(version);
+    }
+
+    /// Pick the latest published version of `package` that satisfies all the requirements on it,
+    /// and has not been replaced before.
+    fn pick(&self, package: PackageName) -> Result<Published> {
+        let (_, versions) = &self.published[&package];
+        let superseded = self.superseded.get(&package);
+        let Some(published) = versions.iter().rev().find(|published| {
+            !superseded.map_or(false, |s| s.contains(&published.version))
+                && self.satisfies(package, &published.version)
+        })
+        else {
+            bail!(
+                "No published version of package '{}' satisfies all the requirements on it:\n{}\
+                 Published versions: {}",
+                package,
+                self.explain(package),
+                if versions.is_empty() {
+                    "none".to_string()
+                } else {
+                    versions
+                        .iter()
+                        .map(|published| published.version.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            );
+        };
+
+        Ok(published.clone())
+    }
+
+    fn satisfies(&self, package: PackageName, version: &SemVer) -> bool {
+        self.requirements
+            .get(&package)
+            .into_iter()
+            .flatten()
+            .all(|req| req.requirement.matches(version))
+    }
+
+    /// Describe the requirements on `package`, one per line.
+    fn explain(&self, package: PackageName) -> String {
+        self.requirements
+            .get(&package)
+            .into_iter()
+            .flatten()
+            .map(|req| format!("  {}\n", req))
+            .collect()
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' {} requires {}",
+            self.dependent, self.dependent_version, self.requirement
+        )
+    }
+}
+
+/// Read the versions of `package` published to `registry`, in increasing order, fetching the
+/// registry first if it is a git repository.
+fn read_published_versions<Progress: Write>(
+    package: PackageName,
+    registry: &RegistryInfo,
+    root_path: &Path,
+    skip_fetch_latest_git_deps: bool,
+    progress_output: &mut Progress,
+) -> Result<Vec<Published>> {
+    let index_path = index_path(
+        registry,
+        root_path,
+        skip_fetch_latest_git_deps,
+        progress_output,
+    )
+    .with_context(|| format!("Fetching registry '{}'", registry.index))?;
+
+    let entry_path = index_path.join(format!("{}.toml", package));
+    let contents = fs::read_to_string(&entry_path).with_context(|| {
+        format!(
+            "Unable to find package '{}' in registry '{}' at {:?}",
+            package, registry.index, entry_path,
+        )
+    })?;
+
+    let IndexFile { versions } = toml::de::from_str(&contents)
+        .with_context(|| format!("Deserializing registry entry for package '{}'", package))?;
+
+    let mut published = versions
+        .into_iter()
+        .map(|IndexEntry { version, source }| {
+            let version = SemVer::parse(&version)
+                .with_context(|| format!("Invalid published version '{}'", version))?;
+            let mut source = parse_dependency(package.as_str(), source)
+                .with_context(|| format!("Invalid source for version {}", version))?;
+
+            match &mut source.kind {
+                DependencyKind::Local(path) => {
+                    *path =
+                        normalize_path(index_path.join(&*path), /* allow_cwd_parent */ true)?
+                }
+                DependencyKind::Registry(_) => bail!(
+                    "Version {} is sourced from another registry, expected a local or git source",
+                    version,
+                ),
+                DependencyKind::Git(_) | DependencyKind::Custom(_) => (),
+            }
+
+            Ok(Published { version, source })
+        })
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| {
+            format!(
+                "Reading versions of package '{}' published to registry '{}'",
+                package, registry.index,
+            )
+        })?;
+
+    published.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(published)
+}
+
+/// The local directory holding the registry index, which is cloned to `MOVE_HOME` if it is a git
+/// repository, and otherwise relative to `root_path`.
+fn index_path<Progress: Write>(
+    registry: &RegistryInfo,
+    root_path: &Path,
+    skip_fetch_latest_git_deps: bool,
+    progress_output: &mut Progress,
+) -> Result<PathBuf> {
+    let index = registry.index.as_str();
+    if !index.contains("://") && !index.starts_with("git@") {
+        // Sources of published versions are found from here, relative to any package.
+        return root_path
+            .join(index)
+            .canonicalize()
+            .with_context(|| format!("Unable to find registry directory '{}'", index));
+    }
+
+    // Track the default branch of the repository.
+    let dep = Dependency {
+        kind: DependencyKind::Git(GitInfo {
+            git_url: registry.index,
+            git_rev: "HEAD".into(),
+            subdir: PathBuf::new(),
+        }),
+        subst: None,
+        version: None,
+        digest: None,
+        features: vec![],
+    };
+
+    download_and_update_if_remote(
+        registry.index,
+        &dep,
+        skip_fetch_latest_git_deps,
+        progress_output,
+    )?;
+
+    Ok(local_path(&dep.kind))
+}
+
+fn to_semver((major, minor, patch): Version) -> SemVer {
+    SemVer::new(major, minor, patch)
+}
+
+fn exact_requirement(version: &SemVer) -> VersionRequirement {
+    VersionRequirement::parse(&format!("={}", version))
+        .expect("An exact requirement on a valid version is valid")
+}
@@ -7,8 +7,8 @@ use crate::{
     source_package::{
         layout::SourcePackageLayout,
         parsed_manifest::{
-            Dependency, FileName, NamedAddress, PackageDigest, PackageName, SourceManifest,
-            SubstOrRename,
+            Dependency, DependencyKind, DevAddressDeclarations, FeatureName, FileName,
+            NamedAddress, PackageDigest, PackageName, ProfileName, SourceManifest, SubstOrRename,
         },
     },
     BuildConfig,
@@ -17,7 +17,7 @@ use anyhow::{bail, Context, Result};
 use move_command_line_common::files::{find_move_filenames, FileHash};
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::Symbol;
-use petgraph::{algo, graphmap::DiGraphMap, Outgoing};
+use petgraph::{algo, graphmap::DiGraphMap, Incoming, Outgoing};
 use ptree::{print_tree, TreeBuilder};
 use std::{
     cell::RefCell,
@@ -28,7 +28,9 @@ use std::{
     rc::Rc,
 };
 
-use super::{download_and_update_if_remote, parse_package_manifest};
+use super::{
+    dependency_graph::DependencyGraph, download_and_update_if_remote, parse_package_manifest,
+};
 
 pub type ResolvedTable = ResolutionTable<AccountAddress>;
 pub type ResolvedPackage = ResolutionPackage<AccountAddress>;
@@ -43,6 +45,9 @@ type ResolvingTable = ResolutionTable<ResolvingNamedAddress>;
 type ResolvingGraph = ResolutionGraph<ResolvingNamedAddress>;
 type ResolvingPackage = ResolutionPackage<ResolvingNamedAddress>;
 
+/// The feature of a package that is enabled whenever the package is part of a build.
+const DEFAULT_FEATURE: &str = "default";
+
 #[derive(Debug, Clone)]
 pub struct ResolvingNamedAddress {
     value: Rc<RefCell<Option<AccountAddress>>>,
@@ -60,11 +65,17 @@ pub struct ResolvingNamedAddress {
 ///    through unification across the package graph.
 ///
 /// Named addresses can also be renamed in a package and will be re-exported under thes new names in this case.
+///
+/// The graph of a workspace contains all of its members, which are all treated as root packages
+/// during resolution. Its `root_package_path` is the root of the workspace.
 #[derive(Debug, Clone)]
 pub struct ResolutionGraph<T> {
     pub root_package_path: PathBuf,
     /// Build options
     pub build_options: BuildConfig,
+    /// The addresses assigned by the selected address profile, which override the addresses that
+    /// packages assign themselves
+    pub profile_addresses: DevAddressDeclarations,
     /// Root package
     pub root_package: SourceManifest,
     /// Dependency graph
@@ -87,48 +98,146 @@ pub struct ResolutionPackage<T> {
     pub resolution_table: ResolutionTable<T>,
     /// The digest of the contents of all source files and manifest under the package root
     pub source_digest: PackageDigest,
+    /// The features enabled in this package, computed once the whole graph is known
+    pub enabled_features: BTreeSet<FeatureName>,
 }
 
 impl ResolvingGraph {
+    /// Creates the resolution graph of `root_package`, first building its dependency graph to
+    /// pick versions for its dependencies on registry packages.
     pub fn new<Progress: Write>(
         root_package: SourceManifest,
         root_package_path: PathBuf,
-        mut build_options: BuildConfig,
+        build_options: BuildConfig,
+        progress_output: &mut Progress,
+    ) -> Result<ResolvingGraph> {
+        let dependency_graph = DependencyGraph::build(
+            &root_package,
+            root_package_path.clone(),
+            build_options.skip_fetch_latest_git_deps,
+            build_options.vendored_only,
+            progress_output,
+        )?;
+        Self::new_with_dependency_graph(
+            root_package,
+            root_package_path,
+            &dependency_graph,
+            build_options,
+            progress_output,
+        )
+    }
+
+    /// Creates the resolution graph of `root_package`, whose dependencies on registry packages
+    /// are sourced from the versions picked for them in its `dependency_graph`.
+    pub fn new_with_dependency_graph<Progress: Write>(
+        root_package: SourceManifest,
+        root_package_path: PathBuf,
+        dependency_graph: &DependencyGraph,
+        build_options: BuildConfig,
         progress_output: &mut Progress,
     ) -> Result<ResolvingGraph> {
+        let profile_addresses =
+            address_profile(&build_options, [&root_package])?.unwrap_or_default();
+        let mut resolution_graph = Self::empty(
+            &root_package,
+            root_package_path.clone(),
+            build_options,
+            profile_addresses,
+        );
+        resolution_graph.add_root_package(
+            root_package,
+            root_package_path,
+            dependency_graph,
+            progress_output,
+        )?;
+        Ok(resolution_graph)
+    }
+
+    /// Creates the resolution graph of the workspace rooted at `workspace_path`, given its
+    /// `members` with their paths. Members must be given in dependency order, so that a member
+    /// is resolved as a root package, with its dev-addresses in dev mode, before other members
+    /// reach it as a dependency. The root package of the graph is the last member; use
+    /// `ResolvedGraph::rooted_at` to get the graph of each member.
+    pub fn new_workspace<Progress: Write>(
+        members: Vec<(SourceManifest, PathBuf)>,
+        workspace_path: PathBuf,
+        dependency_graph: &DependencyGraph,
+        build_options: BuildConfig,
+        progress_output: &mut Progress,
+    ) -> Result<ResolvingGraph> {
+        let Some((root_package, _)) = members.last() else {
+            bail!(
+                "Workspace at '{}' has no members",
+                workspace_path.to_string_lossy()
+            )
+        };
+        let profile_addresses =
+            address_profile(&build_options, members.iter().map(|(member, _)| member))?
+                .unwrap_or_default();
+        let mut resolution_graph = Self::empty(
+            root_package,
+            workspace_path,
+            build_options,
+            profile_addresses,
+        );
+        for (member, member_path) in members {
+            resolution_graph.add_root_package(
+                member,
+                member_path,
+                dependency_graph,
+                progress_output,
+            )?;
+        }
+        Ok(resolution_graph)
+    }
+
+    fn empty(
+        root_package: &SourceManifest,
+        root_package_path: PathBuf,
+        mut build_options: BuildConfig,
+        profile_addresses: DevAddressDeclarations,
+    ) -> ResolvingGraph {
         if build_options.architecture.is_none() {
             if let Some(info) = &root_package.build {
                 build_options.architecture = info.architecture;
             }
         }
-        let mut resolution_graph = Self {
-            root_package_path: root_package_path.clone(),
+        Self {
+            root_package_path,
             build_options,
+            profile_addresses,
             root_package: root_package.clone(),
             graph: DiGraphMap::new(),
             package_table: BTreeMap::new(),
-        };
+        }
+    }
 
-        resolution_graph
-            .build_resolution_graph(
-                root_package.clone(),
-                root_package_path,
-                true,
-                progress_output,
-            )
-            .with_context(|| {
-                format!(
-                    "Unable to resolve packages for package '{}'",
-                    root_package.package.name
-                )
-            })?;
-        Ok(resolution_graph)
+    fn add_root_package<Progress: Write>(
+        &mut self,
+        package: SourceManifest,
+        package_path: PathBuf,
+        dependency_graph: &DependencyGraph,
+        progress_output: &mut Progress,
+    ) -> Result<()> {
+        let package_name = package.package.name;
+        self.build_resolution_graph(
+            package,
+            package_path,
+            true,
+            dependency_graph,
+            progress_output,
+        )
+        .with_context(|| format!("Unable to resolve packages for package '{}'", package_name))
     }
 
     pub fn resolve(self) -> Result<ResolvedGraph> {
+        let mut enabled_features = self
+            .enabled_features()
+            .context("Unable to resolve the features enabled in packages")?;
         let ResolvingGraph {
             root_package_path,
             build_options,
+            profile_addresses,
             root_package,
             graph,
             package_table,
@@ -146,6 +255,7 @@ impl ResolvingGraph {
                     renaming,
                     resolution_table,
                     source_digest,
+                    enabled_features: _,
                 } = package;
 
                 let resolved_table = resolution_table
@@ -170,12 +280,25 @@ impl ResolvingGraph {
                     renaming,
                     resolution_table: resolved_table,
                     source_digest,
+                    enabled_features: enabled_features.remove(&name).unwrap_or_default(),
                 };
                 (name, resolved_pkg)
             })
             .collect::<BTreeMap<_, _>>();
 
         if !unresolved_addresses.is_empty() {
+            if let Some(profile) = &build_options.profile {
+                bail!(
+                    "Unresolved addresses found with address profile '{}': [\n{}\n]\n\
+                    To fix this, assign each unresolved address in the [addresses.profile.{}] \
+                    section of {}/Move.toml: e.g.,\n[addresses.profile.{}]\nStd = \"0x1\"",
+                    profile,
+                    unresolved_addresses.join("\n"),
+                    profile,
+                    root_package_path.to_string_lossy(),
+                    profile
+                )
+            }
             bail!(
                 "Unresolved addresses found: [\n{}\n]\n\
                 To fix this, add an entry for each unresolved address to the [addresses] section of {}/Move.toml: \
@@ -189,6 +312,7 @@ impl ResolvingGraph {
         Ok(ResolvedGraph {
             root_package_path,
             build_options,
+            profile_addresses,
             root_package,
             graph,
             package_table: resolved_package_table,
@@ -200,6 +324,7 @@ impl ResolvingGraph {
         package: SourceManifest,
         package_path: PathBuf,
         is_root_package: bool,
+        dependency_graph: &DependencyGraph,
         progress_output: &mut Progress,
     ) -> Result<()> {
         let package_name = package.package.name;
@@ -253,7 +378,13 @@ impl ResolvingGraph {
             self.graph.add_edge(package_node_id, dep_node_id, ());
 
             let (dep_renaming, dep_resolution_table) = self
-                .process_dependency(dep_name, dep, package_path.clone(), progress_output)
+                .process_dependency(
+                    dep_name,
+                    dep,
+                    package_path.clone(),
+                    dependency_graph,
+                    progress_output,
+                )
                 .with_context(|| {
                     format!(
                         "While resolving dependency '{}' in package '{}'",
@@ -295,12 +426,116 @@ impl ResolvingGraph {
             renaming,
             resolution_table,
             source_digest,
+            enabled_features: BTreeSet::new(),
         };
 
         self.package_table.insert(package_name, resolved_package);
         Ok(())
     }
 
+    /// The features enabled in each package of the graph: its default feature, the features
+    /// requested in the build options, and the features its dependents enable in it, along with
+    /// all the features that these enable in turn.
+    fn enabled_features(&self) -> Result<BTreeMap<PackageName, BTreeSet<FeatureName>>> {
+        let default_feature = FeatureName::from(DEFAULT_FEATURE);
+        let mut to_enable = vec![];
+
+        for (name, package) in &self.package_table {
+            let manifest = &package.source_package;
+            if manifest.features.contains_key(&default_feature) {
+                to_enable.push((*name, default_feature));
+            }
+
+            let dev_deps = if self.build_options.dev_mode {
+                Some(&manifest.dev_dependencies)
+            } else {
+                None
+            };
+            for (dep_name, dep) in manifest
+                .dependencies
+                .iter()
+                .chain(dev_deps.into_iter().flatten())
+            {
+                to_enable.extend(dep.features.iter().map(|feature| (*dep_name, *feature)));
+            }
+        }
+
+        // Features requested without a package are enabled in the root packages of the graph,
+        // which no other package depends on.
+        for requested in &self.build_options.features {
+            match requested.split_once('/') {
+                Some((package, feature)) => to_enable.push((package.into(), feature.into())),
+                None => to_enable.extend(
+                    self.graph
+                        .nodes()
+                        .filter(|node| {
+                            self.graph
+                                .neighbors_directed(*node, Incoming)
+                                .next()
+                                .is_none()
+                        })
+                        .map(|root| (root, FeatureName::from(requested.as_str()))),
+                ),
+            }
+        }
+
+        let mut enabled: BTreeMap<PackageName, BTreeSet<FeatureName>> = BTreeMap::new();
+        while let Some((package_name, feature)) = to_enable.pop() {
+            let Some(package) = self.package_table.get(&package_name) else {
+                bail!(
+                    "Unable to enable feature '{}' of package '{}', which is not part of the \
+                     package graph",
+                    feature,
+                    package_name
+                )
+            };
+
+            if !enabled.entry(package_name).or_default().insert(feature) {
+                continue;
+            }
+
+            let Some(enables) = package.source_package.features.get(&feature) else {
+                bail!("Package '{}' has no feature '{}'", package_name, feature)
+            };
+
+            for enabled_feature in enables {
+                match enabled_feature.as_str().split_once('/') {
+                    None => to_enable.push((package_name, *enabled_feature)),
+                    Some((dep_name, dep_feature)) => {
+                        let dep_name = PackageName::from(dep_name);
+                        if !self.graph.contains_edge(package_name, dep_name) {
+                            bail!(
+                                "Feature '{}' of package '{}' enables '{}', but '{}' is not a \
+                                 dependency of '{}'",
+                                feature,
+                                package_name,
+                                enabled_feature,
+                                dep_name,
+                                package_name
+                            )
+                        }
+                        to_enable.push((dep_name, dep_feature.into()));
+                    }
+                }
+            }
+        }
+
+        Ok(enabled)
+    }
+
+    /// The address that the selected address profile assigns to `name`, unless an additional
+    /// named address takes precedence over it.
+    fn profile_address(&self, name: NamedAddress) -> Option<AccountAddress> {
+        if self
+            .build_options
+            .additional_named_addresses
+            .contains_key(name.as_str())
+        {
+            return None;
+        }
+        self.profile_addresses.get(&name).copied()
+    }
+
     fn unify_addresses_in_package(
         &mut self,
         package: &SourceManifest,
@@ -309,6 +544,7 @@ impl ResolvingGraph {
     ) -> Result<()> {
         let package_name = &package.package.name;
         for (name, addr_opt) in package.addresses.clone().unwrap_or_default().into_iter() {
+            let addr_opt = self.profile_address(name).or(addr_opt);
             match resolution_table.get(&name) {
                 Some(other) => {
                     other.unify(addr_opt).with_context(|| {
@@ -342,6 +578,7 @@ impl ResolvingGraph {
                 .clone()
                 .unwrap_or_default()
                 .into_iter()
+                .filter(|(name, _)| self.profile_address(*name).is_none())
             {
                 match resolution_table.get(&name) {
                     Some(other) => {
@@ -394,21 +631,40 @@ impl ResolvingGraph {
         dep_name_in_pkg: PackageName,
         dep: Dependency,
         root_path: PathBuf,
+        dependency_graph: &DependencyGraph,
         progress_output: &mut Progress,
     ) -> Result<(Renaming, ResolvingTable)> {
-        download_and_update_if_remote(
-            dep_name_in_pkg,
-            &dep,
-            self.build_options.skip_fetch_latest_git_deps,
-            progress_output,
-        )?;
+        let dep = dependency_graph.pinned(dep_name_in_pkg, dep)?;
+
+        // Prefer the copy of the package vendored into the root package, over fetching it.
+        let fetched = match dependency_graph.vendored_copy(dep_name_in_pkg)? {
+            Some(path) => Dependency {
+                kind: DependencyKind::Local(path),
+                ..dep.clone()
+            },
+
+            None => {
+                download_and_update_if_remote(
+                    dep_name_in_pkg,
+                    &dep,
+                    self.build_options.skip_fetch_latest_git_deps,
+                    progress_output,
+                )?;
+                dep.clone()
+            }
+        };
+
         let (dep_package, dep_package_dir) =
-            parse_package_manifest(&dep, &dep_name_in_pkg, root_path)
+            parse_package_manifest(&fetched, &dep_name_in_pkg, root_path)
                 .with_context(|| format!("While processing dependency '{}'", dep_name_in_pkg))?;
-        self.build_resolution_graph(dep_package.clone(), dep_package_dir, false, progress_output)
-            .with_context(|| {
-                format!("Unable to resolve package dependency '{}'", dep_name_in_pkg)
-            })?;
+        self.build_resolution_graph(
+            dep_package.clone(),
+            dep_package_dir,
+            false,
+            dependency_graph,
+            progress_output,
+        )
+        .with_context(|| format!("Unable to resolve package dependency '{}'", dep_name_in_pkg))?;
 
         if dep_name_in_pkg != dep_package.package.name {
             bail!("Name of dependency declared in package '{}' does not match dependency's package name '{}'",
@@ -629,6 +885,36 @@ impl ResolvedGraph {
         self.package_table.get(package_ident).unwrap()
     }
 
+    /// The features enabled in each package of the graph.
+    pub fn features_by_package(&self) -> BTreeMap<PackageName, BTreeSet<FeatureName>> {
+        self.package_table
+            .iter()
+            .map(|(name, package)| (*name, package.enabled_features.clone()))
+            .collect()
+    }
+
+    /// Returns the graph of a workspace rooted at its `member`, whose package table only contains
+    /// the member and its transitive dependencies. The dependency graph is kept whole, so that
+    /// building the member keeps the outputs of the other members in the shared build directory.
+    pub fn rooted_at(&self, member: PackageName) -> ResolvedGraph {
+        let root_package = self.get_package(&member);
+        let mut packages = root_package.transitive_dependencies(self);
+        packages.insert(member);
+        ResolvedGraph {
+            root_package_path: self.root_package_path.clone(),
+            build_options: self.build_options.clone(),
+            profile_addresses: self.profile_addresses.clone(),
+            root_package: root_package.source_package.clone(),
+            graph: self.graph.clone(),
+            package_table: self
+                .package_table
+                .iter()
+                .filter(|(name, _)| packages.contains(name))
+                .map(|(name, package)| (*name, package.clone()))
+                .collect(),
+        }
+    }
+
     fn print_info_dfs(&self, current_node: &PackageName, tree: &mut TreeBuilder) -> Result<()> {
         let pkg = self.package_table.get(current_node).unwrap();
 
@@ -744,3 +1030,47 @@ impl ResolvedPackage {
         }
     }
 }
+
+/// The addresses assigned by the address profile selected in `build_options`, merged across the
+/// root packages `manifests`, if a profile is selected.
+pub fn address_profile<'a>(
+    build_options: &BuildConfig,
+    manifests: impl IntoIterator<Item = &'a SourceManifest>,
+) -> Result<Option<DevAddressDeclarations>> {
+    let Some(profile) = &build_options.profile else {
+        return Ok(None);
+    };
+    let profile = ProfileName::from(profile.as_str());
+
+    let mut packages = vec![];
+    let mut found = false;
+    let mut addresses = DevAddressDeclarations::new();
+    for manifest in manifests {
+        packages.push(format!("'{}'", manifest.package.name));
+        let Some(assignments) = manifest.address_profiles.get(&profile) else {
+            continue;
+        };
+        found = true;
+        for (name, addr) in assignments {
+            match addresses.insert(*name, *addr) {
+                Some(other) if other != *addr => bail!(
+                    "Address profile '{}' assigns conflicting addresses to '{}': 0x{} and 0x{}",
+                    profile,
+                    name,
+                    other.short_str_lossless(),
+                    addr.short_str_lossless()
+                ),
+                _ => (),
+            }
+        }
+    }
+
+    if !found {
+        bail!(
+            "No address profile '{}' found in the manifest of {}",
+            profile,
+            packages.join(", ")
+        )
+    }
+    Ok(Some(addresses))
+}
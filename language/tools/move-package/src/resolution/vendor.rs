@@ -0,0 +1,201 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Copies of a package's remote dependencies, vendored into its `vendor` directory so that it can
+//! be built without fetching them.
+//!
+//! Each dependency is copied to `vendor/<PackageName>`, and `vendor/Vendor.toml` records the source
+//! it was copied from, and the digest of the copy, which is checked whenever the copy is used:
+//!
+//! ```toml
+//! [[package]]
+//! name = "MoveStdlib"
+//! source = { git = "https://github.com/move-language/move.git", rev = "main", subdir = "language/move-stdlib" }
+//! digest = "1A9C..."
+//! ```
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::source_package::{
+    manifest_parser::parse_dependency,
+    parsed_manifest::{Dependency, DependencyKind, PackageDigest, PackageName},
+};
+
+use super::digest::compute_digest;
+
+/// Directory in the root package that dependencies are vendored into.
+pub const VENDOR_DIR: &str = "vendor";
+
+/// File in the vendor directory recording the sources and digests of vendored dependencies.
+pub const VENDOR_MANIFEST: &str = "Vendor.toml";
+
+/// Directories of a dependency that are not copied when it is vendored.
+const SKIPPED_DIRS: &[&str] = &[".git", "build"];
+
+/// The dependencies vendored into a root package.
+#[derive(Debug, Default)]
+pub struct VendoredPackages {
+    /// Path to the vendor directory of the root package.
+    vendor_path: PathBuf,
+
+    /// The source that each vendored package was copied from, and the digest of its copy.
+    packages: BTreeMap<PackageName, (Dependency, PackageDigest)>,
+
+    /// Whether remote dependencies must be read from their vendored copies, rather than fetched.
+    vendored_only: bool,
+}
+
+#[derive(Deserialize)]
+struct Schema {
+    #[serde(rename = "package", default)]
+    packages: Vec<VendoredPackage>,
+}
+
+#[derive(Deserialize)]
+struct VendoredPackage {
+    name: String,
+    source: toml::Value,
+    digest: String,
+}
+
+impl VendoredPackages {
+    /// Read the dependencies vendored into the root package at `root_path`, if it has a vendor
+    /// directory.  If `vendored_only` is set, remote dependencies without an up to date vendored
+    /// copy are an error, instead of being fetched.
+    pub fn read(root_path: &Path, vendored_only: bool) -> Result<VendoredPackages> {
+        let vendor_path = root_path.join(VENDOR_DIR);
+        let manifest_path = vendor_path.join(VENDOR_MANIFEST);
+        if !manifest_path.is_file() {
+            return Ok(VendoredPackages {
+                vendor_path,
+                packages: BTreeMap::new(),
+                vendored_only,
+            });
+        }
+
+        let contents = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Reading {:?}", manifest_path))?;
+        let Schema { packages } = toml::de::from_str(&contents)
+            .with_context(|| format!("Deserializing {:?}", manifest_path))?;
+
+        let packages = packages
+            .into_iter()
+            .map(
+                |VendoredPackage {
+                     name,
+                     source,
+                     digest,
+                 }| {
+                    let source = parse_dependency(&name, source)
+                        .with_context(|| format!("Deserializing vendored package {}", name))?;
+                    Ok((
+                        PackageName::from(name),
+                        (source, PackageDigest::from(digest)),
+                    ))
+                },
+            )
+            .collect::<Result<_>>()?;
+
+        Ok(VendoredPackages {
+            vendor_path,
+            packages,
+            vendored_only,
+        })
+    }
+
+    /// The path to the vendored copy of package `name`, if it was vendored from `source`.  Fails
+    /// if the copy no longer matches the digest it was vendored with, or if only vendored copies
+    /// may be used and a remote `source` has no vendored copy.
+    pub fn copy_of(&self, name: PackageName, source: &Dependency) -> Result<Option<PathBuf>> {
+        let is_remote = matches!(
+            source.kind,
+            DependencyKind::Git(_) | DependencyKind::Custom(_)
+        );
+
+        let Some((vendored_source, digest)) = self.packages.get(&name) else {
+            if self.vendored_only && is_remote {
+                bail!(
+                    "Package '{}' is not vendored into {:?}, and only vendored dependencies are \
+                     allowed, run `move vendor` to vendor it",
+                    name,
+                    self.vendor_path,
+                );
+            }
+            return Ok(None);
+        };
+
+        // The copy is out of date, and the dependency is fetched from its source instead.
+        if vendored_source.kind != source.kind {
+            if self.vendored_only {
+                bail!(
+                    "Vendored copy of package '{}' in {:?} was vendored from a different source \
+                     than it is depended on from, and only vendored dependencies are allowed, run \
+                     `move vendor` to update it",
+                    name,
+                    self.vendor_path,
+                );
+            }
+            return Ok(None);
+        }
+
+        let path = self.vendor_path.join(name.as_str());
+        if !path.is_dir() {
+            bail!(
+                "Vendored copy of package '{}' is missing from {:?}, run `move vendor` to \
+                 restore it",
+                name,
+                path,
+            );
+        }
+
+        let actual = compute_digest(&[path.clone()])?;
+        if actual != *digest {
+            bail!(
+                "Vendored copy of package '{}' at {:?} has been modified: expected digest '{}' \
+                 but found '{}'. Remove it and run `move vendor` to restore it",
+                name,
+                path,
+                digest,
+                actual,
+            );
+        }
+
+        Ok(Some(path))
+    }
+}
+
+/// Copy the package at `from` to `to`, replacing any existing copy, and return the digest of the
+/// copy.
+pub(crate) fn copy_package(from: &Path, to: &Path) -> Result<PackageDigest> {
+    if to.exists() {
+        fs::remove_dir_all(to)?;
+    }
+
+    let walker = walkdir::WalkDir::new(from)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir)
+        });
+
+    for entry in walker {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Copying {:?} to {:?}", entry.path(), target))?;
+        }
+    }
+
+    compute_digest(&[to.to_path_buf()])
+}
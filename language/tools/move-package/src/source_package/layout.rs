@@ -6,6 +6,8 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 
+use super::manifest_parser;
+
 /// References file for documentation generation
 pub const REFERENCE_TEMPLATE_FILENAME: &str = "references.md";
 
@@ -18,12 +20,14 @@ pub enum SourcePackageLayout {
     Examples,
     Manifest,
     DocTemplates,
+    Lock,
 }
 
 impl SourcePackageLayout {
     /// A Move source package is laid out on-disk as
     /// a_move_package
     /// ├── Move.toml      (required)
+    /// ├── Move.lock      (optional)
     /// ├── sources        (required)
     /// ├── examples       (optional, dev mode)
     /// ├── scripts        (optional)
@@ -49,6 +53,29 @@ impl SourcePackageLayout {
         }
     }
 
+    /// Finds the root of the workspace containing `starting_path`: the closest directory at or
+    /// above it whose manifest has a `[workspace]` section.
+    pub fn try_find_workspace_root(starting_path: &Path) -> Result<PathBuf> {
+        // The workspace can be above a relative starting path, e.g. the current directory.
+        let mut current_path = starting_path.canonicalize()?;
+        loop {
+            let manifest_path = current_path.join(Self::Manifest.path());
+            if manifest_path.is_file() {
+                let manifest_string = std::fs::read_to_string(&manifest_path)?;
+                let manifest = manifest_parser::parse_move_manifest_string(manifest_string)?;
+                if manifest_parser::parse_workspace_manifest(&manifest)?.is_some() {
+                    break Ok(current_path);
+                }
+            }
+            if !current_path.pop() {
+                bail!(
+                    "Unable to find workspace manifest in '{}' or in its parents",
+                    starting_path.to_string_lossy()
+                )
+            }
+        }
+    }
+
     pub fn location_str(&self) -> &'static str {
         match self {
             Self::Sources => "sources",
@@ -58,6 +85,7 @@ impl SourcePackageLayout {
             Self::Examples => "examples",
             Self::Specifications => "specifications",
             Self::DocTemplates => "doc_templates",
+            Self::Lock => "Move.lock",
         }
     }
 
@@ -68,7 +96,8 @@ impl SourcePackageLayout {
             | Self::Scripts
             | Self::Examples
             | Self::Specifications
-            | Self::DocTemplates => true,
+            | Self::DocTemplates
+            | Self::Lock => true,
         }
     }
 }
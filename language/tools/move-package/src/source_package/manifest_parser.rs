@@ -16,12 +16,19 @@ use super::layout::SourcePackageLayout;
 
 const EMPTY_ADDR_STR: &str = "_";
 
+/// Environment variable holding the registry index to resolve dependencies against, when they only
+/// specify a version requirement and no 'registry'.
+const REGISTRY_ENV_VAR: &str = "MOVE_REGISTRY";
+
 const PACKAGE_NAME: &str = "package";
 const BUILD_NAME: &str = "build";
 const ADDRESSES_NAME: &str = "addresses";
 const DEV_ADDRESSES_NAME: &str = "dev-addresses";
 const DEPENDENCY_NAME: &str = "dependencies";
 const DEV_DEPENDENCY_NAME: &str = "dev-dependencies";
+const WORKSPACE_NAME: &str = "workspace";
+const FEATURES_NAME: &str = "features";
+const PROFILE_NAME: &str = "profile";
 
 const KNOWN_NAMES: &[&str] = &[
     PACKAGE_NAME,
@@ -30,6 +37,8 @@ const KNOWN_NAMES: &[&str] = &[
     DEV_ADDRESSES_NAME,
     DEPENDENCY_NAME,
     DEV_DEPENDENCY_NAME,
+    WORKSPACE_NAME,
+    FEATURES_NAME,
 ];
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
@@ -50,11 +59,24 @@ pub fn parse_move_manifest_string(manifest_string: String) -> Result<TV> {
 pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
     match tval {
         TV::Table(mut table) => {
+            if !table.contains_key(PACKAGE_NAME) && table.contains_key(WORKSPACE_NAME) {
+                bail!(
+                    "Error parsing package manifest: found a '[workspace]' section but no \
+                     '[package]' section. This manifest describes a workspace, not a package"
+                )
+            }
             check_for_required_field_names(&table, REQUIRED_FIELDS)
                 .context("Error parsing package manifest")?;
             warn_if_unknown_field_names(&table, KNOWN_NAMES);
-            let addresses = table
-                .remove(ADDRESSES_NAME)
+            let mut addresses = table.remove(ADDRESSES_NAME);
+            let address_profiles = addresses
+                .as_mut()
+                .and_then(take_address_profiles)
+                .map(parse_address_profiles)
+                .transpose()
+                .context("Error parsing '[addresses.profile]' sections of manifest")?
+                .unwrap_or_default();
+            let addresses = addresses
                 .map(parse_addresses)
                 .transpose()
                 .context("Error parsing '[addresses]' section of manifest")?;
@@ -86,6 +108,12 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 .transpose()
                 .context("Error parsing '[dev-dependencies]' section of manifest")?
                 .unwrap_or_default();
+            let features = table
+                .remove(FEATURES_NAME)
+                .map(parse_features)
+                .transpose()
+                .context("Error parsing '[features]' section of manifest")?
+                .unwrap_or_default();
             Ok(PM::SourceManifest {
                 package,
                 addresses,
@@ -93,6 +121,8 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 build,
                 dependencies,
                 dev_dependencies,
+                features,
+                address_profiles,
             })
         }
         x => {
@@ -105,6 +135,53 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
     }
 }
 
+/// Parses the '[workspace]' section of a manifest, returning `None` if the manifest has none. If
+/// the manifest also has a '[package]' section, that package is a member of the workspace too.
+pub fn parse_workspace_manifest(tval: &TV) -> Result<Option<PM::WorkspaceManifest>> {
+    let Some(workspace) = tval.get(WORKSPACE_NAME) else {
+        return Ok(None);
+    };
+    let mut workspace = parse_workspace_info(workspace.clone())
+        .context("Error parsing '[workspace]' section of manifest")?;
+    if tval.get(PACKAGE_NAME).is_some() && !workspace.members.contains(&PathBuf::new()) {
+        workspace.members.insert(0, PathBuf::new());
+    }
+    Ok(Some(workspace))
+}
+
+fn parse_workspace_info(tval: TV) -> Result<PM::WorkspaceManifest> {
+    match tval {
+        TV::Table(mut table) => {
+            check_for_required_field_names(&table, &["members"])?;
+            warn_if_unknown_field_names(&table, &["members"]);
+            let members = table.remove("members").unwrap();
+            let members = members
+                .as_array()
+                .ok_or_else(|| format_err!("Invalid members list"))?
+                .iter()
+                .map(|tval| {
+                    let path = tval.as_str().ok_or_else(|| {
+                        format_err!(
+                            "Invalid member '{}' of type {} found. Expected a path string.",
+                            tval,
+                            tval.type_str()
+                        )
+                    })?;
+                    // Members live under the root of the workspace, whose manifest lists them.
+                    PM::normalize_path(path, /* allow_cwd_parent */ false)
+                        .with_context(|| format!("Invalid path for member '{}'", path))
+                })
+                .collect::<Result<_>>()?;
+            Ok(PM::WorkspaceManifest { members })
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
 pub fn parse_package_info(tval: TV) -> Result<PM::PackageInfo> {
     match tval {
         TV::Table(mut table) => {
@@ -297,6 +374,85 @@ pub fn parse_dev_addresses(tval: TV) -> Result<PM::DevAddressDeclarations> {
     }
 }
 
+/// Removes the '[addresses.profile.<name>]' sections from the '[addresses]' section `tval`, if it
+/// has any. A `profile` entry that is not a table is left in place, as a named address.
+fn take_address_profiles(tval: &mut TV) -> Option<TV> {
+    let table = tval.as_table_mut()?;
+    if !table.get(PROFILE_NAME)?.is_table() {
+        return None;
+    }
+    table.remove(PROFILE_NAME)
+}
+
+pub fn parse_address_profiles(tval: TV) -> Result<PM::AddressProfiles> {
+    match tval {
+        TV::Table(table) => table
+            .into_iter()
+            .map(|(profile, addresses)| {
+                if !addresses.is_table() {
+                    bail!(
+                        "Invalid address profile '{}'. Expected a table of address assignments, \
+                         but encountered a {}",
+                        profile,
+                        addresses.type_str()
+                    )
+                }
+                // Like dev-addresses, profiles assign every address they mention.
+                let addresses = parse_dev_addresses(addresses)
+                    .with_context(|| format!("Invalid address profile '{}'", profile))?;
+                Ok((PM::ProfileName::from(profile), addresses))
+            })
+            .collect(),
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
+pub fn parse_features(tval: TV) -> Result<PM::FeatureDeclarations> {
+    match tval {
+        TV::Table(table) => table
+            .into_iter()
+            .map(|(feature, enables)| {
+                if feature.contains('/') {
+                    bail!(
+                        "Invalid feature name '{}'. Feature names cannot contain '/'",
+                        feature
+                    )
+                }
+                let enables = parse_feature_list(enables)
+                    .with_context(|| format!("Invalid feature '{}'", feature))?;
+                Ok((PM::FeatureName::from(feature), enables))
+            })
+            .collect(),
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
+fn parse_feature_list(tval: TV) -> Result<Vec<Symbol>> {
+    let Some(features) = tval.as_array() else {
+        bail!("Expected a list of features, but encountered a {}", tval.type_str())
+    };
+    features
+        .iter()
+        .map(|tval| {
+            tval.as_str().map(Symbol::from).ok_or_else(|| {
+                format_err!(
+                    "Invalid feature '{}' of type {} found. Expected a string.",
+                    tval,
+                    tval.type_str()
+                )
+            })
+        })
+        .collect()
+}
+
 // Safely parses address for both the 0x and non prefixed hex format.
 fn parse_address_literal(address_str: &str) -> Result<AccountAddress, AccountAddressParseError> {
     if !address_str.starts_with("0x") {
@@ -319,6 +475,8 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         "rev",
         "subdir",
         "address",
+        "registry",
+        "features",
     ];
 
     let custom_key_opt = &package_hooks::custom_dependency_key();
@@ -332,8 +490,17 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         .remove("addr_subst")
         .map(parse_substitution)
         .transpose()?;
-    let version = table.remove("version").map(parse_version).transpose()?;
+    let version = table
+        .remove("version")
+        .map(parse_version_requirement)
+        .transpose()?;
     let digest = table.remove("digest").map(parse_digest).transpose()?;
+    let features = table
+        .remove("features")
+        .map(parse_feature_list)
+        .transpose()
+        .context("Invalid 'features' of dependency")?
+        .unwrap_or_default();
 
     let kind = match (
         table.remove("local"),
@@ -412,6 +579,28 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
+        // A dependency with only a version requirement is resolved against a registry index.
+        (None, None, None, None) if version.is_some() => {
+            let index = match table.remove("registry") {
+                Some(index) => index
+                    .as_str()
+                    .map(Symbol::from)
+                    .ok_or_else(|| anyhow!("'registry' not a string"))?,
+                None => std::env::var(REGISTRY_ENV_VAR)
+                    .map(Symbol::from)
+                    .map_err(|_| {
+                        anyhow!(
+                            "No registry to resolve the version of dependency '{}' against, set \
+                             'registry' for the dependency or the {} environment variable",
+                            dep_name,
+                            REGISTRY_ENV_VAR,
+                        )
+                    })?,
+            };
+
+            PM::DependencyKind::Registry(PM::RegistryInfo { index })
+        }
+
         _ => {
             let mut keys = vec!["'local'", "'git'"];
             let quoted_custom_key = custom_key_opt.as_ref().map(|k| format!("'{}'", k));
@@ -430,6 +619,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         subst,
         version,
         digest,
+        features,
     })
 }
 
@@ -488,6 +678,15 @@ fn parse_version(tval: TV) -> Result<PM::Version> {
     ))
 }
 
+fn parse_version_requirement(tval: TV) -> Result<PM::VersionRequirement> {
+    let Some(requirement) = tval.as_str() else {
+        bail!("Version requirement not a string")
+    };
+
+    PM::VersionRequirement::parse(requirement)
+        .with_context(|| format!("Invalid version requirement '{}'", requirement))
+}
+
 fn parse_architecture(tval: TV) -> Result<Architecture> {
     Architecture::try_parse_from_str(tval.as_str().unwrap())
 }
@@ -16,12 +16,21 @@ pub type NamedAddress = Symbol;
 pub type PackageName = Symbol;
 pub type FileName = Symbol;
 pub type PackageDigest = Symbol;
+pub type FeatureName = Symbol;
 
 pub type AddressDeclarations = BTreeMap<NamedAddress, Option<AccountAddress>>;
 pub type DevAddressDeclarations = BTreeMap<NamedAddress, AccountAddress>;
 pub type Version = (u64, u64, u64);
+pub type VersionRequirement = semver::VersionReq;
 pub type Dependencies = BTreeMap<PackageName, Dependency>;
 pub type Substitution = BTreeMap<NamedAddress, SubstOrRename>;
+/// The features of a package, each mapped to the features it enables: either other features of the
+/// package, or features of one of its dependencies, written `<dependency>/<feature>`.
+pub type FeatureDeclarations = BTreeMap<FeatureName, Vec<Symbol>>;
+pub type ProfileName = Symbol;
+/// Named address assignments grouped by profile (e.g. one per network the package is deployed
+/// to), of which one can be selected for a build.
+pub type AddressProfiles = BTreeMap<ProfileName, DevAddressDeclarations>;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SourceManifest {
@@ -31,6 +40,16 @@ pub struct SourceManifest {
     pub build: Option<BuildInfo>,
     pub dependencies: Dependencies,
     pub dev_dependencies: Dependencies,
+    pub features: FeatureDeclarations,
+    pub address_profiles: AddressProfiles,
+}
+
+/// The `[workspace]` section of a manifest, which makes the directory containing it the root of a
+/// workspace: a set of packages resolved together, sharing a lock file and a build directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WorkspaceManifest {
+    /// Paths to the member packages, relative to the root of the workspace.
+    pub members: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -46,8 +65,10 @@ pub struct PackageInfo {
 pub struct Dependency {
     pub kind: DependencyKind,
     pub subst: Option<Substitution>,
-    pub version: Option<Version>,
+    pub version: Option<VersionRequirement>,
     pub digest: Option<PackageDigest>,
+    /// Features of the dependency to enable, on top of its default features
+    pub features: Vec<FeatureName>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -55,6 +76,7 @@ pub enum DependencyKind {
     Local(PathBuf),
     Git(GitInfo),
     Custom(CustomDepInfo),
+    Registry(RegistryInfo),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -81,6 +103,14 @@ pub struct CustomDepInfo {
     pub subdir: PathBuf,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistryInfo {
+    /// The registry index to pick a version of the package from: the path to a directory
+    /// (relative to the root package), or the clone url of a git repository, holding the metadata
+    /// of published packages. The dependency is sourced from the version picked.
+    pub index: Symbol,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
@@ -107,8 +137,12 @@ impl DependencyKind {
         match (&mut parent, &self) {
             // If `self` is a git or custom dependency kind, it does not need to be re-rooted
             // because its URI is already absolute. (i.e. the location of an absolute URI does not
-            // change if referenced relative to some other URI).
-            (_, DependencyKind::Git(_) | DependencyKind::Custom(_)) => return Ok(()),
+            // change if referenced relative to some other URI).  Registry dependencies are
+            // resolved against the root package.
+            (
+                _,
+                DependencyKind::Git(_) | DependencyKind::Custom(_) | DependencyKind::Registry(_),
+            ) => return Ok(()),
 
             (DependencyKind::Local(parent), DependencyKind::Local(subdir)) => {
                 parent.push(subdir);
@@ -124,6 +158,13 @@ impl DependencyKind {
                 custom.subdir.push(subdir);
                 custom.subdir = normalize_path(&custom.subdir, /* allow_cwd_parent */ false)?;
             }
+
+            // The package a registry dependency refers to is only known once a version of it has
+            // been picked, at which point the dependency is replaced by the source of that version.
+            (DependencyKind::Registry(_), DependencyKind::Local(subdir)) => bail!(
+                "Cannot resolve local dependency '{}' relative to an unpinned registry dependency",
+                subdir.to_string_lossy(),
+            ),
         };
 
         *self = parent;
@@ -147,7 +188,7 @@ impl Default for DependencyKind {
 /// or is prefixed by accesses to parent directories when `allow_cwd_parent` is false.
 ///
 /// Returns the normalized path on success.
-fn normalize_path(path: impl AsRef<Path>, allow_cwd_parent: bool) -> Result<PathBuf> {
+pub(crate) fn normalize_path(path: impl AsRef<Path>, allow_cwd_parent: bool) -> Result<PathBuf> {
     use Component::*;
 
     let mut stack = Vec::new();
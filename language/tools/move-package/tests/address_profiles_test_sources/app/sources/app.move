@@ -0,0 +1,5 @@
+module App::app {
+    public fun oracle(): address {
+        @Oracle
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the integration tests of this crate.
+
+// Each test only uses some of the helpers.
+#![allow(dead_code)]
+
+use std::{fs, path::Path, process::Command};
+
+/// Copy the directory `from`, with all its contents, to `to`.
+pub fn copy_dir(from: &Path, to: &Path) {
+    for entry in walkdir::WalkDir::new(from) {
+        let entry = entry.unwrap();
+        let target = to.join(entry.path().strip_prefix(from).unwrap());
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).unwrap();
+        } else {
+            fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// Run git with `args` in the repository at `dir`, returning its output.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}", args);
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
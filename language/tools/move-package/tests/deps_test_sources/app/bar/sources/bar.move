@@ -0,0 +1,5 @@
+module 0x43::bar {
+    public fun value(): u64 {
+        42
+    }
+}
@@ -0,0 +1,6 @@
+#[test_only]
+module 0x44::baz {
+    public fun value(): u64 {
+        0x43::bar::value()
+    }
+}
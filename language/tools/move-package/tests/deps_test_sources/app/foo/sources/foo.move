@@ -0,0 +1,5 @@
+module 0x42::foo {
+    public fun value(): u64 {
+        0x43::bar::value()
+    }
+}
@@ -0,0 +1,5 @@
+module 0x41::app {
+    public fun value(): u64 {
+        0x42::foo::value() + 0x43::bar::value()
+    }
+}
@@ -0,0 +1,10 @@
+module App::app {
+    #[cfg(feature = oracle)]
+    public fun oracle(): u64 { 1 }
+
+    #[cfg(feature = b"evm")]
+    public fun evm(): u64 { Dep::dep::evm() }
+
+    #[cfg(not(feature = evm))]
+    public fun with_extra(): u64 { Dep::dep::extra() }
+}
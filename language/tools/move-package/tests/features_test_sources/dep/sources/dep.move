@@ -0,0 +1,12 @@
+module Dep::dep {
+    #[cfg(feature = extra)]
+    public fun extra(): u64 { 2 }
+
+    #[cfg(all(feature = evm, not(feature = missing)))]
+    public fun evm(): u64 { 3 }
+}
+
+#[cfg(feature = evm)]
+module Dep::evm_only {
+    public fun answer(): u64 { 42 }
+}
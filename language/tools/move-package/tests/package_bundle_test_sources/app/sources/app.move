@@ -0,0 +1,5 @@
+module App::app {
+    public fun value(): u64 {
+        Dep::dep::value() + 1
+    }
+}
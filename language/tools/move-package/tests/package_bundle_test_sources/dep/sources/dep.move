@@ -0,0 +1,5 @@
+module Dep::dep {
+    public fun value(): u64 {
+        41
+    }
+}
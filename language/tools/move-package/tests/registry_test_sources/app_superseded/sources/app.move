@@ -0,0 +1 @@
+module 0x44::app {}
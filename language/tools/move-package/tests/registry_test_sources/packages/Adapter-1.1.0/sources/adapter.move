@@ -0,0 +1 @@
+module 0x46::adapter {}
@@ -0,0 +1 @@
+module 0x45::base {}
@@ -0,0 +1 @@
+module 0x42::lib {}
@@ -0,0 +1 @@
+module 0x47::pinner {}
@@ -0,0 +1 @@
+module 0x43::util {}
@@ -0,0 +1,145 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_package::{
+    resolution::dependency_graph::DependencyGraph,
+    source_package::manifest_parser::parse_move_manifest_from_file, BuildConfig,
+};
+use move_symbol_pool::Symbol;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    path::PathBuf,
+};
+
+#[test]
+fn assigns_addresses_of_selected_profile() {
+    assert_eq!(
+        addresses(config("testnet")),
+        ["App=0x42", "Oracle=0x142", "Treasury=0x107"]
+    );
+    assert_eq!(
+        addresses(config("mainnet")),
+        ["App=0x43", "Oracle=0x143", "Treasury=0x7"]
+    );
+}
+
+#[test]
+fn overrides_addresses_assigned_in_manifest() {
+    // `Treasury` is assigned 0x7 in '[addresses]', which the 'testnet' profile overrides, and the
+    // 'mainnet' profile leaves as is.
+    assert!(addresses(config("testnet")).contains(&"Treasury=0x107".to_string()));
+    assert!(addresses(config("mainnet")).contains(&"Treasury=0x7".to_string()));
+}
+
+#[test]
+fn prefers_additional_named_addresses_over_profile() {
+    let config = BuildConfig {
+        additional_named_addresses: BTreeMap::from([(
+            "Oracle".to_string(),
+            AccountAddress::from_hex_literal("0x99").unwrap(),
+        )]),
+        ..config("testnet")
+    };
+    assert_eq!(
+        addresses(config),
+        ["App=0x42", "Oracle=0x99", "Treasury=0x107"]
+    );
+}
+
+#[test]
+fn rejects_unknown_profiles() {
+    let err = config("devnet")
+        .resolution_graph_for_package(&test_package(), &mut std::io::sink())
+        .expect_err("Unknown profile");
+    assert!(
+        format!("{:#}", err).contains("No address profile 'devnet' found in the manifest of 'App'"),
+        "{:#}",
+        err
+    );
+}
+
+#[test]
+fn rejects_unassigned_addresses() {
+    let err = config("partial")
+        .resolution_graph_for_package(&test_package(), &mut std::io::sink())
+        .expect_err("Unassigned address");
+    let err = format!("{:#}", err);
+    assert!(
+        err.contains("Unresolved addresses found with address profile 'partial'")
+            && err.contains("Named address 'Oracle' in package 'App'"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn records_profile_in_lock_file_and_build_metadata() {
+    let out = tempfile::tempdir().unwrap();
+    let lock_path = out.path().join("Move.lock");
+    let package = BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        lock_file: Some(lock_path.clone()),
+        ..config("testnet")
+    }
+    .compile_package_no_exit(&test_package(), &mut std::io::sink())
+    .expect("Compiling package");
+
+    let lock = std::fs::read_to_string(&lock_path).unwrap();
+    assert!(
+        lock.ends_with(
+            "\n[move.address-profile]\n\
+             name = \"testnet\"\n\
+             \n\
+             [move.address-profile.addresses]\n\
+             App = \"0x42\"\n\
+             Oracle = \"0x142\"\n\
+             Treasury = \"0x107\"\n"
+        ),
+        "{}",
+        lock
+    );
+
+    let metadata = &package.build_metadata;
+    assert_eq!(metadata.build_options.profile.as_deref(), Some("testnet"));
+    assert_eq!(
+        metadata.address_alias_instantiation[&Symbol::from("Treasury")].short_str_lossless(),
+        "107"
+    );
+
+    // The profile recorded in the lock file does not get in the way of reading it back.
+    let manifest = parse_move_manifest_from_file(&test_package()).unwrap();
+    let graph = DependencyGraph::read_from_lock(
+        test_package(),
+        manifest,
+        &mut File::open(&lock_path).unwrap(),
+    )
+    .expect("Reading lock file");
+    assert_eq!(graph.always_deps, BTreeSet::from([Symbol::from("App")]));
+}
+
+/// The addresses assigned in the test package when resolved with `config`, as `<name>=<address>`.
+fn addresses(config: BuildConfig) -> Vec<String> {
+    let graph = config
+        .resolution_graph_for_package(&test_package(), &mut std::io::sink())
+        .expect("Resolving package");
+    graph.package_table[&Symbol::from("App")]
+        .resolution_table
+        .iter()
+        .map(|(name, addr)| format!("{}=0x{}", name, addr.short_str_lossless()))
+        .collect()
+}
+
+fn config(profile: &str) -> BuildConfig {
+    BuildConfig {
+        profile: Some(profile.to_string()),
+        ..Default::default()
+    }
+}
+
+fn test_package() -> PathBuf {
+    [".", "tests", "address_profiles_test_sources", "app"]
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,121 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::copy_dir;
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_package::{
+    compilation::{
+        build_metadata::BuildMetadata,
+        compiled_package::{CompiledPackage, OnDiskCompiledPackage},
+    },
+    BuildConfig,
+};
+use move_symbol_pool::Symbol;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[test]
+fn records_build_metadata() {
+    let root = tempfile::tempdir().unwrap();
+    let package = build_copy(root.path());
+    let metadata = &package.build_metadata;
+
+    assert_eq!(metadata.compiler_version, move_compiler::COMPILER_VERSION);
+    assert!(metadata.build_options.dev_mode);
+    assert_eq!(
+        metadata.address_alias_instantiation[&Symbol::from("App")].short_str_lossless(),
+        "42",
+    );
+    assert_eq!(
+        metadata.dependency_digests.keys().collect::<Vec<_>>(),
+        vec![&Symbol::from("Dep")],
+    );
+
+    let saved = fs::read_to_string(root.path().join("app/build/App/BuildMetadata.yaml")).unwrap();
+    assert_eq!(
+        serde_yaml::from_str::<BuildMetadata>(&saved).unwrap(),
+        *metadata
+    );
+
+    for unit in package.root_modules() {
+        let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &unit.unit else {
+            panic!("Expected a module");
+        };
+
+        let embedded = BuildMetadata::from_module(module).unwrap();
+        assert_eq!(embedded.as_ref(), Some(metadata));
+    }
+}
+
+#[test]
+fn builds_identical_output_from_different_directories() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    build_copy(first.path());
+    build_copy(second.path());
+
+    for file in [
+        "BuildMetadata.yaml",
+        "bytecode_modules/app.mv",
+        "bytecode_modules/dependencies/Dep/dep.mv",
+    ] {
+        let path = Path::new("app/build/App").join(file);
+        assert_eq!(
+            fs::read(first.path().join(&path)).unwrap(),
+            fs::read(second.path().join(&path)).unwrap(),
+            "{} differs between builds",
+            file,
+        );
+    }
+}
+
+#[test]
+fn loads_packages_built_without_build_metadata() {
+    let root = tempfile::tempdir().unwrap();
+    let package = build_copy(root.path());
+    let build_path = root.path().join("app/build/App");
+    fs::remove_file(build_path.join("BuildMetadata.yaml")).unwrap();
+
+    let loaded = OnDiskCompiledPackage::from_path(&build_path)
+        .unwrap()
+        .into_compiled_package()
+        .unwrap();
+    let metadata = &loaded.build_metadata;
+    assert_eq!(metadata.compiler_version, "");
+    assert_eq!(
+        metadata.bytecode_version,
+        package.build_metadata.bytecode_version
+    );
+    assert_eq!(metadata.build_options, package.build_metadata.build_options);
+    assert_eq!(
+        metadata.address_alias_instantiation,
+        package.build_metadata.address_alias_instantiation,
+    );
+    assert_eq!(metadata.source_digest, package.build_metadata.source_digest);
+    assert!(metadata.dependency_digests.is_empty());
+}
+
+/// Copy the test packages under `root`, and build them there, embedding their build metadata.
+fn build_copy(root: &Path) -> CompiledPackage {
+    for package in ["app", "dep"] {
+        copy_dir(&test_sources().join(package), &root.join(package));
+    }
+
+    BuildConfig {
+        dev_mode: true,
+        embed_build_metadata: true,
+        ..Default::default()
+    }
+    .compile_package_no_exit(&root.join("app"), &mut std::io::sink())
+    .expect("Compiling package")
+}
+
+fn test_sources() -> PathBuf {
+    [".", "tests", "build_metadata_test_sources"]
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,63 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{resolution::dependency_graph::DependencyGraph, BuildConfig};
+use move_symbol_pool::Symbol;
+use std::path::PathBuf;
+
+#[test]
+fn prints_tree_with_sources_and_digests() {
+    let mut output = Vec::new();
+    dependency_graph().print_tree(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let bar = "Bar = { local = \"bar\" }, \
+               digest C8AC9AF9A380AFFC249B65B511174810CE0A59DB4B9C9BF95B22DCB345298FFE";
+    assert!(output.starts_with("App\n"), "{output}");
+    assert!(output.contains(&format!("{bar}\n")), "{output}");
+    assert!(output.contains(&format!("{bar} (*)\n")), "{output}");
+    assert!(
+        output.contains("Foo = { local = \"foo\" }, digest "),
+        "{output}"
+    );
+    assert!(
+        output.contains("Baz (dev) = { local = \"baz\" }, digest "),
+        "{output}"
+    );
+}
+
+#[test]
+fn prints_every_path_to_a_dependency() {
+    let mut output = Vec::new();
+    dependency_graph()
+        .print_why(Symbol::from("Bar"), &mut output)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "App -> Bar\n\
+         App -> Baz (dev) -> Bar\n\
+         App -> Foo -> Bar\n",
+    );
+}
+
+#[test]
+fn rejects_why_for_unknown_packages() {
+    let err = dependency_graph()
+        .print_why(Symbol::from("Qux"), &mut Vec::new())
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Package 'Qux' is not a dependency of 'App'"
+    );
+}
+
+fn dependency_graph() -> DependencyGraph {
+    let path: PathBuf = [".", "tests", "deps_test_sources", "app"]
+        .into_iter()
+        .collect();
+    BuildConfig::default()
+        .dependency_graph_for_package(&path, &mut std::io::sink())
+        .expect("Resolving dependencies")
+}
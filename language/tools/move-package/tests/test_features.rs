@@ -0,0 +1,173 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
+use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig};
+use std::path::PathBuf;
+
+#[test]
+fn enables_default_and_dependency_features() {
+    let (package, features) = build(&[]);
+
+    assert_eq!(features, ["App/default", "App/oracle", "Dep/extra"]);
+    assert_eq!(
+        functions(&package),
+        ["app::oracle", "app::with_extra", "dep::extra"]
+    );
+}
+
+#[test]
+fn propagates_requested_features_to_dependencies() {
+    let (package, features) = build(&["evm"]);
+
+    assert_eq!(
+        features,
+        [
+            "App/default",
+            "App/evm",
+            "App/oracle",
+            "Dep/evm",
+            "Dep/extra"
+        ]
+    );
+    assert_eq!(
+        functions(&package),
+        [
+            "app::evm",
+            "app::oracle",
+            "dep::evm",
+            "dep::extra",
+            "evm_only::answer"
+        ]
+    );
+}
+
+#[test]
+fn enables_requested_features_of_dependencies() {
+    let (package, features) = build(&["Dep/evm"]);
+
+    assert_eq!(
+        features,
+        ["App/default", "App/oracle", "Dep/evm", "Dep/extra"]
+    );
+    assert_eq!(
+        functions(&package),
+        [
+            "app::oracle",
+            "app::with_extra",
+            "dep::evm",
+            "dep::extra",
+            "evm_only::answer"
+        ]
+    );
+}
+
+#[test]
+fn records_enabled_features_in_build_metadata() {
+    let (package, _) = build(&[]);
+    let (evm_package, _) = build(&["evm"]);
+
+    let recorded = |package: &CompiledPackage| {
+        package
+            .build_metadata
+            .build_options
+            .features
+            .iter()
+            .flat_map(|(package, features)| {
+                features
+                    .iter()
+                    .map(move |feature| format!("{}/{}", package, feature))
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        recorded(&package),
+        ["App/default", "App/oracle", "Dep/extra"]
+    );
+    assert_eq!(
+        recorded(&evm_package),
+        [
+            "App/default",
+            "App/evm",
+            "App/oracle",
+            "Dep/evm",
+            "Dep/extra"
+        ]
+    );
+    assert_ne!(package.build_metadata, evm_package.build_metadata);
+}
+
+#[test]
+fn rejects_unknown_features() {
+    let err = config(&["missing"])
+        .resolution_graph_for_package(&test_package(), &mut std::io::sink())
+        .expect_err("Unknown feature");
+    assert!(
+        format!("{:#}", err).contains("Package 'App' has no feature 'missing'"),
+        "{:#}",
+        err
+    );
+}
+
+/// Build the test package with the given `features` requested, returning it along with the
+/// features enabled in each of its packages, as `<package>/<feature>`.
+fn build(features: &[&str]) -> (CompiledPackage, Vec<String>) {
+    let enabled = config(features)
+        .resolution_graph_for_package(&test_package(), &mut std::io::sink())
+        .expect("Resolving package")
+        .features_by_package()
+        .into_iter()
+        .flat_map(|(package, features)| {
+            features
+                .into_iter()
+                .map(move |feature| format!("{}/{}", package, feature))
+        })
+        .collect();
+
+    let out = tempfile::tempdir().unwrap();
+    let package = BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        ..config(features)
+    }
+    .compile_package_no_exit(&test_package(), &mut std::io::sink())
+    .expect("Compiling package");
+
+    (package, enabled)
+}
+
+/// The functions of the modules of `package` and its dependencies, as `<module>::<function>`.
+fn functions(package: &CompiledPackage) -> Vec<String> {
+    let mut functions: Vec<_> = package
+        .all_modules()
+        .flat_map(|unit| {
+            let module = CompiledModule::deserialize(&unit.unit.serialize(None)).unwrap();
+            module
+                .function_defs()
+                .iter()
+                .map(|def| {
+                    let handle = module.function_handle_at(def.function);
+                    format!(
+                        "{}::{}",
+                        module.self_id().name(),
+                        module.identifier_at(handle.name)
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    functions.sort();
+    functions
+}
+
+fn config(features: &[&str]) -> BuildConfig {
+    BuildConfig {
+        features: features.iter().map(|feature| feature.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn test_package() -> PathBuf {
+    [".", "tests", "features_test_sources", "app"]
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,95 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::git;
+use move_package::BuildConfig;
+use std::fs;
+
+#[test]
+fn compares_pinned_commits_against_tracked_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    // Clones of git dependencies go to `MOVE_HOME`, which no other test in this file reads.
+    std::env::set_var("MOVE_HOME", tmp.path().join("move_home"));
+
+    // A git dependency tracking the `main` branch of a local repository.
+    let remote = tmp.path().join("remote");
+    fs::create_dir_all(remote.join("sources")).unwrap();
+    fs::write(
+        remote.join("Move.toml"),
+        "[package]\nname = \"Remote\"\nversion = \"1.0.0\"\n",
+    )
+    .unwrap();
+    fs::write(
+        remote.join("sources/remote.move"),
+        "module 0x42::remote {}\n",
+    )
+    .unwrap();
+    git(&remote, &["init", "--quiet"]);
+    git(&remote, &["add", "."]);
+    git(&remote, &["commit", "--quiet", "-m", "First"]);
+    git(&remote, &["branch", "-M", "main"]);
+    let pinned = git(&remote, &["rev-parse", "HEAD"]);
+
+    let app = tmp.path().join("app");
+    fs::create_dir_all(app.join("sources")).unwrap();
+    fs::write(
+        app.join("Move.toml"),
+        format!(
+            "[package]\n\
+             name = \"App\"\n\
+             version = \"0.1.0\"\n\
+             \n\
+             [dependencies]\n\
+             Remote = {{ git = \"file://{}\", rev = \"main\" }}\n",
+            remote.display()
+        ),
+    )
+    .unwrap();
+
+    let config = BuildConfig {
+        install_dir: Some(tmp.path().join("build")),
+        lock_file: Some(app.join("Move.lock")),
+        ..Default::default()
+    };
+    config
+        .clone()
+        .resolution_graph_for_package(&app, &mut std::io::sink())
+        .expect("Resolving package");
+
+    let lock = fs::read_to_string(app.join("Move.lock")).unwrap();
+    assert!(lock.contains(&format!("commit = \"{}\"", pinned)), "{lock}");
+
+    // The branch moves on, but the local copy of the repository is only updated when fetching.
+    fs::write(
+        remote.join("sources/remote.move"),
+        "module 0x42::remote { }\n",
+    )
+    .unwrap();
+    git(&remote, &["commit", "--quiet", "-am", "Second"]);
+    let latest = git(&remote, &["rev-parse", "HEAD"]);
+
+    let mut output = Vec::new();
+    config
+        .print_outdated_dependencies(&app, false, &mut output)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.contains("All git dependencies are up to date"),
+        "{output}"
+    );
+
+    let mut output = Vec::new();
+    config
+        .print_outdated_dependencies(&app, true, &mut output)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.contains(&format!(
+            "pinned at {}, latest commit on 'main' is {}",
+            pinned, latest
+        )),
+        "{output}"
+    );
+}
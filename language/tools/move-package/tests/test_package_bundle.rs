@@ -0,0 +1,84 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::copy_dir;
+use move_package::{compilation::package_bundle::PackageBundle, BuildConfig};
+use move_symbol_pool::Symbol;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn bundles_modules_in_dependency_order() {
+    let root = tempfile::tempdir().unwrap();
+    let bundle = bundle_copy(root.path());
+
+    assert_eq!(bundle.package_name, Symbol::from("App"));
+    assert!(bundle.manifest.contains("name = \"App\""));
+    assert_eq!(
+        bundle
+            .modules
+            .iter()
+            .map(|module| format!("{}::{}", module.package_name, module.name))
+            .collect::<Vec<_>>(),
+        ["Dep::dep", "App::app"],
+    );
+    assert!(bundle.modules[1].source.contains("Dep::dep::value() + 1"));
+    assert!(bundle.abis.contains_key("0x42_app.json"));
+
+    let (sender, modules) = bundle.package_module_bundle().unwrap();
+    assert_eq!(sender.short_str_lossless(), "42");
+    assert_eq!(modules, [bundle.modules[1].bytecode.clone()]);
+}
+
+#[test]
+fn round_trips_through_bytes() {
+    let root = tempfile::tempdir().unwrap();
+    let bundle = bundle_copy(root.path());
+
+    let path = root.path().join("App.mvb");
+    bundle.write(&path).unwrap();
+    assert_eq!(PackageBundle::read(&path).unwrap(), bundle);
+}
+
+#[test]
+fn rejects_tampered_bundles() {
+    let root = tempfile::tempdir().unwrap();
+    let mut bundle = bundle_copy(root.path());
+    bundle.modules[1].source = bundle.modules[1].source.replace("+ 1", "+ 2");
+
+    let err = PackageBundle::from_bytes(&bundle.to_bytes().unwrap()).expect_err("Tampered bundle");
+    assert!(
+        format!("{:#}", err).contains("do not match its hash"),
+        "{:#}",
+        err
+    );
+}
+
+#[test]
+fn bundles_identically_from_different_directories() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+
+    assert_eq!(
+        bundle_copy(first.path()).content_hash,
+        bundle_copy(second.path()).content_hash,
+    );
+}
+
+/// Copy the test packages under `root`, and bundle them there.
+fn bundle_copy(root: &Path) -> PackageBundle {
+    for package in ["app", "dep"] {
+        copy_dir(&test_sources().join(package), &root.join(package));
+    }
+
+    BuildConfig::default()
+        .bundle_package(&root.join("app"), &mut std::io::sink())
+        .expect("Bundling package")
+}
+
+fn test_sources() -> PathBuf {
+    [".", "tests", "package_bundle_test_sources"]
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,155 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::{copy_dir, git};
+use move_package::BuildConfig;
+use move_symbol_pool::Symbol;
+use std::{fs, path::PathBuf};
+
+#[test]
+fn picks_latest_matching_version() {
+    let out = tempfile::tempdir().unwrap();
+    let lock_path = out.path().join("Move.lock");
+
+    let graph = BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        lock_file: Some(lock_path.clone()),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&test_sources().join("app"), &mut std::io::sink())
+    .expect("Resolving package");
+
+    let lib = graph.get_package(&Symbol::from("Lib"));
+    assert_eq!(lib.source_package.package.version, (1, 1, 0));
+
+    let lock = fs::read_to_string(lock_path).unwrap();
+    assert!(lock.contains("name = \"Lib\""), "{lock}");
+    assert!(
+        lock.contains("Lib-1.1.0\", version = \"=1.1.0\" }"),
+        "{lock}"
+    );
+}
+
+#[test]
+fn repicks_version_for_new_requirements() {
+    let graph = BuildConfig {
+        install_dir: Some(tempfile::tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&test_sources().join("app_repin"), &mut std::io::sink())
+    .expect("Resolving package");
+
+    // The latest version of `Util` only works with `Lib` 1.0.x
+    let util = graph.get_package(&Symbol::from("Util"));
+    assert_eq!(util.source_package.package.version, (1, 1, 0));
+    let lib = graph.get_package(&Symbol::from("Lib"));
+    assert_eq!(lib.source_package.package.version, (1, 0, 0));
+}
+
+#[test]
+fn drops_requirements_of_replaced_versions() {
+    let graph = BuildConfig {
+        install_dir: Some(tempfile::tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&test_sources().join("app_superseded"), &mut std::io::sink())
+    .expect("Resolving package");
+
+    // `Adapter` 1.1.0 is picked first and requires `Base` 1.1.x, but `Pinner` needs `Adapter`
+    // 1.0.0 and `Base` 1.0.x. Once `Adapter` 1.1.0 is replaced, its requirement on `Base` no
+    // longer applies.
+    let adapter = graph.get_package(&Symbol::from("Adapter"));
+    assert_eq!(adapter.source_package.package.version, (1, 0, 0));
+    let base = graph.get_package(&Symbol::from("Base"));
+    assert_eq!(base.source_package.package.version, (1, 0, 0));
+}
+
+#[test]
+fn explains_conflicting_requirements() {
+    let Err(err) = BuildConfig {
+        install_dir: Some(tempfile::tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&test_sources().join("app_conflict"), &mut std::io::sink())
+    else {
+        panic!("Expected conflicting version requirements to fail.");
+    };
+
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains(
+            "No published version of package 'Lib' satisfies all the requirements on it:\n  \
+             'App' 0.1.0 requires ^2.0\n  \
+             'Util' 1.1.0 requires ~1.0\n\
+             Published versions: 1.0.0, 1.1.0, 2.0.0"
+        ),
+        "{message}",
+    );
+}
+
+#[test]
+fn picks_version_from_git_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    // Clones of git dependencies go to `MOVE_HOME`, which no other test in this file reads.
+    std::env::set_var("MOVE_HOME", tmp.path().join("move_home"));
+
+    // A registry index in a git repository, with the published versions alongside it.
+    let index = tmp.path().join("index");
+    for version in ["Lib-1.0.0", "Lib-1.1.0"] {
+        copy_dir(
+            &test_sources().join("packages").join(version),
+            &index.join("packages").join(version),
+        );
+    }
+    fs::write(
+        index.join("Lib.toml"),
+        "[[version]]\n\
+         version = \"1.0.0\"\n\
+         source = { local = \"packages/Lib-1.0.0\" }\n\
+         \n\
+         [[version]]\n\
+         version = \"1.1.0\"\n\
+         source = { local = \"packages/Lib-1.1.0\" }\n",
+    )
+    .unwrap();
+    git(&index, &["init", "--quiet"]);
+    git(&index, &["add", "."]);
+    git(&index, &["commit", "--quiet", "-m", "Publish Lib"]);
+
+    let app = tmp.path().join("app");
+    copy_dir(
+        &test_sources().join("app").join("sources"),
+        &app.join("sources"),
+    );
+    fs::write(
+        app.join("Move.toml"),
+        format!(
+            "[package]\n\
+             name = \"App\"\n\
+             version = \"0.1.0\"\n\
+             \n\
+             [dependencies]\n\
+             Lib = {{ version = \"~1.0.0\", registry = \"file://{}\" }}\n",
+            index.display()
+        ),
+    )
+    .unwrap();
+
+    let graph = BuildConfig {
+        install_dir: Some(tmp.path().join("build")),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&app, &mut std::io::sink())
+    .expect("Resolving package");
+
+    let lib = graph.get_package(&Symbol::from("Lib"));
+    assert_eq!(lib.source_package.package.version, (1, 0, 0));
+}
+
+fn test_sources() -> PathBuf {
+    [".", "tests", "registry_test_sources"]
+        .into_iter()
+        .collect()
+}
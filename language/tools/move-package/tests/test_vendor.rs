@@ -0,0 +1,110 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::copy_dir;
+use move_package::BuildConfig;
+use move_symbol_pool::Symbol;
+use std::{fs, path::PathBuf};
+
+#[test]
+fn resolves_from_vendored_copies() {
+    let out = tempfile::tempdir().unwrap();
+    let graph = BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        lock_file: Some(out.path().join("Move.lock")),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&test_sources().join("app"), &mut std::io::sink())
+    .expect("Resolving package");
+
+    // Neither dependency is reachable, so both must have come from `vendor`.
+    let remote = graph.get_package(&Symbol::from("Remote"));
+    assert!(remote.package_path.ends_with("vendor/Remote"));
+    let base = graph.get_package(&Symbol::from("Base"));
+    assert!(base.package_path.ends_with("vendor/Base"));
+}
+
+#[test]
+fn rejects_modified_vendored_copies() {
+    let root = tempfile::tempdir().unwrap();
+    let app = root.path().join("app");
+    copy_dir(&test_sources().join("app"), &app);
+    fs::write(
+        app.join("vendor/Remote/sources/remote.move"),
+        "module 0x42::remote { public fun value(): u64 { 0 } }\n",
+    )
+    .unwrap();
+
+    let Err(err) = BuildConfig {
+        install_dir: Some(root.path().join("build")),
+        lock_file: Some(root.path().join("Move.lock")),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&app, &mut std::io::sink())
+    else {
+        panic!("Expected a modified vendored copy to be rejected.");
+    };
+
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("Vendored copy of package 'Remote'")
+            && message.contains("has been modified"),
+        "{message}",
+    );
+}
+
+#[test]
+fn revendors_from_vendored_copies() {
+    let root = tempfile::tempdir().unwrap();
+    let app = root.path().join("app");
+    copy_dir(&test_sources().join("app"), &app);
+
+    BuildConfig {
+        skip_fetch_latest_git_deps: true,
+        ..Default::default()
+    }
+    .vendor_dependencies(&app, &mut std::io::sink())
+    .expect("Vendoring dependencies");
+
+    let expected = fs::read_to_string(test_sources().join("app/vendor/Vendor.toml")).unwrap();
+    let actual = fs::read_to_string(app.join("vendor/Vendor.toml")).unwrap();
+    assert_eq!(expected, actual);
+    assert!(app.join("vendor/Base/sources/base.move").is_file());
+    assert!(app.join("vendor/Remote/sources/remote.move").is_file());
+}
+
+#[test]
+fn vendored_only_rejects_copies_from_other_sources() {
+    let root = tempfile::tempdir().unwrap();
+    let app = root.path().join("app");
+    copy_dir(&test_sources().join("app"), &app);
+    let manifest = fs::read_to_string(app.join("Move.toml")).unwrap();
+    fs::write(
+        app.join("Move.toml"),
+        manifest.replace("rev = \"v1\"", "rev = \"v2\""),
+    )
+    .unwrap();
+
+    let Err(err) = BuildConfig {
+        install_dir: Some(root.path().join("build")),
+        vendored_only: true,
+        ..Default::default()
+    }
+    .resolution_graph_for_package(&app, &mut std::io::sink())
+    else {
+        panic!("Expected a vendored copy from another source to be rejected.");
+    };
+
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("Vendored copy of package 'Remote'")
+            && message.contains("vendored from a different source"),
+        "{message}",
+    );
+}
+
+fn test_sources() -> PathBuf {
+    [".", "tests", "vendor_test_sources"].into_iter().collect()
+}
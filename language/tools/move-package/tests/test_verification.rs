@@ -0,0 +1,101 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::CompiledModule;
+use move_core_types::metadata::Metadata;
+use move_package::{compilation::verification::ModuleVerification, BuildConfig};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[test]
+fn matches_deployed_modules_ignoring_metadata() {
+    let deployed = tempfile::tempdir().unwrap();
+    for mut module in compile_modules() {
+        module.metadata.push(Metadata {
+            key: b"deployer".to_vec(),
+            value: b"0xcafe".to_vec(),
+        });
+        deploy(deployed.path(), &module);
+    }
+
+    let report = verify(deployed.path());
+    assert_eq!(report.modules.len(), 2);
+    assert!(report.is_verified(), "{:?}", report);
+}
+
+#[test]
+fn reports_first_difference_and_missing_modules() {
+    let deployed = tempfile::tempdir().unwrap();
+    for mut module in compile_modules() {
+        if module.self_id().name().as_str() == "limits" {
+            module.constant_pool[0].data = 101u64.to_le_bytes().to_vec();
+            deploy(deployed.path(), &module);
+        }
+    }
+
+    let report = verify(deployed.path());
+    assert!(!report.is_verified());
+
+    let results: Vec<_> = report
+        .modules
+        .iter()
+        .map(|(id, result)| (id.name().as_str(), result.clone()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            ("counter", ModuleVerification::Missing),
+            (
+                "limits",
+                ModuleVerification::Differs {
+                    table: "constant_pool",
+                    index: Some(0),
+                }
+            ),
+        ]
+    );
+}
+
+fn compile_modules() -> Vec<CompiledModule> {
+    let out = tempfile::tempdir().unwrap();
+    BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package_no_exit(&test_package(), &mut std::io::sink())
+    .expect("Compiling package")
+    .root_modules()
+    .map(|unit| CompiledModule::deserialize(&unit.unit.serialize(None)).unwrap())
+    .collect()
+}
+
+/// Store `module` where the sandbox would publish it, under `storage`.
+fn deploy(storage: &Path, module: &CompiledModule) {
+    let id = module.self_id();
+    let dir = storage
+        .join(format!("0x{}", id.address().short_str_lossless()))
+        .join("modules");
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut bytes = Vec::new();
+    module.serialize(&mut bytes).unwrap();
+    fs::write(dir.join(format!("{}.mv", id.name())), bytes).unwrap();
+}
+
+fn verify(deployed: &Path) -> move_package::compilation::verification::VerificationReport {
+    let out = tempfile::tempdir().unwrap();
+    BuildConfig {
+        install_dir: Some(out.path().to_path_buf()),
+        ..Default::default()
+    }
+    .verify_bytecode(&test_package(), deployed, &mut std::io::sink())
+    .expect("Verifying package")
+}
+
+fn test_package() -> PathBuf {
+    [".", "tests", "verification_test_sources", "app"]
+        .into_iter()
+        .collect()
+}
@@ -0,0 +1,93 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_package::BuildConfig;
+use move_symbol_pool::Symbol;
+use std::{fs, path::PathBuf};
+
+#[test]
+fn members_in_dependency_order() {
+    let graphs = BuildConfig {
+        dev_mode: true,
+        ..Default::default()
+    }
+    .resolution_graphs_for_workspace(&workspace_root(), &mut std::io::sink())
+    .expect("Resolving workspace");
+
+    let roots: Vec<_> = graphs
+        .iter()
+        .map(|graph| graph.root_package.package.name)
+        .collect();
+    assert_eq!(roots, vec![Symbol::from("Core"), Symbol::from("App")]);
+
+    // Each member only sees its own dependencies.
+    let packages: Vec<Vec<_>> = graphs
+        .iter()
+        .map(|graph| graph.package_table.keys().copied().collect())
+        .collect();
+    assert_eq!(
+        packages,
+        vec![
+            vec![Symbol::from("Core")],
+            vec![Symbol::from("App"), Symbol::from("Core")],
+        ]
+    );
+
+    // The dev-address of `Core` applies, although `App` depends on it.
+    let core = graphs[1].get_package(&Symbol::from("Core"));
+    assert_eq!(
+        core.resolution_table[&Symbol::from("core")],
+        AccountAddress::from_hex_literal("0xC0").unwrap(),
+    );
+}
+
+#[test]
+fn shared_lock_file_and_build_directory() {
+    let out = tempfile::tempdir().unwrap();
+    let lock_path = out.path().join("Move.lock");
+
+    let compiled = BuildConfig {
+        dev_mode: true,
+        install_dir: Some(out.path().to_path_buf()),
+        lock_file: Some(lock_path.clone()),
+        ..Default::default()
+    }
+    .compile_workspace(&workspace_root().join("app"), &mut std::io::sink())
+    .expect("Compiling workspace");
+
+    assert_eq!(compiled.len(), 2);
+    assert!(out.path().join("build").join("Core").is_dir());
+    assert!(out.path().join("build").join("App").is_dir());
+
+    let lock = fs::read_to_string(lock_path).unwrap();
+    assert!(
+        lock.contains("name = \"App\"\nsource = { local = \"app\" }\n"),
+        "{lock}"
+    );
+    assert!(
+        lock.contains("name = \"Core\"\nsource = { local = \"core\" }\n"),
+        "{lock}"
+    );
+}
+
+#[test]
+fn workspace_is_not_a_package() {
+    let Err(err) = BuildConfig::default()
+        .resolution_graph_for_package(&workspace_root(), &mut std::io::sink())
+    else {
+        panic!("Expected resolving the workspace as a package to fail.");
+    };
+
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("This manifest describes a workspace, not a package"),
+        "{message}",
+    );
+}
+
+fn workspace_root() -> PathBuf {
+    [".", "tests", "workspace_test_sources"]
+        .into_iter()
+        .collect()
+}
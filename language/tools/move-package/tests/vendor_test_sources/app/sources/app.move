@@ -0,0 +1,5 @@
+module 0x44::app {
+    public fun value(): u64 {
+        0x42::remote::value()
+    }
+}
@@ -0,0 +1,13 @@
+module App::counter {
+    struct Counter has key {
+        value: u64,
+    }
+
+    public fun new(): Counter {
+        Counter { value: 0 }
+    }
+
+    public fun increment(counter: &mut Counter) {
+        counter.value = counter.value + 1;
+    }
+}
@@ -0,0 +1,7 @@
+module App::limits {
+    const MAX: u64 = 100;
+
+    public fun max(): u64 {
+        MAX
+    }
+}
@@ -0,0 +1,9 @@
+module app::Wallet {
+    use core::Coin::{Self, Coin};
+
+    struct Wallet has key { coin: Coin }
+
+    public fun balance(wallet: &Wallet): u64 {
+        Coin::value(&wallet.coin)
+    }
+}
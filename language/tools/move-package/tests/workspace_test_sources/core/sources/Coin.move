@@ -0,0 +1,7 @@
+module core::Coin {
+    struct Coin has store { value: u64 }
+
+    public fun value(coin: &Coin): u64 {
+        coin.value
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshots of the gas used by unit tests, to detect gas regressions.
+//!
+//! A snapshot is a text file with one line `<test name> (gas: <amount>)` per passing test, sorted
+//! by test name, which is meant to be committed next to the package.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+/// The default name of the gas snapshot file of a package.
+pub const GAS_SNAPSHOT_FILE: &str = ".gas-snapshot";
+
+/// The gas used by each test, keyed by fully qualified test name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GasSnapshot {
+    pub entries: BTreeMap<String, u64>,
+}
+
+/// A difference between a snapshot and the gas used by a test run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasChange {
+    pub test_name: String,
+    /// The gas recorded in the snapshot, or `None` for a test not in the snapshot.
+    pub before: Option<u64>,
+    pub after: u64,
+    pub exceeds_tolerance: bool,
+}
+
+impl GasSnapshot {
+    pub fn new(entries: BTreeMap<String, u64>) -> Self {
+        Self { entries }
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("Unable to read gas snapshot {}: {}", path.display(), e),
+            )
+        })?;
+        let mut entries = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = line
+                .strip_suffix(')')
+                .and_then(|line| line.rsplit_once(" (gas: "))
+                .and_then(|(name, gas)| Some((name.to_string(), gas.parse::<u64>().ok()?)));
+            match entry {
+                Some((name, gas)) => {
+                    entries.insert(name, gas);
+                }
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "Malformed entry in gas snapshot {} at line {}: `{}`",
+                            path.display(),
+                            index + 1,
+                            line
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut content = String::new();
+        for (name, gas) in &self.entries {
+            writeln!(content, "{} (gas: {})", name, gas).unwrap();
+        }
+        fs::write(path, content)
+    }
+
+    /// Updates the entries of the tests in `other`, keeping the others.
+    pub fn merge(&mut self, other: GasSnapshot) {
+        self.entries.extend(other.entries)
+    }
+
+    /// Compares the gas used by a test run against this snapshot. Tests in the snapshot which
+    /// were not run are ignored, so a filtered test run can be checked as well. Tests which are
+    /// not in the snapshot always exceed the tolerance, as the snapshot needs to be updated.
+    pub fn compare(&self, actual: &GasSnapshot, tolerance: f64) -> Vec<GasChange> {
+        actual
+            .entries
+            .iter()
+            .filter_map(|(name, after)| {
+                let before = self.entries.get(name).copied();
+                if before == Some(*after) {
+                    return None;
+                }
+                let exceeds_tolerance = match before {
+                    None => true,
+                    Some(before) => {
+                        before.abs_diff(*after) as f64 * 100.0 > tolerance * before as f64
+                    }
+                };
+                Some(GasChange {
+                    test_name: name.clone(),
+                    before,
+                    after: *after,
+                    exceeds_tolerance,
+                })
+            })
+            .collect()
+    }
+}
+
+impl GasChange {
+    fn render_change(&self) -> String {
+        match self.before {
+            None => "new".to_string(),
+            Some(before) => {
+                let diff = self.after as i128 - before as i128;
+                if before == 0 {
+                    format!("{:+}", diff)
+                } else {
+                    format!("{:+} ({:+.2}%)", diff, diff as f64 * 100.0 / before as f64)
+                }
+            }
+        }
+    }
+}
+
+/// Renders a table of the given gas changes.
+pub fn render_gas_changes(changes: &[GasChange]) -> String {
+    let rows = changes
+        .iter()
+        .map(|change| {
+            [
+                format!(
+                    "{}{}",
+                    if change.exceeds_tolerance { "! " } else { "  " },
+                    change.test_name
+                ),
+                change
+                    .before
+                    .map(|gas| gas.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                change.after.to_string(),
+                change.render_change(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let header = [
+        "  Test Name".to_string(),
+        "Before".to_string(),
+        "After".to_string(),
+        "Change".to_string(),
+    ];
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, cell.chars().count());
+        }
+    }
+
+    let separator = |left: &str, middle: &str, right: &str| {
+        format!(
+            "{}{}{}\n",
+            left,
+            widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<_>>()
+                .join(middle),
+            right
+        )
+    };
+    let row = |cells: &[String; 4]| {
+        format!(
+            "│ {:<w0$} │ {:>w1$} │ {:>w2$} │ {:>w3$} │\n",
+            cells[0],
+            cells[1],
+            cells[2],
+            cells[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )
+    };
+
+    let mut table = separator("┌", "┬", "┐");
+    table.push_str(&row(&header));
+    for cells in &rows {
+        table.push_str(&separator("├", "┼", "┤"));
+        table.push_str(&row(cells));
+    }
+    table.push_str(&separator("└", "┴", "┘"));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(entries: &[(&str, u64)]) -> GasSnapshot {
+        GasSnapshot::new(
+            entries
+                .iter()
+                .map(|(name, gas)| (name.to_string(), *gas))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(GAS_SNAPSHOT_FILE);
+        let expected = snapshot(&[("0x1::M::a", 10), ("0x1::M::b", 0)]);
+        expected.write(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "0x1::M::a (gas: 10)\n0x1::M::b (gas: 0)\n"
+        );
+        assert_eq!(GasSnapshot::read(&path).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_compare() {
+        let before = snapshot(&[("a", 100), ("b", 100), ("c", 100), ("not_run", 100)]);
+        let after = snapshot(&[("a", 100), ("b", 104), ("c", 90), ("new", 7)]);
+        let changes = before.compare(&after, 5.0);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|c| (c.test_name.as_str(), c.before, c.after, c.exceeds_tolerance))
+                .collect::<Vec<_>>(),
+            vec![
+                ("b", Some(100), 104, false),
+                ("c", Some(100), 90, true),
+                ("new", None, 7, true),
+            ]
+        );
+        assert_eq!(
+            render_gas_changes(&changes),
+            "\
+┌─────────────┬────────┬───────┬───────────────┐
+│   Test Name │ Before │ After │        Change │
+├─────────────┼────────┼───────┼───────────────┤
+│   b         │    100 │   104 │   +4 (+4.00%) │
+├─────────────┼────────┼───────┼───────────────┤
+│ ! c         │    100 │    90 │ -10 (-10.00%) │
+├─────────────┼────────┼───────┼───────────────┤
+│ ! new       │      - │     7 │           new │
+└─────────────┴────────┴───────┴───────────────┘
+"
+        );
+    }
+}
@@ -4,11 +4,20 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+pub mod gas_snapshot;
+pub mod mutation;
+pub mod random_test;
 pub mod test_reporter;
 pub mod test_runner;
 
-use crate::test_runner::TestRunner;
+use crate::{
+    gas_snapshot::{render_gas_changes, GasSnapshot},
+    mutation::run_mutation_testing,
+    test_reporter::TestReport,
+    test_runner::TestRunner,
+};
 use clap::*;
+use colored::Colorize;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
     self,
@@ -21,15 +30,19 @@ use move_core_types::language_storage::ModuleId;
 use move_vm_runtime::native_functions::NativeFunctionTable;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     io::{Result, Write},
     marker::Send,
+    path::{Path, PathBuf},
     sync::Mutex,
 };
 
 /// The default value bounding the amount of gas consumed in a test.
 const DEFAULT_EXECUTION_BOUND: u64 = 1_000_000;
 
+/// The default number of times a `#[random_test]` is run with generated arguments.
+const DEFAULT_RAND_NUM_ITERS: u64 = 100;
+
 #[derive(Debug, Parser, Clone)]
 #[clap(author, version, about)]
 pub struct UnitTestingConfig {
@@ -65,7 +78,8 @@ pub struct UnitTestingConfig {
     )]
     pub dep_files: Vec<String>,
 
-    /// Report test statistics at the end of testing
+    /// Report test statistics at the end of testing, including the gas used by each test in
+    /// internal gas units, the unit of JSON and JUnit reports and gas snapshots
     #[clap(name = "report_statistics", short = 's', long = "statistics")]
     pub report_statistics: bool,
 
@@ -115,6 +129,43 @@ pub struct UnitTestingConfig {
     #[clap(short = 'v', long = "verbose")]
     pub report_writeset: bool,
 
+    /// Number of times a #[random_test] is run with generated arguments
+    #[clap(name = "rand_num_iters", long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+
+    /// Seed for generating the arguments of #[random_test]s. A random seed is used if not given
+    #[clap(name = "seed", long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Write a machine-readable report of the test results, given as `junit=<path>` or
+    /// `json=<path>`. Can be given multiple times
+    #[clap(name = "report", long = "report")]
+    pub reports: Vec<TestReport>,
+
+    /// Record the gas used by each passing test in the given gas snapshot file. Random tests are
+    /// not recorded, as the gas they use depends on the seed
+    #[clap(name = "gas_snapshot", long = "gas-snapshot")]
+    pub gas_snapshot: Option<PathBuf>,
+
+    /// Instead of updating the gas snapshot, fail if the gas used by a test differs from it by
+    /// more than the gas tolerance
+    #[clap(
+        name = "check_gas_snapshot",
+        long = "check-gas-snapshot",
+        requires = "gas_snapshot"
+    )]
+    pub check_gas_snapshot: bool,
+
+    /// The change in gas used by a test, in percent, which is accepted when checking the gas
+    /// snapshot
+    #[clap(name = "gas_tolerance", long = "gas-tolerance", default_value = "0")]
+    pub gas_tolerance: f64,
+
+    /// After the tests passed, run them against mutants of the tested modules and report the
+    /// mutation score of each module along with the surviving mutants
+    #[clap(name = "mutate", long = "mutate")]
+    pub mutate: bool,
+
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
     #[cfg(feature = "evm-backend")]
@@ -148,6 +199,13 @@ impl UnitTestingConfig {
             list: false,
             named_address_values: vec![],
             report_writeset: false,
+            rand_num_iters: None,
+            seed: None,
+            reports: vec![],
+            gas_snapshot: None,
+            check_gas_snapshot: false,
+            gas_tolerance: 0.0,
+            mutate: false,
 
             #[cfg(feature = "evm-backend")]
             evm: false,
@@ -240,6 +298,100 @@ impl UnitTestingConfig {
         }
 
         writeln!(shared_writer.lock().unwrap(), "Running Move unit tests")?;
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mutation_plan = self.mutate.then(|| test_plan.clone());
+        // The gas used by a random test depends on the generated arguments, so recording it
+        // would make checking the snapshot depend on the seed.
+        let random_tests: BTreeSet<_> = test_plan
+            .module_tests
+            .iter()
+            .flat_map(|(module_id, module_plan)| {
+                module_plan
+                    .tests
+                    .iter()
+                    .filter(|(_, test_case)| test_case.is_random_test())
+                    .map(move |(name, _)| format!("{}::{}", format_module_id(module_id), name))
+            })
+            .collect();
+        let test_runner = self.test_runner(
+            test_plan,
+            native_function_table.clone(),
+            cost_table.clone(),
+            seed,
+        );
+
+        let test_results = test_runner.run(&shared_writer).unwrap();
+        if self.report_statistics {
+            test_results.report_statistics(&shared_writer)?;
+        }
+
+        if self.report_writeset {
+            test_results.report_goldens(&shared_writer)?;
+        }
+
+        for report in &self.reports {
+            test_results.write_report(report)?;
+        }
+
+        let mut gas_used = test_results.gas_used_by_passing_tests();
+        gas_used.retain(|name, _| !random_tests.contains(name));
+        let gas_used = GasSnapshot::new(gas_used);
+        let tests_ok = test_results.summarize(&shared_writer)?;
+
+        let gas_snapshot_ok = match &self.gas_snapshot {
+            Some(path) => self.process_gas_snapshot(path, gas_used, &shared_writer)?,
+            None => true,
+        };
+
+        if let Some(test_plan) = mutation_plan {
+            if tests_ok {
+                writeln!(
+                    shared_writer.lock().unwrap(),
+                    "\nRunning the tests against mutants of the tested modules"
+                )?;
+                // the stackless VM runs the unmutated code, so it would disagree with any mutant
+                let mutant_config = Self {
+                    check_stackless_vm: false,
+                    ..self.clone()
+                };
+                let report = run_mutation_testing(&test_plan, |mutant_plan| {
+                    let silent_writer = Mutex::new(std::io::sink());
+                    mutant_config
+                        .test_runner(
+                            mutant_plan,
+                            native_function_table.clone(),
+                            cost_table.clone(),
+                            seed,
+                        )
+                        .run(&silent_writer)
+                        .map_or(false, |results| {
+                            results.summarize(&silent_writer).unwrap_or(false)
+                        })
+                });
+                write!(
+                    shared_writer.lock().unwrap(),
+                    "{}",
+                    report.render(&test_plan.files)
+                )?;
+            } else {
+                writeln!(
+                    shared_writer.lock().unwrap(),
+                    "Skipping mutation testing as not all tests passed"
+                )?;
+            }
+        }
+
+        let writer = shared_writer.into_inner().unwrap();
+        Ok((writer, tests_ok && gas_snapshot_ok))
+    }
+
+    fn test_runner(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+        seed: u64,
+    ) -> TestRunner {
         let mut test_runner = TestRunner::new(
             self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
             self.num_threads,
@@ -252,6 +404,8 @@ impl UnitTestingConfig {
             cost_table,
             verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
             self.report_writeset,
+            self.rand_num_iters.unwrap_or(DEFAULT_RAND_NUM_ITERS),
+            seed,
             #[cfg(feature = "evm-backend")]
             self.evm,
         )
@@ -260,19 +414,59 @@ impl UnitTestingConfig {
         if let Some(filter_str) = &self.filter {
             test_runner.filter(filter_str)
         }
+        test_runner
+    }
 
-        let test_results = test_runner.run(&shared_writer).unwrap();
-        if self.report_statistics {
-            test_results.report_statistics(&shared_writer)?;
+    /// Checks or updates the gas snapshot at `path` with the gas used by the tests. Returns
+    /// `false` if checking found changes beyond the tolerance.
+    fn process_gas_snapshot<W: Write>(
+        &self,
+        path: &Path,
+        gas_used: GasSnapshot,
+        writer: &Mutex<W>,
+    ) -> Result<bool> {
+        if !self.check_gas_snapshot {
+            // When only some tests were run, keep the entries of the others.
+            let mut snapshot = if self.filter.is_some() && path.exists() {
+                GasSnapshot::read(path)?
+            } else {
+                GasSnapshot::default()
+            };
+            snapshot.merge(gas_used);
+            snapshot.write(path)?;
+            writeln!(
+                writer.lock().unwrap(),
+                "Wrote gas snapshot to {}",
+                path.display()
+            )?;
+            return Ok(true);
         }
 
-        if self.report_writeset {
-            test_results.report_goldens(&shared_writer)?;
+        let changes = GasSnapshot::read(path)?.compare(&gas_used, self.gas_tolerance);
+        if changes.is_empty() {
+            return Ok(true);
         }
-
-        let ok = test_results.summarize(&shared_writer)?;
-
-        let writer = shared_writer.into_inner().unwrap();
-        Ok((writer, ok))
+        writeln!(
+            writer.lock().unwrap(),
+            "\nGas usage differs from the snapshot {} (tolerance: {}%):\n\n{}",
+            path.display(),
+            self.gas_tolerance,
+            render_gas_changes(&changes)
+        )?;
+        let num_exceeding = changes
+            .iter()
+            .filter(|change| change.exceeds_tolerance)
+            .count();
+        if num_exceeding == 0 {
+            return Ok(true);
+        }
+        writeln!(
+            writer.lock().unwrap(),
+            "Gas snapshot check: {}. {} test(s) marked with `!` changed beyond the tolerance. \
+            Rerun without checking to update the snapshot.",
+            "FAILED".bold().bright_red(),
+            num_exceeding
+        )?;
+        Ok(false)
     }
 }
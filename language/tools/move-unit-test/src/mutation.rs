@@ -0,0 +1,484 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutation testing of Move unit tests.
+//!
+//! The modules under test are changed in small ways, each change giving a mutant of the module,
+//! and the tests are run against every mutant. A mutant is killed if some test fails on it. A
+//! surviving mutant points at behavior which the tests do not check.
+//!
+//! Mutations are applied to the compiled bytecode, so the package is only compiled once. Mutants
+//! which do not pass the bytecode verifier (e.g. because a deleted statement leaves a value
+//! without the `drop` ability on the stack) are discarded.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_verifier::verify_module;
+use move_compiler::{
+    compiled_unit::NamedCompiledModule,
+    diagnostics::FilesSourceText,
+    unit_test::{filter_test_members::UNIT_TEST_POISON_FUN_NAME, TestPlan},
+};
+use move_core_types::{language_storage::ModuleId, u256::U256};
+use move_ir_types::location::Loc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+};
+
+/// The kinds of changes made to create mutants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MutationOperator {
+    /// Swaps `+` with `-` and `*` with `/`
+    Arithmetic,
+    /// Replaces a comparison with its negation, e.g. `<` with `>=`
+    Comparison,
+    /// Removes a `!`, flips a boolean constant, or swaps `&&` with `||`
+    BooleanNegation,
+    /// Changes an integer constant by one
+    OffByOne,
+    /// Removes an `abort`, e.g. of a failing `assert!`
+    AssertRemoval,
+    /// Negates the condition of a conditional branch, which swaps the branches of an `if`
+    IfSwap,
+    /// Deletes a call of a function without return values, a `move_to`, or a write through a
+    /// reference
+    StatementDeletion,
+}
+
+impl fmt::Display for MutationOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            MutationOperator::Arithmetic => "arithmetic",
+            MutationOperator::Comparison => "comparison",
+            MutationOperator::BooleanNegation => "boolean negation",
+            MutationOperator::OffByOne => "off-by-one",
+            MutationOperator::AssertRemoval => "assert removal",
+            MutationOperator::IfSwap => "if swap",
+            MutationOperator::StatementDeletion => "statement deletion",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A change of a single instruction of a module.
+#[derive(Debug, Clone)]
+pub struct Mutation {
+    pub function: FunctionDefinitionIndex,
+    pub offset: CodeOffset,
+    pub operator: MutationOperator,
+    /// The instructions replacing the one at `offset`
+    pub replacement: Vec<Bytecode>,
+    pub description: String,
+}
+
+/// The outcome of running the tests against a mutant.
+#[derive(Debug, Clone)]
+pub struct MutantResult {
+    pub module_id: ModuleId,
+    pub function_name: String,
+    pub mutation: Mutation,
+    pub loc: Option<Loc>,
+    pub killed: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MutationReport {
+    pub results: Vec<MutantResult>,
+    /// The number of mutants which did not pass the bytecode verifier
+    pub discarded: usize,
+}
+
+/// Finds the mutations of the functions of `module` for which `is_tested_function` holds.
+pub fn find_mutations(
+    module: &CompiledModule,
+    is_tested_function: impl Fn(&str) -> bool,
+) -> Vec<Mutation> {
+    let mut mutations = vec![];
+    for (idx, def) in module.function_defs().iter().enumerate() {
+        let function = FunctionDefinitionIndex(idx as u16);
+        let name = module.identifier_at(module.function_handle_at(def.function).name);
+        let code = match &def.code {
+            Some(code) if is_tested_function(name.as_str()) => &code.code,
+            _ => continue,
+        };
+        for (offset, instr) in code.iter().enumerate() {
+            let offset = offset as CodeOffset;
+            if let Some((operator, replacement, description)) = mutate_instruction(module, instr) {
+                mutations.push(Mutation {
+                    function,
+                    offset,
+                    operator,
+                    replacement,
+                    description,
+                });
+            }
+        }
+    }
+    mutations
+}
+
+fn mutate_instruction(
+    module: &CompiledModule,
+    instr: &Bytecode,
+) -> Option<(MutationOperator, Vec<Bytecode>, String)> {
+    use Bytecode as B;
+    use MutationOperator as M;
+
+    let replace = |operator, new: Bytecode, from: &str, to: &str| {
+        Some((
+            operator,
+            vec![new],
+            format!("replaced `{}` with `{}`", from, to),
+        ))
+    };
+    let delete = |num_operands: usize, what: String| {
+        let replacement = if num_operands == 0 {
+            vec![B::Nop]
+        } else {
+            vec![B::Pop; num_operands]
+        };
+        Some((
+            M::StatementDeletion,
+            replacement,
+            format!("deleted {}", what),
+        ))
+    };
+    match instr {
+        B::Add => replace(M::Arithmetic, B::Sub, "+", "-"),
+        B::Sub => replace(M::Arithmetic, B::Add, "-", "+"),
+        B::Mul => replace(M::Arithmetic, B::Div, "*", "/"),
+        B::Div => replace(M::Arithmetic, B::Mul, "/", "*"),
+        B::Lt => replace(M::Comparison, B::Ge, "<", ">="),
+        B::Ge => replace(M::Comparison, B::Lt, ">=", "<"),
+        B::Gt => replace(M::Comparison, B::Le, ">", "<="),
+        B::Le => replace(M::Comparison, B::Gt, "<=", ">"),
+        B::Eq => replace(M::Comparison, B::Neq, "==", "!="),
+        B::Neq => replace(M::Comparison, B::Eq, "!=", "=="),
+        B::Not => Some((M::BooleanNegation, vec![B::Nop], "removed `!`".to_string())),
+        B::LdTrue => replace(M::BooleanNegation, B::LdFalse, "true", "false"),
+        B::LdFalse => replace(M::BooleanNegation, B::LdTrue, "false", "true"),
+        B::And => replace(M::BooleanNegation, B::Or, "&&", "||"),
+        B::Or => replace(M::BooleanNegation, B::And, "||", "&&"),
+        B::LdU8(x) => {
+            let y = x.checked_add(1).unwrap_or_else(|| x - 1);
+            replace(M::OffByOne, B::LdU8(y), &x.to_string(), &y.to_string())
+        }
+        B::LdU16(x) => {
+            let y = x.checked_add(1).unwrap_or_else(|| x - 1);
+            replace(M::OffByOne, B::LdU16(y), &x.to_string(), &y.to_string())
+        }
+        B::LdU32(x) => {
+            let y = x.checked_add(1).unwrap_or_else(|| x - 1);
+            replace(M::OffByOne, B::LdU32(y), &x.to_string(), &y.to_string())
+        }
+        B::LdU64(x) => {
+            let y = x.checked_add(1).unwrap_or_else(|| x - 1);
+            replace(M::OffByOne, B::LdU64(y), &x.to_string(), &y.to_string())
+        }
+        B::LdU128(x) => {
+            let y = x.checked_add(1).unwrap_or_else(|| x - 1);
+            replace(M::OffByOne, B::LdU128(y), &x.to_string(), &y.to_string())
+        }
+        B::LdU256(x) => {
+            let y = x
+                .checked_add(U256::one())
+                .unwrap_or_else(|| *x - U256::one());
+            replace(M::OffByOne, B::LdU256(y), &x.to_string(), &y.to_string())
+        }
+        // the abort code is dropped and execution continues after the `abort`
+        B::Abort => Some((
+            M::AssertRemoval,
+            vec![B::Pop],
+            "removed `abort`".to_string(),
+        )),
+        B::BrTrue(target) => Some((
+            M::IfSwap,
+            vec![B::BrFalse(*target)],
+            "negated the branch condition".to_string(),
+        )),
+        B::BrFalse(target) => Some((
+            M::IfSwap,
+            vec![B::BrTrue(*target)],
+            "negated the branch condition".to_string(),
+        )),
+        B::WriteRef => delete(2, "write through a reference".to_string()),
+        B::MoveTo(_) | B::MoveToGeneric(_) => delete(2, "`move_to`".to_string()),
+        B::Call(_) | B::CallGeneric(_) => {
+            let handle_idx = match instr {
+                B::Call(idx) => *idx,
+                B::CallGeneric(idx) => module.function_instantiation_at(*idx).handle,
+                _ => unreachable!(),
+            };
+            let handle = module.function_handle_at(handle_idx);
+            if !module.signature_at(handle.return_).is_empty() {
+                return None;
+            }
+            let callee_module = module.module_handle_at(handle.module);
+            delete(
+                module.signature_at(handle.parameters).len(),
+                format!(
+                    "call to `{}::{}`",
+                    module.identifier_at(callee_module.name),
+                    module.identifier_at(handle.name)
+                ),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Returns a copy of `module` with the mutation applied.
+pub fn apply_mutation(module: &CompiledModule, mutation: &Mutation) -> CompiledModule {
+    let mut module = module.clone();
+    let code = &mut module.function_defs[mutation.function.0 as usize]
+        .code
+        .as_mut()
+        .expect("mutated functions have code")
+        .code;
+    // instructions replaced by several ones shift the offsets of the following instructions
+    let shift = mutation.replacement.len() as CodeOffset - 1;
+    if shift > 0 {
+        for instr in code.iter_mut() {
+            match instr {
+                Bytecode::BrTrue(target) | Bytecode::BrFalse(target) | Bytecode::Branch(target)
+                    if *target > mutation.offset =>
+                {
+                    *target += shift
+                }
+                _ => (),
+            }
+        }
+    }
+    let offset = mutation.offset as usize;
+    code.splice(offset..offset + 1, mutation.replacement.iter().cloned());
+    module
+}
+
+/// Runs the tests of `test_plan` against the mutants of the tested modules, using
+/// `run_tests` which returns whether all tests passed. Test functions, fixtures and the functions
+/// added by the compiler for testing are not mutated.
+pub fn run_mutation_testing(
+    test_plan: &TestPlan,
+    run_tests: impl Fn(TestPlan) -> bool,
+) -> MutationReport {
+    // Modules are mutated if they belong to the package of a module with tests. Outside of a
+    // package, e.g. when testing individual files, they must be defined in the same file instead.
+    let tested_modules: Vec<_> = test_plan
+        .module_tests
+        .keys()
+        .filter_map(|module_id| test_plan.module_info.get(module_id))
+        .collect();
+    let is_tested = |named_module: &NamedCompiledModule| {
+        tested_modules.iter().any(
+            |tested| match (tested.package_name, named_module.package_name) {
+                (Some(tested_package), Some(package)) => tested_package == package,
+                _ => {
+                    tested.source_map.definition_location.file_hash()
+                        == named_module.source_map.definition_location.file_hash()
+                }
+            },
+        )
+    };
+    let test_functions: BTreeSet<_> = test_plan
+        .module_tests
+        .values()
+        .flat_map(|module_tests| {
+            module_tests.tests.values().flat_map(move |test| {
+                std::iter::once(&test.test_name)
+                    .chain(test.fixtures.iter().map(|fixture| &fixture.name))
+                    .map(move |name| (module_tests.module_id.clone(), name.clone()))
+            })
+        })
+        .collect();
+
+    let mut report = MutationReport::default();
+    for (module_id, named_module) in &test_plan.module_info {
+        if !is_tested(named_module) {
+            continue;
+        }
+        let module = &named_module.module;
+        let mutations = find_mutations(module, |name| {
+            name != UNIT_TEST_POISON_FUN_NAME
+                && !test_functions.contains(&(module_id.clone(), name.to_string()))
+        });
+        for mutation in mutations {
+            let mutant = apply_mutation(module, &mutation);
+            if verify_module(&mutant).is_err() {
+                report.discarded += 1;
+                continue;
+            }
+            let mut mutant_plan = test_plan.clone();
+            mutant_plan
+                .module_info
+                .get_mut(module_id)
+                .expect("mutated module is part of the test plan")
+                .module = mutant;
+            let killed = !run_tests(mutant_plan);
+
+            let def = module.function_def_at(mutation.function);
+            let function_name = module
+                .identifier_at(module.function_handle_at(def.function).name)
+                .to_string();
+            let loc = named_module
+                .source_map
+                .get_code_location(mutation.function, mutation.offset)
+                .ok();
+            report.results.push(MutantResult {
+                module_id: module_id.clone(),
+                function_name,
+                mutation,
+                loc,
+                killed,
+            });
+        }
+    }
+    report
+}
+
+impl MutationReport {
+    /// Renders the mutation score of each module, and the surviving mutants against the source
+    /// in `files`.
+    pub fn render(&self, files: &FilesSourceText) -> String {
+        let mut out = String::new();
+        let mut by_module: BTreeMap<&ModuleId, (usize, usize)> = BTreeMap::new();
+        for result in &self.results {
+            let (killed, total) = by_module.entry(&result.module_id).or_default();
+            *total += 1;
+            if result.killed {
+                *killed += 1;
+            }
+        }
+
+        writeln!(out, "Mutation score by module:").unwrap();
+        for (module_id, (killed, total)) in &by_module {
+            writeln!(
+                out,
+                "  0x{}::{}: {}",
+                module_id.address().short_str_lossless(),
+                module_id.name(),
+                format_score(*killed, *total)
+            )
+            .unwrap();
+        }
+        let killed = self.results.iter().filter(|result| result.killed).count();
+        writeln!(out, "Total: {}", format_score(killed, self.results.len())).unwrap();
+        if self.discarded > 0 {
+            writeln!(
+                out,
+                "{} mutant(s) were discarded as they did not pass the bytecode verifier",
+                self.discarded
+            )
+            .unwrap();
+        }
+
+        let survivors: Vec<_> = self
+            .results
+            .iter()
+            .filter(|result| !result.killed)
+            .collect();
+        if !survivors.is_empty() {
+            writeln!(out, "\nSurviving mutants:").unwrap();
+        }
+        for result in survivors {
+            let source = result
+                .loc
+                .and_then(|loc| files.get(&loc.file_hash()).map(|file| (loc, file)));
+            match source {
+                Some((loc, (file_name, text))) => {
+                    let (line, column) = line_and_column(text, loc.start() as usize);
+                    writeln!(
+                        out,
+                        "  {}:{}:{}: {} in `{}` ({})",
+                        file_name,
+                        line,
+                        column,
+                        result.mutation.description,
+                        result.function_name,
+                        result.mutation.operator
+                    )
+                    .unwrap();
+                    render_source_line(&mut out, text, loc, line);
+                }
+                None => writeln!(
+                    out,
+                    "  0x{}::{}::{}: {} ({})",
+                    result.module_id.address().short_str_lossless(),
+                    result.module_id.name(),
+                    result.function_name,
+                    result.mutation.description,
+                    result.mutation.operator
+                )
+                .unwrap(),
+            }
+        }
+        out
+    }
+}
+
+fn format_score(killed: usize, total: usize) -> String {
+    let score = if total == 0 {
+        100.0
+    } else {
+        killed as f64 * 100.0 / total as f64
+    };
+    format!("{}/{} mutants killed ({:.1}%)", killed, total, score)
+}
+
+/// The 1-based line and column of the byte `offset` in `text`.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, offset - line_start + 1)
+}
+
+/// Renders the line of `loc` with the mutated code underlined.
+fn render_source_line(out: &mut String, text: &str, loc: Loc, line: usize) {
+    let start = loc.start() as usize;
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+    // Underline at least one character, which may span several bytes.
+    let next = text[start..]
+        .chars()
+        .next()
+        .map_or(start, |c| start + c.len_utf8());
+    let end = (loc.end() as usize).clamp(next, line_end.max(next));
+    let gutter = line.to_string();
+    writeln!(out, "   {} | {}", gutter, &text[line_start..line_end]).unwrap();
+    writeln!(
+        out,
+        "   {} | {}{}",
+        " ".repeat(gutter.len()),
+        " ".repeat(text[line_start..start].chars().count()),
+        "^".repeat(text[start..end].chars().count().max(1))
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_command_line_common::files::FileHash;
+
+    #[test]
+    fn test_render_source_line_with_multibyte_characters() {
+        let text = "let s = b\"é\"; // ü\n";
+        let loc =
+            |start: usize, end: usize| Loc::new(FileHash::new(text), start as u32, end as u32);
+        let start = text.find('é').unwrap();
+        let mut out = String::new();
+        render_source_line(&mut out, text, loc(start, start), 1);
+        assert_eq!(out, "   1 | let s = b\"é\"; // ü\n     |           ^\n");
+
+        let start = text.find('ü').unwrap();
+        let mut out = String::new();
+        render_source_line(&mut out, text, loc(start, start + 1), 1);
+        assert_eq!(
+            out,
+            "   1 | let s = b\"é\"; // ü\n     |                  ^\n"
+        );
+    }
+}
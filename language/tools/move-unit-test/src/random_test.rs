@@ -0,0 +1,200 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation and shrinking of the arguments of `#[random_test]` tests.
+
+use move_compiler::unit_test::TestArgument;
+use move_core_types::{
+    account_address::AccountAddress,
+    u256::U256,
+    value::{MoveStruct, MoveTypeLayout, MoveValue},
+};
+use rand::{rngs::StdRng, Rng};
+
+/// The maximal length of generated vectors.
+const MAX_VECTOR_LENGTH: usize = 16;
+
+/// Produces the arguments for one run of a test, generating values for those which are not
+/// assigned in the test attribute.
+pub fn generate_arguments(arguments: &[TestArgument], rng: &mut StdRng) -> Vec<MoveValue> {
+    arguments
+        .iter()
+        .map(|arg| match arg {
+            TestArgument::Value(value) => value.clone(),
+            TestArgument::Generate { layout, .. } => generate_value(layout, rng),
+        })
+        .collect()
+}
+
+/// Produces candidates for simpler arguments than `values`, simplest first. Only generated
+/// arguments are changed.
+pub fn shrink_arguments(arguments: &[TestArgument], values: &[MoveValue]) -> Vec<Vec<MoveValue>> {
+    arguments
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| matches!(arg, TestArgument::Generate { .. }))
+        .flat_map(|(i, _)| {
+            shrink_value(&values[i]).into_iter().map(move |candidate| {
+                let mut values = values.to_vec();
+                values[i] = candidate;
+                values
+            })
+        })
+        .collect()
+}
+
+/// Renders the generated arguments as `name = value`.
+pub fn describe_arguments(arguments: &[TestArgument], values: &[MoveValue]) -> Vec<String> {
+    arguments
+        .iter()
+        .zip(values)
+        .filter_map(|(arg, value)| match arg {
+            TestArgument::Value(_) => None,
+            TestArgument::Generate { name, .. } => Some(format!("{} = {}", name, value)),
+        })
+        .collect()
+}
+
+/// Generates a random value with the given layout.
+pub fn generate_value(layout: &MoveTypeLayout, rng: &mut StdRng) -> MoveValue {
+    use MoveTypeLayout as L;
+    match layout {
+        L::Bool => MoveValue::Bool(rng.gen()),
+        L::Address => MoveValue::Address(AccountAddress::new(rng.gen())),
+        L::Signer => MoveValue::Signer(AccountAddress::new(rng.gen())),
+        L::Vector(elem) => {
+            let len = rng.gen_range(0..=MAX_VECTOR_LENGTH);
+            MoveValue::Vector((0..len).map(|_| generate_value(elem, rng)).collect())
+        }
+        L::Struct(layout) => MoveValue::Struct(MoveStruct::Runtime(
+            layout
+                .fields()
+                .iter()
+                .map(|field| generate_value(field, rng))
+                .collect(),
+        )),
+        L::U8 | L::U16 | L::U32 | L::U64 | L::U128 | L::U256 => {
+            let bits = int_bits(layout);
+            let max = U256::max_value() >> (256 - bits) as u8;
+            // Bias towards boundary and small values, which are more likely to uncover bugs
+            // than values drawn uniformly from the whole range.
+            let value = match rng.gen_range(0..4) {
+                0 => [U256::zero(), U256::one(), max][rng.gen_range(0..3)],
+                1 => U256::from(rng.gen::<u8>()) & max,
+                _ => U256::from_le_bytes(&rng.gen()) & max,
+            };
+            int_value(layout, value)
+        }
+    }
+}
+
+/// Produces candidates for values simpler than `value`, simplest first.
+pub fn shrink_value(value: &MoveValue) -> Vec<MoveValue> {
+    match value {
+        MoveValue::Bool(b) => {
+            if *b {
+                vec![MoveValue::Bool(false)]
+            } else {
+                vec![]
+            }
+        }
+        MoveValue::Address(addr) => {
+            if *addr != AccountAddress::ZERO {
+                vec![MoveValue::Address(AccountAddress::ZERO)]
+            } else {
+                vec![]
+            }
+        }
+        MoveValue::Vector(elems) => shrink_vector(elems)
+            .into_iter()
+            .map(MoveValue::Vector)
+            .collect(),
+        MoveValue::Struct(MoveStruct::Runtime(fields)) => shrink_elements(fields)
+            .into_iter()
+            .map(|fields| MoveValue::Struct(MoveStruct::Runtime(fields)))
+            .collect(),
+        MoveValue::Struct(_) | MoveValue::Signer(_) => vec![],
+        MoveValue::U8(x) => shrink_int(MoveTypeLayout::U8, U256::from(*x)),
+        MoveValue::U16(x) => shrink_int(MoveTypeLayout::U16, U256::from(*x)),
+        MoveValue::U32(x) => shrink_int(MoveTypeLayout::U32, U256::from(*x)),
+        MoveValue::U64(x) => shrink_int(MoveTypeLayout::U64, U256::from(*x)),
+        MoveValue::U128(x) => shrink_int(MoveTypeLayout::U128, U256::from(*x)),
+        MoveValue::U256(x) => shrink_int(MoveTypeLayout::U256, *x),
+    }
+}
+
+/// Shrinks an integer towards zero: first zero itself, then by halving distances, which
+/// converges to a minimal failing value in a logarithmic number of steps.
+fn shrink_int(layout: MoveTypeLayout, x: U256) -> Vec<MoveValue> {
+    let mut candidates = vec![];
+    if x != U256::zero() {
+        candidates.push(U256::zero());
+        let mut delta = x >> 1u8;
+        while delta != U256::zero() {
+            candidates.push(x - delta);
+            delta = delta >> 1u8;
+        }
+    }
+    candidates
+        .into_iter()
+        .map(|c| int_value(&layout, c))
+        .collect()
+}
+
+/// Shrinks a vector by removing chunks of decreasing size, then by shrinking its elements.
+fn shrink_vector(elems: &[MoveValue]) -> Vec<Vec<MoveValue>> {
+    let mut candidates = vec![];
+    if elems.is_empty() {
+        return candidates;
+    }
+    candidates.push(vec![]);
+    let mut size = elems.len() / 2;
+    while size > 0 {
+        for start in (0..elems.len()).step_by(size) {
+            let mut candidate = elems.to_vec();
+            candidate.drain(start..usize::min(start + size, elems.len()));
+            candidates.push(candidate);
+        }
+        size /= 2;
+    }
+    candidates.extend(shrink_elements(elems));
+    candidates
+}
+
+fn shrink_elements(elems: &[MoveValue]) -> Vec<Vec<MoveValue>> {
+    elems
+        .iter()
+        .enumerate()
+        .flat_map(|(i, elem)| {
+            shrink_value(elem).into_iter().map(move |candidate| {
+                let mut elems = elems.to_vec();
+                elems[i] = candidate;
+                elems
+            })
+        })
+        .collect()
+}
+
+fn int_bits(layout: &MoveTypeLayout) -> u32 {
+    match layout {
+        MoveTypeLayout::U8 => 8,
+        MoveTypeLayout::U16 => 16,
+        MoveTypeLayout::U32 => 32,
+        MoveTypeLayout::U64 => 64,
+        MoveTypeLayout::U128 => 128,
+        MoveTypeLayout::U256 => 256,
+        _ => unreachable!("not an integer layout"),
+    }
+}
+
+fn int_value(layout: &MoveTypeLayout, x: U256) -> MoveValue {
+    match layout {
+        MoveTypeLayout::U8 => MoveValue::U8(x.unchecked_as_u8()),
+        MoveTypeLayout::U16 => MoveValue::U16(x.unchecked_as_u16()),
+        MoveTypeLayout::U32 => MoveValue::U32(x.unchecked_as_u32()),
+        MoveTypeLayout::U64 => MoveValue::U64(x.unchecked_as_u64()),
+        MoveTypeLayout::U128 => MoveValue::U128(x.unchecked_as_u128()),
+        MoveTypeLayout::U256 => MoveValue::U256(x),
+        _ => unreachable!("not an integer layout"),
+    }
+}
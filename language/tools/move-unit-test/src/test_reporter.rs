@@ -5,6 +5,7 @@
 use crate::format_module_id;
 use codespan_reporting::files::{Files, SimpleFiles};
 use colored::{control, Colorize};
+use itertools::Itertools;
 use move_binary_format::{
     access::ModuleAccess,
     errors::{ExecutionState, Location, VMError, VMResult},
@@ -17,9 +18,14 @@ use move_compiler::{
 use move_core_types::{effects::ChangeSet, language_storage::ModuleId, vm_status::StatusType};
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write as _,
+    fs,
     io::{Result, Write},
+    path::PathBuf,
+    str::FromStr,
     sync::Mutex,
     time::Duration,
 };
@@ -59,6 +65,14 @@ pub struct TestFailure {
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
     pub storage_state: Option<String>,
+    pub random_inputs: Option<RandomTestInputs>,
+}
+
+/// The inputs with which a `#[random_test]` failed, after shrinking.
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+pub struct RandomTestInputs {
+    pub seed: u64,
+    pub arguments: Vec<String>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -66,6 +80,10 @@ pub struct TestRunInfo {
     pub function_ident: String,
     pub elapsed_time: Duration,
     pub instructions_executed: u64,
+    /// Gas consumed by the test, in internal gas units.
+    pub gas_used: u64,
+    /// Output printed via `debug::print` while running the test.
+    pub output: String,
 }
 
 #[derive(Debug, Clone)]
@@ -81,14 +99,93 @@ pub struct TestResults {
     test_plan: TestPlan,
 }
 
+/// A machine-readable report of the test results, given as `<format>=<path>` on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    pub format: TestReportFormat,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestReportFormat {
+    JUnit,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TestStatus {
+    Pass,
+    Fail,
+    Timeout,
+}
+
+/// The outcome of a single test, as it appears in a report.
+#[derive(Debug, Clone, Serialize)]
+struct TestReportEntry {
+    name: String,
+    module: String,
+    status: TestStatus,
+    duration_secs: f64,
+    instructions_executed: u64,
+    /// Gas consumed by the test, in internal gas units as in the test statistics.
+    gas_used: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<String>,
+    output: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JsonReport<'a> {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    tests: &'a [TestReportEntry],
+}
+
 impl TestRunInfo {
-    pub fn new(function_ident: String, elapsed_time: Duration, instructions_executed: u64) -> Self {
+    pub fn new(
+        function_ident: String,
+        elapsed_time: Duration,
+        instructions_executed: u64,
+        gas_used: u64,
+    ) -> Self {
         Self {
             function_ident,
             elapsed_time,
             instructions_executed,
+            gas_used,
+            output: String::new(),
         }
     }
+
+    pub fn with_output(self, output: String) -> Self {
+        Self { output, ..self }
+    }
+}
+
+impl FromStr for TestReport {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (format, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid report `{}`, expected `<format>=<path>`", s))?;
+        let format = match format {
+            "junit" => TestReportFormat::JUnit,
+            "json" => TestReportFormat::Json,
+            _ => {
+                return Err(format!(
+                    "Unknown report format `{}`, expected `junit` or `json`",
+                    format
+                ))
+            }
+        };
+        Ok(Self {
+            format,
+            path: PathBuf::from(path),
+        })
+    }
 }
 
 impl FailureReason {
@@ -116,6 +213,13 @@ impl FailureReason {
         FailureReason::UnexpectedError("Test was not expected to error".to_string(), error)
     }
 
+    pub fn fixture_error(fixture_name: &str, error: MoveError) -> Self {
+        FailureReason::UnexpectedError(
+            format!("Fixture `{}` was not expected to error", fixture_name),
+            error,
+        )
+    }
+
     pub fn timeout() -> Self {
         FailureReason::Timeout("Test timed out".to_string())
     }
@@ -156,10 +260,22 @@ impl TestFailure {
             vm_error,
             failure_reason,
             storage_state,
+            random_inputs: None,
+        }
+    }
+
+    pub fn with_random_inputs(self, random_inputs: RandomTestInputs) -> Self {
+        Self {
+            random_inputs: Some(random_inputs),
+            ..self
         }
     }
 
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
+        self.render_error_with_color(test_plan, control::SHOULD_COLORIZE.should_colorize())
+    }
+
+    fn render_error_with_color(&self, test_plan: &TestPlan, colorize: bool) -> String {
         let error_string = match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
             FailureReason::Timeout(message) => message.to_string(),
@@ -169,7 +285,7 @@ impl TestFailure {
                     expected.verbiage(/* is_past_tense */ false),
                     actual.verbiage(/* is_past_tense */ true),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::WrongAbortDEPRECATED(message, expected_code, actual) => {
                 let base_message = format!(
@@ -179,7 +295,7 @@ impl TestFailure {
                     expected_code,
                     actual.verbiage(/* is_past_tense */ true),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::UnexpectedError(message, error) => {
                 let prefix = match error.0.status_type() {
@@ -203,7 +319,7 @@ impl TestFailure {
                     message,
                     error.verbiage(/* is_past_tense */ true)
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::Mismatch {
                 move_vm_return_values,
@@ -235,6 +351,24 @@ impl TestFailure {
             }
         };
 
+        let error_string = match &self.random_inputs {
+            None => error_string,
+            Some(RandomTestInputs { seed, arguments }) => {
+                format!(
+                    "{}\n────── Generated arguments ──────\n{}\n\
+                    This test uses randomly generated arguments, shrunk to the ones above. \
+                    Rerun with `--seed {}` to reproduce this failure.",
+                    error_string,
+                    arguments
+                        .iter()
+                        .map(|arg| format!("\t{}", arg))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    seed
+                )
+            }
+        };
+
         match &self.storage_state {
             None => error_string,
             Some(storage_state) => {
@@ -333,8 +467,9 @@ impl TestFailure {
         test_plan: &TestPlan,
         base_message: String,
         vm_error: &Option<VMError>,
+        colorize: bool,
     ) -> String {
-        let report_diagnostics = if control::SHOULD_COLORIZE.should_colorize() {
+        let report_diagnostics = if colorize {
             diagnostics::report_diagnostics_to_color_buffer
         } else {
             diagnostics::report_diagnostics_to_buffer
@@ -478,7 +613,7 @@ impl TestResults {
                 stats.push((
                     qualified_function_name,
                     test_result.elapsed_time.as_secs_f32(),
-                    test_result.instructions_executed,
+                    test_result.gas_used,
                 ))
             }
         }
@@ -495,7 +630,7 @@ impl TestResults {
                 stats.push((
                     qualified_function_name,
                     test_failure.test_run_info.elapsed_time.as_secs_f32(),
-                    test_failure.test_run_info.instructions_executed,
+                    test_failure.test_run_info.gas_used,
                 ));
             }
         }
@@ -515,7 +650,7 @@ impl TestResults {
                 width = max_function_name_size,
                 name = "Test Name",
                 time = "Time",
-                instructions = "Gas Used"
+                instructions = "Gas Used (internal)"
             )?;
 
             for (qualified_function_name, time, instructions) in stats {
@@ -550,6 +685,89 @@ impl TestResults {
         writeln!(writer.lock().unwrap())
     }
 
+    /// Returns the gas used by each passing test, keyed by its fully qualified name.
+    pub fn gas_used_by_passing_tests(&self) -> BTreeMap<String, u64> {
+        self.final_statistics
+            .passed
+            .iter()
+            .flat_map(|(module_id, test_results)| {
+                test_results.iter().map(move |test_result| {
+                    (
+                        format!(
+                            "{}::{}",
+                            format_module_id(module_id),
+                            test_result.function_ident
+                        ),
+                        test_result.gas_used,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Writes a machine-readable report of the results to the file given in `report`.
+    pub fn write_report(&self, report: &TestReport) -> Result<()> {
+        let entries = self.report_entries();
+        let contents = match report.format {
+            TestReportFormat::JUnit => render_junit_report(&entries),
+            TestReportFormat::Json => {
+                let passed = entries
+                    .iter()
+                    .filter(|entry| entry.status == TestStatus::Pass)
+                    .count();
+                serde_json::to_string_pretty(&JsonReport {
+                    total: entries.len(),
+                    passed,
+                    failed: entries.len() - passed,
+                    tests: &entries,
+                })?
+            }
+        };
+        if let Some(parent) = report.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&report.path, contents)
+    }
+
+    fn report_entries(&self) -> Vec<TestReportEntry> {
+        let entry = |module_id: &ModuleId,
+                     info: &TestRunInfo,
+                     status: TestStatus,
+                     failure: Option<String>| TestReportEntry {
+            name: info.function_ident.clone(),
+            module: format_module_id(module_id),
+            status,
+            duration_secs: info.elapsed_time.as_secs_f64(),
+            instructions_executed: info.instructions_executed,
+            gas_used: info.gas_used,
+            failure,
+            output: info.output.clone(),
+        };
+
+        let mut entries = vec![];
+        for (module_id, test_results) in &self.final_statistics.passed {
+            for test_result in test_results {
+                entries.push(entry(module_id, test_result, TestStatus::Pass, None));
+            }
+        }
+        for (module_id, test_failures) in &self.final_statistics.failed {
+            for test_failure in test_failures {
+                let status = match test_failure.failure_reason {
+                    FailureReason::Timeout(_) => TestStatus::Timeout,
+                    _ => TestStatus::Fail,
+                };
+                entries.push(entry(
+                    module_id,
+                    &test_failure.test_run_info,
+                    status,
+                    Some(test_failure.render_error_with_color(&self.test_plan, false)),
+                ));
+            }
+        }
+        entries.sort_by(|e1, e2| (&e1.module, &e1.name).cmp(&(&e2.module, &e2.name)));
+        entries
+    }
+
     /// Returns `true` if all tests passed, `false` if there was a test failure/timeout
     pub fn summarize<W: Write>(self, writer: &Mutex<W>) -> Result<bool> {
         let num_failed_tests = self
@@ -603,3 +821,104 @@ impl TestResults {
         Ok(num_failed_tests == 0)
     }
 }
+
+fn render_junit_report(entries: &[TestReportEntry]) -> String {
+    let total_time: f64 = entries.iter().map(|entry| entry.duration_secs).sum();
+    let num_failures = entries
+        .iter()
+        .filter(|entry| entry.status != TestStatus::Pass)
+        .count();
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        xml,
+        r#"<testsuites name="Move unit tests" tests="{}" failures="{}" time="{:.3}">"#,
+        entries.len(),
+        num_failures,
+        total_time
+    )
+    .unwrap();
+    for (module, tests) in &entries.iter().group_by(|entry| &entry.module) {
+        let tests = tests.collect::<Vec<_>>();
+        writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+            xml_escape(module),
+            tests.len(),
+            tests
+                .iter()
+                .filter(|entry| entry.status != TestStatus::Pass)
+                .count(),
+            tests.iter().map(|entry| entry.duration_secs).sum::<f64>()
+        )
+        .unwrap();
+        for entry in tests {
+            writeln!(
+                xml,
+                r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
+                xml_escape(&entry.name),
+                xml_escape(module),
+                entry.duration_secs
+            )
+            .unwrap();
+            writeln!(xml, "      <properties>").unwrap();
+            writeln!(
+                xml,
+                r#"        <property name="instructions_executed" value="{}"/>"#,
+                entry.instructions_executed
+            )
+            .unwrap();
+            writeln!(
+                xml,
+                r#"        <property name="gas_used" value="{}"/>"#,
+                entry.gas_used
+            )
+            .unwrap();
+            writeln!(xml, "      </properties>").unwrap();
+            if let Some(failure) = &entry.failure {
+                writeln!(
+                    xml,
+                    r#"      <failure type="{}" message="{}">{}</failure>"#,
+                    if entry.status == TestStatus::Timeout {
+                        "timeout"
+                    } else {
+                        "failure"
+                    },
+                    xml_escape(failure.lines().next().unwrap_or_default()),
+                    xml_escape(failure)
+                )
+                .unwrap();
+            }
+            if !entry.output.is_empty() {
+                writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    xml_escape(&entry.output)
+                )
+                .unwrap();
+            }
+            writeln!(xml, "    </testcase>").unwrap();
+        }
+        writeln!(xml, "  </testsuite>").unwrap();
+    }
+    writeln!(xml, "</testsuites>").unwrap();
+    xml
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters are not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -4,8 +4,10 @@
 
 use crate::{
     extensions, format_module_id,
+    random_test::{describe_arguments, generate_arguments, shrink_arguments},
     test_reporter::{
-        FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
+        FailureReason, MoveError, RandomTestInputs, TestFailure, TestResults, TestRunInfo,
+        TestStatistics,
     },
 };
 use anyhow::Result;
@@ -15,17 +17,21 @@ use codespan_reporting::{
 };
 use colored::*;
 
-use move_binary_format::{errors::VMResult, file_format::CompiledModule};
+use move_binary_format::{
+    errors::{VMError, VMResult},
+    file_format::CompiledModule,
+};
 use move_bytecode_utils::Modules;
 use move_compiler::{
     shared::{Flags, NumericalAddress, PackagePaths},
-    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+    unit_test::{ExpectedFailure, Fixture, ModuleTestPlan, TestCase, TestPlan},
 };
 use move_core_types::{
     account_address::AccountAddress,
     effects::{ChangeSet, Op},
+    gas_algebra::InternalGasUnit,
     identifier::IdentStr,
-    value::serialize_values,
+    value::{serialize_values, MoveValue},
     vm_status::StatusCode,
 };
 use move_model::{
@@ -38,13 +44,22 @@ use move_stackless_bytecode_interpreter::{
     shared::bridge::{adapt_move_vm_change_set, adapt_move_vm_result},
     StacklessBytecodeInterpreter,
 };
+use move_stdlib::natives::debug::capture_output;
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use move_vm_test_utils::{
     gas_schedule::{zero_cost_schedule, CostTable, Gas, GasCost, GasStatus},
     InMemoryStorage,
 };
+use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 #[cfg(feature = "evm-backend")]
@@ -54,7 +69,6 @@ use {
     move_to_yul,
     primitive_types::{H160, U256},
     std::convert::TryInto,
-    std::time::Duration,
 };
 
 /// Test state common to all tests
@@ -70,6 +84,8 @@ pub struct SharedTestingConfig {
     check_stackless_vm: bool,
     verbose: bool,
     record_writeset: bool,
+    rand_num_iters: u64,
+    seed: u64,
 
     #[cfg(feature = "evm-backend")]
     evm: bool,
@@ -81,6 +97,20 @@ pub struct TestRunner {
     tests: TestPlan,
 }
 
+/// The maximal number of test runs spent on shrinking the arguments of a failing random test.
+const MAX_SHRINK_RUNS: usize = 1000;
+
+/// The storage states reached by applying fixtures, keyed by the names of the fixtures applied,
+/// or the error of the fixture that failed.
+type FixtureStates = BTreeMap<Vec<String>, Result<Rc<InMemoryStorage>, (String, VMError)>>;
+
+/// The outcome of running a test.
+enum TestOutcome {
+    Pass(TestRunInfo),
+    Fail(TestFailure),
+    Timeout(TestFailure),
+}
+
 /// A gas schedule where every instruction has a cost of "1". This is used to bound execution of a
 /// test to a certain number of ticks.
 fn unit_cost_table() -> CostTable {
@@ -153,6 +183,8 @@ impl TestRunner {
         cost_table: Option<CostTable>,
         named_address_values: BTreeMap<String, NumericalAddress>,
         record_writeset: bool,
+        rand_num_iters: u64,
+        seed: u64,
         #[cfg(feature = "evm-backend")] evm: bool,
     ) -> Result<Self> {
         let source_files = tests
@@ -186,6 +218,8 @@ impl TestRunner {
                 verbose,
                 named_address_values,
                 record_writeset,
+                rand_num_iters,
+                seed,
                 #[cfg(feature = "evm-backend")]
                 evm,
             },
@@ -272,32 +306,34 @@ impl<'a, 'b, W: Write> TestOutput<'a, 'b, W> {
 }
 
 impl SharedTestingConfig {
-    fn execute_via_move_vm(
+    fn execute_via_move_vm<'a>(
         &self,
+        storage: &'a InMemoryStorage,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (
         VMResult<ChangeSet>,
-        VMResult<NativeContextExtensions>,
+        VMResult<NativeContextExtensions<'a>>,
         VMResult<Vec<Vec<u8>>>,
         TestRunInfo,
     ) {
         let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
         let extensions = extensions::new_extensions();
-        let mut session =
-            move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
+        let mut session = move_vm.new_session_with_extensions(storage, extensions);
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
         let now = Instant::now();
-        let serialized_return_values_result = session.execute_function_bypass_visibility(
-            &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
-            vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
-            &mut gas_meter,
-        );
+        let (serialized_return_values_result, output) = capture_output(|| {
+            session.execute_function_bypass_visibility(
+                &test_plan.module_id,
+                IdentStr::new(function_name).unwrap(),
+                vec![], // no ty args, at least for now
+                serialize_values(arguments),
+                &mut gas_meter,
+            )
+        });
         let mut return_result = serialized_return_values_result.map(|res| {
             res.return_values
                 .into_iter()
@@ -312,25 +348,89 @@ impl SharedTestingConfig {
         let test_run_info = TestRunInfo::new(
             function_name.to_string(),
             now.elapsed(),
-            // TODO(Gas): This doesn't look quite right...
-            //            We're not computing the number of instructions executed even with a unit gas schedule.
+            gas_meter.instructions_executed(),
             Gas::new(self.execution_bound)
-                .checked_sub(gas_meter.remaining_gas())
+                .to_unit::<InternalGasUnit>()
+                .checked_sub(gas_meter.remaining_internal_gas())
                 .unwrap()
                 .into(),
-        );
+        )
+        .with_output(output);
         match session.finish_with_extensions() {
             Ok((cs, _, extensions)) => (Ok(cs), Ok(extensions), return_result, test_run_info),
             Err(err) => (Err(err.clone()), Err(err), return_result, test_run_info),
         }
     }
 
+    /// Runs a fixture on top of `storage` and returns the resulting storage state. Changes made
+    /// through native extensions, such as tables, are not carried over.
+    fn execute_fixture(
+        &self,
+        storage: &InMemoryStorage,
+        test_plan: &ModuleTestPlan,
+        fixture: &Fixture,
+    ) -> VMResult<InMemoryStorage> {
+        let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
+        let mut session =
+            move_vm.new_session_with_extensions(storage, extensions::new_extensions());
+        let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
+        let result = session.execute_function_bypass_visibility(
+            &test_plan.module_id,
+            IdentStr::new(&fixture.name).unwrap(),
+            vec![],
+            serialize_values(&fixture.arguments),
+            &mut gas_meter,
+        );
+        if let Err(mut err) = result {
+            if !self.report_stacktrace_on_abort {
+                err.remove_exec_state();
+            }
+            return Err(err);
+        }
+        let (change_set, _, _) = session.finish_with_extensions()?;
+        let mut storage = storage.clone();
+        storage
+            .apply(change_set)
+            .expect("the changes of a fixture apply to the storage it ran on");
+        Ok(storage)
+    }
+
+    /// Computes the storage state a test starts from, by applying its fixtures in order. The
+    /// state reached after each prefix of the fixtures is kept in `states`, so the fixtures shared
+    /// by the tests of a module are only run once. Returns `None` for a test without fixtures,
+    /// which starts from the starting storage state.
+    fn storage_with_fixtures(
+        &self,
+        test_plan: &ModuleTestPlan,
+        fixtures: &[Fixture],
+        states: &mut FixtureStates,
+    ) -> Result<Option<Rc<InMemoryStorage>>, (String, VMError)> {
+        let mut storage: Option<Rc<InMemoryStorage>> = None;
+        for (i, fixture) in fixtures.iter().enumerate() {
+            let applied = fixtures[..=i]
+                .iter()
+                .map(|fixture| fixture.name.clone())
+                .collect::<Vec<_>>();
+            let state = states
+                .entry(applied)
+                .or_insert_with(|| {
+                    let base = storage.as_deref().unwrap_or(&self.starting_storage_state);
+                    self.execute_fixture(base, test_plan, fixture)
+                        .map(Rc::new)
+                        .map_err(|err| (fixture.name.clone(), err))
+                })
+                .clone()?;
+            storage = Some(state);
+        }
+        Ok(storage)
+    }
+
     fn execute_via_stackless_vm(
         &self,
         env: &GlobalEnv,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (
         VMResult<ChangeSet>,
         VMResult<Vec<Vec<u8>>>,
@@ -354,7 +454,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             &[], // no ty args, at least for now
-            &test_info.arguments,
+            arguments,
             &global_state,
         );
         let prop_check_result = interpreter.report_property_checking_results();
@@ -365,6 +465,7 @@ impl SharedTestingConfig {
             // NOTE (mengxu) instruction counting on stackless VM might not be very useful because
             // gas is not charged against stackless VM instruction.
             0,
+            0,
         );
         (
             Ok(change_set),
@@ -414,183 +515,332 @@ impl SharedTestingConfig {
         };
 
         let mut stats = TestStatistics::new();
+        let mut fixture_states = FixtureStates::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let (cs_result, ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
-
-            if self.record_writeset {
-                stats.test_output(
-                    function_name.to_string(),
-                    test_plan,
-                    format!("{:?}", cs_result),
-                );
-            }
-
-            if self.check_stackless_vm {
-                let (stackless_vm_change_set, stackless_vm_result, _, prop_check_result) = self
-                    .execute_via_stackless_vm(
-                        stackless_model.as_ref().unwrap(),
-                        test_plan,
-                        function_name,
-                        test_info,
-                    );
-                let move_vm_result = adapt_move_vm_result(exec_result.clone());
-                let move_vm_change_set =
-                    adapt_move_vm_change_set(cs_result.clone(), &self.starting_storage_state);
-                if stackless_vm_result != move_vm_result
-                    || stackless_vm_change_set != move_vm_change_set
-                {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::mismatch(
-                                move_vm_result,
-                                move_vm_change_set,
-                                stackless_vm_result,
-                                stackless_vm_change_set,
-                            ),
-                            test_run_info,
-                            None,
-                            None,
+            let fixture_storage = match self.storage_with_fixtures(
+                test_plan,
+                &test_info.fixtures,
+                &mut fixture_states,
+            ) {
+                Ok(fixture_storage) => fixture_storage,
+                Err((fixture_name, err)) => {
+                    let test_failure = TestFailure::new(
+                        FailureReason::fixture_error(
+                            &fixture_name,
+                            MoveError(err.major_status(), err.sub_status(), err.location().clone()),
                         ),
-                        test_plan,
+                        TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0, 0),
+                        Some(err),
+                        None,
                     );
+                    output.fail(function_name);
+                    stats.test_failure(test_failure, test_plan);
                     continue;
                 }
-                if let Some(prop_failure) = prop_check_result {
+            };
+            // The stackless VM starts from a state without resources, so tests with fixtures are
+            // only run on the Move VM
+            let stackless_model = stackless_model
+                .as_ref()
+                .filter(|_| fixture_storage.is_none());
+            match self.exec_test_with_generated_arguments(
+                fixture_storage
+                    .as_deref()
+                    .unwrap_or(&self.starting_storage_state),
+                stackless_model,
+                test_plan,
+                function_name,
+                test_info,
+                &mut stats,
+            ) {
+                TestOutcome::Pass(test_run_info) => {
+                    output.pass(function_name);
+                    stats.test_success(test_run_info, test_plan);
+                }
+                TestOutcome::Fail(test_failure) => {
                     output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::property(prop_failure),
-                            test_run_info,
-                            None,
-                            None,
-                        ),
+                    stats.test_failure(test_failure, test_plan);
+                }
+                TestOutcome::Timeout(test_failure) => {
+                    output.timeout(function_name);
+                    stats.test_failure(test_failure, test_plan);
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Runs a test, generating its arguments if it is a `#[random_test]`. Random tests are run
+    /// `rand_num_iters` times, and the arguments of a failing run are shrunk before the failure
+    /// is reported. Only the writeset of the reported run is recorded, i.e. none for a passing
+    /// random test.
+    fn exec_test_with_generated_arguments(
+        &self,
+        storage: &InMemoryStorage,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        stats: &mut TestStatistics,
+    ) -> TestOutcome {
+        let is_random_test = test_info.is_random_test();
+        let iterations = if is_random_test {
+            self.rand_num_iters
+        } else {
+            1
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut total_run_info = TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0, 0);
+        for _ in 0..iterations {
+            let arguments = generate_arguments(&test_info.arguments, &mut rng);
+            let (outcome, writeset) = self.exec_test(
+                storage,
+                stackless_model,
+                test_plan,
+                function_name,
+                test_info,
+                &arguments,
+            );
+            match outcome {
+                outcome if !is_random_test => {
+                    if let Some(writeset) = writeset {
+                        stats.test_output(function_name.to_string(), test_plan, writeset);
+                    }
+                    return outcome;
+                }
+                TestOutcome::Pass(test_run_info) => {
+                    total_run_info.elapsed_time += test_run_info.elapsed_time;
+                    total_run_info.instructions_executed += test_run_info.instructions_executed;
+                    total_run_info.gas_used += test_run_info.gas_used;
+                    total_run_info.output.push_str(&test_run_info.output);
+                }
+                outcome => {
+                    return self.shrink_failure(
+                        storage,
+                        stackless_model,
                         test_plan,
-                    );
-                    continue;
+                        function_name,
+                        test_info,
+                        arguments,
+                        (outcome, writeset),
+                        stats,
+                    )
                 }
             }
+        }
+        TestOutcome::Pass(total_run_info)
+    }
 
-            let save_session_state = || {
-                if self.save_storage_state_on_failure {
-                    cs_result.ok().and_then(|changeset| {
-                        ext_result.ok().and_then(|extensions| {
-                            print_resources_and_extensions(
-                                &changeset,
-                                extensions,
-                                &self.starting_storage_state,
-                            )
-                            .ok()
-                        })
-                    })
-                } else {
-                    None
+    /// Repeatedly replaces the arguments of a failing random test run with simpler ones for which
+    /// the test still fails, and attaches the final arguments to the failure.
+    fn shrink_failure(
+        &self,
+        storage: &InMemoryStorage,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        mut arguments: Vec<MoveValue>,
+        (mut outcome, mut writeset): (TestOutcome, Option<String>),
+        stats: &mut TestStatistics,
+    ) -> TestOutcome {
+        let mut runs = 0;
+        'shrink: loop {
+            for candidate in shrink_arguments(&test_info.arguments, &arguments) {
+                if runs >= MAX_SHRINK_RUNS {
+                    break 'shrink;
                 }
-            };
-            match exec_result {
-                Err(err) => {
-                    let actual_err =
-                        MoveError(err.major_status(), err.sub_status(), err.location().clone());
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.unwrap() == *code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        *expected_code,
-                                        actual_err,
-                                    ),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        }
-                    }
+                runs += 1;
+                let (candidate_outcome, candidate_writeset) = self.exec_test(
+                    storage,
+                    stackless_model,
+                    test_plan,
+                    function_name,
+                    test_info,
+                    &candidate,
+                );
+                if !matches!(candidate_outcome, TestOutcome::Pass(_)) {
+                    arguments = candidate;
+                    outcome = candidate_outcome;
+                    writeset = candidate_writeset;
+                    continue 'shrink;
                 }
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(
-                                FailureReason::no_error(),
-                                test_run_info,
-                                None,
-                                save_session_state(),
-                            ),
-                            test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
+            }
+            break;
+        }
+
+        if let Some(writeset) = writeset {
+            stats.test_output(function_name.to_string(), test_plan, writeset);
+        }
+        let random_inputs = RandomTestInputs {
+            seed: self.seed,
+            arguments: describe_arguments(&test_info.arguments, &arguments),
+        };
+        match outcome {
+            TestOutcome::Fail(test_failure) => {
+                TestOutcome::Fail(test_failure.with_random_inputs(random_inputs))
+            }
+            TestOutcome::Timeout(test_failure) => {
+                TestOutcome::Timeout(test_failure.with_random_inputs(random_inputs))
+            }
+            TestOutcome::Pass(_) => unreachable!("shrinking a passing test"),
+        }
+    }
+
+    /// Executes a test once with the given arguments. Returns the outcome along with the
+    /// writeset of the run if writesets are recorded.
+    fn exec_test(
+        &self,
+        storage: &InMemoryStorage,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+    ) -> (TestOutcome, Option<String>) {
+        let (cs_result, ext_result, exec_result, test_run_info) =
+            self.execute_via_move_vm(storage, test_plan, function_name, arguments);
+        let writeset = self.record_writeset.then(|| format!("{:?}", cs_result));
+        let outcome = self.test_outcome(
+            storage,
+            stackless_model,
+            test_plan,
+            function_name,
+            test_info,
+            arguments,
+            (cs_result, ext_result, exec_result, test_run_info),
+        );
+        (outcome, writeset)
+    }
+
+    /// Determines the outcome of a test run from its results on the Move VM, comparing them
+    /// with the results on the stackless VM if a model is given.
+    fn test_outcome<'a>(
+        &self,
+        storage: &'a InMemoryStorage,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+        (cs_result, ext_result, exec_result, test_run_info): (
+            VMResult<ChangeSet>,
+            VMResult<NativeContextExtensions<'a>>,
+            VMResult<Vec<Vec<u8>>>,
+            TestRunInfo,
+        ),
+    ) -> TestOutcome {
+        if let Some(stackless_model) = stackless_model {
+            let (stackless_vm_change_set, stackless_vm_result, _, prop_check_result) =
+                self.execute_via_stackless_vm(stackless_model, test_plan, function_name, arguments);
+            let move_vm_result = adapt_move_vm_result(exec_result.clone());
+            let move_vm_change_set = adapt_move_vm_change_set(cs_result.clone(), storage);
+            if stackless_vm_result != move_vm_result
+                || stackless_vm_change_set != move_vm_change_set
+            {
+                return TestOutcome::Fail(TestFailure::new(
+                    FailureReason::mismatch(
+                        move_vm_result,
+                        move_vm_change_set,
+                        stackless_vm_result,
+                        stackless_vm_change_set,
+                    ),
+                    test_run_info,
+                    None,
+                    None,
+                ));
+            }
+            if let Some(prop_failure) = prop_check_result {
+                return TestOutcome::Fail(TestFailure::new(
+                    FailureReason::property(prop_failure),
+                    test_run_info,
+                    None,
+                    None,
+                ));
+            }
+        }
+
+        let save_session_state = || {
+            if self.save_storage_state_on_failure {
+                cs_result.ok().and_then(|changeset| {
+                    ext_result.ok().and_then(|extensions| {
+                        print_resources_and_extensions(&changeset, extensions, storage).ok()
+                    })
+                })
+            } else {
+                None
+            }
+        };
+        match exec_result {
+            Err(err) => {
+                let actual_err =
+                    MoveError(err.major_status(), err.sub_status(), err.location().clone());
+                assert!(err.major_status() != StatusCode::EXECUTED);
+                match test_info.expected_failure.as_ref() {
+                    Some(ExpectedFailure::Expected) => TestOutcome::Pass(test_run_info),
+                    Some(ExpectedFailure::ExpectedWithError(expected_err))
+                        if expected_err == &actual_err =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                        if actual_err.0 == StatusCode::ABORTED
+                            && actual_err.1.is_some()
+                            && actual_err.1.unwrap() == *code =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    // incorrect cases
+                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                        TestOutcome::Fail(TestFailure::new(
+                            FailureReason::wrong_error(expected_err.clone(), actual_err),
+                            test_run_info,
+                            Some(err),
+                            save_session_state(),
+                        ))
                     }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                        TestOutcome::Fail(TestFailure::new(
+                            FailureReason::wrong_abort_deprecated(*expected_code, actual_err),
+                            test_run_info,
+                            Some(err),
+                            save_session_state(),
+                        ))
+                    }
+                    None if err.major_status() == StatusCode::OUT_OF_GAS => {
+                        // Ran out of ticks, report a test timeout and log a test failure
+                        TestOutcome::Timeout(TestFailure::new(
+                            FailureReason::timeout(),
+                            test_run_info,
+                            Some(err),
+                            save_session_state(),
+                        ))
+                    }
+                    None => TestOutcome::Fail(TestFailure::new(
+                        FailureReason::unexpected_error(actual_err),
+                        test_run_info,
+                        Some(err),
+                        save_session_state(),
+                    )),
+                }
+            }
+            Ok(_) => {
+                // Expected the test to fail, but it executed
+                if test_info.expected_failure.is_some() {
+                    TestOutcome::Fail(TestFailure::new(
+                        FailureReason::no_error(),
+                        test_run_info,
+                        None,
+                        save_session_state(),
+                    ))
+                } else {
+                    // Expected the test to execute fully and it did
+                    TestOutcome::Pass(test_run_info)
                 }
             }
         }
-
-        stats
     }
 
     #[cfg(feature = "evm-backend")]
@@ -664,7 +914,7 @@ impl SharedTestingConfig {
                 &model,
                 &test_plan.module_id,
                 IdentStr::new(function_name).unwrap(),
-                &test_info.arguments,
+                &generate_arguments(&test_info.arguments, &mut StdRng::seed_from_u64(self.seed)),
             ) {
                 Ok(yul_code) => yul_code,
                 Err(diagnostics) => {
@@ -674,7 +924,7 @@ impl SharedTestingConfig {
                     stats.test_failure(
                         TestFailure::new(
                             FailureReason::move_to_evm_error(diagnostics),
-                            TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0),
+                            TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0, 0),
                             None,
                             None,
                         ),
@@ -693,10 +943,11 @@ impl SharedTestingConfig {
             };
 
             let test_run_info =
-                || -> TestRunInfo { TestRunInfo::new(function_name.to_string(), duration, 0) };
+                || -> TestRunInfo { TestRunInfo::new(function_name.to_string(), duration, 0, 0) };
 
             // TODO: gas/timeout
             // TODO: arguments
+            // TODO: fixtures
             // TODO: locations
 
             match (test_info.expected_failure.as_ref(), &res.exit_reason) {
@@ -5,8 +5,15 @@
 use move_command_line_common::testing::{
     add_update_baseline_fix, format_diff, read_env_update_baseline, EXP_EXT,
 };
-use move_unit_test::{self, UnitTestingConfig};
-use regex::RegexBuilder;
+use move_core_types::account_address::AccountAddress;
+use move_stdlib::natives::{all_natives, nursery_natives, GasParameters, NurseryGasParameters};
+use move_unit_test::{
+    self,
+    test_reporter::{TestReport, TestReportFormat},
+    UnitTestingConfig,
+};
+use move_vm_runtime::native_functions::NativeFunctionTable;
+use regex::{Regex, RegexBuilder};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -16,14 +23,24 @@ use std::{
 // tests flaky.
 const TEST_MODIFIER_STRS: &[&str] = &[
     "storage",
+    "junit",
+    "json",
+    "mutate",
     #[cfg(feature = "evm-backend")]
     "evm",
 ];
 
-pub fn modify(mut base_config: UnitTestingConfig, modifier_str: &str) -> Option<UnitTestingConfig> {
+pub fn modify(
+    mut base_config: UnitTestingConfig,
+    modifier_str: &str,
+    path: &Path,
+) -> Option<UnitTestingConfig> {
     // Add future test modifiers here
     match modifier_str {
         "storage" => base_config.report_storage_on_error = true,
+        "junit" => base_config.reports = vec![report_for(TestReportFormat::JUnit, path)],
+        "json" => base_config.reports = vec![report_for(TestReportFormat::Json, path)],
+        "mutate" => base_config.mutate = true,
         #[cfg(feature = "evm-backend")]
         "evm" => base_config.evm = true,
         _ => return None,
@@ -31,6 +48,44 @@ pub fn modify(mut base_config: UnitTestingConfig, modifier_str: &str) -> Option<
     Some(base_config)
 }
 
+/// The standard library natives, including those of the nursery.
+fn natives() -> NativeFunctionTable {
+    let addr = AccountAddress::from_hex_literal("0x1").unwrap();
+    all_natives(addr, GasParameters::zeros())
+        .into_iter()
+        .chain(nursery_natives(addr, NurseryGasParameters::zeros()))
+        .collect()
+}
+
+/// Reports are written to a temporary file, which is appended to the test output.
+fn report_for(format: TestReportFormat, path: &Path) -> TestReport {
+    let file_name = format!(
+        "move_unit_test_{}.{:?}",
+        path.file_stem().unwrap().to_string_lossy(),
+        format
+    );
+    TestReport {
+        format,
+        path: std::env::temp_dir().join(file_name),
+    }
+}
+
+/// Appends the reports written by a test run to its output. Times are erased, as they vary.
+fn append_reports(
+    (mut buffer, ok): (Vec<u8>, bool),
+    test_config: &UnitTestingConfig,
+    path: &Path,
+) -> anyhow::Result<(Vec<u8>, bool)> {
+    let source_dir = format!("{}/", path.parent().unwrap().display());
+    let times = Regex::new(r#"(time="|"duration_secs": )[0-9.e-]+"#).unwrap();
+    for report in &test_config.reports {
+        let contents = fs::read_to_string(&report.path)?.replace(&source_dir, "");
+        fs::remove_file(&report.path)?;
+        buffer.extend(times.replace_all(&contents, "${1}<time>").as_bytes());
+    }
+    Ok((buffer, ok))
+}
+
 fn run_test_with_modifiers(
     unit_test_config: UnitTestingConfig,
     path: &Path,
@@ -40,7 +95,7 @@ fn run_test_with_modifiers(
     for modifier in TEST_MODIFIER_STRS.iter() {
         let modified_exp_path = path.with_extension(format!("{}.{}", modifier, EXP_EXT));
         if let (Some(test_config), true) = (
-            modify(unit_test_config.clone(), modifier),
+            modify(unit_test_config.clone(), modifier, path),
             modified_exp_path.exists(),
         ) {
             let buffer = Vec::new();
@@ -53,8 +108,14 @@ fn run_test_with_modifiers(
                 );
             }
 
+            let result = test_config.run_and_report_unit_tests(
+                test_plan.unwrap(),
+                Some(natives()),
+                None,
+                buffer,
+            )?;
             results.push((
-                test_config.run_and_report_unit_tests(test_plan.unwrap(), None, None, buffer)?,
+                append_reports(result, &test_config, path)?,
                 modified_exp_path,
             ))
         }
@@ -68,7 +129,12 @@ fn run_test_with_modifiers(
     }
 
     results.push((
-        unit_test_config.run_and_report_unit_tests(test_plan.unwrap(), None, None, buffer)?,
+        unit_test_config.run_and_report_unit_tests(
+            test_plan.unwrap(),
+            Some(natives()),
+            None,
+            buffer,
+        )?,
         path.with_extension(EXP_EXT),
     ));
 
@@ -84,12 +150,17 @@ fn run_test_impl(path: &Path) -> anyhow::Result<()> {
         num_threads: 1,
         gas_limit: Some(1000),
         source_files,
-        dep_files: move_stdlib::move_stdlib_files(),
+        dep_files: [
+            move_stdlib::move_stdlib_files(),
+            move_stdlib::move_nursery_files(),
+        ]
+        .concat(),
         named_address_values: move_stdlib::move_stdlib_named_addresses()
             .into_iter()
             .collect(),
         report_writeset: true,
         report_stacktrace_on_abort: true,
+        seed: Some(0),
 
         ..UnitTestingConfig::default_with_bound(None)
     };
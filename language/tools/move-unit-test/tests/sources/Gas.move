@@ -0,0 +1,13 @@
+module 0x1::Gas {
+    #[test]
+    fun fixed() {
+        let i = 0;
+        while (i < 10) i = i + 1;
+    }
+
+    #[random_test]
+    fun varying(n: u8) {
+        let i = 0;
+        while (i < n) i = i + 1;
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{self, gas_snapshot::GasSnapshot, UnitTestingConfig};
+use std::path::PathBuf;
+
+fn run(config: &UnitTestingConfig) -> bool {
+    let test_plan = config.build_test_plan().unwrap();
+    let (_, ok) = config
+        .run_and_report_unit_tests(test_plan, None, None, Vec::new())
+        .unwrap();
+    ok
+}
+
+// Random tests are run with a new seed each time, so they must not be part of the snapshot for
+// checking it to be deterministic.
+#[test]
+fn test_random_tests_are_not_in_gas_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot_path = dir.path().join(".gas-snapshot");
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut config = UnitTestingConfig {
+        source_files: vec![path
+            .join("tests/sources/Gas.move")
+            .to_string_lossy()
+            .to_string()],
+        gas_snapshot: Some(snapshot_path.clone()),
+        ..UnitTestingConfig::default_with_bound(None)
+    };
+
+    assert!(run(&config));
+    let snapshot = GasSnapshot::read(&snapshot_path).unwrap();
+    assert_eq!(
+        snapshot.entries.keys().collect::<Vec<_>>(),
+        vec!["0x1::Gas::fixed"]
+    );
+
+    config.check_gas_snapshot = true;
+    assert!(run(&config));
+    assert!(run(&config));
+}
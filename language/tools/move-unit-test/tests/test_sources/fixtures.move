@@ -0,0 +1,58 @@
+#[before_each(publish)]
+module 0x1::M {
+    struct Counter has key { value: u64 }
+
+    #[fixture(s = @0x1)]
+    fun publish(s: signer) {
+        move_to(&s, Counter { value: 0 })
+    }
+
+    #[fixture]
+    fun increment() acquires Counter {
+        let counter = borrow_global_mut<Counter>(@0x1);
+        counter.value = counter.value + 1;
+    }
+
+    #[fixture]
+    fun fails() {
+        abort 7
+    }
+
+    #[test]
+    fun starts_from_fixture() acquires Counter {
+        assert!(borrow_global<Counter>(@0x1).value == 0, 0);
+        borrow_global_mut<Counter>(@0x1).value = 10;
+    }
+
+    #[test]
+    fun changes_do_not_leak_between_tests() acquires Counter {
+        assert!(borrow_global<Counter>(@0x1).value == 0, 0);
+    }
+
+    #[test]
+    #[before_each(increment)]
+    fun applies_test_fixtures_after_module_fixtures() acquires Counter {
+        assert!(borrow_global<Counter>(@0x1).value == 1, 0);
+    }
+
+    #[test]
+    #[before_each(increment, increment_again)]
+    fun applies_fixtures_in_order() acquires Counter {
+        assert!(borrow_global<Counter>(@0x1).value == 12, 0);
+    }
+
+    #[fixture]
+    fun increment_again() acquires Counter {
+        let counter = borrow_global_mut<Counter>(@0x1);
+        counter.value = counter.value * 10 + 2;
+    }
+
+    #[test(s = @0x1)]
+    fun fixture_resources_exist_at_signer(s: signer) {
+        assert!(exists<Counter>(std::signer::address_of(&s)), 0);
+    }
+
+    #[test]
+    #[before_each(fails)]
+    fun failing_fixture() { }
+}
@@ -0,0 +1,32 @@
+module 0x1::Counter {
+    const ETOO_LARGE: u64 = 1;
+
+    public fun increment(x: u64, by: u64): u64 {
+        assert!(x < 100, ETOO_LARGE);
+        x + by
+    }
+
+    public fun max(a: u64, b: u64): u64 {
+        if (a >= b) a else b
+    }
+
+    public fun is_even(x: u64): bool {
+        x % 2 == 0
+    }
+
+    #[test]
+    fun test_increment() {
+        assert!(increment(1, 2) == 3, 0);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1, location = Self)]
+    fun test_increment_too_large() {
+        increment(100, 1);
+    }
+
+    #[test]
+    fun test_max() {
+        assert!(max(1, 2) == 2, 0);
+    }
+}
@@ -0,0 +1,28 @@
+address 0x1 {
+module M {
+    use std::vector;
+
+    struct Pair has drop { a: u8, b: bool }
+
+    #[random_test]
+    fun add_commutes(x: u32, y: u32) {
+        assert!((x as u64) + (y as u64) == (y as u64) + (x as u64), 0);
+    }
+
+    #[random_test(_s = @0x1)]
+    fun small_number(_s: signer, x: u64) {
+        assert!(x < 1000, 1);
+    }
+
+    #[random_test]
+    fun short_vector(v: vector<Pair>) {
+        assert!(vector::length(&v) < 3, 2);
+    }
+
+    #[random_test]
+    #[expected_failure(arithmetic_error, location = Self)]
+    fun always_underflows(x: u8) {
+        let _ = x - x - 1;
+    }
+}
+}
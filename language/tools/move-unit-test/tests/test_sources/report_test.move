@@ -0,0 +1,20 @@
+module 0x1::M {
+    use std::debug;
+
+    #[test]
+    fun prints_output() {
+        let x = 1 + 2;
+        debug::print(&x);
+    }
+
+    #[test]
+    fun fails_with_output() {
+        debug::print(&true);
+        abort 42
+    }
+
+    #[test]
+    fun times_out() {
+        while (true) {}
+    }
+}